@@ -0,0 +1,218 @@
+//! "Align Selection…": pads the selected lines with spaces so a separator
+//! (`=`, `:`, `,`, `|` or a regular expression) lines up in one column, or in
+//! several when every occurrence is aligned, as in Markdown tables.
+//!
+//! A separator that directly follows its text on every line (`key: value`,
+//! `a, b`) stays attached and the text after it is aligned instead.
+
+use crate::TextEditorApp;
+use crate::i18n::{tr, trf};
+use crate::wrap::paragraph_at;
+use eframe::egui;
+use regex::Regex;
+
+const PRESETS: &[&str] = &["=", ":", ",", "|"];
+
+pub struct AlignDialog {
+    pub open: bool,
+    separator: String,
+    regex: bool,
+    every: bool,
+}
+
+impl Default for AlignDialog {
+    fn default() -> Self {
+        Self {
+            open: false,
+            separator: "=".to_string(),
+            regex: false,
+            every: false,
+        }
+    }
+}
+
+/// A line cut at its separators: `cells` has one more entry than `seps`.
+struct Split<'a> {
+    cells: Vec<&'a str>,
+    seps: Vec<&'a str>,
+}
+
+fn split<'a>(line: &'a str, pattern: &Regex, every: bool) -> Split<'a> {
+    let mut cells = Vec::new();
+    let mut seps = Vec::new();
+    let mut start = 0;
+    for m in pattern.find_iter(line).filter(|m| !m.is_empty()) {
+        cells.push(&line[start..m.start()]);
+        seps.push(m.as_str());
+        start = m.end();
+        if !every {
+            break;
+        }
+    }
+    cells.push(&line[start..]);
+    Split { cells, seps }
+}
+
+fn width(text: &str) -> usize {
+    text.chars().count()
+}
+
+fn pad(text: &mut String, to: usize) {
+    let len = width(text);
+    text.extend(std::iter::repeat_n(' ', to.saturating_sub(len)));
+}
+
+/// The cell in column `k`, without the spacing around it. The first keeps
+/// its indentation.
+fn cell<'a>(split: &Split<'a>, k: usize) -> &'a str {
+    if k == 0 {
+        split.cells[0].trim_end()
+    } else {
+        split.cells[k].trim()
+    }
+}
+
+/// Aligns the separators matched by `pattern` in `text`. Lines without a
+/// separator are left alone.
+pub fn align(text: &str, pattern: &Regex, every: bool) -> String {
+    let lines: Vec<&str> = text.split('\n').collect();
+    let splits: Vec<Split> = lines
+        .iter()
+        .map(|line| split(line, pattern, every))
+        .collect();
+    let columns = splits.iter().map(|s| s.seps.len()).max().unwrap_or(0);
+
+    // Per column: whether the separator hugs its text, and how wide the
+    // padded part is.
+    let mut attached = vec![true; columns];
+    let mut widths = vec![0; columns];
+    for split in &splits {
+        let before_seps = &split.cells[..split.seps.len()];
+        for (cell, attached) in before_seps.iter().zip(&mut attached) {
+            if cell.is_empty() || cell.ends_with(char::is_whitespace) {
+                *attached = false;
+            }
+        }
+    }
+    for split in &splits {
+        for k in 0..split.seps.len() {
+            let mut piece = cell(split, k).to_string();
+            if attached[k] {
+                piece.push_str(split.seps[k]);
+            }
+            widths[k] = widths[k].max(width(&piece));
+        }
+    }
+
+    lines
+        .iter()
+        .zip(&splits)
+        .map(|(line, split)| {
+            if split.seps.is_empty() {
+                return line.to_string();
+            }
+            let mut out = String::new();
+            for (k, sep) in split.seps.iter().enumerate() {
+                let mut piece = cell(split, k).to_string();
+                if attached[k] {
+                    piece.push_str(sep);
+                    pad(&mut piece, widths[k]);
+                } else {
+                    pad(&mut piece, widths[k]);
+                    if widths[k] > 0 {
+                        piece.push(' ');
+                    }
+                    piece.push_str(sep);
+                }
+                if k > 0 {
+                    out.push(' ');
+                }
+                out.push_str(&piece);
+            }
+            out.push(' ');
+            out.push_str(cell(split, split.seps.len()));
+            out.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl TextEditorApp {
+    pub(crate) fn show_align_window(&mut self, ctx: &egui::Context) {
+        let dialog = &mut self.align;
+        if !dialog.open {
+            return;
+        }
+        let mut open = true;
+        let mut apply = false;
+        egui::Window::new(tr("Align Selection"))
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(tr("Align on:"));
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut dialog.separator).desired_width(120.0),
+                    );
+                    apply |= response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    for preset in PRESETS {
+                        if ui.small_button(*preset).clicked() {
+                            dialog.separator = preset.to_string();
+                            dialog.regex = false;
+                            dialog.every = *preset == "|";
+                        }
+                    }
+                });
+                ui.checkbox(&mut dialog.regex, tr("Regular expression"));
+                ui.checkbox(&mut dialog.every, tr("Align every occurrence"));
+                ui.label(tr(
+                    "Aligns the selected lines, or the block around the caret.",
+                ));
+                apply |= ui.button(tr("Align")).clicked();
+            });
+        dialog.open = open;
+        if apply {
+            self.align_selection(ctx);
+        }
+    }
+
+    fn align_selection(&mut self, ctx: &egui::Context) {
+        let dialog = &self.align;
+        let source = if dialog.regex {
+            dialog.separator.clone()
+        } else {
+            regex::escape(&dialog.separator)
+        };
+        let pattern = match Regex::new(&source) {
+            Ok(pattern) if !dialog.separator.is_empty() => pattern,
+            Ok(_) => return,
+            Err(err) => {
+                self.notifications
+                    .error(trf("Invalid pattern: {error}", &[("error", &err)]));
+                return;
+            }
+        };
+        let every = dialog.every;
+        let Some(range) = self.selection_or(ctx, paragraph_at) else {
+            return;
+        };
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        // Whole lines, so indentation and the first cell are part of it.
+        let text = &tab.content;
+        let start = text[..range.start].rfind('\n').map_or(0, |i| i + 1);
+        let end = if range.end > start && text[..range.end].ends_with('\n') {
+            range.end - 1
+        } else {
+            text[range.end..]
+                .find('\n')
+                .map_or(text.len(), |i| range.end + i)
+        };
+        let aligned = align(&text[start..end], &pattern, every);
+        if aligned != text[start..end] {
+            self.replace_range(ctx, start..end, &aligned);
+        }
+    }
+}
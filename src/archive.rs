@@ -0,0 +1,275 @@
+//! Browsing zip and tar(.gz) archives without unpacking them. Entries are
+//! listed in the sidebar and read into memory on demand; zip entries can be
+//! edited and written back, tar entries open read-only.
+
+use crate::i18n::{tr, trf};
+use crate::{FileTab, TextEditorApp, atomic_write};
+use eframe::egui;
+use flate2::read::GzDecoder;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+pub fn kind(path: &Path) -> Option<ArchiveKind> {
+    let name = path.file_name()?.to_string_lossy().to_lowercase();
+    let ext = |e: &str| name.ends_with(e);
+    if ext(".zip") || ext(".jar") || ext(".whl") || ext(".vsix") {
+        Some(ArchiveKind::Zip)
+    } else if ext(".tar.gz") || ext(".tgz") || ext(".crate") {
+        Some(ArchiveKind::TarGz)
+    } else if ext(".tar") {
+        Some(ArchiveKind::Tar)
+    } else {
+        None
+    }
+}
+
+fn tar_reader(path: &Path, kind: ArchiveKind) -> io::Result<tar::Archive<Box<dyn Read>>> {
+    let file = File::open(path)?;
+    let reader: Box<dyn Read> = if kind == ArchiveKind::TarGz {
+        Box::new(GzDecoder::new(file))
+    } else {
+        Box::new(file)
+    };
+    Ok(tar::Archive::new(reader))
+}
+
+/// The names of all file entries, sorted.
+pub fn list(path: &Path, kind: ArchiveKind) -> io::Result<Vec<String>> {
+    let mut names = Vec::new();
+    if kind == ArchiveKind::Zip {
+        let archive = ZipArchive::new(File::open(path)?)?;
+        names.extend(
+            archive
+                .file_names()
+                .filter(|name| !name.ends_with('/'))
+                .map(str::to_string),
+        );
+    } else {
+        for entry in tar_reader(path, kind)?.entries()? {
+            let entry = entry?;
+            if entry.header().entry_type().is_file() {
+                names.push(entry.path()?.to_string_lossy().into_owned());
+            }
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+pub fn read_entry(path: &Path, kind: ArchiveKind, name: &str) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    if kind == ArchiveKind::Zip {
+        let mut archive = ZipArchive::new(File::open(path)?)?;
+        archive.by_name(name)?.read_to_end(&mut bytes)?;
+        return Ok(bytes);
+    }
+    for entry in tar_reader(path, kind)?.entries()? {
+        let mut entry = entry?;
+        if entry.path()?.to_string_lossy() == name {
+            entry.read_to_end(&mut bytes)?;
+            return Ok(bytes);
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::NotFound, name.to_string()))
+}
+
+/// Replaces entry `name` of the zip at `path` with `bytes`. The archive is
+/// rebuilt in memory, copying every other entry without recompressing, and
+/// written over the original atomically.
+pub fn write_zip_entry(path: &Path, name: &str, bytes: &[u8]) -> io::Result<()> {
+    let mut archive = ZipArchive::new(File::open(path)?)?;
+    let mut buffer = io::Cursor::new(Vec::new());
+    let mut writer = ZipWriter::new(&mut buffer);
+    for i in 0..archive.len() {
+        let file = archive.by_index_raw(i)?;
+        if file.name() == name {
+            let options = SimpleFileOptions::default().compression_method(file.compression());
+            drop(file);
+            writer.start_file(name, options)?;
+            writer.write_all(bytes)?;
+        } else {
+            writer.raw_copy_file(file)?;
+        }
+    }
+    writer.finish()?;
+    atomic_write::write(path, buffer.get_ref())
+}
+
+/// An archive shown in the sidebar.
+pub struct OpenArchive {
+    pub path: PathBuf,
+    pub kind: ArchiveKind,
+    pub entries: Vec<String>,
+}
+
+/// Draws `entries` (paths relative to the directory `prefix`) as a tree,
+/// storing the full name of a clicked file in `clicked`.
+fn entry_tree(ui: &mut egui::Ui, prefix: &str, entries: &[&str], clicked: &mut Option<String>) {
+    let mut dirs: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    let mut files = Vec::new();
+    for entry in entries {
+        match entry.split_once('/') {
+            Some((dir, rest)) => dirs.entry(dir).or_default().push(rest),
+            None => files.push(*entry),
+        }
+    }
+    for (dir, children) in dirs {
+        let path = format!("{prefix}{dir}/");
+        egui::CollapsingHeader::new(dir)
+            .id_source(&path)
+            .show(ui, |ui| entry_tree(ui, &path, &children, clicked));
+    }
+    for file in files {
+        if ui.selectable_label(false, file).clicked() {
+            *clicked = Some(format!("{prefix}{file}"));
+        }
+    }
+}
+
+impl TextEditorApp {
+    pub(crate) fn open_archive(&mut self, path: &Path) {
+        let Some(kind) = kind(path) else {
+            return;
+        };
+        if self.archives.iter().any(|a| a.path == path) {
+            return;
+        }
+        match list(path, kind) {
+            Ok(entries) => self.archives.push(OpenArchive {
+                path: path.to_path_buf(),
+                kind,
+                entries,
+            }),
+            Err(err) => self.notifications.error(trf(
+                "Could not open {path}: {error}",
+                &[("path", &path.display()), ("error", &err)],
+            )),
+        }
+    }
+
+    fn open_archive_entry(&mut self, index: usize, name: &str) {
+        let archive = &self.archives[index];
+        let bytes = match read_entry(&archive.path, archive.kind, name) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                self.notifications.error(trf(
+                    "Could not read {name}: {error}",
+                    &[("name", &name), ("error", &err)],
+                ));
+                return;
+            }
+        };
+        let (content, binary) = match String::from_utf8(bytes) {
+            Ok(text) => (text, false),
+            Err(e) => (String::from_utf8_lossy(e.as_bytes()).into_owned(), true),
+        };
+        let archive_name = archive
+            .path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        let file_name = name.rsplit('/').next().unwrap_or(name);
+        let title = format!("{file_name} ({archive_name})");
+        let read_only = binary || archive.kind != ArchiveKind::Zip;
+        let archive_entry = Some((archive.path.clone(), name.to_string()));
+        let syntax = self.detect_syntax(Some(Path::new(file_name)), &content);
+        let mut tab = FileTab {
+            title,
+            content,
+            syntax,
+            read_only,
+            archive_entry,
+            ..Default::default()
+        };
+        tab.mark_saved();
+        self.add_tab(tab);
+    }
+
+    /// The sidebar section listing the entries of opened archives.
+    pub(crate) fn archive_sidebar(&mut self, ui: &mut egui::Ui) {
+        if self.archives.is_empty() {
+            return;
+        }
+        let mut clicked = None;
+        let mut close = None;
+        ui.separator();
+        ui.strong(tr("Archives"));
+        for (index, archive) in self.archives.iter().enumerate() {
+            let name = archive
+                .path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy();
+            let entries: Vec<&str> = archive.entries.iter().map(String::as_str).collect();
+            let header = egui::CollapsingHeader::new(name.as_ref())
+                .id_source(&archive.path)
+                .show(ui, |ui| {
+                    let mut entry = None;
+                    entry_tree(ui, "", &entries, &mut entry);
+                    if let Some(entry) = entry {
+                        clicked = Some((index, entry));
+                    }
+                });
+            header.header_response.context_menu(|ui| {
+                if ui.button(tr("Close archive")).clicked() {
+                    close = Some(index);
+                    ui.close_menu();
+                }
+            });
+        }
+        if let Some((index, entry)) = clicked {
+            self.open_archive_entry(index, &entry);
+        }
+        if let Some(index) = close {
+            self.archives.remove(index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn writing_an_entry_keeps_the_others() {
+        let dir = std::env::temp_dir().join(format!("rusty-editor-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("docs.zip");
+        let mut writer = ZipWriter::new(File::create(&path).unwrap());
+        for (name, text) in [("a.txt", "one"), ("b.txt", "two")] {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(text.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+
+        write_zip_entry(&path, "b.txt", b"changed").unwrap();
+        let mut archive = ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut read = |name: &str| {
+            let mut text = String::new();
+            archive
+                .by_name(name)
+                .unwrap()
+                .read_to_string(&mut text)
+                .unwrap();
+            text
+        };
+        assert_eq!(read("a.txt"), "one");
+        assert_eq!(read("b.txt"), "changed");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,161 @@
+//! Replacing files without ever leaving them half-written.
+//!
+//! The new contents go to a temporary file in the same directory, which is
+//! flushed to disk and then renamed over the target. A failure at any point
+//! leaves the original untouched. The temporary file has a random name and
+//! is created exclusively, so nothing planted under that name is followed,
+//! and it has its final permissions before any contents are written.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Atomically replaces the file at `path` with `contents`, keeping the
+/// permissions (and on Unix, where allowed, the owner) of the existing file.
+/// A symlink is followed so the link itself survives.
+pub fn write(path: &Path, contents: &[u8]) -> io::Result<()> {
+    replace(path, contents, false)
+}
+
+/// Like [`write`], for contents only the current user may read: the file
+/// ends up with mode 0600 whatever it had before.
+pub fn write_private(path: &Path, contents: &[u8]) -> io::Result<()> {
+    replace(path, contents, true)
+}
+
+fn replace(path: &Path, contents: &[u8], private: bool) -> io::Result<()> {
+    let target = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let name = target
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "not a file path"))?;
+    let tmp = target.with_file_name(format!(
+        ".{}.{}.tmp",
+        name.to_string_lossy(),
+        uuid::Uuid::new_v4().simple()
+    ));
+    let mut file = create_temp(&tmp, &target, private)?;
+    let result = file.write_all(contents).and_then(|()| file.sync_all());
+    drop(file);
+    let result = result.and_then(|()| fs::rename(&tmp, &target));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+        return result;
+    }
+    sync_dir(&target);
+    Ok(())
+}
+
+/// Creates the empty temporary file with the permissions the target will
+/// have: those of the existing file, 0600 when `private`, or the usual
+/// ones for a new file.
+fn create_temp(tmp: &Path, target: &Path, private: bool) -> io::Result<File> {
+    let existing = fs::metadata(target).ok();
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        // The umask may narrow this further; the exact mode is set below.
+        options.mode(match &existing {
+            _ if private => 0o600,
+            Some(metadata) => metadata.permissions().mode() & 0o7777,
+            None => 0o666,
+        });
+    }
+    let file = options.open(tmp)?;
+    if let Err(err) = set_permissions(&file, existing.as_ref(), private) {
+        let _ = fs::remove_file(tmp);
+        return Err(err);
+    }
+    Ok(file)
+}
+
+fn set_permissions(file: &File, existing: Option<&fs::Metadata>, private: bool) -> io::Result<()> {
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::PermissionsExt;
+        return file.set_permissions(fs::Permissions::from_mode(0o600));
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+    let Some(metadata) = existing else {
+        return Ok(());
+    };
+    file.set_permissions(metadata.permissions())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        // Only root can give a file away; for everyone else this fails
+        // harmlessly and the file ends up owned by the current user.
+        let _ = std::os::unix::fs::fchown(file, Some(metadata.uid()), Some(metadata.gid()));
+    }
+    Ok(())
+}
+
+/// Flushes the directory entry created by the rename.
+fn sync_dir(target: &Path) {
+    #[cfg(unix)]
+    if let Some(dir) = target.parent()
+        && let Ok(dir) = File::open(dir)
+    {
+        let _ = dir.sync_all();
+    }
+    #[cfg(not(unix))]
+    let _ = target;
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
+
+    fn scratch_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rusty-editor-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn mode(path: &Path) -> u32 {
+        fs::metadata(path).unwrap().permissions().mode() & 0o7777
+    }
+
+    #[test]
+    fn keeps_the_mode_of_the_existing_file() {
+        let dir = scratch_dir();
+        let path = dir.join("secret.txt");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+        write(&path, b"new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(mode(&path), 0o600);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn private_files_are_owner_only() {
+        let dir = scratch_dir();
+        let path = dir.join("journal.json");
+        write_private(&path, b"{}").unwrap();
+        assert_eq!(mode(&path), 0o600);
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        write_private(&path, b"{}").unwrap();
+        assert_eq!(mode(&path), 0o600);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn does_not_follow_a_planted_temp_file() {
+        let dir = scratch_dir();
+        let victim = dir.join("victim.txt");
+        fs::write(&victim, "keep").unwrap();
+        let path = dir.join("file.txt");
+        std::os::unix::fs::symlink(&victim, dir.join(".file.txt.tmp")).unwrap();
+        write(&path, b"contents").unwrap();
+        assert_eq!(fs::read_to_string(&victim).unwrap(), "keep");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "contents");
+        let leftovers = fs::read_dir(&dir).unwrap().count();
+        assert_eq!(leftovers, 3);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,222 @@
+//! Audit log of saves: when enabled in the settings, every file the editor
+//! writes (saving a tab, a merge result or a Replace in Files) is recorded
+//! with the time, its size, how many bytes changed and the SHA-1 of its
+//! content before and after. The log is a JSON line per save appended to
+//! `audit.log` in the data directory, and shown in the Audit Log panel.
+//!
+//! Saves of sensitive files are recorded without their hashes.
+
+use crate::TextEditorApp;
+use crate::i18n::{tr, trf};
+use crate::settings::data_dir;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use similar::{ChangeTag, TextDiff};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+const AUDIT_FILE: &str = "audit.log";
+
+fn audit_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join(AUDIT_FILE))
+}
+
+fn sha1_hex(bytes: &[u8]) -> String {
+    Sha1::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Bytes of the lines removed plus those of the lines added.
+fn changed_bytes(before: &[u8], after: &[u8]) -> usize {
+    let before = String::from_utf8_lossy(before);
+    let after = String::from_utf8_lossy(after);
+    TextDiff::from_lines(before.as_ref(), after.as_ref())
+        .iter_all_changes()
+        .filter(|change| change.tag() != ChangeTag::Equal)
+        .map(|change| change.value().len())
+        .sum()
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AuditEntry {
+    /// RFC 3339 local time of the save.
+    pub time: String,
+    pub path: PathBuf,
+    /// `None` when the file was created.
+    pub size_before: Option<usize>,
+    pub size_after: usize,
+    pub changed_bytes: usize,
+    #[serde(default)]
+    pub hash_before: Option<String>,
+    #[serde(default)]
+    pub hash_after: Option<String>,
+}
+
+/// A file as it was before a save, taken while saves are logged.
+pub struct Snapshot {
+    path: PathBuf,
+    before: Option<Vec<u8>>,
+    sensitive: bool,
+}
+
+impl Snapshot {
+    fn entry(&self, after: &[u8]) -> AuditEntry {
+        let hash = |bytes: &[u8]| (!self.sensitive).then(|| sha1_hex(bytes));
+        AuditEntry {
+            time: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+            path: self.path.clone(),
+            size_before: self.before.as_ref().map(Vec::len),
+            size_after: after.len(),
+            changed_bytes: changed_bytes(self.before.as_deref().unwrap_or_default(), after),
+            hash_before: self.before.as_deref().and_then(hash),
+            hash_after: hash(after),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct AuditLog {
+    /// The entries read from the log, read when the panel is first shown.
+    entries: Option<Vec<AuditEntry>>,
+    filter: String,
+}
+
+impl AuditLog {
+    fn append(&mut self, entry: AuditEntry) -> io::Result<()> {
+        let path = audit_path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut line = serde_json::to_string(&entry)?;
+        line.push('\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?
+            .write_all(line.as_bytes())?;
+        if let Some(entries) = &mut self.entries {
+            entries.push(entry);
+        }
+        Ok(())
+    }
+
+    fn entries(&mut self) -> &[AuditEntry] {
+        self.entries.get_or_insert_with(|| {
+            audit_path()
+                .and_then(|path| fs::read_to_string(path).ok())
+                .map(|log| {
+                    log.lines()
+                        .filter_map(|line| serde_json::from_str(line).ok())
+                        .collect()
+                })
+                .unwrap_or_default()
+        })
+    }
+}
+
+impl TextEditorApp {
+    /// Reads `path` before it is written, if saves are logged.
+    pub(crate) fn audit_snapshot(&self, path: &Path, sensitive: bool) -> Option<Snapshot> {
+        self.settings.audit_log.then(|| Snapshot {
+            path: path.to_path_buf(),
+            before: fs::read(path).ok(),
+            sensitive: sensitive || self.settings.is_sensitive(path),
+        })
+    }
+
+    /// Logs that the file of `snapshot` was written with `after`.
+    pub(crate) fn record_save(&mut self, snapshot: Option<Snapshot>, after: &[u8]) {
+        let Some(snapshot) = snapshot else {
+            return;
+        };
+        if let Err(err) = self.audit_log.append(snapshot.entry(after)) {
+            self.notifications.error(trf(
+                "Could not write to the audit log: {error}",
+                &[("error", &err)],
+            ));
+        }
+    }
+
+    pub(crate) fn audit_log_ui(&mut self, ui: &mut egui::Ui) {
+        let enabled = self.settings.audit_log;
+        let log = &mut self.audit_log;
+        let count = log.entries().len();
+        ui.horizontal(|ui| {
+            ui.label(trf("{count} saves logged", &[("count", &count)]));
+            if !enabled {
+                ui.weak(tr("(logging is off, see Preferences)"));
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button(tr("Reload")).clicked() {
+                    log.entries = None;
+                }
+                ui.add(
+                    egui::TextEdit::singleline(&mut log.filter)
+                        .hint_text(tr("Filter by path"))
+                        .desired_width(200.0),
+                );
+            });
+        });
+        ui.separator();
+        let filter = log.filter.to_lowercase();
+        let entries = log.entries();
+        let mut open = None;
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                egui::Grid::new("audit_log")
+                    .striped(true)
+                    .num_columns(5)
+                    .show(ui, |ui| {
+                        ui.strong(tr("Time"));
+                        ui.strong(tr("File"));
+                        ui.strong(tr("Size"));
+                        ui.strong(tr("Changed"));
+                        ui.strong(tr("SHA-1 before → after"));
+                        ui.end_row();
+                        for entry in entries.iter().rev().filter(|entry| {
+                            entry
+                                .path
+                                .to_string_lossy()
+                                .to_lowercase()
+                                .contains(&filter)
+                        }) {
+                            ui.weak(&entry.time);
+                            if ui.link(entry.path.display().to_string()).clicked() {
+                                open = Some(entry.path.clone());
+                            }
+                            let size = match entry.size_before {
+                                Some(before) => format!("{before} → {}", entry.size_after),
+                                None => format!("{} ({})", entry.size_after, tr("new")),
+                            };
+                            ui.label(size);
+                            ui.label(entry.changed_bytes.to_string());
+                            let short = |hash: &Option<String>| {
+                                hash.as_deref().map_or("-".to_string(), |h| {
+                                    h.get(..12).unwrap_or(h).to_string()
+                                })
+                            };
+                            ui.monospace(format!(
+                                "{} → {}",
+                                short(&entry.hash_before),
+                                short(&entry.hash_after)
+                            ))
+                            .on_hover_text(format!(
+                                "{}\n{}",
+                                entry.hash_before.as_deref().unwrap_or("-"),
+                                entry.hash_after.as_deref().unwrap_or("-")
+                            ));
+                            ui.end_row();
+                        }
+                    });
+            });
+        if let Some(path) = open {
+            self.activate_or_open(&path);
+        }
+    }
+}
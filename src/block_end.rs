@@ -0,0 +1,167 @@
+//! Closing keywords for languages whose blocks end in a word rather than a
+//! bracket: Enter at the end of a line opening a block in Ruby, Lua or
+//! shell scripts adds the `end`, `fi`, `done` or `esac` below and leaves
+//! the caret on an indented line in between.
+//!
+//! A block counts as closed already when the next line is indented deeper
+//! or is the closing keyword, so Enter on an existing opener only breaks
+//! the line.
+
+use crate::TextEditorApp;
+use crate::editing::{byte_to_char, char_to_byte};
+use crate::motion::take_key;
+use eframe::egui;
+
+/// A kind of line opening a block and the keyword closing it. The line
+/// must start with one of `starts` and end with one of `ends`; an empty
+/// list matches any line.
+struct Rule {
+    syntaxes: &'static [&'static str],
+    starts: &'static [&'static str],
+    ends: &'static [&'static str],
+    closer: &'static str,
+}
+
+const SHELL: &[&str] = &["Bourne Again Shell (bash)", "Shell-Unix-Generic"];
+
+const RULES: &[Rule] = &[
+    Rule {
+        syntaxes: &["Ruby"],
+        starts: &[
+            "def", "class", "module", "if", "unless", "while", "until", "case", "begin", "for",
+        ],
+        ends: &[],
+        closer: "end",
+    },
+    Rule {
+        syntaxes: &["Ruby"],
+        starts: &[],
+        ends: &["do"],
+        closer: "end",
+    },
+    Rule {
+        syntaxes: &["Lua"],
+        starts: &["function", "local function"],
+        ends: &[],
+        closer: "end",
+    },
+    Rule {
+        syntaxes: &["Lua"],
+        starts: &["if", "for", "while", "do"],
+        ends: &["then", "do"],
+        closer: "end",
+    },
+    Rule {
+        syntaxes: SHELL,
+        starts: &["if"],
+        ends: &["then"],
+        closer: "fi",
+    },
+    Rule {
+        syntaxes: SHELL,
+        starts: &["for", "while", "until", "select"],
+        ends: &["do"],
+        closer: "done",
+    },
+    Rule {
+        syntaxes: SHELL,
+        starts: &["case"],
+        ends: &["in"],
+        closer: "esac",
+    },
+];
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn starts_with_word(line: &str, word: &str) -> bool {
+    line.strip_prefix(word)
+        .is_some_and(|rest| !rest.starts_with(is_word_char))
+}
+
+fn ends_with_word(line: &str, word: &str) -> bool {
+    line.strip_suffix(word)
+        .is_some_and(|rest| !rest.ends_with(is_word_char))
+}
+
+/// The keyword closing the block `line` opens in `syntax`, if it opens one
+/// that is not closed on the same line.
+pub fn closer_for(syntax: &str, line: &str) -> Option<&'static str> {
+    let mut line = line.trim();
+    // Ruby block parameters: `items.each do |item|`.
+    if let Some(inner) = line.strip_suffix('|')
+        && let Some(open) = inner.rfind('|')
+    {
+        line = inner[..open].trim_end();
+    }
+    RULES
+        .iter()
+        .filter(|rule| rule.syntaxes.contains(&syntax))
+        .find(|rule| {
+            (rule.starts.is_empty() || rule.starts.iter().any(|w| starts_with_word(line, w)))
+                && (rule.ends.is_empty() || rule.ends.iter().any(|w| ends_with_word(line, w)))
+        })
+        .map(|rule| rule.closer)
+        .filter(|closer| !ends_with_word(line, closer))
+}
+
+fn indent_of(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+impl TextEditorApp {
+    /// Closes the block opened on the caret's line when Enter is pressed at
+    /// its end.
+    pub(crate) fn handle_block_end(&mut self, ctx: &egui::Context) {
+        if !self.settings.close_blocks || !self.editor_has_focus(ctx) {
+            return;
+        }
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        let Some(syntax) = tab.syntax.as_deref().filter(|_| !tab.read_only) else {
+            return;
+        };
+        let Some(selection) = self.selection(ctx).filter(|s| s.is_empty()) else {
+            return;
+        };
+        let text = &tab.content;
+        let caret = char_to_byte(text, selection.start);
+        let line_start = text[..caret].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = text[caret..].find('\n').map_or(text.len(), |i| caret + i);
+        if !text[caret..line_end].trim().is_empty() {
+            return;
+        }
+        let line = &text[line_start..caret];
+        let Some(closer) = closer_for(syntax, line) else {
+            return;
+        };
+        let indent = indent_of(line);
+        if let Some(next) = text[line_end..]
+            .lines()
+            .skip(1)
+            .find(|l| !l.trim().is_empty())
+            && (indent_of(next).len() > indent.len()
+                || (indent_of(next) == indent && starts_with_word(next.trim(), closer)))
+        {
+            return;
+        }
+        if take_key(ctx, egui::Key::Enter, |m| m.is_none()).is_none() {
+            return;
+        }
+        let options = tab.editor_options(&self.settings);
+        let unit = if options.insert_spaces {
+            " ".repeat(options.tab_width)
+        } else {
+            "\t".to_string()
+        };
+        let body = format!("\n{indent}{unit}");
+        let inserted = format!("{body}\n{indent}{closer}");
+        self.replace_range(ctx, caret..line_end, &inserted);
+        if let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) {
+            let target = byte_to_char(&tab.content, caret + body.len());
+            self.set_selection(ctx, target..target);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! The tabbed panel below the editor holding Problems, program Output, the
+//! interactive Console, Find in Files results, the notification log and the
+//! audit log of saves.
+
+use crate::TextEditorApp;
+use crate::i18n::tr;
+use crate::profiler;
+use eframe::egui;
+
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum BottomTab {
+    #[default]
+    Problems,
+    Output,
+    Console,
+    Search,
+    Backlinks,
+    Notifications,
+    AuditLog,
+}
+
+#[derive(Default)]
+pub struct BottomPanel {
+    pub open: bool,
+    pub tab: BottomTab,
+}
+
+impl BottomPanel {
+    pub fn show(&mut self, tab: BottomTab) {
+        self.open = true;
+        self.tab = tab;
+    }
+
+    /// Shows `tab`, or closes the panel if `tab` is already showing.
+    pub fn toggle(&mut self, tab: BottomTab) {
+        if self.open && self.tab == tab {
+            self.open = false;
+        } else {
+            self.show(tab);
+        }
+    }
+}
+
+impl TextEditorApp {
+    pub(crate) fn show_bottom_panel(&mut self, ctx: &egui::Context) {
+        let _scope = profiler::scope("bottom panel");
+        self.poll_problems();
+        self.poll_run_output();
+        self.console.poll();
+        self.poll_project_search();
+        self.poll_backlinks();
+        if !self.bottom_panel.open {
+            return;
+        }
+        egui::TopBottomPanel::bottom("bottom_panel")
+            .resizable(true)
+            .default_height(180.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let panel = &mut self.bottom_panel;
+                    ui.selectable_value(&mut panel.tab, BottomTab::Problems, tr("Problems"));
+                    ui.selectable_value(&mut panel.tab, BottomTab::Output, tr("Output"));
+                    ui.selectable_value(&mut panel.tab, BottomTab::Console, tr("Console"));
+                    ui.selectable_value(&mut panel.tab, BottomTab::Search, tr("Search"));
+                    ui.selectable_value(&mut panel.tab, BottomTab::Backlinks, tr("Backlinks"));
+                    ui.selectable_value(
+                        &mut panel.tab,
+                        BottomTab::Notifications,
+                        tr("Notifications"),
+                    );
+                    ui.selectable_value(&mut panel.tab, BottomTab::AuditLog, tr("Audit Log"));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("×").on_hover_text(tr("Close")).clicked() {
+                            panel.open = false;
+                        }
+                    });
+                });
+                ui.separator();
+                match self.bottom_panel.tab {
+                    BottomTab::Problems => self.problems_ui(ui),
+                    BottomTab::Output => self.run_output_ui(ui),
+                    BottomTab::Console => self.console_ui(ui),
+                    BottomTab::Search => self.project_search_ui(ui),
+                    BottomTab::Backlinks => self.backlinks_ui(ui),
+                    BottomTab::Notifications => self.notifications_ui(ui),
+                    BottomTab::AuditLog => self.audit_log_ui(ui),
+                }
+            });
+    }
+}
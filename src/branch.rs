@@ -0,0 +1,179 @@
+//! The current git branch in the status bar, refreshed in the background,
+//! and the branch picker used to check out or create branches.
+
+use crate::TextEditorApp;
+use crate::git::{self, BranchStatus};
+use crate::i18n::{tr, trf};
+use crate::picker::{Picker, PickerItem};
+use eframe::egui;
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, channel};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Clone)]
+enum BranchAction {
+    Checkout(String),
+    Create(String),
+}
+
+#[derive(Default)]
+pub struct BranchIndicator {
+    /// Repository root and branch of the last refresh.
+    current: Option<(PathBuf, BranchStatus)>,
+    pending: Option<Receiver<Option<(PathBuf, BranchStatus)>>>,
+    last_refresh: Option<Instant>,
+    /// The directory the last refresh looked at.
+    base: Option<PathBuf>,
+    pub picker: Picker,
+    /// Local branches, listed when the picker opens.
+    branches: Vec<String>,
+    /// The checkout running in the background.
+    checkout: Option<Receiver<Result<String, String>>>,
+}
+
+impl BranchIndicator {
+    /// Starts a refresh for the repository containing `base` when the
+    /// interval has passed or `base` changed, and picks up finished ones.
+    fn update(&mut self, ctx: &egui::Context, base: Option<PathBuf>, interval: Duration) {
+        if let Some(pending) = &self.pending
+            && let Ok(result) = pending.try_recv()
+        {
+            self.current = result;
+            self.pending = None;
+        }
+        let Some(dir) = base.clone() else {
+            self.current = None;
+            self.base = None;
+            return;
+        };
+        let due = self.last_refresh.is_none_or(|at| at.elapsed() >= interval);
+        if self.pending.is_none() && (due || self.base != base) {
+            self.base = base;
+            self.last_refresh = Some(Instant::now());
+            let (sender, receiver) = channel();
+            let ctx = ctx.clone();
+            thread::spawn(move || {
+                let result = git::repo_root(&dir)
+                    .and_then(|root| git::branch_status(&root).map(|status| (root, status)));
+                let _ = sender.send(result);
+                ctx.request_repaint();
+            });
+            self.pending = Some(receiver);
+        }
+        ctx.request_repaint_after(interval);
+    }
+
+    fn refresh_now(&mut self) {
+        self.last_refresh = None;
+    }
+}
+
+impl TextEditorApp {
+    /// The folder or file whose repository the status bar reports on.
+    fn branch_base(&self) -> Option<PathBuf> {
+        self.folder_path().or_else(|| {
+            self.active_tab
+                .as_ref()
+                .and_then(|key| self.tabs.get(key))
+                .and_then(|tab| tab.path.as_ref())
+                .and_then(|path| path.parent())
+                .map(|dir| dir.to_path_buf())
+        })
+    }
+
+    /// Status bar entry with the branch and ahead/behind counts; opens the
+    /// branch picker.
+    pub(crate) fn branch_button(&mut self, ui: &mut egui::Ui) {
+        let base = self.branch_base().filter(|base| self.is_trusted(base));
+        let interval = self.poll_interval(REFRESH_INTERVAL);
+        let branch = &mut self.branch;
+        branch.update(ui.ctx(), base, interval);
+        let Some((root, status)) = &branch.current else {
+            return;
+        };
+        let mut text = format!("⎇ {}", status.head);
+        if status.ahead > 0 {
+            text += &format!(" ↑{}", status.ahead);
+        }
+        if status.behind > 0 {
+            text += &format!(" ↓{}", status.behind);
+        }
+        if ui
+            .add(egui::Button::new(text).frame(false))
+            .on_hover_text(tr("Check out branch"))
+            .clicked()
+        {
+            branch.branches = git::local_branches(root);
+            branch.picker = Picker::opened();
+        }
+        ui.separator();
+    }
+
+    pub(crate) fn show_branch_picker(&mut self, ctx: &egui::Context) {
+        if let Some(receiver) = &self.branch.checkout
+            && let Ok(result) = receiver.try_recv()
+        {
+            self.branch.checkout = None;
+            if let Err(message) = result {
+                self.notifications.error(trf(
+                    "Checkout failed: {error}",
+                    &[("error", &message.trim())],
+                ));
+            }
+            self.branch.refresh_now();
+        }
+        let branch = &mut self.branch;
+        if !branch.picker.open {
+            return;
+        }
+        let Some((root, status)) = branch.current.clone() else {
+            branch.picker.open = false;
+            return;
+        };
+        let mut items: Vec<PickerItem<BranchAction>> = branch
+            .branches
+            .iter()
+            .cloned()
+            .map(|name| PickerItem {
+                detail: if name == status.head {
+                    "current".to_string()
+                } else {
+                    String::new()
+                },
+                label: name.clone(),
+                value: BranchAction::Checkout(name),
+            })
+            .collect();
+        let query = branch.picker.query().trim().to_string();
+        if !query.is_empty() && !items.iter().any(|item| item.label == query) {
+            items.push(PickerItem {
+                value: BranchAction::Create(query.clone()),
+                label: trf("Create branch {name}", &[("name", &query)]),
+                detail: trf("from {branch}", &[("branch", &status.head)]),
+            });
+        }
+        let Some(action) = branch.picker.show(ctx, &tr("Check out branch"), items) else {
+            return;
+        };
+        let title = match &action {
+            BranchAction::Checkout(name) => trf("Checking out {name}", &[("name", name)]),
+            BranchAction::Create(name) => trf("Creating branch {name}", &[("name", name)]),
+        };
+        let job = self.jobs.start(title, false);
+        let (sender, receiver) = channel();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let _job = job;
+            let result = match &action {
+                BranchAction::Checkout(name) => git::run_checked(&root, &["checkout", name]),
+                BranchAction::Create(name) => git::run_checked(&root, &["checkout", "-b", name]),
+            };
+            let _ = sender.send(result);
+            ctx.request_repaint();
+        });
+        branch.checkout = Some(receiver);
+    }
+}
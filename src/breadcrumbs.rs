@@ -0,0 +1,85 @@
+//! The breadcrumb bar above the editor: the active file's path, one button
+//! per folder. A folder's button drops down its contents, with subfolders
+//! as submenus; the file's own button lists the files next to it.
+
+use crate::TextEditorApp;
+use crate::i18n::tr;
+use crate::project::list_dir;
+use eframe::egui;
+use std::path::{Path, PathBuf};
+
+/// The dropdown of `dir`; picking a file sets `picked`.
+fn directory_menu(ui: &mut egui::Ui, dir: &Path, current: &Path, picked: &mut Option<PathBuf>) {
+    let entries = list_dir(dir);
+    if entries.is_empty() {
+        ui.weak(tr("Empty folder"));
+        return;
+    }
+    egui::ScrollArea::vertical()
+        .max_height(400.0)
+        .show(ui, |ui| {
+            for entry in entries {
+                let name = entry
+                    .path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned();
+                if entry.is_dir {
+                    ui.menu_button(format!("{name}/"), |ui| {
+                        directory_menu(ui, &entry.path, current, picked);
+                    });
+                } else if ui.selectable_label(entry.path == current, name).clicked() {
+                    *picked = Some(entry.path);
+                    ui.close_menu();
+                }
+            }
+        });
+}
+
+impl TextEditorApp {
+    pub(crate) fn breadcrumb_bar(&mut self, ui: &mut egui::Ui) {
+        if !self.settings.breadcrumbs {
+            return;
+        }
+        let Some(path) = self
+            .active_tab
+            .as_ref()
+            .and_then(|key| self.tabs[key].path.clone())
+        else {
+            return;
+        };
+        // Inside a workspace folder the bar starts at that folder, elsewhere
+        // at the top of the file system.
+        let top = self.folders.iter().find(|root| path.starts_with(root));
+        let mut dirs: Vec<&Path> = path
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.file_name().is_some())
+            .collect();
+        if let Some(top) = top {
+            dirs.retain(|dir| dir.starts_with(top));
+        }
+        dirs.reverse();
+        let mut picked = None;
+        ui.horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = 2.0;
+            for dir in &dirs {
+                let name = dir.file_name().unwrap_or_default().to_string_lossy();
+                ui.menu_button(name.as_ref(), |ui| {
+                    directory_menu(ui, dir, &path, &mut picked)
+                });
+                ui.weak("›");
+            }
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            if let Some(parent) = path.parent() {
+                ui.menu_button(name.as_ref(), |ui| {
+                    directory_menu(ui, parent, &path, &mut picked)
+                });
+            }
+        });
+        if let Some(path) = picked {
+            self.activate_or_open(&path);
+        }
+    }
+}
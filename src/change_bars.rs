@@ -0,0 +1,170 @@
+//! Change bars: a thin gutter left of the text marks lines added, changed
+//! or removed since the tab was last loaded or saved, whatever git thinks
+//! of them. Clicking a bar puts that one hunk back the way it was saved.
+
+use crate::TextEditorApp;
+use crate::editing::byte_to_char;
+use crate::i18n::tr;
+use crate::journal::content_hash;
+use eframe::egui;
+use egui::text::CCursor;
+use egui::text_edit::TextEditOutput;
+use similar::{DiffTag, TextDiff};
+use std::ops::Range;
+
+/// Width reserved left of the text for the bars.
+pub const GUTTER_WIDTH: f32 = 6.0;
+
+/// A run of lines differing from the saved text, as 0-based line ranges
+/// into the saved and the current text.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Hunk {
+    pub saved: Range<usize>,
+    pub current: Range<usize>,
+}
+
+/// The hunks turning `saved` into `current`.
+pub fn hunks(saved: &str, current: &str) -> Vec<Hunk> {
+    let diff = TextDiff::from_lines(saved, current);
+    let mut hunks: Vec<Hunk> = Vec::new();
+    for op in diff.ops() {
+        if op.tag() == DiffTag::Equal {
+            continue;
+        }
+        let (saved, current) = (op.old_range(), op.new_range());
+        // A deletion right before an insertion is one changed hunk.
+        match hunks.last_mut() {
+            Some(last) if last.saved.end == saved.start && last.current.end == current.start => {
+                last.saved.end = saved.end;
+                last.current.end = current.end;
+            }
+            _ => hunks.push(Hunk { saved, current }),
+        }
+    }
+    hunks
+}
+
+/// Byte range of the 0-based `lines` of `text`, newlines included.
+pub fn line_bytes(text: &str, lines: &Range<usize>) -> Range<usize> {
+    let offset = |line: usize| {
+        text.split_inclusive('\n')
+            .take(line)
+            .map(str::len)
+            .sum::<usize>()
+    };
+    offset(lines.start)..offset(lines.end)
+}
+
+#[derive(Default)]
+pub struct ChangeBars {
+    /// The hunks last computed, with the tab and the hashes of the saved
+    /// and current text they were computed from.
+    cache: Option<(String, u64, u64, Vec<Hunk>)>,
+}
+
+impl ChangeBars {
+    fn hunks(&mut self, tab_key: &str, saved: &str, current: &str) -> &[Hunk] {
+        let (saved_hash, current_hash) = (content_hash(saved), content_hash(current));
+        let fresh = matches!(&self.cache, Some((key, s, c, _))
+            if key == tab_key && *s == saved_hash && *c == current_hash);
+        if !fresh {
+            self.cache = Some((
+                tab_key.to_string(),
+                saved_hash,
+                current_hash,
+                hunks(saved, current),
+            ));
+        }
+        self.cache.as_ref().map_or(&[], |(.., hunks)| hunks)
+    }
+
+    /// Draws the bars of the editor of `tab_key` into the gutter ending at
+    /// `right`, and returns the hunk whose bar was clicked.
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        tab_key: &str,
+        saved: &str,
+        text: &str,
+        output: &TextEditOutput,
+        right: f32,
+    ) -> Option<Hunk> {
+        let clip = ui.clip_rect();
+        let dark_mode = ui.visuals().dark_mode;
+        let (added, changed, removed) = if dark_mode {
+            (
+                egui::Color32::from_rgb(80, 160, 90),
+                egui::Color32::from_rgb(80, 130, 200),
+                egui::Color32::from_rgb(200, 80, 80),
+            )
+        } else {
+            (
+                egui::Color32::from_rgb(60, 150, 70),
+                egui::Color32::from_rgb(50, 110, 190),
+                egui::Color32::from_rgb(200, 60, 60),
+            )
+        };
+        let x_range = egui::Rangef::new(right - GUTTER_WIDTH + 1.0, right - 1.0);
+        let pos_at = |byte: usize| {
+            output
+                .galley
+                .pos_from_ccursor(CCursor::new(byte_to_char(text, byte)))
+                .translate(output.galley_pos.to_vec2())
+        };
+        let mut clicked = None;
+        for hunk in self.hunks(tab_key, saved, text) {
+            let bytes = line_bytes(text, &hunk.current);
+            let (y_range, color) = if hunk.current.is_empty() {
+                // Removed lines: a short mark on the boundary where they were.
+                let y = pos_at(bytes.start).top();
+                (egui::Rangef::new(y - 2.0, y + 2.0), removed)
+            } else {
+                let last = text[bytes.clone()]
+                    .strip_suffix('\n')
+                    .map_or(bytes.end, |line| bytes.start + line.len());
+                let y_range = egui::Rangef::new(pos_at(bytes.start).top(), pos_at(last).bottom());
+                let color = if hunk.saved.is_empty() {
+                    added
+                } else {
+                    changed
+                };
+                (y_range, color)
+            };
+            if !clip.y_range().intersects(y_range) {
+                continue;
+            }
+            let rect = egui::Rect::from_x_y_ranges(x_range, y_range);
+            let response = ui
+                .interact(
+                    rect,
+                    egui::Id::new(("change_bar", tab_key, hunk.current.start)),
+                    egui::Sense::click(),
+                )
+                .on_hover_cursor(egui::CursorIcon::PointingHand)
+                .on_hover_text(tr("Click to revert this change"));
+            ui.painter().rect_filled(rect, 1.0, color);
+            if response.clicked() {
+                clicked = Some(hunk.clone());
+            }
+        }
+        clicked
+    }
+}
+
+impl TextEditorApp {
+    /// Puts the lines of `hunk` in the active tab back the way they were
+    /// last saved.
+    pub(crate) fn revert_hunk(&mut self, ctx: &egui::Context, hunk: &Hunk) {
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        let Some(saved) = &tab.saved_content else {
+            return;
+        };
+        let original = saved[line_bytes(saved, &hunk.saved)].to_string();
+        let range = line_bytes(&tab.content, &hunk.current);
+        let caret = byte_to_char(&tab.content, range.start);
+        self.replace_range(ctx, range, &original);
+        self.set_selection(ctx, caret..caret);
+    }
+}
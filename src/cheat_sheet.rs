@@ -0,0 +1,213 @@
+//! The keyboard shortcut cheat sheet (Help > Keyboard Shortcuts): every
+//! command with a shortcut, grouped by menu and searchable, exported as a
+//! Markdown or printable HTML page. It is built from the command registry,
+//! so it lists exactly the shortcuts the editor responds to.
+
+use crate::commands::{CATEGORIES, Command};
+use crate::i18n::{tr, trf};
+use crate::platform;
+use crate::transform::html_escape;
+use crate::{TextEditorApp, atomic_write};
+use eframe::egui;
+
+struct Entry {
+    category: &'static str,
+    name: String,
+    keys: String,
+}
+
+#[derive(Default)]
+pub struct CheatSheet {
+    pub open: bool,
+    filter: String,
+    /// Also list the commands without a shortcut.
+    all_commands: bool,
+}
+
+/// The commands to list by category, in menu bar order, keeping those
+/// whose name or shortcut contains `filter`.
+fn entries(ctx: &egui::Context, all_commands: bool, filter: &str) -> Vec<Entry> {
+    let filter = filter.to_lowercase();
+    let commands = Command::all();
+    CATEGORIES
+        .iter()
+        .flat_map(|category| {
+            commands
+                .iter()
+                .filter(move |command| command.category() == *category)
+                .filter_map(|command| {
+                    let keys = command.shortcut().map(|s| ctx.format_shortcut(&s));
+                    (keys.is_some() || all_commands).then(|| Entry {
+                        category,
+                        name: command.name(),
+                        keys: keys.unwrap_or_default(),
+                    })
+                })
+        })
+        .filter(|entry| {
+            entry.name.to_lowercase().contains(&filter)
+                || entry.keys.to_lowercase().contains(&filter)
+        })
+        .collect()
+}
+
+fn markdown(entries: &[Entry]) -> String {
+    let mut out = format!("# {}\n", tr("Keyboard Shortcuts"));
+    let mut category = "";
+    for entry in entries {
+        if entry.category != category {
+            category = entry.category;
+            out.push_str(&format!(
+                "\n## {}\n\n| {} | {} |\n|---|---|\n",
+                tr(category),
+                tr("Command"),
+                tr("Shortcut")
+            ));
+        }
+        let keys = if entry.keys.is_empty() {
+            String::new()
+        } else {
+            format!("`{}`", entry.keys)
+        };
+        out.push_str(&format!(
+            "| {} | {keys} |\n",
+            entry.name.replace('|', "\\|")
+        ));
+    }
+    out
+}
+
+fn html(entries: &[Entry]) -> String {
+    let title = html_escape(&tr("Keyboard Shortcuts"));
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n\
+         body {{ font-family: sans-serif; columns: 2; }}\n\
+         section {{ break-inside: avoid; }}\n\
+         table {{ border-collapse: collapse; width: 100%; }}\n\
+         td {{ padding: 2px 8px; border-bottom: 1px solid #ddd; }}\n\
+         kbd {{ font-family: monospace; white-space: nowrap; }}\n\
+         </style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+    );
+    let mut category = "";
+    for entry in entries {
+        if entry.category != category {
+            if !category.is_empty() {
+                out.push_str("</table>\n</section>\n");
+            }
+            category = entry.category;
+            out.push_str(&format!(
+                "<section>\n<h2>{}</h2>\n<table>\n",
+                html_escape(&tr(category))
+            ));
+        }
+        out.push_str(&format!(
+            "<tr><td>{}</td><td><kbd>{}</kbd></td></tr>\n",
+            html_escape(&entry.name),
+            html_escape(&entry.keys)
+        ));
+    }
+    if !category.is_empty() {
+        out.push_str("</table>\n</section>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+impl TextEditorApp {
+    fn export_cheat_sheet(&mut self, entries: &[Entry], html_page: bool) {
+        let (name, filter, extension) = if html_page {
+            ("keyboard-shortcuts.html", tr("HTML page"), "html")
+        } else {
+            ("keyboard-shortcuts.md", tr("Markdown"), "md")
+        };
+        let Some(target) = platform::file_dialog()
+            .set_file_name(name)
+            .add_filter(filter, &[extension])
+            .save_file()
+        else {
+            return;
+        };
+        let text = if html_page {
+            html(entries)
+        } else {
+            markdown(entries)
+        };
+        match atomic_write::write(&target, text.as_bytes()) {
+            Ok(()) => self.notifications.info(trf(
+                "Shortcuts exported to {path}",
+                &[("path", &target.display())],
+            )),
+            Err(err) => self.notifications.error(trf(
+                "Could not export the shortcuts: {error}",
+                &[("error", &err)],
+            )),
+        }
+    }
+
+    pub(crate) fn show_cheat_sheet(&mut self, ctx: &egui::Context) {
+        if !self.cheat_sheet.open {
+            return;
+        }
+        let sheet = &mut self.cheat_sheet;
+        let entries = entries(ctx, sheet.all_commands, &sheet.filter);
+        let mut open = true;
+        let mut export = None;
+        egui::Window::new(tr("Keyboard Shortcuts"))
+            .default_size([420.0, 480.0])
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut sheet.filter)
+                            .hint_text(tr("Search commands or keys"))
+                            .desired_width(200.0),
+                    );
+                    ui.checkbox(&mut sheet.all_commands, tr("Commands without a shortcut"));
+                });
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        if entries.is_empty() {
+                            ui.weak(tr("No matching shortcuts"));
+                        }
+                        for category in CATEGORIES {
+                            let group: Vec<&Entry> = entries
+                                .iter()
+                                .filter(|entry| entry.category == *category)
+                                .collect();
+                            if group.is_empty() {
+                                continue;
+                            }
+                            ui.strong(tr(category));
+                            egui::Grid::new(("cheat_sheet", *category))
+                                .num_columns(2)
+                                .striped(true)
+                                .min_col_width(200.0)
+                                .show(ui, |ui| {
+                                    for entry in group {
+                                        ui.label(&entry.name);
+                                        ui.monospace(&entry.keys);
+                                        ui.end_row();
+                                    }
+                                });
+                            ui.add_space(6.0);
+                        }
+                    });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Export Markdown…")).clicked() {
+                        export = Some(false);
+                    }
+                    if ui.button(tr("Export HTML…")).clicked() {
+                        export = Some(true);
+                    }
+                });
+            });
+        self.cheat_sheet.open = open;
+        if let Some(html_page) = export {
+            self.export_cheat_sheet(&entries, html_page);
+        }
+    }
+}
@@ -0,0 +1,320 @@
+//! Named checkpoints: snapshots of a buffer taken on request ("before
+//! refactor") that the buffer can later be put back to. Going back to a
+//! checkpoint is a preview until kept, so the text it replaced is not lost
+//! by looking; going back keeps edits made during the preview rather than
+//! the replaced text. Checkpoints live as long as their tab.
+
+use crate::diff::DiffView;
+use crate::i18n::{tr, trf};
+use crate::{FileTab, TextEditorApp};
+use chrono::{DateTime, Local};
+use eframe::egui;
+use std::collections::HashMap;
+
+struct Checkpoint {
+    name: String,
+    content: String,
+    time: DateTime<Local>,
+}
+
+/// The tab and checkpoint being previewed, the text it replaced and the
+/// text it shows, to notice edits made during the preview.
+struct Preview {
+    tab: String,
+    index: usize,
+    replaced: String,
+    shown: String,
+}
+
+#[derive(Default)]
+pub struct Checkpoints {
+    pub open: bool,
+    /// Per tab key, oldest first.
+    by_tab: HashMap<String, Vec<Checkpoint>>,
+    name: String,
+    preview: Option<Preview>,
+}
+
+impl Checkpoints {
+    /// Forgets the checkpoints of a closed tab.
+    pub fn remove_tab(&mut self, key: &str) {
+        self.by_tab.remove(key);
+        if self.preview.as_ref().is_some_and(|p| p.tab == key) {
+            self.preview = None;
+        }
+    }
+
+    /// Ends the preview. Unless `keep`, its tab gets back the text the
+    /// preview replaced if it was not edited since. Returns the tab and
+    /// whether its text was put back.
+    fn end_preview(
+        &mut self,
+        tabs: &mut HashMap<String, FileTab>,
+        keep: bool,
+    ) -> Option<(String, bool)> {
+        let preview = self.preview.take()?;
+        let Some(tab) = tabs.get_mut(&preview.tab).filter(|_| !keep) else {
+            return Some((preview.tab, false));
+        };
+        let restored = tab.content == preview.shown;
+        if restored {
+            tab.content = preview.replaced;
+        }
+        Some((preview.tab, restored))
+    }
+}
+
+impl TextEditorApp {
+    fn create_checkpoint(&mut self) {
+        let Some(key) = self.active_tab.clone() else {
+            return;
+        };
+        let checkpoints = self.checkpoints.by_tab.entry(key.clone()).or_default();
+        let name = match self.checkpoints.name.trim() {
+            "" => trf(
+                "Checkpoint {number}",
+                &[("number", &(checkpoints.len() + 1))],
+            ),
+            name => name.to_string(),
+        };
+        checkpoints.push(Checkpoint {
+            name,
+            content: self.tabs[&key].content.clone(),
+            time: Local::now(),
+        });
+        self.checkpoints.name.clear();
+    }
+
+    /// Replaces the active tab's text with checkpoint `index`, keeping the
+    /// current text until the preview is kept or dropped.
+    fn preview_checkpoint(&mut self, ctx: &egui::Context, index: usize) {
+        let Some(key) = self.active_tab.clone() else {
+            return;
+        };
+        let Some(content) = self
+            .checkpoints
+            .by_tab
+            .get(&key)
+            .and_then(|list| list.get(index))
+            .map(|checkpoint| checkpoint.content.clone())
+        else {
+            return;
+        };
+        // Moving on to another checkpoint still goes back to the text
+        // before the first, unless that preview was edited.
+        let current = &self.tabs[&key].content;
+        let replaced = match self.checkpoints.preview.take() {
+            Some(preview) if preview.tab == key && preview.shown == *current => preview.replaced,
+            _ => current.clone(),
+        };
+        let len = current.len();
+        self.replace_range(ctx, 0..len, &content);
+        self.set_selection(ctx, 0..0);
+        self.checkpoints.preview = Some(Preview {
+            shown: self.tabs[&key].content.clone(),
+            tab: key,
+            index,
+            replaced,
+        });
+    }
+
+    /// Ends the preview, putting back the text it replaced unless `keep`.
+    fn end_preview(&mut self, ctx: &egui::Context, keep: bool) {
+        let Some((tab, restored)) = self.checkpoints.end_preview(&mut self.tabs, keep) else {
+            return;
+        };
+        if restored && self.active_tab.as_ref() == Some(&tab) {
+            self.set_selection(ctx, 0..0);
+        } else if !keep && !restored {
+            self.notifications.info(trf(
+                "{name} was edited during the checkpoint preview; the edits were kept",
+                &[("name", &tab)],
+            ));
+        }
+    }
+
+    pub(crate) fn show_checkpoints_window(&mut self, ctx: &egui::Context) {
+        if !self.checkpoints.open {
+            return;
+        }
+        // A preview belongs to the tab it was started in; switching to
+        // another tab goes back.
+        if let Some(preview) = &self.checkpoints.preview
+            && self.active_tab.as_ref() != Some(&preview.tab)
+        {
+            self.end_preview(ctx, false);
+        }
+        let key = self.active_tab.clone();
+        let mut open = true;
+        let mut create = false;
+        let mut compare = None;
+        let mut revert = None;
+        let mut delete = None;
+        let mut end_preview = None;
+        egui::Window::new(tr("Checkpoints"))
+            .default_size((420.0, 300.0))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let Some(key) = &key else {
+                    ui.label(tr("No file is open."));
+                    return;
+                };
+                ui.horizontal(|ui| {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut self.checkpoints.name)
+                            .hint_text(tr("Name, e.g. before refactor"))
+                            .desired_width(220.0),
+                    );
+                    create |=
+                        response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    create |= ui.button(tr("Create Checkpoint")).clicked();
+                });
+                let list = self
+                    .checkpoints
+                    .by_tab
+                    .get(key)
+                    .map_or(&[][..], Vec::as_slice);
+                if let Some(preview) = &self.checkpoints.preview {
+                    ui.separator();
+                    ui.label(trf(
+                        "Showing {name}. Keep it, or go back to the text it replaced?",
+                        &[("name", &list[preview.index].name)],
+                    ));
+                    ui.horizontal(|ui| {
+                        if ui.button(tr("Keep")).clicked() {
+                            end_preview = Some(true);
+                        }
+                        if ui.button(tr("Go Back")).clicked() {
+                            end_preview = Some(false);
+                        }
+                    });
+                }
+                ui.separator();
+                if list.is_empty() {
+                    ui.weak(tr("No checkpoints for this file yet."));
+                    return;
+                }
+                let previewed = self.checkpoints.preview.as_ref().map(|p| p.index);
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        for (i, checkpoint) in list.iter().enumerate().rev() {
+                            ui.horizontal(|ui| {
+                                if ui
+                                    .selectable_label(previewed == Some(i), &checkpoint.name)
+                                    .clicked()
+                                {
+                                    revert = Some(i);
+                                }
+                                ui.weak(checkpoint.time.format("%H:%M:%S").to_string());
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        if ui
+                                            .small_button("×")
+                                            .on_hover_text(tr("Delete"))
+                                            .clicked()
+                                        {
+                                            delete = Some(i);
+                                        }
+                                        if ui.small_button(tr("Revert")).clicked() {
+                                            revert = Some(i);
+                                        }
+                                        if ui
+                                            .small_button(tr("Compare"))
+                                            .on_hover_text(tr("Compare with the current text"))
+                                            .clicked()
+                                        {
+                                            compare = Some(i);
+                                        }
+                                    },
+                                );
+                            });
+                        }
+                    });
+            });
+        if create {
+            self.create_checkpoint();
+        }
+        if let Some(keep) = end_preview {
+            self.end_preview(ctx, keep);
+        }
+        if let Some(i) = revert {
+            self.preview_checkpoint(ctx, i);
+        }
+        if let Some(key) = &key {
+            if let Some(i) = compare {
+                let checkpoint = &self.checkpoints.by_tab[key][i];
+                self.diff_view = Some(DiffView::new(
+                    checkpoint.name.clone(),
+                    &checkpoint.content,
+                    trf("{name} (current)", &[("name", key)]),
+                    &self.tabs[key].content,
+                ));
+            }
+            if let Some(i) = delete {
+                // Deleting the previewed checkpoint keeps what it shows.
+                match &mut self.checkpoints.preview {
+                    Some(preview) if preview.index == i => self.checkpoints.preview = None,
+                    Some(preview) if preview.index > i => preview.index -= 1,
+                    _ => {}
+                }
+                if let Some(list) = self.checkpoints.by_tab.get_mut(key) {
+                    list.remove(i);
+                }
+            }
+        }
+        // Closing the window keeps whatever it shows.
+        if !open {
+            self.checkpoints.open = false;
+            self.end_preview(ctx, true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn previewing(content: &str) -> (Checkpoints, HashMap<String, FileTab>) {
+        let checkpoints = Checkpoints {
+            preview: Some(Preview {
+                tab: "notes.txt".to_string(),
+                index: 0,
+                replaced: "current".to_string(),
+                shown: "checkpoint".to_string(),
+            }),
+            ..Default::default()
+        };
+        let tab = FileTab {
+            content: content.to_string(),
+            ..Default::default()
+        };
+        (checkpoints, HashMap::from([("notes.txt".to_string(), tab)]))
+    }
+
+    #[test]
+    fn going_back_or_switching_tabs_puts_back_the_replaced_text() {
+        let (mut checkpoints, mut tabs) = previewing("checkpoint");
+        let ended = checkpoints.end_preview(&mut tabs, false);
+        assert_eq!(ended, Some(("notes.txt".to_string(), true)));
+        assert_eq!(tabs["notes.txt"].content, "current");
+        assert!(checkpoints.preview.is_none());
+    }
+
+    #[test]
+    fn going_back_keeps_edits_made_during_the_preview() {
+        let (mut checkpoints, mut tabs) = previewing("checkpoint, edited");
+        let ended = checkpoints.end_preview(&mut tabs, false);
+        assert_eq!(ended, Some(("notes.txt".to_string(), false)));
+        assert_eq!(tabs["notes.txt"].content, "checkpoint, edited");
+    }
+
+    #[test]
+    fn keeping_leaves_the_checkpoint_text() {
+        let (mut checkpoints, mut tabs) = previewing("checkpoint");
+        checkpoints.end_preview(&mut tabs, true);
+        assert_eq!(tabs["notes.txt"].content, "checkpoint");
+        assert!(checkpoints.preview.is_none());
+    }
+}
@@ -0,0 +1,168 @@
+//! Command-line arguments. Files may be given as `path`, `path:line` or
+//! `path:line:col` (1-based, like compiler messages) to open them with the
+//! caret at that position.
+//!
+//! `--diff a b` opens the diff viewer on two files instead, and
+//! `--merge base ours theirs result` the merge view, so the editor can
+//! serve as git's difftool and mergetool. The editor runs until its window
+//! closes; `--wait` is accepted for tools that ask for that explicitly.
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// A file to open from the command line. `line` and `column` are 0-based.
+pub struct Location {
+    pub path: PathBuf,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Splits a trailing `:line` or `:line:col` off `arg`, unless `arg` names an
+/// existing file as a whole (file names may contain colons).
+fn parse_location(arg: &str) -> Location {
+    let whole = Location {
+        path: PathBuf::from(arg),
+        line: 0,
+        column: 0,
+    };
+    if Path::new(arg).exists() {
+        return whole;
+    }
+    let mut numbers = Vec::new();
+    let mut rest = arg;
+    while numbers.len() < 2
+        && let Some((head, tail)) = rest.rsplit_once(':')
+        && let Ok(number) = tail.parse::<usize>()
+    {
+        numbers.insert(0, number.saturating_sub(1));
+        rest = head;
+    }
+    match numbers[..] {
+        [line] => Location {
+            path: PathBuf::from(rest),
+            line,
+            column: 0,
+        },
+        [line, column] => Location {
+            path: PathBuf::from(rest),
+            line,
+            column,
+        },
+        _ => whole,
+    }
+}
+
+/// Flags followed by a fixed number of files.
+const FILE_FLAGS: &[(&str, usize)] = &[("--diff", 2), ("--merge", 4)];
+
+/// Indices in `args` of the files following `flag`.
+fn operands(args: &[String], flag: &str) -> Range<usize> {
+    let count = FILE_FLAGS
+        .iter()
+        .find(|(name, _)| *name == flag)
+        .map_or(0, |(_, count)| *count);
+    args.iter()
+        .position(|arg| arg == flag)
+        .map_or(0..0, |i| i + 1..(i + 1 + count).min(args.len()))
+}
+
+/// The files given after `flag`, if all of them are.
+fn files<const N: usize>(args: &[String], flag: &str) -> Option<[PathBuf; N]> {
+    let paths: Vec<PathBuf> = args[operands(args, flag)]
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+    paths.try_into().ok()
+}
+
+/// The two files given after `--diff`.
+pub fn diff_files(args: &[String]) -> Option<[PathBuf; 2]> {
+    files(args, "--diff")
+}
+
+/// The base, ours, theirs and result files given after `--merge`.
+pub fn merge_files(args: &[String]) -> Option<[PathBuf; 4]> {
+    files(args, "--merge")
+}
+
+/// The files named on the command line, skipping the program name,
+/// `--flags` and the files they take.
+pub fn locations(args: &[String]) -> Vec<Location> {
+    let taken: Vec<Range<usize>> = FILE_FLAGS
+        .iter()
+        .map(|(flag, _)| operands(args, flag))
+        .collect();
+    args.iter()
+        .enumerate()
+        .skip(1)
+        .filter(|(i, arg)| !arg.starts_with("--") && !taken.iter().any(|r| r.contains(i)))
+        .map(|(_, arg)| parse_location(arg))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn locations_take_line_and_column() {
+        let found = locations(&args(&[
+            "rusty-editor",
+            "no/such/a.rs:12",
+            "no/such/b.rs:3:7",
+            "no/such/c.rs",
+        ]));
+        let found: Vec<_> = found
+            .iter()
+            .map(|l| (l.path.to_str().unwrap(), l.line, l.column))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("no/such/a.rs", 11, 0),
+                ("no/such/b.rs", 2, 6),
+                ("no/such/c.rs", 0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn non_numeric_suffixes_are_part_of_the_path() {
+        let location = parse_location("no/such/file:name");
+        assert_eq!(location.path, PathBuf::from("no/such/file:name"));
+        assert_eq!((location.line, location.column), (0, 0));
+    }
+
+    #[test]
+    fn flags_and_their_files_are_not_opened() {
+        let list = args(&["rusty-editor", "--wait", "--diff", "a", "b", "c"]);
+        assert_eq!(
+            diff_files(&list),
+            Some([PathBuf::from("a"), PathBuf::from("b")])
+        );
+        let opened: Vec<_> = locations(&list).into_iter().map(|l| l.path).collect();
+        assert_eq!(opened, [PathBuf::from("c")]);
+    }
+
+    #[test]
+    fn merge_needs_all_four_files() {
+        assert!(
+            merge_files(&args(&[
+                "rusty-editor",
+                "--merge",
+                "base",
+                "ours",
+                "theirs"
+            ]))
+            .is_none()
+        );
+        assert_eq!(
+            merge_files(&args(&["rusty-editor", "--merge", "b", "o", "t", "r"])),
+            Some(["b", "o", "t", "r"].map(PathBuf::from))
+        );
+    }
+}
@@ -0,0 +1,13 @@
+//! Direct clipboard access for menu commands. Keyboard pastes arrive as
+//! `egui::Event::Paste` instead and do not go through here.
+
+pub fn read_text() -> Option<String> {
+    let text = arboard::Clipboard::new().ok()?.get_text().ok()?;
+    Some(text.replace("\r\n", "\n"))
+}
+
+pub fn write_text(text: &str) -> bool {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .is_ok()
+}
@@ -0,0 +1,126 @@
+//! Recently closed tabs: closing a file's tab remembers its path, caret and
+//! scroll position, so Reopen Closed Tab (Ctrl+Shift+T) brings back the
+//! last one as it was, and the File menu lists the last ten.
+
+use crate::editing::editor_id;
+use crate::i18n::tr;
+use crate::{FileTab, TextEditorApp};
+use eframe::egui;
+use std::path::PathBuf;
+
+/// Closed tabs remembered, and listed in the File menu.
+const MAX_CLOSED: usize = 10;
+
+struct ClosedTab {
+    path: PathBuf,
+    caret: usize,
+    scroll_offset: egui::Vec2,
+}
+
+#[derive(Default)]
+pub struct ClosedTabs {
+    /// Most recently closed last.
+    stack: Vec<ClosedTab>,
+}
+
+impl ClosedTabs {
+    /// Remembers a closed tab. Tabs without a file of their own, such as
+    /// untitled buffers and archive entries, cannot be reopened, and
+    /// sensitive ones are not listed.
+    pub fn push(&mut self, tab: &FileTab) {
+        let Some(path) = tab
+            .path
+            .clone()
+            .filter(|_| tab.archive_entry.is_none() && !tab.sensitive)
+        else {
+            return;
+        };
+        self.stack.retain(|closed| closed.path != path);
+        self.stack.push(ClosedTab {
+            path,
+            caret: tab.caret,
+            scroll_offset: tab.scroll_offset,
+        });
+        if self.stack.len() > MAX_CLOSED {
+            self.stack.remove(0);
+        }
+    }
+}
+
+impl TextEditorApp {
+    /// Reopens the closed tab at `index` of the stack, the most recent one
+    /// if `None`, where its caret and scroll position were.
+    pub(crate) fn reopen_closed_tab(&mut self, ctx: &egui::Context, index: Option<usize>) {
+        let stack = &mut self.closed_tabs.stack;
+        let Some(index) = index.or(stack.len().checked_sub(1)) else {
+            return;
+        };
+        if index >= stack.len() {
+            return;
+        }
+        let closed = stack.remove(index);
+        self.activate_or_open(&closed.path);
+        let Some(key) = self.active_tab.clone() else {
+            return;
+        };
+        let Some(tab) = self.tabs.get_mut(&key) else {
+            return;
+        };
+        if tab.path.as_ref() != Some(&closed.path) {
+            return;
+        }
+        let caret = closed.caret.min(tab.content.chars().count());
+        tab.scroll_offset = closed.scroll_offset;
+        tab.restore_scroll = true;
+        self.set_selection(ctx, caret..caret);
+        ctx.memory_mut(|m| m.request_focus(editor_id(&key)));
+    }
+
+    /// The Reopen Closed submenu of the File menu, most recent first.
+    pub(crate) fn reopen_closed_menu(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        let mut reopen = None;
+        ui.add_enabled_ui(!self.closed_tabs.stack.is_empty(), |ui| {
+            ui.menu_button(tr("Reopen Closed"), |ui| {
+                for (i, closed) in self.closed_tabs.stack.iter().enumerate().rev() {
+                    let name = closed
+                        .path
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy();
+                    if ui
+                        .button(name)
+                        .on_hover_text(closed.path.display().to_string())
+                        .clicked()
+                    {
+                        reopen = Some(i);
+                        ui.close_menu();
+                    }
+                }
+            });
+        });
+        if let Some(i) = reopen {
+            self.reopen_closed_tab(ctx, Some(i));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sensitive_tabs_are_not_remembered() {
+        let mut closed = ClosedTabs::default();
+        closed.push(&FileTab {
+            path: Some(PathBuf::from("/home/user/.env")),
+            sensitive: true,
+            ..Default::default()
+        });
+        assert!(closed.stack.is_empty());
+        closed.push(&FileTab {
+            path: Some(PathBuf::from("/home/user/notes.md")),
+            ..Default::default()
+        });
+        assert_eq!(closed.stack.len(), 1);
+    }
+}
@@ -0,0 +1,607 @@
+//! The command registry: every user-invocable action, its display name and
+//! default shortcut, plus the command palette (Ctrl+Shift+P) that lists them.
+
+use crate::TextEditorApp;
+use crate::bottom_panel::BottomTab;
+use crate::i18n::{tr, trf};
+use crate::indentation::Indentation;
+use crate::markdown_table::TableEdit;
+use crate::picker::{Picker, PickerItem};
+use crate::platform;
+use crate::transform::Transform;
+use eframe::egui;
+use egui::{Key, KeyboardShortcut, Modifiers};
+
+/// The menus commands are grouped by, in menu bar order.
+pub const CATEGORIES: &[&str] = &["File", "Edit", "View", "Search", "Tools", "Help"];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Command {
+    NewFile,
+    NewFileFromTemplate,
+    NewProject,
+    OpenFile,
+    OpenFolder,
+    AddFolderToWorkspace,
+    OpenUrl,
+    ReopenClosedTab,
+    Save,
+    SaveAs,
+    Rename,
+    Find,
+    Replace,
+    FindInFiles,
+    FindAllReferences,
+    Preferences,
+    ExportSettings,
+    ImportSettings,
+    ToggleTheme,
+    EditTheme,
+    ShowCommandPalette,
+    HardWrap,
+    AlignSelection,
+    PrettyPrint,
+    Minify,
+    ConvertIndentationToSpaces,
+    ConvertIndentationToTabs,
+    Reindent,
+    ExpandSelection,
+    ShrinkSelection,
+    RenameOccurrences,
+    Checkpoints,
+    GoToMatchingTag,
+    SelectTagContents,
+    FormatTable,
+    InsertTableRow,
+    InsertTableColumn,
+    PasteAndIndent,
+    PasteAsColumn,
+    PastePlainText,
+    PasteAsMarkdownLink,
+    RemoveSuspicious,
+    EscapeSuspicious,
+    Transform(Transform),
+    InsertDateTime,
+    InsertTimestamp,
+    InsertUuid,
+    InsertUnicode,
+    InsertLinkToFile,
+    InsertImage,
+    CompareWithClipboard,
+    GoToWorkspaceSymbol,
+    GoToSymbolInFile,
+    SwitchToCounterpart,
+    ToggleProblems,
+    RunCargoCheck,
+    ScanTodos,
+    ShowFileHistory,
+    ShowResources,
+    ToggleProfiler,
+    NextConflict,
+    PreviousConflict,
+    ShareAsGist,
+    ToggleFollow,
+    RunCurrentFile,
+    StopRun,
+    OpenTerminalInProject,
+    OpenTerminalInFileFolder,
+    ToggleOutput,
+    ToggleConsole,
+    ToggleBacklinks,
+    ToggleAuditLog,
+    FocusFileBrowser,
+    RevealActiveFile,
+    TabGroups,
+    AddToFavorites,
+    ToggleFold,
+    UnfoldAll,
+    NextHeading,
+    PreviousHeading,
+    EvaluateSelection,
+    ToggleProseMode,
+    ToggleLiveView,
+    ToggleSensitive,
+    CheckForUpdates,
+    KeyboardShortcuts,
+    About,
+}
+
+impl Command {
+    pub fn all() -> Vec<Command> {
+        let mut all = vec![
+            Command::NewFile,
+            Command::NewFileFromTemplate,
+            Command::NewProject,
+            Command::OpenFile,
+            Command::OpenFolder,
+            Command::AddFolderToWorkspace,
+            Command::OpenUrl,
+            Command::ReopenClosedTab,
+            Command::Save,
+            Command::SaveAs,
+            Command::Rename,
+            Command::Find,
+            Command::Replace,
+            Command::FindInFiles,
+            Command::FindAllReferences,
+            Command::Preferences,
+            Command::ExportSettings,
+            Command::ImportSettings,
+            Command::ToggleTheme,
+            Command::EditTheme,
+            Command::ShowCommandPalette,
+            Command::HardWrap,
+            Command::AlignSelection,
+            Command::PrettyPrint,
+            Command::Minify,
+            Command::ConvertIndentationToSpaces,
+            Command::ConvertIndentationToTabs,
+            Command::Reindent,
+            Command::ExpandSelection,
+            Command::ShrinkSelection,
+            Command::RenameOccurrences,
+            Command::Checkpoints,
+            Command::GoToMatchingTag,
+            Command::SelectTagContents,
+            Command::FormatTable,
+            Command::InsertTableRow,
+            Command::InsertTableColumn,
+            Command::PasteAndIndent,
+            Command::PasteAsColumn,
+            Command::PastePlainText,
+            Command::PasteAsMarkdownLink,
+            Command::RemoveSuspicious,
+            Command::EscapeSuspicious,
+        ];
+        all.extend(Transform::ALL.map(Command::Transform));
+        all.extend([
+            Command::InsertDateTime,
+            Command::InsertTimestamp,
+            Command::InsertUuid,
+            Command::InsertUnicode,
+            Command::InsertLinkToFile,
+            Command::InsertImage,
+            Command::CompareWithClipboard,
+            Command::GoToWorkspaceSymbol,
+            Command::GoToSymbolInFile,
+            Command::SwitchToCounterpart,
+            Command::ToggleProblems,
+            Command::RunCargoCheck,
+            Command::ScanTodos,
+            Command::ShowFileHistory,
+            Command::ShowResources,
+            Command::ToggleProfiler,
+            Command::NextConflict,
+            Command::PreviousConflict,
+            Command::ShareAsGist,
+            Command::ToggleFollow,
+            Command::RunCurrentFile,
+            Command::StopRun,
+            Command::OpenTerminalInProject,
+            Command::OpenTerminalInFileFolder,
+            Command::ToggleOutput,
+            Command::ToggleConsole,
+            Command::ToggleBacklinks,
+            Command::ToggleAuditLog,
+            Command::FocusFileBrowser,
+            Command::RevealActiveFile,
+            Command::TabGroups,
+            Command::AddToFavorites,
+            Command::ToggleFold,
+            Command::UnfoldAll,
+            Command::NextHeading,
+            Command::PreviousHeading,
+            Command::EvaluateSelection,
+            Command::ToggleProseMode,
+            Command::ToggleLiveView,
+            Command::ToggleSensitive,
+            Command::CheckForUpdates,
+            Command::KeyboardShortcuts,
+            Command::About,
+        ]);
+        all
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Command::NewFile => tr("New File"),
+            Command::NewFileFromTemplate => tr("New File from Template…"),
+            Command::NewProject => tr("New Project…"),
+            Command::OpenFile => tr("Open File…"),
+            Command::OpenFolder => tr("Open Folder…"),
+            Command::AddFolderToWorkspace => tr("Add Folder to Workspace…"),
+            Command::OpenUrl => tr("Open URL…"),
+            Command::ReopenClosedTab => tr("Reopen Closed Tab"),
+            Command::Save => tr("Save"),
+            Command::SaveAs => tr("Save As…"),
+            Command::Rename => tr("Rename File…"),
+            Command::Find => tr("Find…"),
+            Command::Replace => tr("Find and Replace…"),
+            Command::FindInFiles => tr("Find in Files…"),
+            Command::FindAllReferences => tr("Find All References"),
+            Command::Preferences => tr("Preferences"),
+            Command::ExportSettings => tr("Export Settings…"),
+            Command::ImportSettings => tr("Import Settings…"),
+            Command::ToggleTheme => tr("Toggle Light/Dark Theme"),
+            Command::EditTheme => tr("Theme Editor…"),
+            Command::ShowCommandPalette => tr("Command Palette"),
+            Command::HardWrap => tr("Hard Wrap Paragraph"),
+            Command::AlignSelection => tr("Align Selection…"),
+            Command::PrettyPrint => tr("Pretty Print JSON/XML/HTML"),
+            Command::Minify => tr("Minify JSON/XML/HTML"),
+            Command::ConvertIndentationToSpaces => tr("Convert Indentation to Spaces"),
+            Command::ConvertIndentationToTabs => tr("Convert Indentation to Tabs"),
+            Command::Reindent => tr("Reindent Document"),
+            Command::ExpandSelection => tr("Expand Selection"),
+            Command::ShrinkSelection => tr("Shrink Selection"),
+            Command::RenameOccurrences => tr("Rename Occurrences…"),
+            Command::Checkpoints => tr("Checkpoints…"),
+            Command::GoToMatchingTag => tr("Go to Matching Tag"),
+            Command::SelectTagContents => tr("Select Tag Contents"),
+            Command::FormatTable => tr("Format Table"),
+            Command::InsertTableRow => tr("Insert Table Row"),
+            Command::InsertTableColumn => tr("Insert Table Column"),
+            Command::PasteAndIndent => tr("Paste and Indent"),
+            Command::PasteAsColumn => tr("Paste as Column"),
+            Command::PastePlainText => tr("Paste as Plain Text"),
+            Command::PasteAsMarkdownLink => tr("Paste as Markdown Link"),
+            Command::RemoveSuspicious => tr("Remove Suspicious Characters"),
+            Command::EscapeSuspicious => tr("Escape Suspicious Characters"),
+            Command::Transform(t) => trf("Transform: {name}", &[("name", &tr(t.label()))]),
+            Command::InsertDateTime => tr("Insert Date/Time"),
+            Command::InsertTimestamp => tr("Insert ISO-8601 Timestamp"),
+            Command::InsertUuid => tr("Insert UUID"),
+            Command::InsertUnicode => tr("Insert Unicode Character…"),
+            Command::InsertLinkToFile => tr("Insert Link to File…"),
+            Command::InsertImage => tr("Insert Image…"),
+            Command::CompareWithClipboard => tr("Compare with Clipboard"),
+            Command::GoToWorkspaceSymbol => tr("Go to Symbol in Workspace…"),
+            Command::GoToSymbolInFile => tr("Go to Symbol in File…"),
+            Command::SwitchToCounterpart => tr("Switch to Counterpart"),
+            Command::ToggleProblems => tr("Toggle Problems Panel"),
+            Command::RunCargoCheck => tr("Run cargo check"),
+            Command::ScanTodos => tr("Scan Folder for TODOs"),
+            Command::ShowFileHistory => tr("Show File History"),
+            Command::ShowResources => tr("Resources"),
+            Command::ToggleProfiler => tr("Toggle Profiler"),
+            Command::NextConflict => tr("Next Merge Conflict"),
+            Command::PreviousConflict => tr("Previous Merge Conflict"),
+            Command::ShareAsGist => tr("Share as Gist…"),
+            Command::ToggleFollow => tr("Toggle Follow (Tail File)"),
+            Command::RunCurrentFile => tr("Run Current File"),
+            Command::StopRun => tr("Stop Running Program"),
+            Command::OpenTerminalInProject => tr("Open Terminal in Project Folder"),
+            Command::OpenTerminalInFileFolder => tr("Open Terminal in File's Folder"),
+            Command::ToggleOutput => tr("Toggle Output Panel"),
+            Command::ToggleConsole => tr("Toggle Console"),
+            Command::ToggleBacklinks => tr("Toggle Backlinks Panel"),
+            Command::ToggleAuditLog => tr("Toggle Audit Log Panel"),
+            Command::FocusFileBrowser => tr("Focus File Browser"),
+            Command::RevealActiveFile => tr("Reveal Active File"),
+            Command::TabGroups => tr("Tab Groups…"),
+            Command::AddToFavorites => tr("Add File to Favorites"),
+            Command::ToggleFold => tr("Fold/Unfold Section"),
+            Command::UnfoldAll => tr("Unfold All Sections"),
+            Command::NextHeading => tr("Next Heading"),
+            Command::PreviousHeading => tr("Previous Heading"),
+            Command::EvaluateSelection => tr("Evaluate Selection in Console"),
+            Command::ToggleProseMode => tr("Toggle Prose Mode"),
+            Command::ToggleLiveView => tr("Start/Stop Live View in Browser"),
+            Command::ToggleSensitive => tr("Toggle Sensitive (Privacy Mode)"),
+            Command::CheckForUpdates => tr("Check for Updates"),
+            Command::KeyboardShortcuts => tr("Keyboard Shortcuts"),
+            Command::About => tr("About Rusty Editor"),
+        }
+    }
+
+    /// The menu the command belongs to, one of [`CATEGORIES`]. Every
+    /// command is listed so a new one cannot be left out.
+    pub fn category(self) -> &'static str {
+        match self {
+            Command::NewFile
+            | Command::NewFileFromTemplate
+            | Command::NewProject
+            | Command::OpenFile
+            | Command::OpenFolder
+            | Command::AddFolderToWorkspace
+            | Command::OpenUrl
+            | Command::ReopenClosedTab
+            | Command::Save
+            | Command::SaveAs
+            | Command::Rename
+            | Command::ToggleSensitive
+            | Command::AddToFavorites
+            | Command::Preferences
+            | Command::ExportSettings
+            | Command::ImportSettings => "File",
+            Command::HardWrap
+            | Command::AlignSelection
+            | Command::ExpandSelection
+            | Command::ShrinkSelection
+            | Command::RenameOccurrences
+            | Command::SelectTagContents
+            | Command::PasteAndIndent
+            | Command::PasteAsColumn
+            | Command::PastePlainText
+            | Command::PasteAsMarkdownLink
+            | Command::Transform(_)
+            | Command::InsertDateTime
+            | Command::InsertTimestamp
+            | Command::InsertUuid
+            | Command::InsertUnicode
+            | Command::InsertLinkToFile
+            | Command::InsertImage
+            | Command::PrettyPrint
+            | Command::Minify
+            | Command::ConvertIndentationToSpaces
+            | Command::ConvertIndentationToTabs
+            | Command::Reindent
+            | Command::FormatTable
+            | Command::InsertTableRow
+            | Command::InsertTableColumn
+            | Command::RemoveSuspicious
+            | Command::EscapeSuspicious
+            | Command::CompareWithClipboard
+            | Command::Checkpoints => "Edit",
+            Command::ShowCommandPalette
+            | Command::ToggleTheme
+            | Command::EditTheme
+            | Command::ToggleProseMode
+            | Command::ToggleFold
+            | Command::UnfoldAll
+            | Command::TabGroups
+            | Command::ToggleProblems
+            | Command::ToggleOutput
+            | Command::ToggleConsole
+            | Command::ToggleBacklinks
+            | Command::ToggleAuditLog
+            | Command::FocusFileBrowser
+            | Command::RevealActiveFile => "View",
+            Command::Find
+            | Command::Replace
+            | Command::FindInFiles
+            | Command::FindAllReferences
+            | Command::GoToWorkspaceSymbol
+            | Command::GoToSymbolInFile
+            | Command::SwitchToCounterpart
+            | Command::NextConflict
+            | Command::PreviousConflict
+            | Command::NextHeading
+            | Command::PreviousHeading
+            | Command::GoToMatchingTag => "Search",
+            Command::RunCurrentFile
+            | Command::StopRun
+            | Command::EvaluateSelection
+            | Command::RunCargoCheck
+            | Command::ScanTodos
+            | Command::OpenTerminalInProject
+            | Command::OpenTerminalInFileFolder
+            | Command::ShowFileHistory
+            | Command::ToggleFollow
+            | Command::ShareAsGist
+            | Command::ToggleLiveView => "Tools",
+            Command::ShowResources
+            | Command::ToggleProfiler
+            | Command::CheckForUpdates
+            | Command::KeyboardShortcuts
+            | Command::About => "Help",
+        }
+    }
+
+    pub fn shortcut(self) -> Option<KeyboardShortcut> {
+        let ctrl = Modifiers::COMMAND;
+        let ctrl_shift = Modifiers::COMMAND | Modifiers::SHIFT;
+        let (modifiers, key) = match self {
+            Command::NewFile => (ctrl, Key::N),
+            Command::OpenFile => (ctrl, Key::O),
+            Command::Save => (ctrl, Key::S),
+            Command::SaveAs => (ctrl_shift, Key::S),
+            Command::Find => (ctrl, Key::F),
+            Command::Replace => (ctrl, Key::H),
+            Command::FindInFiles => (ctrl_shift, Key::F),
+            Command::FindAllReferences => (Modifiers::SHIFT, Key::F12),
+            Command::GoToWorkspaceSymbol => (ctrl, Key::T),
+            Command::ReopenClosedTab => (ctrl_shift, Key::T),
+            Command::GoToSymbolInFile => (ctrl_shift, Key::O),
+            Command::SwitchToCounterpart => (Modifiers::ALT, Key::O),
+            Command::ShowCommandPalette => (ctrl_shift, Key::P),
+            Command::PasteAndIndent => (ctrl_shift, Key::V),
+            Command::ToggleProblems => (ctrl_shift, Key::M),
+            Command::ToggleOutput => (ctrl_shift, Key::U),
+            Command::FocusFileBrowser => (ctrl_shift, Key::E),
+            Command::RunCurrentFile => (Modifiers::NONE, Key::F5),
+            Command::StopRun => (Modifiers::SHIFT, Key::F5),
+            Command::EvaluateSelection => (ctrl, Key::Enter),
+            Command::PasteAsColumn => (ctrl | Modifiers::ALT, Key::V),
+            Command::NextHeading => (ctrl | Modifiers::ALT, Key::ArrowDown),
+            Command::PreviousHeading => (ctrl | Modifiers::ALT, Key::ArrowUp),
+            Command::ExpandSelection => (Modifiers::ALT | Modifiers::SHIFT, Key::ArrowUp),
+            Command::ShrinkSelection => (Modifiers::ALT | Modifiers::SHIFT, Key::ArrowDown),
+            Command::RenameOccurrences => (Modifiers::NONE, Key::F2),
+            _ => return None,
+        };
+        Some(KeyboardShortcut::new(modifiers, key))
+    }
+}
+
+impl TextEditorApp {
+    pub(crate) fn run_command(&mut self, ctx: &egui::Context, command: Command) {
+        match command {
+            Command::NewFile => self.create_new_file(),
+            Command::NewFileFromTemplate => self.open_template_picker(),
+            Command::NewProject => self.new_project.show = true,
+            Command::OpenFile => {
+                if let Some(path) = platform::file_dialog().pick_file() {
+                    self.open_file(&path);
+                }
+            }
+            Command::OpenFolder => {
+                if let Some(folder) = platform::file_dialog().pick_folder() {
+                    self.open_folder(folder);
+                    self.problems.clear();
+                    self.run_todo_scan(ctx);
+                }
+            }
+            Command::AddFolderToWorkspace => {
+                if let Some(folder) = platform::file_dialog().pick_folder() {
+                    self.add_workspace_folder(folder);
+                    self.run_todo_scan(ctx);
+                }
+            }
+            Command::OpenUrl => self.url_opener.open(),
+            Command::ReopenClosedTab => self.reopen_closed_tab(ctx, None),
+            Command::Save => {
+                self.save_active();
+                self.run_todo_scan(ctx);
+            }
+            Command::SaveAs => {
+                self.save_active_as();
+                self.run_todo_scan(ctx);
+            }
+            Command::Rename => self.start_rename(),
+            Command::Find => self.show_find = true,
+            Command::Replace => self.show_replace = true,
+            Command::FindInFiles => self.find_in_files(),
+            Command::FindAllReferences => self.find_all_references(ctx),
+            Command::Preferences => self.show_preferences = true,
+            Command::ExportSettings => self.export_settings(),
+            Command::ImportSettings => self.import_settings(ctx),
+            Command::ToggleTheme => self.toggle_theme(ctx),
+            Command::EditTheme => self.open_theme_editor(),
+            Command::ShowCommandPalette => self.palette = Picker::opened(),
+            Command::HardWrap => self.hard_wrap(ctx),
+            Command::AlignSelection => self.align.open = true,
+            Command::PrettyPrint => self.reformat_document(ctx, false),
+            Command::Minify => self.reformat_document(ctx, true),
+            Command::ConvertIndentationToSpaces => self.convert_indentation_to_spaces(ctx),
+            Command::ConvertIndentationToTabs => {
+                self.convert_indentation(ctx, Some(Indentation::Tabs))
+            }
+            Command::Reindent => self.convert_indentation(ctx, None),
+            Command::ExpandSelection => self.expand_selection(ctx),
+            Command::ShrinkSelection => self.shrink_selection(ctx),
+            Command::RenameOccurrences => self.start_rename_occurrences(ctx),
+            Command::Checkpoints => self.checkpoints.open = true,
+            Command::GoToMatchingTag => self.go_to_matching_tag(ctx),
+            Command::SelectTagContents => self.select_tag_contents(ctx),
+            Command::FormatTable => self.edit_table(ctx, TableEdit::Format),
+            Command::InsertTableRow => self.edit_table(ctx, TableEdit::InsertRow),
+            Command::InsertTableColumn => self.edit_table(ctx, TableEdit::InsertColumn),
+            Command::PasteAndIndent => self.paste_from_clipboard(ctx, false),
+            Command::PasteAsColumn => self.paste_from_clipboard(ctx, true),
+            Command::PastePlainText => self.paste_plain_text(ctx),
+            Command::PasteAsMarkdownLink => self.paste_as_markdown_link(ctx),
+            Command::RemoveSuspicious => self.clean_suspicious(ctx, false),
+            Command::EscapeSuspicious => self.clean_suspicious(ctx, true),
+            Command::Transform(t) => self.apply_transform(ctx, t),
+            Command::InsertDateTime => {
+                let text = chrono::Local::now()
+                    .format(&self.settings.date_format)
+                    .to_string();
+                self.insert_text(ctx, &text);
+            }
+            Command::InsertTimestamp => {
+                let text = chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false);
+                self.insert_text(ctx, &text);
+            }
+            Command::InsertUuid => self.insert_text(ctx, &uuid::Uuid::new_v4().to_string()),
+            Command::InsertUnicode => self.unicode_picker.open = true,
+            Command::InsertLinkToFile => self.open_link_picker(),
+            Command::InsertImage => self.insert_image(ctx),
+            Command::CompareWithClipboard => self.compare_with_clipboard(ctx),
+            Command::GoToWorkspaceSymbol => {
+                if self.symbol_index.is_some() {
+                    self.symbol_picker = Picker::opened();
+                }
+            }
+            Command::SwitchToCounterpart => self.switch_to_counterpart(),
+            Command::GoToSymbolInFile => {
+                if self.active_tab.is_some() {
+                    self.file_symbol_picker = Picker::opened();
+                }
+            }
+            Command::ToggleProblems => self.bottom_panel.toggle(BottomTab::Problems),
+            Command::RunCargoCheck => self.run_cargo_check(ctx),
+            Command::ScanTodos => self.run_todo_scan(ctx),
+            Command::ShowFileHistory => self.show_file_history_for_active(),
+            Command::ShowResources => self.resources.open = true,
+            Command::ToggleProfiler => self.profiler.open = !self.profiler.open,
+            Command::NextConflict => self.go_to_conflict(ctx, true),
+            Command::PreviousConflict => self.go_to_conflict(ctx, false),
+            Command::ShareAsGist => self.share_as_gist(ctx),
+            Command::ToggleFollow => self.toggle_follow(),
+            Command::RunCurrentFile => self.run_current_file(ctx),
+            Command::StopRun => self.run_output.stop(),
+            Command::OpenTerminalInProject => self.open_terminal_at_root(),
+            Command::OpenTerminalInFileFolder => self.open_terminal_at_file(),
+            Command::ToggleOutput => self.bottom_panel.toggle(BottomTab::Output),
+            Command::ToggleConsole => self.bottom_panel.toggle(BottomTab::Console),
+            Command::ToggleBacklinks => self.bottom_panel.toggle(BottomTab::Backlinks),
+            Command::ToggleAuditLog => self.bottom_panel.toggle(BottomTab::AuditLog),
+            Command::FocusFileBrowser => self.toggle_file_tree_focus(ctx),
+            Command::RevealActiveFile => self.reveal_active_file(),
+            Command::TabGroups => self.tab_groups.open = true,
+            Command::AddToFavorites => self.pin_active_file(),
+            Command::ToggleFold => self.toggle_fold_at_caret(ctx),
+            Command::UnfoldAll => self.unfold_all(),
+            Command::NextHeading => self.go_to_heading(ctx, true),
+            Command::PreviousHeading => self.go_to_heading(ctx, false),
+            Command::EvaluateSelection => self.evaluate_selection(ctx),
+            Command::ToggleProseMode => self.toggle_prose_mode(),
+            Command::ToggleLiveView => self.toggle_live_view(),
+            Command::ToggleSensitive => self.toggle_sensitive(),
+            Command::CheckForUpdates => self.check_for_updates(ctx, true),
+            Command::KeyboardShortcuts => self.cheat_sheet.open = true,
+            Command::About => self.show_about = true,
+        }
+    }
+
+    /// Runs commands whose shortcut was pressed this frame.
+    pub(crate) fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        // Most specific first, so Ctrl+Shift+P does not also count as Ctrl+P.
+        let mut commands: Vec<(Command, KeyboardShortcut)> = Command::all()
+            .into_iter()
+            .filter_map(|c| c.shortcut().map(|s| (c, s)))
+            .collect();
+        commands.sort_by_key(|(_, s)| {
+            std::cmp::Reverse(u8::from(s.modifiers.shift) + u8::from(s.modifiers.alt))
+        });
+        for (command, shortcut) in commands {
+            if ctx.input_mut(|i| i.consume_shortcut(&shortcut)) {
+                self.run_command(ctx, command);
+            }
+        }
+    }
+
+    /// A menu entry showing the command's name and shortcut.
+    pub(crate) fn command_button(&mut self, ui: &mut egui::Ui, command: Command) {
+        let mut button = egui::Button::new(command.name());
+        if let Some(shortcut) = command.shortcut() {
+            button = button.shortcut_text(ui.ctx().format_shortcut(&shortcut));
+        }
+        if ui.add(button).clicked() {
+            ui.close_menu();
+            self.run_command(ui.ctx(), command);
+        }
+    }
+
+    pub(crate) fn show_command_palette(&mut self, ctx: &egui::Context) {
+        if !self.palette.open {
+            return;
+        }
+        let items = Command::all()
+            .into_iter()
+            .map(|command| PickerItem {
+                value: command,
+                label: command.name(),
+                detail: command
+                    .shortcut()
+                    .map(|s| ctx.format_shortcut(&s))
+                    .unwrap_or_default(),
+            })
+            .collect();
+        if let Some(command) = self.palette.show(ctx, &tr("Command Palette"), items) {
+            self.run_command(ctx, command);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Merge conflict markers: finding `<<<<<<<` / `=======` / `>>>>>>>`
+//! regions (with an optional diff3 `|||||||` base), tinting them in the
+//! editor, and resolving them with the inline Accept buttons.
+
+use crate::TextEditorApp;
+use eframe::egui;
+use std::ops::Range;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Resolution {
+    Ours,
+    Theirs,
+    Both,
+}
+
+impl Resolution {
+    pub const ALL: [Resolution; 3] = [Resolution::Ours, Resolution::Theirs, Resolution::Both];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Resolution::Ours => "Accept Ours",
+            Resolution::Theirs => "Accept Theirs",
+            Resolution::Both => "Accept Both",
+        }
+    }
+}
+
+/// One conflict region. Lines are 0-based; byte ranges cover whole lines
+/// including their line breaks.
+#[derive(Clone, Debug)]
+pub struct Conflict {
+    pub start_line: usize,
+    pub base_line: Option<usize>,
+    pub separator_line: usize,
+    pub end_line: usize,
+    /// The whole region, markers included.
+    pub range: Range<usize>,
+    pub ours: Range<usize>,
+    pub theirs: Range<usize>,
+}
+
+fn is_marker(line: &str, marker: &str) -> bool {
+    line.strip_prefix(marker)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\r', '\n']))
+}
+
+pub fn find_conflicts(text: &str) -> Vec<Conflict> {
+    if !text.contains("<<<<<<<") {
+        return Vec::new();
+    }
+    let mut conflicts = Vec::new();
+    let mut open: Option<(usize, usize)> = None;
+    let mut base_line = None;
+    let mut separator: Option<(usize, usize)> = None;
+    let mut ours_end = 0;
+    let mut offset = 0;
+    for (line, content) in text.split_inclusive('\n').enumerate() {
+        let next = offset + content.len();
+        if is_marker(content, "<<<<<<<") {
+            open = Some((line, offset));
+            base_line = None;
+            separator = None;
+        } else if open.is_some() && separator.is_none() && is_marker(content, "|||||||") {
+            base_line = Some(line);
+            ours_end = offset;
+        } else if open.is_some() && separator.is_none() && is_marker(content, "=======") {
+            if base_line.is_none() {
+                ours_end = offset;
+            }
+            separator = Some((line, next));
+        } else if is_marker(content, ">>>>>>>")
+            && let (Some((start_line, start)), Some((separator_line, theirs_start))) =
+                (open, separator)
+        {
+            let ours_start = start + text[start..].find('\n').map_or(0, |i| i + 1);
+            conflicts.push(Conflict {
+                start_line,
+                base_line,
+                separator_line,
+                end_line: line,
+                range: start..next,
+                ours: ours_start..ours_end,
+                theirs: theirs_start..offset,
+            });
+            open = None;
+        }
+        offset = next;
+    }
+    conflicts
+}
+
+impl Conflict {
+    pub fn resolved_text(&self, text: &str, resolution: Resolution) -> String {
+        let ours = &text[self.ours.clone()];
+        let theirs = &text[self.theirs.clone()];
+        match resolution {
+            Resolution::Ours => ours.to_string(),
+            Resolution::Theirs => theirs.to_string(),
+            Resolution::Both => format!("{ours}{theirs}"),
+        }
+    }
+
+    /// Background tint for `line` if it is part of this conflict.
+    pub fn line_color(&self, line: usize, dark_mode: bool) -> Option<egui::Color32> {
+        if !(self.start_line..=self.end_line).contains(&line) {
+            return None;
+        }
+        let alpha = if dark_mode { 40 } else { 28 };
+        let marker = [self.start_line, self.separator_line, self.end_line].contains(&line)
+            || self.base_line == Some(line);
+        Some(if marker {
+            egui::Color32::from_gray(128).gamma_multiply(0.35)
+        } else if line < self.base_line.unwrap_or(self.separator_line) {
+            egui::Color32::from_rgba_unmultiplied(40, 160, 90, alpha)
+        } else if line < self.separator_line {
+            egui::Color32::from_rgba_unmultiplied(128, 128, 128, alpha)
+        } else {
+            egui::Color32::from_rgba_unmultiplied(60, 120, 220, alpha)
+        })
+    }
+}
+
+impl TextEditorApp {
+    /// Replaces conflict `index` of the active tab with the chosen side.
+    pub(crate) fn resolve_conflict(
+        &mut self,
+        ctx: &egui::Context,
+        index: usize,
+        resolution: Resolution,
+    ) {
+        let Some(tab) = self.editable_tab() else {
+            return;
+        };
+        let Some(conflict) = find_conflicts(&tab.content).into_iter().nth(index) else {
+            return;
+        };
+        let text = conflict.resolved_text(&tab.content, resolution);
+        self.replace_range(ctx, conflict.range.clone(), &text);
+        if let Some(start) = self.selection(ctx).map(|s| s.start) {
+            self.set_selection(ctx, start..start);
+        }
+    }
+
+    /// Moves the caret to the next (or previous) conflict, wrapping around.
+    pub(crate) fn go_to_conflict(&mut self, ctx: &egui::Context, forward: bool) {
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        let conflicts = find_conflicts(&tab.content);
+        let caret = self
+            .selection_or(ctx, |_, pos| pos..pos)
+            .map_or(0, |range| range.start);
+        let target = if forward {
+            conflicts
+                .iter()
+                .find(|c| c.range.start > caret)
+                .or(conflicts.first())
+        } else {
+            conflicts
+                .iter()
+                .rev()
+                .find(|c| c.range.start < caret)
+                .or(conflicts.last())
+        };
+        if let Some(line) = target.map(|c| c.start_line) {
+            self.go_to_line(ctx, line);
+        }
+    }
+}
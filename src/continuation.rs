@@ -0,0 +1,112 @@
+//! List and comment continuation: Enter inside a Markdown list item or
+//! blockquote, or a line comment, starts the next line with the same
+//! prefix. Enter on a line that holds nothing but the prefix removes it
+//! instead, ending the list. Shift+Enter always inserts a plain newline.
+
+use crate::TextEditorApp;
+use crate::editing::char_to_byte;
+use crate::motion::take_key;
+use eframe::egui;
+
+/// Line comment markers continued in code, longest first so `///` wins
+/// over `//`. `*` continues the body of `/** ... */` blocks.
+const COMMENT_MARKERS: &[&str] = &["///", "//!", "//", "#", "--", ";;", ";", "%", "*"];
+
+/// The prefix of `line` worth continuing: its length in bytes, and what
+/// the next line starts with. Ordered list numbers count up and checked
+/// task boxes come back unchecked.
+pub fn continuation(line: &str, markdown: bool) -> Option<(usize, String)> {
+    let indent = &line[..line.len() - line.trim_start().len()];
+    let rest = &line[indent.len()..];
+    if markdown {
+        markdown_marker(rest).map(|(len, next)| (indent.len() + len, format!("{indent}{next}")))
+    } else {
+        let marker = COMMENT_MARKERS
+            .iter()
+            .find(|m| rest.starts_with(&format!("{m} ")))?;
+        let len = indent.len() + marker.len() + 1;
+        Some((len, line[..len].to_string()))
+    }
+}
+
+fn markdown_marker(rest: &str) -> Option<(usize, String)> {
+    // Nested quotes (`> > `) are continued as a whole, followed by any list
+    // marker inside them.
+    let quotes = rest.len() - rest.trim_start_matches(['>', ' ']).len();
+    if quotes > 0 {
+        let quote = &rest[..quotes];
+        let inner = &rest[quotes..];
+        let (len, next) = list_marker(inner).unwrap_or((0, String::new()));
+        let quote = if quote.ends_with(' ') {
+            quote.to_string()
+        } else {
+            format!("{quote} ")
+        };
+        return Some((quotes + len, quote + &next));
+    }
+    list_marker(rest)
+}
+
+fn list_marker(rest: &str) -> Option<(usize, String)> {
+    let (len, next) =
+        if let Some(bullet) = ["- ", "* ", "+ "].iter().find(|b| rest.starts_with(**b)) {
+            (2, bullet.to_string())
+        } else {
+            let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            let delimiter = rest[digits..]
+                .chars()
+                .next()
+                .filter(|c| matches!(c, '.' | ')'))?;
+            if digits == 0 || !rest[digits + 1..].starts_with(' ') {
+                return None;
+            }
+            let number: u64 = rest[..digits].parse().ok()?;
+            (digits + 2, format!("{}{delimiter} ", number + 1))
+        };
+    let task = ["[ ] ", "[x] ", "[X] "]
+        .iter()
+        .find(|t| rest[len..].starts_with(**t))
+        .map_or(0, |t| t.len());
+    let next = if task > 0 { next + "[ ] " } else { next };
+    Some((len + task, next))
+}
+
+impl TextEditorApp {
+    /// Continues or ends the list or comment on the caret's line when Enter
+    /// is pressed, in file types where the option is on.
+    pub(crate) fn handle_continuation(&mut self, ctx: &egui::Context) {
+        if !self.editor_has_focus(ctx) {
+            return;
+        }
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        if tab.read_only || !tab.editor_options(&self.settings).continue_lists {
+            return;
+        }
+        let Some(selection) = self.selection(ctx).filter(|s| s.is_empty()) else {
+            return;
+        };
+        let text = &tab.content;
+        let caret = char_to_byte(text, selection.start);
+        let line_start = text[..caret].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = text[caret..].find('\n').map_or(text.len(), |i| caret + i);
+        let line = text[line_start..line_end].trim_end_matches('\r');
+        let markdown = tab.syntax.as_deref() == Some("Markdown");
+        let Some((len, next)) = continuation(line, markdown) else {
+            return;
+        };
+        // Enter inside the prefix itself just breaks the line.
+        if caret < line_start + len {
+            return;
+        }
+        if take_key(ctx, egui::Key::Enter, |m| m.is_none()).is_none() {
+            return;
+        }
+        if line[len..].trim().is_empty() {
+            self.replace_range(ctx, line_start..line_start + line.len(), "");
+        } else {
+            self.insert_text(ctx, &format!("\n{next}"));
+        }
+    }
+}
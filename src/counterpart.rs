@@ -0,0 +1,137 @@
+//! Switch to Counterpart: jumps between files that belong together, like a
+//! C header and its source or a module and its tests.
+//!
+//! Each rule lists paths that pair up, relative to a common folder, with
+//! `{name}` standing for the shared part of the file name. The first rule
+//! with a pattern matching the current file and a counterpart that exists
+//! wins; in rules of more than two, repeated switching cycles through them.
+
+use crate::TextEditorApp;
+use crate::i18n::trf;
+use std::path::{Component, Path, PathBuf};
+
+/// The rules used until the user edits them.
+pub fn default_rules() -> Vec<String> {
+    [
+        "{name}.h, {name}.c, {name}.cpp, {name}.cc, {name}.m",
+        "{name}.hpp, {name}.cpp",
+        "src/{name}.rs, tests/{name}.rs",
+        "{name}.go, {name}_test.go",
+        "{name}.py, test_{name}.py, tests/test_{name}.py",
+        "{name}.ts, {name}.test.ts, {name}.spec.ts",
+        "{name}.tsx, {name}.test.tsx",
+        "{name}.js, {name}.test.js, {name}.spec.js",
+    ]
+    .map(String::from)
+    .to_vec()
+}
+
+fn patterns(rule: &str) -> Vec<&str> {
+    rule.split(',')
+        .map(str::trim)
+        .filter(|pattern| pattern.contains("{name}"))
+        .collect()
+}
+
+/// Matches one path component against one pattern component, binding
+/// `{name}`.
+fn match_component<'a>(pattern: &str, component: &'a str) -> Option<Option<&'a str>> {
+    let Some((prefix, suffix)) = pattern.split_once("{name}") else {
+        return (pattern == component).then_some(None);
+    };
+    let name = component
+        .strip_prefix(prefix)?
+        .strip_suffix(suffix)
+        .filter(|name| !name.is_empty())?;
+    Some(Some(name))
+}
+
+/// The folder `pattern` is relative to and the name it binds, if `path`
+/// matches it.
+fn match_pattern(pattern: &str, path: &Path) -> Option<(PathBuf, String)> {
+    let pattern_parts: Vec<&str> = pattern.split('/').collect();
+    let components: Vec<&str> = path
+        .components()
+        .map(|c| c.as_os_str().to_str())
+        .collect::<Option<_>>()?;
+    let tail = components.len().checked_sub(pattern_parts.len())?;
+    let mut name: Option<&str> = None;
+    for (part, component) in pattern_parts.iter().zip(&components[tail..]) {
+        if let Some(bound) = match_component(part, component)? {
+            if name.is_some_and(|name| name != bound) {
+                return None;
+            }
+            name = Some(bound);
+        }
+    }
+    let base: PathBuf = path.components().take(tail).collect();
+    Some((base, name?.to_string()))
+}
+
+/// Resolves `..` and `.` without touching the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                out.pop();
+            }
+            Component::CurDir => {}
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// The counterpart of `path` under `rules`, where `exists` says which
+/// candidates are there.
+pub fn counterpart(
+    path: &Path,
+    rules: &[String],
+    exists: impl Fn(&Path) -> bool,
+) -> Option<PathBuf> {
+    for rule in rules {
+        let patterns = patterns(rule);
+        // `foo_test.go` also matches `{name}.go`, so every match is tried.
+        for (index, pattern) in patterns.iter().enumerate() {
+            let Some((base, name)) = match_pattern(pattern, path) else {
+                continue;
+            };
+            // The patterns after the current one first, wrapping around.
+            let found = (1..patterns.len())
+                .map(|offset| patterns[(index + offset) % patterns.len()])
+                .map(|pattern| normalize(&base.join(pattern.replace("{name}", &name))))
+                .find(|candidate| candidate != path && exists(candidate));
+            if found.is_some() {
+                return found;
+            }
+        }
+    }
+    None
+}
+
+impl TextEditorApp {
+    pub(crate) fn switch_to_counterpart(&mut self) {
+        let Some(path) = self
+            .active_tab
+            .as_ref()
+            .and_then(|key| self.tabs[key].path.clone())
+        else {
+            return;
+        };
+        let open = |candidate: &Path| {
+            self.tabs
+                .values()
+                .any(|tab| tab.path.as_deref() == Some(candidate))
+        };
+        let Some(target) = counterpart(&path, &self.settings.counterpart_rules, |candidate| {
+            open(candidate) || candidate.is_file()
+        }) else {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            self.notifications
+                .info(trf("No counterpart found for {name}.", &[("name", &name)]));
+            return;
+        };
+        self.activate_or_open(&target);
+    }
+}
@@ -0,0 +1,275 @@
+//! Line-based diffing and the side-by-side diff viewer.
+
+use crate::clipboard;
+use crate::i18n::{tr, trf};
+use crate::{TextEditorApp, UTF8_BOM};
+use eframe::egui;
+use similar::{ChangeTag, TextDiff};
+use std::fs;
+use std::path::Path;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RowKind {
+    Equal,
+    Removed,
+    Added,
+    Changed,
+}
+
+/// One aligned row of a side-by-side diff. Line numbers are 1-based.
+pub struct DiffRow {
+    pub kind: RowKind,
+    pub left: Option<(usize, String)>,
+    pub right: Option<(usize, String)>,
+}
+
+/// Aligns `left` and `right` line by line, pairing removed and added
+/// lines of the same hunk into `Changed` rows.
+pub fn diff_rows(left: &str, right: &str) -> Vec<DiffRow> {
+    let diff = TextDiff::from_lines(left, right);
+    let mut rows = Vec::new();
+    let mut removed: Vec<(usize, String)> = Vec::new();
+    let mut added: Vec<(usize, String)> = Vec::new();
+
+    let flush = |rows: &mut Vec<DiffRow>,
+                 removed: &mut Vec<(usize, String)>,
+                 added: &mut Vec<(usize, String)>| {
+        let len = removed.len().max(added.len());
+        let mut removed = removed.drain(..);
+        let mut added = added.drain(..);
+        for _ in 0..len {
+            let (left, right) = (removed.next(), added.next());
+            let kind = match (&left, &right) {
+                (Some(_), Some(_)) => RowKind::Changed,
+                (Some(_), None) => RowKind::Removed,
+                _ => RowKind::Added,
+            };
+            rows.push(DiffRow { kind, left, right });
+        }
+    };
+
+    for change in diff.iter_all_changes() {
+        let text = change.value().trim_end_matches(['\r', '\n']).to_string();
+        match change.tag() {
+            ChangeTag::Delete => removed.push((change.old_index().unwrap_or(0) + 1, text)),
+            ChangeTag::Insert => added.push((change.new_index().unwrap_or(0) + 1, text)),
+            ChangeTag::Equal => {
+                flush(&mut rows, &mut removed, &mut added);
+                rows.push(DiffRow {
+                    kind: RowKind::Equal,
+                    left: Some((change.old_index().unwrap_or(0) + 1, text.clone())),
+                    right: Some((change.new_index().unwrap_or(0) + 1, text)),
+                });
+            }
+        }
+    }
+    flush(&mut rows, &mut removed, &mut added);
+    rows
+}
+
+pub struct DiffView {
+    pub left_title: String,
+    pub right_title: String,
+    pub rows: Vec<DiffRow>,
+    /// Scroll both sides together. The rows are aligned, so the same offset
+    /// shows the same place on each side.
+    lock_scrolling: bool,
+    /// Vertical offset shared by both sides while scrolling is locked.
+    offset: f32,
+}
+
+impl DiffView {
+    pub fn new(left_title: String, left: &str, right_title: String, right: &str) -> Self {
+        Self {
+            left_title,
+            right_title,
+            rows: diff_rows(left, right),
+            lock_scrolling: true,
+            offset: 0.0,
+        }
+    }
+
+    fn stats(&self) -> (usize, usize) {
+        self.rows
+            .iter()
+            .fold((0, 0), |(del, ins), row| match row.kind {
+                RowKind::Equal => (del, ins),
+                RowKind::Removed => (del + 1, ins),
+                RowKind::Added => (del, ins + 1),
+                RowKind::Changed => (del + 1, ins + 1),
+            })
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        let (removed, added) = self.stats();
+        ui.horizontal(|ui| {
+            ui.label(format!("−{removed}  +{added}"));
+            if removed == 0 && added == 0 {
+                ui.label(tr("Contents are identical"));
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.checkbox(&mut self.lock_scrolling, tr("Lock scrolling"));
+            });
+        });
+        ui.separator();
+
+        let half = (ui.available_width() - ui.spacing().item_spacing.x) / 2.0;
+        ui.horizontal(|ui| {
+            ui.add_sized(
+                [half, 0.0],
+                egui::Label::new(egui::RichText::new(&self.left_title).strong()),
+            );
+            ui.add_sized(
+                [half, 0.0],
+                egui::Label::new(egui::RichText::new(&self.right_title).strong()),
+            );
+        });
+
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        let visuals = ui.visuals().clone();
+        let rows = &self.rows;
+        let lock = self.lock_scrolling;
+        let offset = self.offset;
+        let side = |ui: &mut egui::Ui, left: bool| {
+            let mut area = egui::ScrollArea::both()
+                .id_source(("diff_side", left))
+                .auto_shrink([false, false]);
+            if lock {
+                area = area.vertical_scroll_offset(offset);
+            }
+            area.show_rows(ui, row_height, rows.len(), |ui, range| {
+                for row in &rows[range] {
+                    let (left_bg, right_bg) = row_colors(row.kind, &visuals);
+                    if left {
+                        diff_cell(ui, half, row.left.as_ref(), left_bg);
+                    } else {
+                        diff_cell(ui, half, row.right.as_ref(), right_bg);
+                    }
+                }
+            })
+            .state
+            .offset
+            .y
+        };
+        let (left, right) = ui.columns(2, |columns| {
+            let left = side(&mut columns[0], true);
+            let right = side(&mut columns[1], false);
+            (left, right)
+        });
+        // Whichever side was scrolled this frame leads; the other catches
+        // up on the next one.
+        if lock {
+            let scrolled = if left != offset { left } else { right };
+            if scrolled != offset {
+                self.offset = scrolled;
+                ui.ctx().request_repaint();
+            }
+        } else {
+            self.offset = left;
+        }
+    }
+}
+
+fn row_colors(kind: RowKind, visuals: &egui::Visuals) -> (egui::Color32, egui::Color32) {
+    let (red, green) = if visuals.dark_mode {
+        (
+            egui::Color32::from_rgb(90, 30, 30),
+            egui::Color32::from_rgb(30, 80, 30),
+        )
+    } else {
+        (
+            egui::Color32::from_rgb(255, 215, 215),
+            egui::Color32::from_rgb(210, 250, 210),
+        )
+    };
+    match kind {
+        RowKind::Equal => (egui::Color32::TRANSPARENT, egui::Color32::TRANSPARENT),
+        RowKind::Removed => (red, egui::Color32::TRANSPARENT),
+        RowKind::Added => (egui::Color32::TRANSPARENT, green),
+        RowKind::Changed => (red, green),
+    }
+}
+
+fn diff_cell(ui: &mut egui::Ui, width: f32, line: Option<&(usize, String)>, bg: egui::Color32) {
+    let text = match line {
+        Some((number, text)) => format!("{number:>5}  {text}"),
+        None => String::new(),
+    };
+    let label =
+        egui::Label::new(egui::RichText::new(text).monospace().background_color(bg)).truncate(true);
+    ui.add_sized(
+        [width, ui.text_style_height(&egui::TextStyle::Monospace)],
+        label,
+    );
+}
+
+impl TextEditorApp {
+    /// Diffs the selection (or the whole buffer) against the clipboard.
+    pub(crate) fn compare_with_clipboard(&mut self, ctx: &egui::Context) {
+        let Some(clipboard) = clipboard::read_text() else {
+            return;
+        };
+        let Some(range) = self.selection_or(ctx, |text, _| 0..text.len()) else {
+            return;
+        };
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        let whole = range.len() == tab.content.len();
+        let left_title = if whole {
+            tab.title.clone()
+        } else {
+            trf("{name} (selection)", &[("name", &tab.title)])
+        };
+        self.diff_view = Some(DiffView::new(
+            left_title,
+            &tab.content[range],
+            tr("Clipboard"),
+            &clipboard,
+        ));
+    }
+
+    /// Diffs two files from disk, as asked for with `--diff`. Closing the
+    /// diff then closes the editor, which is what difftools are expected
+    /// to do.
+    pub(crate) fn open_diff_files(&mut self, left: &Path, right: &Path) {
+        let read = |path: &Path| {
+            fs::read(path).map(|bytes| {
+                let text = String::from_utf8_lossy(&bytes);
+                text.strip_prefix(UTF8_BOM).unwrap_or(&text).to_string()
+            })
+        };
+        match (read(left), read(right)) {
+            (Ok(left_text), Ok(right_text)) => {
+                self.diff_view = Some(DiffView::new(
+                    left.display().to_string(),
+                    &left_text,
+                    right.display().to_string(),
+                    &right_text,
+                ));
+                self.quit_with_view = true;
+            }
+            (Err(err), _) | (_, Err(err)) => self.notifications.error(trf(
+                "Could not read the files to compare: {error}",
+                &[("error", &err)],
+            )),
+        }
+    }
+
+    pub(crate) fn show_diff_window(&mut self, ctx: &egui::Context) {
+        let Some(view) = &mut self.diff_view else {
+            return;
+        };
+        let mut open = true;
+        egui::Window::new(tr("Diff"))
+            .default_size((800.0, 500.0))
+            .open(&mut open)
+            .show(ctx, |ui| view.ui(ui));
+        if !open {
+            self.diff_view = None;
+            if std::mem::take(&mut self.quit_with_view) {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Periodic check that open files still exist. A tab whose file was deleted
+//! or moved away is flagged, and a banner above the editor offers to keep
+//! the buffer in memory, save it elsewhere or close it, rather than letting
+//! the next save quietly recreate the file.
+
+use crate::TextEditorApp;
+use crate::i18n::{tr, trf};
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiskState {
+    #[default]
+    Present,
+    /// The file is gone and the user has not decided what to do yet.
+    Deleted,
+    /// The file is gone and the user chose to keep the buffer open.
+    Kept,
+}
+
+pub struct DiskCheck {
+    last_check: Instant,
+}
+
+impl Default for DiskCheck {
+    fn default() -> Self {
+        Self {
+            last_check: Instant::now(),
+        }
+    }
+}
+
+impl TextEditorApp {
+    /// Updates the disk state of every tab backed by a file, when due.
+    pub(crate) fn check_open_files(&mut self, ctx: &egui::Context) {
+        let interval = self.poll_interval(CHECK_INTERVAL);
+        let elapsed = self.disk_check.last_check.elapsed();
+        if elapsed < interval {
+            ctx.request_repaint_after(interval - elapsed);
+            return;
+        }
+        self.disk_check.last_check = Instant::now();
+        ctx.request_repaint_after(interval);
+        for tab in self.tabs.values_mut() {
+            let Some(path) = &tab.path else {
+                continue;
+            };
+            let exists = path.try_exists().unwrap_or(true);
+            match (exists, tab.disk_state) {
+                (false, DiskState::Present) => {
+                    tab.disk_state = DiskState::Deleted;
+                    self.notifications.warning(trf(
+                        "{path} was deleted or moved on disk",
+                        &[("path", &path.display())],
+                    ));
+                }
+                (true, DiskState::Deleted | DiskState::Kept) => {
+                    tab.disk_state = DiskState::Present;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// The banner shown above the editor while the active tab's file is
+    /// missing and the user has not decided what to do.
+    pub(crate) fn deleted_file_banner(&mut self, ui: &mut egui::Ui) {
+        let Some(key) = self.active_tab.clone() else {
+            return;
+        };
+        if self.tabs[&key].disk_state != DiskState::Deleted {
+            return;
+        }
+        let mut keep = false;
+        let mut save_as = false;
+        let mut close = false;
+        egui::Frame::none()
+            .fill(ui.visuals().warn_fg_color.gamma_multiply(0.15))
+            .inner_margin(6.0)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.colored_label(ui.visuals().warn_fg_color, "⚠");
+                    ui.label(tr("This file was deleted or moved on disk."));
+                    keep = ui.button(tr("Keep in Memory")).clicked();
+                    save_as = ui.button(tr("Save As…")).clicked();
+                    close = ui.button(tr("Close")).clicked();
+                });
+            });
+        if keep && let Some(tab) = self.tabs.get_mut(&key) {
+            tab.disk_state = DiskState::Kept;
+        }
+        if save_as {
+            self.save_active_as();
+        }
+        if close {
+            self.close_tab(&key);
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! Read-only text views of PDF and Word documents. Extraction runs on a
+//! background thread since large PDFs can take a while.
+
+use crate::archive::{ArchiveKind, read_entry};
+use crate::i18n::{tr, trf};
+use crate::jobs::CancelToken;
+use crate::{FileTab, TextEditorApp};
+use eframe::egui;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, channel};
+use std::thread;
+
+#[derive(Clone, Copy)]
+enum DocumentKind {
+    Pdf,
+    Docx,
+}
+
+fn kind(path: &Path) -> Option<DocumentKind> {
+    match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+        "pdf" => Some(DocumentKind::Pdf),
+        "docx" => Some(DocumentKind::Docx),
+        _ => None,
+    }
+}
+
+pub fn is_document(path: &Path) -> bool {
+    kind(path).is_some()
+}
+
+/// A document whose text is being extracted.
+struct Extraction {
+    path: PathBuf,
+    receiver: Receiver<Result<String, String>>,
+    cancel: CancelToken,
+}
+
+#[derive(Default)]
+pub struct DocumentExtractor {
+    /// Documents opened since the last frame, waiting to be started.
+    pub queued: Vec<PathBuf>,
+    pending: Vec<Extraction>,
+}
+
+fn extract(path: &Path, kind: DocumentKind) -> Result<String, String> {
+    match kind {
+        DocumentKind::Pdf => pdf_extract::extract_text(path).map_err(|e| e.to_string()),
+        DocumentKind::Docx => {
+            let xml = read_entry(path, ArchiveKind::Zip, "word/document.xml")
+                .map_err(|e| e.to_string())?;
+            Ok(docx_text(&String::from_utf8_lossy(&xml)))
+        }
+    }
+}
+
+/// The text runs of a WordprocessingML body, one paragraph per line.
+fn docx_text(xml: &str) -> String {
+    let mut text = String::new();
+    let mut rest = xml;
+    let mut in_text = false;
+    while let Some(open) = rest.find('<') {
+        if in_text {
+            text.push_str(&unescape(&rest[..open]));
+        }
+        let Some(close) = rest[open..].find('>') else {
+            break;
+        };
+        let tag = &rest[open + 1..open + close];
+        let name = tag
+            .trim_end_matches('/')
+            .split_whitespace()
+            .next()
+            .unwrap_or_default();
+        match name {
+            "w:t" => in_text = !tag.ends_with('/'),
+            "/w:t" => in_text = false,
+            "/w:p" => text.push('\n'),
+            "w:tab" => text.push('\t'),
+            "w:br" | "w:cr" => text.push('\n'),
+            _ => {}
+        }
+        rest = &rest[open + close + 1..];
+    }
+    text
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+impl TextEditorApp {
+    /// Starts extracting the text of the document at `path`; the tab opens
+    /// once it is done.
+    fn start_extraction(&mut self, ctx: &egui::Context, path: &Path) {
+        let Some(kind) = kind(path) else {
+            return;
+        };
+        let (sender, receiver) = channel();
+        let ctx = ctx.clone();
+        let target = path.to_path_buf();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let job = self
+            .jobs
+            .start(trf("Extracting text from {name}", &[("name", &name)]), true);
+        let cancel = job.cancel_token();
+        thread::spawn(move || {
+            // Malformed PDFs can make the extractor panic; that only ends
+            // this thread and drops the sender. The extractors cannot be
+            // interrupted, so a cancelled job just has its result ignored.
+            let _job = job;
+            let _ = sender.send(extract(&target, kind));
+            ctx.request_repaint();
+        });
+        self.documents.pending.push(Extraction {
+            path: path.to_path_buf(),
+            receiver,
+            cancel,
+        });
+    }
+
+    /// Starts queued extractions and opens tabs for finished ones.
+    pub(crate) fn poll_documents(&mut self, ctx: &egui::Context) {
+        for path in std::mem::take(&mut self.documents.queued) {
+            self.start_extraction(ctx, &path);
+        }
+        let mut finished = Vec::new();
+        self.documents
+            .pending
+            .retain(|extraction| match extraction.receiver.try_recv() {
+                _ if extraction.cancel.is_cancelled() => false,
+                Ok(result) => {
+                    finished.push((extraction.path.clone(), result));
+                    false
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => true,
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    finished.push((
+                        extraction.path.clone(),
+                        Err(tr("the document could not be read")),
+                    ));
+                    false
+                }
+            });
+        for (path, result) in finished {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            match result {
+                Ok(content) => self.add_tab(FileTab {
+                    title: format!("{name} (text)"),
+                    content,
+                    read_only: true,
+                    ..Default::default()
+                }),
+                Err(message) => self.notifications.error(trf(
+                    "Could not extract text from {name}: {error}",
+                    &[("name", &name), ("error", &message)],
+                )),
+            }
+        }
+    }
+}
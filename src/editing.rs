@@ -0,0 +1,196 @@
+//! Helpers for commands that read or rewrite the active buffer around the
+//! caret. Positions are character indices, matching egui's `CCursor`.
+
+use crate::motion::take_key;
+use crate::{FileTab, TextEditorApp};
+use eframe::egui;
+use egui::text::{CCursor, CCursorRange};
+use egui::text_edit::TextEditState;
+use std::ops::Range;
+use std::path::Path;
+
+/// Id of the `TextEdit` showing the tab stored under `tab_key`.
+pub fn editor_id(tab_key: &str) -> egui::Id {
+    egui::Id::new(("editor", tab_key))
+}
+
+/// Converts a character index into a byte offset in `text`.
+pub fn char_to_byte(text: &str, char_index: usize) -> usize {
+    text.char_indices()
+        .nth(char_index)
+        .map_or(text.len(), |(i, _)| i)
+}
+
+/// Converts a byte offset into a character index in `text`.
+pub fn byte_to_char(text: &str, byte_index: usize) -> usize {
+    text[..byte_index].chars().count()
+}
+
+impl TextEditorApp {
+    pub(crate) fn editor_has_focus(&self, ctx: &egui::Context) -> bool {
+        self.active_tab
+            .as_ref()
+            .is_some_and(|key| ctx.memory(|m| m.has_focus(editor_id(key))))
+    }
+
+    /// The selected character range in the active tab, or the caret position
+    /// as an empty range.
+    pub(crate) fn selection(&self, ctx: &egui::Context) -> Option<Range<usize>> {
+        let [min, max] = self.cursor_range(ctx)?.sorted();
+        Some(min.index..max.index)
+    }
+
+    pub(crate) fn set_selection(&self, ctx: &egui::Context, range: Range<usize>) {
+        self.set_cursor_range(
+            ctx,
+            CCursorRange::two(CCursor::new(range.start), CCursor::new(range.end)),
+        );
+    }
+
+    /// The raw cursor of the active tab, keeping track of which end moves.
+    pub(crate) fn cursor_range(&self, ctx: &egui::Context) -> Option<CCursorRange> {
+        let key = self.active_tab.as_ref()?;
+        TextEditState::load(ctx, editor_id(key))?
+            .cursor
+            .char_range()
+    }
+
+    pub(crate) fn set_cursor_range(&self, ctx: &egui::Context, range: CCursorRange) {
+        let Some(key) = self.active_tab.as_ref() else {
+            return;
+        };
+        let id = editor_id(key);
+        let mut state = TextEditState::load(ctx, id).unwrap_or_default();
+        state.cursor.set_char_range(Some(range));
+        state.store(ctx, id);
+    }
+
+    /// Puts the caret at the start of the 0-based `line` of the active tab,
+    /// focuses the editor and scrolls the line into view.
+    pub(crate) fn go_to_line(&mut self, ctx: &egui::Context, line: usize) {
+        self.go_to_position(ctx, line, 0);
+    }
+
+    /// Like [`Self::go_to_line`], but puts the caret at the 0-based character
+    /// `column`, clamped to the end of the line.
+    pub(crate) fn go_to_position(&mut self, ctx: &egui::Context, line: usize, column: usize) {
+        let Some(key) = self.active_tab.clone() else {
+            return;
+        };
+        let Some(tab) = self.tabs.get(&key) else {
+            return;
+        };
+        let mut lines = tab.content.split('\n');
+        let start: usize = lines
+            .by_ref()
+            .take(line)
+            .map(|l| l.chars().count() + 1)
+            .sum();
+        let width = lines
+            .next()
+            .map_or(0, |l| l.trim_end_matches('\r').chars().count());
+        let start = (start + column.min(width)).min(tab.content.chars().count());
+        self.set_selection(ctx, start..start);
+        ctx.memory_mut(|m| m.request_focus(editor_id(&key)));
+        self.scroll_to_caret = true;
+    }
+
+    /// Activates the tab showing `path`, opening the file if no tab does.
+    pub(crate) fn activate_or_open(&mut self, path: &Path) {
+        let open = self
+            .open_order
+            .iter()
+            .find(|key| self.tabs[*key].path.as_deref() == Some(path))
+            .cloned();
+        match open {
+            Some(key) => self.active_tab = Some(key),
+            None => self.open_file(path),
+        }
+    }
+
+    /// Activates the tab showing `path`, opening it if needed, and puts the
+    /// caret at the 0-based `line` and `column`. Every "jump to" in the
+    /// editor goes through here: search results, problems, symbols and
+    /// `file:line:col` arguments on the command line.
+    pub(crate) fn open_at(&mut self, ctx: &egui::Context, path: &Path, line: usize, column: usize) {
+        self.activate_or_open(path);
+        if self
+            .active_tab
+            .as_ref()
+            .and_then(|key| self.tabs[key].path.as_deref())
+            == Some(path)
+        {
+            self.go_to_position(ctx, line, column);
+        }
+    }
+
+    /// The active tab, unless it is read-only.
+    pub(crate) fn editable_tab(&mut self) -> Option<&mut FileTab> {
+        let tab = self.tabs.get_mut(self.active_tab.as_ref()?)?;
+        (!tab.read_only && tab.follow.is_none()).then_some(tab)
+    }
+
+    /// Replaces the byte range `range` of the active buffer with `text` and
+    /// selects the inserted text.
+    pub(crate) fn replace_range(&mut self, ctx: &egui::Context, range: Range<usize>, text: &str) {
+        let Some(tab) = self.editable_tab() else {
+            return;
+        };
+        let start = byte_to_char(&tab.content, range.start);
+        tab.content.replace_range(range, text);
+        let end = start + text.chars().count();
+        self.set_selection(ctx, start..end);
+    }
+
+    /// Replaces the selection with `text` and leaves the caret after it.
+    pub(crate) fn insert_text(&mut self, ctx: &egui::Context, text: &str) {
+        let Some(range) = self.selection_or(ctx, |_, pos| pos..pos) else {
+            return;
+        };
+        self.replace_range(ctx, range, text);
+        if let Some(end) = self.selection(ctx).map(|s| s.end) {
+            self.set_selection(ctx, end..end);
+        }
+    }
+
+    /// Byte range of the selection, or of the whole line block around the
+    /// caret selected by `expand` when nothing is selected.
+    pub(crate) fn selection_or(
+        &self,
+        ctx: &egui::Context,
+        expand: impl Fn(&str, usize) -> Range<usize>,
+    ) -> Option<Range<usize>> {
+        let content = &self.tabs.get(self.active_tab.as_ref()?)?.content;
+        let chars = self.selection(ctx)?;
+        let range = char_to_byte(content, chars.start)..char_to_byte(content, chars.end);
+        Some(if range.is_empty() {
+            expand(content, range.start)
+        } else {
+            range
+        })
+    }
+
+    /// Makes Tab indent with spaces up to the next tab stop in file types
+    /// that ask for it; otherwise the editor inserts a tab character.
+    pub(crate) fn handle_tab_key(&mut self, ctx: &egui::Context) {
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        let options = tab.editor_options(&self.settings);
+        if !options.insert_spaces
+            || !self.editor_has_focus(ctx)
+            || take_key(ctx, egui::Key::Tab, |m| m.is_none()).is_none()
+        {
+            return;
+        }
+        let Some(selection) = self.selection(ctx) else {
+            return;
+        };
+        let start = char_to_byte(&tab.content, selection.start);
+        let line_start = tab.content[..start].rfind('\n').map_or(0, |i| i + 1);
+        let column = tab.content[line_start..start].chars().count();
+        let width = options.tab_width.max(1);
+        let spaces = " ".repeat(width - column % width);
+        self.insert_text(ctx, &spaces);
+    }
+}
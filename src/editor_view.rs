@@ -0,0 +1,380 @@
+//! The central code editor widget for the active tab.
+
+use crate::TextEditorApp;
+use crate::conflicts::{Resolution, find_conflicts};
+use crate::editing::{byte_to_char, char_to_byte, editor_id};
+use crate::i18n::tr;
+use crate::{change_bars, notes, outline, profiler, prose, soft_wrap, tags, unicode_warnings};
+use eframe::egui;
+use egui::text::CCursor;
+use std::path::Path;
+
+impl TextEditorApp {
+    pub(crate) fn show_editor(&mut self, ui: &mut egui::Ui) {
+        let _scope = profiler::scope("editor");
+        let Some(tab_name) = self.active_tab.clone() else {
+            return;
+        };
+        let word_completion = self.word_completion_enabled();
+        let path_completion = self.settings.path_completion;
+        let gutter_color = self.custom_theme().and_then(|theme| theme.gutter());
+        let Some(tab) = self.tabs.get_mut(&tab_name) else {
+            return;
+        };
+        let syntax = tab
+            .syntax
+            .as_deref()
+            .and_then(|name| self.syntax_set.find_syntax_by_name(name))
+            .unwrap_or_else(|| self.syntax_set.find_syntax_plain_text());
+        let options = tab.editor_options(&self.settings);
+        let word_wrap = tab.prose || options.word_wrap;
+        let wrap_indicators = options.word_wrap && !tab.prose;
+        let change_bars =
+            self.settings.change_bars && !tab.read_only && tab.saved_content.is_some();
+        let highlight = &mut tab.highlight;
+        let syntax_set = &self.syntax_set;
+        let theme = &self.theme;
+        let prose = tab.prose;
+        let unicode_warnings = self.settings.unicode_warnings;
+        let line_spacing = self.settings.line_spacing;
+        let letter_spacing = self.settings.letter_spacing;
+        let markdown = tab.syntax.as_deref() == Some("Markdown");
+        let markup = tags::is_markup(tab.syntax.as_deref());
+        let folded = self.folds.of(&tab_name).filter(|_| markdown);
+        let long_line_limit =
+            (self.settings.long_line_warning && !prose).then_some(self.settings.wrap_column);
+        let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+            {
+                let _scope = profiler::scope("highlight");
+                highlight.update(text, syntax_set, syntax, theme);
+            }
+            let conflicts = find_conflicts(text);
+            let dark_mode = ui.visuals().dark_mode;
+            let font = if prose {
+                prose::font(ui.style())
+            } else {
+                egui::TextStyle::Monospace.resolve(ui.style())
+            };
+            let mut job = highlight.layout_job(
+                font.clone(),
+                long_line_limit,
+                ui.visuals().warn_fg_color.gamma_multiply(0.25),
+                |line| conflicts.iter().find_map(|c| c.line_color(line, dark_mode)),
+            );
+            if !prose && line_spacing != 1.0 {
+                let height = ui.fonts(|f| f.row_height(&font)) * line_spacing;
+                for section in &mut job.sections {
+                    section.format.line_height = Some(height);
+                }
+            }
+            if letter_spacing != 0.0 {
+                for section in &mut job.sections {
+                    section.format.extra_letter_spacing = letter_spacing;
+                }
+            }
+            if unicode_warnings {
+                unicode_warnings::mark(&mut job, ui.visuals().warn_fg_color);
+            }
+            if let Some(folded) = folded {
+                outline::hide(&mut job, &outline::hidden_ranges(text, folded));
+            }
+            if prose {
+                prose::space_lines(&mut job, &font);
+                job.wrap.max_width = wrap_width.min(prose::MAX_WIDTH);
+            } else if word_wrap {
+                job.wrap.max_width = wrap_width;
+            } else {
+                job.wrap.max_width = f32::INFINITY;
+            }
+            let _scope = profiler::scope("layout");
+            ui.fonts(|f| f.layout_job(job))
+        };
+
+        let scroll_to_caret = std::mem::take(&mut self.scroll_to_caret);
+        let typewriter = self.settings.typewriter_scrolling;
+        // Typewriter scrolling needs the room below the last line to center it.
+        let scroll_past_end = self.settings.scroll_past_end || typewriter;
+        let visible_height = ui.available_height();
+        let mut resolve = None;
+        let mut follow = None;
+        let mut revert = None;
+        // Wrapped text only ever scrolls vertically.
+        let mut scroll_area = egui::ScrollArea::new([!word_wrap, true])
+            .id_source(("editor_scroll", &tab_name))
+            .auto_shrink([false, false]);
+        if tab.restore_scroll {
+            scroll_area = scroll_area.scroll_offset(tab.scroll_offset);
+            tab.restore_scroll = false;
+        }
+        let gutter_width = if wrap_indicators {
+            soft_wrap::GUTTER_WIDTH
+        } else {
+            0.0
+        } + if change_bars {
+            change_bars::GUTTER_WIDTH
+        } else {
+            0.0
+        };
+        let scrolled = scroll_area.show(ui, |ui| {
+            let mut read_only_text = tab.content.as_str();
+            let text: &mut dyn egui::TextBuffer = if tab.read_only {
+                &mut read_only_text
+            } else {
+                &mut tab.content
+            };
+            let output = egui::TextEdit::multiline(text)
+                .id(editor_id(&tab_name))
+                .font(if prose {
+                    egui::TextStyle::Body
+                } else {
+                    egui::TextStyle::Monospace
+                })
+                .lock_focus(true)
+                .margin(egui::Margin {
+                    left: 4.0 + gutter_width,
+                    right: 4.0,
+                    top: 2.0,
+                    bottom: 2.0,
+                })
+                .desired_width(f32::INFINITY)
+                .min_size(ui.available_size())
+                .layouter(&mut layouter)
+                .show(ui);
+            if let Some(color) = gutter_color
+                && gutter_width > 0.0
+            {
+                let rect = output.response.rect;
+                ui.painter().rect_filled(
+                    egui::Rect::from_min_size(
+                        rect.min,
+                        egui::vec2(gutter_width + 2.0, rect.height()),
+                    ),
+                    0.0,
+                    color,
+                );
+            }
+            if let Some(range) = output.state.cursor.char_range() {
+                tab.caret = range.primary.index;
+            }
+            if unicode_warnings && let Some(pointer) = output.response.hover_pos() {
+                let cursor = output.galley.cursor_from_pos(pointer - output.galley_pos);
+                let index = char_to_byte(&tab.content, cursor.ccursor.index);
+                let before = tab.content[..index]
+                    .char_indices()
+                    .next_back()
+                    .map(|(i, _)| i);
+                if let Some(description) = unicode_warnings::describe(&tab.content, index)
+                    .or_else(|| before.and_then(|i| unicode_warnings::describe(&tab.content, i)))
+                {
+                    egui::show_tooltip_at_pointer(
+                        ui.ctx(),
+                        egui::Id::new("unicode_warning"),
+                        |ui| ui.label(description),
+                    );
+                }
+            }
+            // Ctrl+click follows a wikilink; holding Ctrl over one shows a hand.
+            if markdown
+                && ui.input(|i| i.modifiers.command)
+                && let Some(pointer) = output.response.hover_pos()
+            {
+                let cursor = output.galley.cursor_from_pos(pointer - output.galley_pos);
+                let index = char_to_byte(&tab.content, cursor.ccursor.index);
+                if let Some(target) = notes::wikilink_at(&tab.content, index) {
+                    ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+                    if output.response.clicked() {
+                        follow = Some(target.to_string());
+                    }
+                }
+            }
+            if let Some(folded) = folded {
+                // An ellipsis after each folded heading.
+                for range in outline::hidden_ranges(&tab.content, folded) {
+                    let end = output
+                        .galley
+                        .pos_from_ccursor(CCursor::new(byte_to_char(&tab.content, range.start)))
+                        .translate(output.galley_pos.to_vec2());
+                    ui.painter().text(
+                        end.left_center() + egui::vec2(8.0, 0.0),
+                        egui::Align2::LEFT_CENTER,
+                        "⋯",
+                        egui::TextStyle::Monospace.resolve(ui.style()),
+                        ui.visuals().weak_text_color(),
+                    );
+                }
+            }
+            self.rename_symbol.paint(
+                ui,
+                &tab_name,
+                &tab.content,
+                &output.galley,
+                output.galley_pos,
+            );
+            if self.settings.highlight_occurrences
+                && output.response.has_focus()
+                && let Some(range) = output.state.cursor.char_range()
+                && range.primary == range.secondary
+            {
+                self.word_highlight.paint(
+                    ui,
+                    &tab_name,
+                    &tab.content,
+                    range.primary.index,
+                    &output.galley,
+                    output.galley_pos,
+                );
+            }
+            if markup && let Some(range) = output.state.cursor.char_range() {
+                tags::paint_matching(
+                    ui,
+                    &tab.content,
+                    range.primary.index,
+                    &output.galley,
+                    output.galley_pos,
+                );
+            }
+            if wrap_indicators {
+                soft_wrap::show_indicators(
+                    ui,
+                    &output.galley,
+                    output.galley_pos,
+                    &mut self.wrapped_rows,
+                );
+            } else {
+                self.wrapped_rows.clear();
+            }
+            if change_bars && let Some(saved) = &tab.saved_content {
+                let right = if wrap_indicators {
+                    output.galley_pos.x - soft_wrap::GUTTER_WIDTH
+                } else {
+                    output.galley_pos.x
+                };
+                revert = self
+                    .change_bars
+                    .show(ui, &tab_name, saved, &tab.content, &output, right);
+            }
+            if !prose && !options.rulers.is_empty() {
+                let font = egui::TextStyle::Monospace.resolve(ui.style());
+                let char_width = ui.fonts(|f| f.glyph_width(&font, ' ')) + letter_spacing;
+                let stroke = ui.visuals().widgets.noninteractive.bg_stroke;
+                let y_range = output.response.rect.y_range();
+                for &column in &options.rulers {
+                    let x = output.galley_pos.x + column as f32 * char_width;
+                    ui.painter().vline(x, y_range, stroke);
+                }
+            }
+            // The word suggestion, greyed after the caret.
+            self.word_completion.suggestion = None;
+            if word_completion
+                && output.response.has_focus()
+                && let Some(range) = output.state.cursor.char_range()
+                && range.primary == range.secondary
+            {
+                let caret = range.primary.index;
+                self.word_completion.update(&tab_name, &tab.content, caret);
+                if let Some((_, suggestion)) = &self.word_completion.suggestion {
+                    let at = output
+                        .galley
+                        .pos_from_ccursor(CCursor::new(caret))
+                        .translate(output.galley_pos.to_vec2());
+                    let font = if prose {
+                        prose::font(ui.style())
+                    } else {
+                        egui::TextStyle::Monospace.resolve(ui.style())
+                    };
+                    ui.painter().text(
+                        at.left_center(),
+                        egui::Align2::LEFT_CENTER,
+                        suggestion,
+                        font,
+                        ui.visuals().weak_text_color(),
+                    );
+                }
+            }
+            // File paths being typed, listed below the caret.
+            if output.response.has_focus() {
+                match output.state.cursor.char_range() {
+                    Some(range)
+                        if path_completion
+                            && !tab.read_only
+                            && range.primary == range.secondary =>
+                    {
+                        let caret = range.primary.index;
+                        let anchor = output
+                            .galley
+                            .pos_from_ccursor(CCursor::new(caret))
+                            .translate(output.galley_pos.to_vec2())
+                            .left_bottom();
+                        self.path_completion.update(
+                            &tab_name,
+                            tab.path.as_deref().and_then(Path::parent),
+                            &self.folders,
+                            &tab.content,
+                            caret,
+                            anchor,
+                        );
+                    }
+                    _ => self.path_completion.hide(),
+                }
+            }
+            if scroll_past_end {
+                let row_height = output.galley.rows.last().map_or(0.0, |row| row.height());
+                ui.add_space((visible_height - row_height).max(0.0));
+            }
+            // Keep the caret centered after typing or moving it with the keyboard.
+            let typed = typewriter
+                && output.response.has_focus()
+                && ui.input(|i| {
+                    i.events.iter().any(|e| {
+                        matches!(
+                            e,
+                            egui::Event::Key { pressed: true, .. } | egui::Event::Text(_)
+                        )
+                    })
+                });
+            if (scroll_to_caret || typed)
+                && let Some(range) = output.state.cursor.char_range()
+            {
+                let rect = output
+                    .galley
+                    .pos_from_ccursor(CCursor::new(range.primary.index))
+                    .translate(output.galley_pos.to_vec2());
+                ui.scroll_to_rect(rect, Some(egui::Align::Center));
+            }
+
+            if !tab.read_only {
+                for (index, conflict) in find_conflicts(&tab.content).iter().enumerate() {
+                    // Buttons go right after the `<<<<<<<` marker text.
+                    let marker_end = conflict.ours.start.saturating_sub(1);
+                    let anchor = output
+                        .galley
+                        .pos_from_ccursor(CCursor::new(byte_to_char(&tab.content, marker_end)))
+                        .translate(output.galley_pos.to_vec2());
+                    let rect = egui::Rect::from_min_size(
+                        anchor.right_top() + egui::vec2(16.0, 0.0),
+                        egui::vec2(320.0, anchor.height()),
+                    );
+                    ui.allocate_ui_at_rect(rect, |ui| {
+                        ui.horizontal(|ui| {
+                            for resolution in Resolution::ALL {
+                                if ui.small_button(tr(resolution.label())).clicked() {
+                                    resolve = Some((index, resolution));
+                                }
+                            }
+                        });
+                    });
+                }
+            }
+        });
+        tab.scroll_offset = scrolled.state.offset;
+        if let Some((index, resolution)) = resolve {
+            self.resolve_conflict(ui.ctx(), index, resolution);
+        }
+        if let Some(hunk) = revert {
+            self.revert_hunk(ui.ctx(), &hunk);
+        }
+        if let Some(target) = follow {
+            self.follow_wikilink(ui.ctx(), &target);
+        }
+    }
+}
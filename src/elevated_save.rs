@@ -0,0 +1,298 @@
+//! Saving files the editor may not write itself, such as system
+//! configuration. When a save fails with "permission denied" the editor
+//! offers to copy the content into place through the platform's elevation
+//! helper (pkexec, osascript or a UAC prompt), showing the exact command
+//! first.
+
+use crate::TextEditorApp;
+use crate::audit_log::Snapshot;
+use crate::i18n::{tr, trf};
+use eframe::egui;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc::{Receiver, channel};
+use std::thread;
+
+/// A save that was refused and may be retried with elevated privileges.
+struct Pending {
+    key: String,
+    path: PathBuf,
+    bytes: Vec<u8>,
+    /// Content hash of the buffer when it was saved.
+    hash: u64,
+    /// The file before the save, if saves are logged.
+    audit: Option<Snapshot>,
+    /// Where the content waits for the helper to copy it.
+    temp: PathBuf,
+    program: String,
+    args: Vec<String>,
+}
+
+impl Pending {
+    fn command_line(&self) -> String {
+        std::iter::once(self.program.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[derive(Default)]
+pub struct ElevatedSave {
+    pending: Option<Pending>,
+    running: Option<(Pending, Receiver<Result<(), String>>)>,
+}
+
+fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+}
+
+/// `text` as a PowerShell string literal.
+fn powershell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+/// The program and arguments that copy `temp` over `target` with elevated
+/// privileges. Copying over an existing file keeps its owner and mode.
+/// The paths are passed as arguments or quoted, never spliced into a
+/// script, so no file name can run commands with those privileges.
+fn elevation_command(temp: &Path, target: &Path) -> Option<(String, Vec<String>)> {
+    let (temp, target) = (temp.display(), target.display());
+    if cfg!(windows) {
+        // Each element reaches cmd as one argument; Windows file names
+        // cannot contain the double quotes around it.
+        let copy = [
+            "/c",
+            "copy",
+            "/y",
+            &format!("\"{temp}\""),
+            &format!("\"{target}\""),
+        ]
+        .map(powershell_quote)
+        .join(",");
+        Some((
+            "powershell".to_string(),
+            vec![
+                "-NoProfile".to_string(),
+                "-Command".to_string(),
+                format!(
+                    "Start-Process cmd -Verb RunAs -Wait -WindowStyle Hidden -ArgumentList {copy}"
+                ),
+            ],
+        ))
+    } else if cfg!(target_os = "macos") {
+        // The paths arrive as the script's arguments and reach the shell
+        // through `quoted form of`.
+        let script = "do shell script \"cp -- \" & quoted form of item 1 of argv & \" \" & \
+                      quoted form of item 2 of argv with administrator privileges";
+        Some((
+            "osascript".to_string(),
+            vec![
+                "-e".to_string(),
+                "on run argv".to_string(),
+                "-e".to_string(),
+                script.to_string(),
+                "-e".to_string(),
+                "end run".to_string(),
+                temp.to_string(),
+                target.to_string(),
+            ],
+        ))
+    } else if on_path("pkexec") {
+        Some((
+            "pkexec".to_string(),
+            vec![
+                "cp".to_string(),
+                "--".to_string(),
+                temp.to_string(),
+                target.to_string(),
+            ],
+        ))
+    } else {
+        None
+    }
+}
+
+/// Writes `bytes` to a new file at `path` that only the user can read, as
+/// it may hold the contents of a protected file.
+fn write_private(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    let result = file.write_all(bytes);
+    if result.is_err() {
+        let _ = fs::remove_file(path);
+    }
+    result
+}
+
+fn run(program: &str, args: &[String]) -> Result<(), String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        Err(if stderr.is_empty() {
+            output.status.to_string()
+        } else {
+            stderr
+        })
+    }
+}
+
+impl TextEditorApp {
+    /// Called when writing `path` was refused; asks whether to try again
+    /// with elevated privileges.
+    pub(crate) fn offer_elevated_save(
+        &mut self,
+        key: String,
+        path: PathBuf,
+        bytes: Vec<u8>,
+        hash: u64,
+        audit: Option<Snapshot>,
+    ) {
+        let temp = std::env::temp_dir().join(format!("rusty-editor-{}", uuid::Uuid::new_v4()));
+        let Some((program, args)) = elevation_command(&temp, &path) else {
+            self.notifications.error(trf(
+                "Could not save {path}: permission denied",
+                &[("path", &path.display())],
+            ));
+            return;
+        };
+        self.elevated_save.pending = Some(Pending {
+            key,
+            path,
+            bytes,
+            hash,
+            audit,
+            temp,
+            program,
+            args,
+        });
+    }
+
+    fn start_elevated_save(&mut self, ctx: &egui::Context, pending: Pending) {
+        if let Err(err) = write_private(&pending.temp, &pending.bytes) {
+            self.notifications.error(trf(
+                "Could not save {path}: {error}",
+                &[("path", &pending.path.display()), ("error", &err)],
+            ));
+            return;
+        }
+        let (sender, receiver) = channel();
+        let (program, args, temp) = (
+            pending.program.clone(),
+            pending.args.clone(),
+            pending.temp.clone(),
+        );
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let result = run(&program, &args);
+            let _ = fs::remove_file(&temp);
+            let _ = sender.send(result);
+            ctx.request_repaint();
+        });
+        self.elevated_save.running = Some((pending, receiver));
+    }
+
+    pub(crate) fn show_elevated_save_window(&mut self, ctx: &egui::Context) {
+        if let Some((_, receiver)) = &self.elevated_save.running
+            && let Ok(result) = receiver.try_recv()
+            && let Some((pending, _)) = self.elevated_save.running.take()
+        {
+            match result {
+                Ok(()) => {
+                    self.notifications.info(trf(
+                        "Saved {path} with elevated privileges",
+                        &[("path", &pending.path.display())],
+                    ));
+                    self.record_save(pending.audit, &pending.bytes);
+                    self.finish_save(&pending.key, pending.path, pending.hash);
+                }
+                Err(err) => self.notifications.error(trf(
+                    "Elevated save of {path} failed: {error}",
+                    &[("path", &pending.path.display()), ("error", &err)],
+                )),
+            }
+        }
+
+        let Some(pending) = &self.elevated_save.pending else {
+            return;
+        };
+        let mut confirm = false;
+        let mut cancel = false;
+        egui::Window::new(tr("Permission Denied"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(trf(
+                    "You do not have permission to write {path}.",
+                    &[("path", &pending.path.display())],
+                ));
+                ui.label(tr("Save it with elevated privileges by running:"));
+                ui.add(
+                    egui::Label::new(egui::RichText::new(pending.command_line()).monospace())
+                        .wrap(true),
+                );
+                ui.separator();
+                ui.horizontal(|ui| {
+                    confirm = ui.button(tr("Save as Administrator")).clicked();
+                    cancel = ui.button(tr("Cancel")).clicked();
+                });
+            });
+        if confirm && let Some(pending) = self.elevated_save.pending.take() {
+            self.start_elevated_save(ctx, pending);
+        } else if cancel {
+            self.elevated_save.pending = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn powershell_quote_doubles_single_quotes() {
+        assert_eq!(powershell_quote(r"C:\it's.txt"), r"'C:\it''s.txt'");
+    }
+
+    #[test]
+    fn paths_are_never_spliced_into_a_script() {
+        let target = Path::new("/etc/a'; touch /tmp/pwned; '\"b");
+        let temp = Path::new("/tmp/rusty-editor-temp");
+        let Some((_, args)) = elevation_command(temp, target) else {
+            return;
+        };
+        if cfg!(windows) {
+            return;
+        }
+        assert!(args.iter().any(|arg| arg == &target.display().to_string()));
+        assert!(
+            args.iter()
+                .all(|arg| arg == &target.display().to_string() || !arg.contains("pwned"))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn temp_file_is_private_and_new() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = std::env::temp_dir().join(format!("rusty-editor-test-{}", uuid::Uuid::new_v4()));
+        write_private(&path, b"secret").unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(write_private(&path, b"again").is_err());
+        assert!(fs::read(&path).unwrap() == b"secret");
+        fs::remove_file(&path).unwrap();
+    }
+}
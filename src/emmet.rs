@@ -0,0 +1,651 @@
+//! Emmet-style abbreviations: in HTML, Tab after `ul>li.item$*3>a` writes
+//! out the nested elements, and in CSS, Tab after `m10-20` or `df` writes
+//! out the declaration. The caret lands on the first empty attribute or
+//! element, or the value of a declaration without one.
+//!
+//! HTML supports elements with `#id`, `.class`, `[attr=value]` and
+//! `{text}`, the `>`, `+` and `^` operators, `(groups)` and `*count` with
+//! `$` numbering. CSS knows a fixed table of the common properties.
+
+use crate::TextEditorApp;
+use crate::editing::{byte_to_char, char_to_byte};
+use crate::motion::take_key;
+use crate::tags::VOID_ELEMENTS;
+use eframe::egui;
+
+/// Marks where the caret may go in an expansion; the first one wins.
+const CARET: char = '\u{0}';
+
+/// Tags expanded even without any operator, so other words still get a
+/// plain Tab.
+const KNOWN_TAGS: &[&str] = &[
+    "a",
+    "abbr",
+    "article",
+    "aside",
+    "audio",
+    "b",
+    "blockquote",
+    "body",
+    "br",
+    "button",
+    "canvas",
+    "caption",
+    "code",
+    "col",
+    "dd",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hr",
+    "html",
+    "i",
+    "iframe",
+    "img",
+    "input",
+    "label",
+    "legend",
+    "li",
+    "link",
+    "main",
+    "meta",
+    "nav",
+    "ol",
+    "option",
+    "p",
+    "pre",
+    "script",
+    "section",
+    "select",
+    "small",
+    "span",
+    "strong",
+    "style",
+    "summary",
+    "table",
+    "tbody",
+    "td",
+    "textarea",
+    "tfoot",
+    "th",
+    "thead",
+    "title",
+    "tr",
+    "ul",
+    "video",
+];
+
+/// Elements written on the same line as their parent.
+const INLINE_TAGS: &[&str] = &[
+    "a", "abbr", "b", "br", "code", "em", "i", "img", "input", "label", "small", "span", "strong",
+    "sub", "sup",
+];
+
+/// Attributes written out even when the abbreviation leaves them out.
+fn default_attributes(name: &str) -> &'static [(&'static str, &'static str)] {
+    match name {
+        "a" => &[("href", "")],
+        "img" => &[("src", ""), ("alt", "")],
+        "input" => &[("type", "text")],
+        "link" => &[("rel", "stylesheet"), ("href", "")],
+        "form" => &[("action", "")],
+        "label" => &[("for", "")],
+        "script" => &[("src", "")],
+        _ => &[],
+    }
+}
+
+/// The element a name-less abbreviation stands for inside `parent`.
+fn implicit_name(parent: Option<&str>) -> &'static str {
+    match parent {
+        Some("ul" | "ol") => "li",
+        Some("table" | "tbody" | "thead" | "tfoot") => "tr",
+        Some("tr") => "td",
+        Some("select") => "option",
+        _ => "div",
+    }
+}
+
+#[derive(Clone, Default)]
+struct Node {
+    name: String,
+    id: String,
+    classes: Vec<String>,
+    attributes: Vec<(String, String)>,
+    text: Option<String>,
+    children: Vec<Node>,
+    /// The number `$` stands for in this node's children.
+    index: Option<usize>,
+}
+
+/// Replaces each run of `$` in `s` with `index`, zero-padded to its length.
+fn number(s: &str, index: usize) -> String {
+    let mut out = String::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            out.push(c);
+            continue;
+        }
+        let mut width = 1;
+        while chars.next_if_eq(&'$').is_some() {
+            width += 1;
+        }
+        out.push_str(&format!("{index:0width$}"));
+    }
+    out
+}
+
+impl Node {
+    fn number(&mut self, index: usize) {
+        self.name = number(&self.name, index);
+        self.id = number(&self.id, index);
+        for class in &mut self.classes {
+            *class = number(class, index);
+        }
+        for (_, value) in &mut self.attributes {
+            *value = number(value, index);
+        }
+        if let Some(text) = &mut self.text {
+            *text = number(text, index);
+        }
+        for child in &mut self.children {
+            child.number(index);
+        }
+    }
+
+    fn fill_names(&mut self, parent: Option<&str>) {
+        if self.name.is_empty() {
+            self.name = implicit_name(parent).to_string();
+        }
+        let name = self.name.clone();
+        for child in &mut self.children {
+            child.fill_names(Some(&name));
+        }
+    }
+
+    fn is_inline(&self) -> bool {
+        INLINE_TAGS.contains(&self.name.as_str()) && self.children.iter().all(Node::is_inline)
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let matched = self.peek() == Some(c);
+        self.pos += usize::from(matched);
+        matched
+    }
+
+    /// Characters up to `end`, which is consumed.
+    fn until(&mut self, end: char) -> Option<String> {
+        let len = self.chars[self.pos..].iter().position(|c| *c == end)?;
+        let s = self.chars[self.pos..self.pos + len].iter().collect();
+        self.pos += len + 1;
+        Some(s)
+    }
+
+    fn name(&mut self) -> String {
+        let len = self.chars[self.pos..]
+            .iter()
+            .take_while(|c| c.is_alphanumeric() || matches!(c, '-' | ':' | '$' | '_'))
+            .count();
+        let s = self.chars[self.pos..self.pos + len].iter().collect();
+        self.pos += len;
+        s
+    }
+
+    fn count(&mut self) -> Option<usize> {
+        if !self.eat('*') {
+            return Some(1);
+        }
+        let len = self.chars[self.pos..]
+            .iter()
+            .take_while(|c| c.is_ascii_digit())
+            .count();
+        let s: String = self.chars[self.pos..self.pos + len].iter().collect();
+        self.pos += len;
+        s.parse().ok().filter(|n| (1..=100).contains(n))
+    }
+
+    fn attributes(&mut self, node: &mut Node) -> Option<()> {
+        let inside = self.until(']')?;
+        let mut rest = inside.trim();
+        while !rest.is_empty() {
+            let key_len = rest
+                .find(|c: char| c == '=' || c.is_whitespace())
+                .unwrap_or(rest.len());
+            let key = rest[..key_len].to_string();
+            rest = &rest[key_len..];
+            let value = if let Some(after) = rest.strip_prefix('=') {
+                if let Some(quoted) = after.strip_prefix('"') {
+                    let end = quoted.find('"')?;
+                    rest = &quoted[end + 1..];
+                    quoted[..end].to_string()
+                } else {
+                    let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                    rest = &after[end..];
+                    after[..end].to_string()
+                }
+            } else {
+                String::new()
+            };
+            node.attributes.push((key, value));
+            rest = rest.trim_start();
+        }
+        Some(())
+    }
+
+    /// An element or a group, repeated by its count.
+    fn term(&mut self) -> Option<Vec<Node>> {
+        let nodes = if self.eat('(') {
+            let nodes = self.sequence()?.0;
+            if !self.eat(')') {
+                return None;
+            }
+            nodes
+        } else {
+            let mut node = Node {
+                name: self.name(),
+                ..Default::default()
+            };
+            loop {
+                if self.eat('#') {
+                    node.id = self.name();
+                } else if self.eat('.') {
+                    node.classes.push(self.name());
+                } else if self.eat('[') {
+                    self.attributes(&mut node)?;
+                } else if self.eat('{') {
+                    node.text = Some(self.until('}')?);
+                } else {
+                    break;
+                }
+            }
+            if node.name.is_empty()
+                && node.id.is_empty()
+                && node.classes.is_empty()
+                && node.attributes.is_empty()
+                && node.text.is_none()
+            {
+                return None;
+            }
+            vec![node]
+        };
+        let count = self.count()?;
+        if count == 1 {
+            return Some(nodes);
+        }
+        let mut repeated = Vec::new();
+        for index in 1..=count {
+            for node in &nodes {
+                let mut node = node.clone();
+                node.number(index);
+                node.index = Some(index);
+                repeated.push(node);
+            }
+        }
+        Some(repeated)
+    }
+
+    /// Siblings up to the end, a `)`, or a `^` climbing out; returns how
+    /// many levels are still to climb.
+    fn sequence(&mut self) -> Option<(Vec<Node>, usize)> {
+        let mut out = Vec::new();
+        loop {
+            let mut nodes = self.term()?;
+            if self.eat('>') {
+                let (children, climb) = self.sequence()?;
+                for node in &mut nodes {
+                    let mut children = children.clone();
+                    if let Some(index) = node.index {
+                        children.iter_mut().for_each(|c| c.number(index));
+                    }
+                    node.children.extend(children);
+                }
+                out.extend(nodes);
+                match climb {
+                    0 => return Some((out, 0)),
+                    1 => continue,
+                    n => return Some((out, n - 1)),
+                }
+            }
+            out.extend(nodes);
+            if self.eat('+') {
+                continue;
+            }
+            let mut climb = 0;
+            while self.eat('^') {
+                climb += 1;
+            }
+            return Some((out, climb));
+        }
+    }
+}
+
+/// Parses an HTML abbreviation into its top-level elements.
+fn parse(abbreviation: &str) -> Option<Vec<Node>> {
+    let mut parser = Parser {
+        chars: abbreviation.chars().collect(),
+        pos: 0,
+    };
+    let (mut nodes, mut climb) = parser.sequence()?;
+    // Climbing past the top just adds more top-level siblings.
+    while climb > 0 && parser.peek().is_some() {
+        let (more, more_climb) = parser.sequence()?;
+        nodes.extend(more);
+        climb = more_climb;
+    }
+    if parser.peek().is_some() {
+        return None;
+    }
+    for node in &mut nodes {
+        node.fill_names(None);
+    }
+    Some(nodes)
+}
+
+fn open_tag(node: &Node) -> String {
+    let mut tag = format!("<{}", node.name);
+    if !node.id.is_empty() {
+        tag.push_str(&format!(" id=\"{}\"", node.id));
+    }
+    if !node.classes.is_empty() {
+        tag.push_str(&format!(" class=\"{}\"", node.classes.join(" ")));
+    }
+    let mut attributes = node.attributes.clone();
+    for (key, value) in default_attributes(&node.name) {
+        if !attributes.iter().any(|(k, _)| k == key) {
+            attributes.push((key.to_string(), value.to_string()));
+        }
+    }
+    for (key, value) in attributes {
+        let value = if value.is_empty() {
+            CARET.to_string()
+        } else {
+            value
+        };
+        tag.push_str(&format!(" {key}=\"{value}\""));
+    }
+    tag.push('>');
+    tag
+}
+
+/// Writes `node` on one line.
+fn render_inline(node: &Node, out: &mut String) {
+    out.push_str(&open_tag(node));
+    if VOID_ELEMENTS.contains(&node.name.as_str()) {
+        return;
+    }
+    if let Some(text) = &node.text {
+        out.push_str(text);
+    }
+    for child in &node.children {
+        render_inline(child, out);
+    }
+    if node.text.is_none() && node.children.is_empty() {
+        out.push(CARET);
+    }
+    out.push_str(&format!("</{}>", node.name));
+}
+
+fn render(node: &Node, indent: &str, depth: usize, lines: &mut Vec<String>) {
+    let prefix = indent.repeat(depth);
+    if node.children.iter().all(Node::is_inline) {
+        let mut line = prefix;
+        render_inline(node, &mut line);
+        lines.push(line);
+        return;
+    }
+    let mut open = prefix.clone() + &open_tag(node);
+    if let Some(text) = &node.text {
+        open.push_str(text);
+    }
+    lines.push(open);
+    for child in &node.children {
+        render(child, indent, depth + 1, lines);
+    }
+    lines.push(format!("{prefix}</{}>", node.name));
+}
+
+/// Expands an HTML abbreviation, with [`CARET`] marking the editable spots.
+fn expand_html(abbreviation: &str, indent: &str) -> Option<String> {
+    let explicit = abbreviation.contains(['>', '+', '^', '*', '.', '#', '[', '{', '(']);
+    if !explicit && !KNOWN_TAGS.contains(&abbreviation) {
+        return None;
+    }
+    let mut lines = Vec::new();
+    for node in parse(abbreviation)? {
+        render(&node, indent, 0, &mut lines);
+    }
+    Some(lines.join("\n"))
+}
+
+/// CSS properties by abbreviation, and whether their numbers take `px`.
+const CSS_PROPERTIES: &[(&str, &str, bool)] = &[
+    ("m", "margin", true),
+    ("mt", "margin-top", true),
+    ("mr", "margin-right", true),
+    ("mb", "margin-bottom", true),
+    ("ml", "margin-left", true),
+    ("p", "padding", true),
+    ("pt", "padding-top", true),
+    ("pr", "padding-right", true),
+    ("pb", "padding-bottom", true),
+    ("pl", "padding-left", true),
+    ("w", "width", true),
+    ("h", "height", true),
+    ("maw", "max-width", true),
+    ("mah", "max-height", true),
+    ("miw", "min-width", true),
+    ("mih", "min-height", true),
+    ("t", "top", true),
+    ("r", "right", true),
+    ("b", "bottom", true),
+    ("l", "left", true),
+    ("g", "gap", true),
+    ("fz", "font-size", true),
+    ("bdrs", "border-radius", true),
+    ("lh", "line-height", false),
+    ("z", "z-index", false),
+    ("op", "opacity", false),
+    ("fw", "font-weight", false),
+    ("c", "color", false),
+    ("bgc", "background-color", false),
+    ("bd", "border", false),
+    ("d", "display", false),
+    ("pos", "position", false),
+    ("ta", "text-align", false),
+    ("ov", "overflow", false),
+    ("cur", "cursor", false),
+];
+
+/// Whole declarations by abbreviation.
+const CSS_KEYWORDS: &[(&str, &str)] = &[
+    ("df", "display: flex;"),
+    ("dg", "display: grid;"),
+    ("db", "display: block;"),
+    ("di", "display: inline;"),
+    ("dib", "display: inline-block;"),
+    ("dn", "display: none;"),
+    ("posa", "position: absolute;"),
+    ("posr", "position: relative;"),
+    ("posf", "position: fixed;"),
+    ("poss", "position: sticky;"),
+    ("tac", "text-align: center;"),
+    ("tal", "text-align: left;"),
+    ("tar", "text-align: right;"),
+    ("fwb", "font-weight: bold;"),
+    ("ovh", "overflow: hidden;"),
+    ("ova", "overflow: auto;"),
+    ("curp", "cursor: pointer;"),
+    ("jcc", "justify-content: center;"),
+    ("jcsb", "justify-content: space-between;"),
+    ("aic", "align-items: center;"),
+    ("fxdc", "flex-direction: column;"),
+];
+
+/// One CSS value: `10` is `10px` where the property takes lengths, and the
+/// suffixes `p`, `e`, `r` and `x` stand for `%`, `em`, `rem` and `ex`.
+fn css_value(value: &str, lengths: bool) -> Option<String> {
+    if value.starts_with('#') {
+        return Some(value.to_string());
+    }
+    let digits = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(digits);
+    number.parse::<f64>().ok()?;
+    let unit = match unit {
+        "" if lengths && number != "0" => "px",
+        "" => "",
+        "p" => "%",
+        "e" => "em",
+        "r" => "rem",
+        "x" => "ex",
+        unit if unit.chars().all(|c| c.is_ascii_alphabetic()) => unit,
+        _ => return None,
+    };
+    Some(format!("{number}{unit}"))
+}
+
+/// Expands a CSS abbreviation, with [`CARET`] marking a missing value.
+fn expand_css(abbreviation: &str) -> Option<String> {
+    if let Some((_, declaration)) = CSS_KEYWORDS.iter().find(|(a, _)| *a == abbreviation) {
+        return Some(declaration.to_string());
+    }
+    let split = abbreviation
+        .find(|c: char| !c.is_ascii_lowercase())
+        .unwrap_or(abbreviation.len());
+    let (name, values) = abbreviation.split_at(split);
+    let (_, property, lengths) = CSS_PROPERTIES.iter().find(|(a, _, _)| *a == name)?;
+    if values.is_empty() {
+        return Some(format!("{property}: {CARET};"));
+    }
+    // `-` separates values; a doubled or leading one makes the next negative.
+    let mut parts = Vec::new();
+    let mut negative = false;
+    for part in values.split('-') {
+        if part.is_empty() {
+            negative = true;
+            continue;
+        }
+        let part = if negative {
+            format!("-{part}")
+        } else {
+            part.to_string()
+        };
+        parts.push(css_value(&part, *lengths)?);
+        negative = false;
+    }
+    Some(format!("{property}: {};", parts.join(" ")))
+}
+
+/// The abbreviation ending at byte offset `end` of `line`: everything back
+/// to the previous whitespace outside brackets and braces.
+fn abbreviation_before(line: &str, end: usize) -> &str {
+    let mut depth = 0i32;
+    let start = line[..end]
+        .char_indices()
+        .rev()
+        .find(|(_, c)| {
+            match c {
+                ']' | '}' => depth += 1,
+                '[' | '{' => depth -= 1,
+                _ => {}
+            }
+            depth == 0 && c.is_whitespace()
+        })
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    &line[start..end]
+}
+
+impl TextEditorApp {
+    /// Tab after an abbreviation in an HTML or CSS tab expands it.
+    pub(crate) fn handle_emmet(&mut self, ctx: &egui::Context) {
+        if !self.settings.emmet || !self.editor_has_focus(ctx) {
+            return;
+        }
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        let css = matches!(
+            tab.syntax.as_deref(),
+            Some("CSS" | "SCSS" | "Sass" | "LESS")
+        );
+        let html = tab
+            .syntax
+            .as_deref()
+            .is_some_and(|name| name.starts_with("HTML"));
+        if tab.read_only || !(css || html) {
+            return;
+        }
+        let Some(selection) = self.selection(ctx).filter(|s| s.is_empty()) else {
+            return;
+        };
+        let text = &tab.content;
+        let caret = char_to_byte(text, selection.start);
+        let line_start = text[..caret].rfind('\n').map_or(0, |i| i + 1);
+        let line = &text[line_start..];
+        let line = &line[..line.find('\n').unwrap_or(line.len())];
+        if line[caret - line_start..].starts_with(|c: char| c.is_alphanumeric()) {
+            return;
+        }
+        let abbreviation = abbreviation_before(line, caret - line_start);
+        if abbreviation.is_empty() {
+            return;
+        }
+        let indentation: String = line
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .collect();
+        let options = tab.editor_options(&self.settings);
+        let unit = if options.insert_spaces {
+            " ".repeat(options.tab_width)
+        } else {
+            "\t".to_string()
+        };
+        let expansion = if css {
+            expand_css(abbreviation)
+        } else {
+            expand_html(abbreviation, &unit)
+        };
+        let Some(expansion) = expansion else {
+            return;
+        };
+        if take_key(ctx, egui::Key::Tab, |m| m.is_none()).is_none() {
+            return;
+        }
+        let expansion = expansion.replace('\n', &format!("\n{indentation}"));
+        let start = caret - abbreviation.len();
+        let range = start..caret;
+        let offset = expansion.find(CARET).unwrap_or(expansion.len());
+        let caret = byte_to_char(text, start) + expansion[..offset].chars().count();
+        let expansion = expansion.replace(CARET, "");
+        self.replace_range(ctx, range, &expansion);
+        self.set_selection(ctx, caret..caret);
+    }
+}
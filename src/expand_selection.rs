@@ -0,0 +1,257 @@
+//! Expand Selection and its inverse. Each step grows the selection to the
+//! next larger unit around it: the word, the contents of the enclosing
+//! string or brackets and then the delimiters too, the line, the paragraph,
+//! the indented block and its parents, and finally the whole file. Shrink
+//! Selection walks back through the steps taken.
+//!
+//! The units come from bracket matching and indentation rather than a
+//! parser, so brackets inside strings and comments can throw them off.
+
+use crate::TextEditorApp;
+use eframe::egui;
+use std::ops::Range;
+
+/// The selections Expand Selection started from, so Shrink Selection can
+/// return to them.
+#[derive(Default)]
+pub struct SelectionSteps {
+    tab: String,
+    /// The selection made by the last expansion.
+    current: Range<usize>,
+    previous: Vec<Range<usize>>,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn opening(close: char) -> Option<char> {
+    match close {
+        ')' => Some('('),
+        ']' => Some('['),
+        '}' => Some('{'),
+        _ => None,
+    }
+}
+
+/// The word around `selection`, if it lies within one.
+fn word(chars: &[char], selection: &Range<usize>) -> Option<Range<usize>> {
+    if selection.end > selection.start && !chars[selection.clone()].iter().all(|c| is_word_char(*c))
+    {
+        return None;
+    }
+    let mut start = selection.start;
+    while start > 0 && is_word_char(chars[start - 1]) {
+        start -= 1;
+    }
+    let mut end = selection.end;
+    while end < chars.len() && is_word_char(chars[end]) {
+        end += 1;
+    }
+    (end > start).then_some(start..end)
+}
+
+/// The index of the bracket closing the one at `open`.
+fn matching_close(chars: &[char], open: usize) -> Option<usize> {
+    let mut stack = Vec::new();
+    for (i, c) in chars.iter().enumerate().skip(open) {
+        match c {
+            '(' | '[' | '{' => stack.push(*c),
+            ')' | ']' | '}' => {
+                if stack.pop() != opening(*c) {
+                    return None;
+                }
+                if stack.is_empty() {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The contents of every bracket pair around `selection`, and each pair
+/// with its brackets.
+fn brackets(chars: &[char], selection: &Range<usize>, out: &mut Vec<Range<usize>>) {
+    let mut closed = Vec::new();
+    for open in (0..selection.start).rev() {
+        match chars[open] {
+            c @ (')' | ']' | '}') => closed.push(c),
+            c @ ('(' | '[' | '{') => {
+                if closed.last().and_then(|c| opening(*c)) == Some(c) {
+                    closed.pop();
+                    continue;
+                }
+                if let Some(close) = matching_close(chars, open)
+                    && close >= selection.end
+                {
+                    out.push(open + 1..close);
+                    out.push(open..close + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The quoted strings on the line of `selection` that contain it, with and
+/// without their quotes.
+fn strings(
+    chars: &[char],
+    selection: &Range<usize>,
+    line: Range<usize>,
+    out: &mut Vec<Range<usize>>,
+) {
+    if selection.end > line.end {
+        return;
+    }
+    let mut i = line.start;
+    while i < line.end {
+        let quote = chars[i];
+        if !matches!(quote, '"' | '\'' | '`') {
+            i += 1;
+            continue;
+        }
+        let mut close = i + 1;
+        while close < line.end && chars[close] != quote {
+            close += if chars[close] == '\\' { 2 } else { 1 };
+        }
+        if close >= line.end {
+            return;
+        }
+        if i < selection.start && selection.end <= close {
+            out.push(i + 1..close);
+            out.push(i..close + 1);
+        }
+        i = close + 1;
+    }
+}
+
+/// The lines of `chars` as character ranges, without their line breaks.
+fn lines(chars: &[char]) -> Vec<Range<usize>> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    for (i, c) in chars.iter().enumerate() {
+        if *c == '\n' {
+            lines.push(start..i);
+            start = i + 1;
+        }
+    }
+    lines.push(start..chars.len());
+    lines
+}
+
+/// The next larger unit around `selection`.
+pub fn expand(chars: &[char], selection: Range<usize>) -> Option<Range<usize>> {
+    let lines = lines(chars);
+    let line_of = |pos: usize| lines.partition_point(|line| line.end < pos);
+    let first = line_of(selection.start);
+    let last = line_of(selection.end);
+    let is_blank = |line: usize| chars[lines[line].clone()].iter().all(|c| c.is_whitespace());
+    let indent = |line: usize| {
+        chars[lines[line].clone()]
+            .iter()
+            .take_while(|c| **c == ' ' || **c == '\t')
+            .count()
+    };
+    let span = |from: usize, to: usize| lines[from].start..lines[to].end;
+
+    let mut candidates = Vec::new();
+    candidates.extend(word(chars, &selection));
+    strings(chars, &selection, lines[first].clone(), &mut candidates);
+    brackets(chars, &selection, &mut candidates);
+    // The text of the lines, then the lines with their indentation.
+    let text_start = lines[first].start + indent(first);
+    let text_end = lines[last].end
+        - chars[lines[last].clone()]
+            .iter()
+            .rev()
+            .take_while(|c| c.is_whitespace())
+            .count();
+    if text_start < text_end {
+        candidates.push(text_start..text_end);
+    }
+    candidates.push(span(first, last));
+    // The paragraph, up to the blank lines around it.
+    let (mut top, mut bottom) = (first, last);
+    while top > 0 && !is_blank(top - 1) {
+        top -= 1;
+    }
+    while bottom + 1 < lines.len() && !is_blank(bottom + 1) {
+        bottom += 1;
+    }
+    candidates.push(span(top, bottom));
+    // Indented blocks, from the selection's indentation outwards.
+    let mut level = (first..=last)
+        .filter(|line| !is_blank(*line))
+        .map(indent)
+        .min();
+    while let Some(depth) = level {
+        let inside = |line: usize| is_blank(line) || indent(line) >= depth;
+        let (mut top, mut bottom) = (first, last);
+        while top > 0 && inside(top - 1) {
+            top -= 1;
+        }
+        while bottom + 1 < lines.len() && inside(bottom + 1) {
+            bottom += 1;
+        }
+        while top < first && is_blank(top) {
+            top += 1;
+        }
+        while bottom > last && is_blank(bottom) {
+            bottom -= 1;
+        }
+        candidates.push(span(top, bottom));
+        // The block around this one starts at the line introducing it.
+        level = top
+            .checked_sub(1)
+            .map(indent)
+            .filter(|outer| *outer < depth);
+        if level.is_some() {
+            candidates.push(span(top - 1, bottom));
+        }
+    }
+    candidates.push(0..chars.len());
+
+    candidates
+        .into_iter()
+        .filter(|c| {
+            c.start <= selection.start && selection.end <= c.end && c.len() > selection.len()
+        })
+        .min_by_key(|c| c.len())
+}
+
+impl TextEditorApp {
+    pub(crate) fn expand_selection(&mut self, ctx: &egui::Context) {
+        let (Some(key), Some(selection)) = (self.active_tab.clone(), self.selection(ctx)) else {
+            return;
+        };
+        let chars: Vec<char> = self.tabs[&key].content.chars().collect();
+        let Some(expanded) = expand(&chars, selection.clone()) else {
+            return;
+        };
+        let steps = &mut self.selection_steps;
+        if steps.tab != key || steps.current != selection {
+            steps.tab = key;
+            steps.previous.clear();
+        }
+        steps.previous.push(selection);
+        steps.current = expanded.clone();
+        self.set_selection(ctx, expanded);
+    }
+
+    pub(crate) fn shrink_selection(&mut self, ctx: &egui::Context) {
+        let (Some(key), Some(selection)) = (self.active_tab.as_ref(), self.selection(ctx)) else {
+            return;
+        };
+        let steps = &mut self.selection_steps;
+        if steps.tab != *key || steps.current != selection {
+            return;
+        }
+        if let Some(previous) = steps.previous.pop() {
+            steps.current = previous.clone();
+            self.set_selection(ctx, previous);
+        }
+    }
+}
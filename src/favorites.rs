@@ -0,0 +1,139 @@
+//! Favorites: files and folders from anywhere on disk pinned to the top of
+//! the sidebar. They are kept in the data directory, so every window and
+//! every opened folder shows the same list.
+
+use crate::i18n::tr;
+use crate::platform;
+use crate::settings::data_dir;
+use crate::{TextEditorApp, json_file};
+use eframe::egui;
+use std::path::{Path, PathBuf};
+
+const FAVORITES_FILE: &str = "favorites.json";
+
+fn favorites_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join(FAVORITES_FILE))
+}
+
+#[derive(Default)]
+pub struct Favorites {
+    /// In the order they were pinned.
+    pub paths: Vec<PathBuf>,
+}
+
+impl Favorites {
+    /// The stored favorites, with a message to show if they cannot be
+    /// read; the file is then not saved over.
+    pub fn load() -> (Self, Option<String>) {
+        let (paths, error) = json_file::load_or_default(favorites_path().as_deref());
+        (Favorites { paths }, error)
+    }
+
+    fn save(&self) {
+        if let Some(path) = favorites_path() {
+            let _ = json_file::save(&path, &self.paths);
+        }
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.paths.iter().any(|p| p == path)
+    }
+
+    pub fn add(&mut self, path: &Path) {
+        if !self.contains(path) {
+            self.paths.push(path.to_path_buf());
+            self.save();
+        }
+    }
+
+    pub fn remove(&mut self, path: &Path) {
+        self.paths.retain(|p| p != path);
+        self.save();
+    }
+
+    /// The context menu entry pinning or unpinning `path`.
+    pub fn menu_item(&mut self, ui: &mut egui::Ui, path: &Path) {
+        let pinned = self.contains(path);
+        let label = if pinned {
+            tr("Remove from Favorites")
+        } else {
+            tr("Add to Favorites")
+        };
+        if ui.button(label).clicked() {
+            if pinned {
+                self.remove(path);
+            } else {
+                self.add(path);
+            }
+            ui.close_menu();
+        }
+    }
+}
+
+impl TextEditorApp {
+    pub(crate) fn pin_active_file(&mut self) {
+        if let Some(path) = self
+            .active_tab
+            .as_ref()
+            .and_then(|key| self.tabs[key].path.clone())
+        {
+            self.favorites.add(&path);
+        }
+    }
+
+    /// The Favorites section at the top of the sidebar. Files open in a
+    /// tab, folders become the opened folder.
+    pub(crate) fn favorites_sidebar(&mut self, ui: &mut egui::Ui) {
+        let mut clicked = None;
+        ui.horizontal(|ui| {
+            ui.strong(tr("Favorites"));
+            ui.menu_button("+", |ui| {
+                if ui.button(tr("Add File…")).clicked() {
+                    ui.close_menu();
+                    if let Some(path) = platform::file_dialog().pick_file() {
+                        self.favorites.add(&path);
+                    }
+                }
+                if ui.button(tr("Add Folder…")).clicked() {
+                    ui.close_menu();
+                    if let Some(path) = platform::file_dialog().pick_folder() {
+                        self.favorites.add(&path);
+                    }
+                }
+            });
+        });
+        if self.favorites.paths.is_empty() {
+            ui.weak(tr("Pin files and folders with their context menu."));
+        }
+        for path in self.favorites.paths.clone() {
+            let name = path.file_name().map_or_else(
+                || path.display().to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            );
+            let label = if path.is_dir() {
+                format!("{name}/")
+            } else {
+                name
+            };
+            let mut response = ui
+                .selectable_label(false, label)
+                .on_hover_text(path.display().to_string());
+            if !path.exists() {
+                response = response.on_hover_text(tr("Not found"));
+            }
+            if response.clicked() {
+                clicked = Some(path.clone());
+            }
+            response.context_menu(|ui| self.favorites.menu_item(ui, &path));
+        }
+        ui.separator();
+        let Some(path) = clicked else {
+            return;
+        };
+        if path.is_dir() {
+            self.open_folder(path);
+        } else {
+            self.activate_or_open(&path);
+        }
+    }
+}
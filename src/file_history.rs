@@ -0,0 +1,142 @@
+//! The File History window: commits that touched the active file. Selecting
+//! a commit shows its change to the file in the diff viewer, and any
+//! revision can be opened as a read-only tab.
+
+use crate::diff::DiffView;
+use crate::git::{self, Commit};
+use crate::i18n::{tr, trf};
+use crate::{FileTab, TextEditorApp};
+use eframe::egui;
+use std::path::PathBuf;
+
+pub struct FileHistory {
+    repo: PathBuf,
+    title: String,
+    commits: Vec<Commit>,
+    selected: Option<usize>,
+}
+
+impl FileHistory {
+    /// The diff introduced by commit `index` to the file.
+    fn diff(&self, index: usize) -> DiffView {
+        let commit = &self.commits[index];
+        // The next (older) entry knows the file's name before a rename.
+        let old_path = self
+            .commits
+            .get(index + 1)
+            .map_or(&commit.path, |c| &c.path);
+        let old =
+            git::show_file(&self.repo, &format!("{}^", commit.hash), old_path).unwrap_or_default();
+        let new = git::show_file(&self.repo, &commit.hash, &commit.path).unwrap_or_default();
+        DiffView::new(
+            format!("{} @ {}^", old_path, commit.short_hash),
+            &old,
+            format!("{} @ {}", commit.path, commit.short_hash),
+            &new,
+        )
+    }
+}
+
+impl TextEditorApp {
+    pub(crate) fn show_file_history_for_active(&mut self) {
+        let Some(path) = self
+            .active_tab
+            .as_ref()
+            .and_then(|key| self.tabs.get(key))
+            .and_then(|tab| tab.path.clone())
+        else {
+            return;
+        };
+        let Some(repo) = git::repo_root(&path) else {
+            return;
+        };
+        let Some(file) = git::relative(&repo, &path) else {
+            return;
+        };
+        self.file_history = Some(FileHistory {
+            commits: git::file_log(&repo, &file),
+            title: file,
+            repo,
+            selected: None,
+        });
+    }
+
+    /// Opens the content of the file as of commit `index` in a read-only tab.
+    fn open_revision(&mut self, index: usize) {
+        let Some(history) = &self.file_history else {
+            return;
+        };
+        let commit = &history.commits[index];
+        let Some(content) = git::show_file(&history.repo, &commit.hash, &commit.path) else {
+            return;
+        };
+        let name = commit.path.rsplit('/').next().unwrap_or(&commit.path);
+        let title = format!("{name} @ {}", commit.short_hash);
+        let path = history.repo.join(&commit.path);
+        let syntax = self.detect_syntax(Some(&path), &content);
+        self.add_tab(FileTab {
+            title,
+            content,
+            syntax,
+            read_only: true,
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn show_file_history_window(&mut self, ctx: &egui::Context) {
+        let Some(history) = &mut self.file_history else {
+            return;
+        };
+        let mut open = true;
+        let mut clicked = None;
+        let mut open_revision = None;
+        egui::Window::new(trf("History: {name}", &[("name", &history.title)]))
+            .default_size((520.0, 360.0))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if history.commits.is_empty() {
+                    ui.label(tr("No commits touch this file."));
+                    return;
+                }
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        for (i, commit) in history.commits.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                let selected = history.selected == Some(i);
+                                let text = format!("{}  {}", commit.short_hash, commit.subject);
+                                if ui
+                                    .selectable_label(
+                                        selected,
+                                        egui::RichText::new(text).monospace(),
+                                    )
+                                    .on_hover_text(format!("{}, {}", commit.author, commit.date))
+                                    .clicked()
+                                {
+                                    clicked = Some(i);
+                                }
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        if ui.small_button(tr("Open")).clicked() {
+                                            open_revision = Some(i);
+                                        }
+                                        ui.weak(&commit.date);
+                                    },
+                                );
+                            });
+                        }
+                    });
+            });
+        if let Some(i) = clicked {
+            history.selected = Some(i);
+            self.diff_view = Some(history.diff(i));
+        }
+        if let Some(i) = open_revision {
+            self.open_revision(i);
+        }
+        if !open {
+            self.file_history = None;
+        }
+    }
+}
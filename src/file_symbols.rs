@@ -0,0 +1,95 @@
+//! Go to Symbol in File: a fuzzy picker over the symbols of the active
+//! buffer, or its headings in Markdown, each shown with the path of
+//! symbols or sections that contain it.
+
+use crate::TextEditorApp;
+use crate::i18n::tr;
+use crate::outline::headings;
+use crate::picker::PickerItem;
+use crate::symbols;
+use eframe::egui;
+
+/// Separates the parts of a breadcrumb.
+const SEPARATOR: &str = " › ";
+
+/// A symbol of the buffer with its nesting depth: the heading level, or
+/// the indentation of the line it is defined on.
+struct Entry {
+    name: String,
+    kind: &'static str,
+    line: usize,
+    depth: usize,
+}
+
+/// Picker items for `entries`, in buffer order, with the names of the
+/// enclosing entries as breadcrumbs.
+fn items(entries: Vec<Entry>) -> Vec<PickerItem<usize>> {
+    let mut open: Vec<(usize, String)> = Vec::new();
+    entries
+        .into_iter()
+        .map(|entry| {
+            while open.last().is_some_and(|(depth, _)| *depth >= entry.depth) {
+                open.pop();
+            }
+            let mut detail: Vec<&str> = open.iter().map(|(_, name)| name.as_str()).collect();
+            detail.push(entry.kind);
+            let detail = format!("{}  :{}", detail.join(SEPARATOR), entry.line + 1);
+            open.push((entry.depth, entry.name.clone()));
+            PickerItem {
+                value: entry.line,
+                label: entry.name,
+                detail,
+            }
+        })
+        .collect()
+}
+
+impl TextEditorApp {
+    /// The symbols of the active tab: headings for Markdown, otherwise
+    /// whatever [`symbols::extract`] finds for the file's extension.
+    fn file_symbols(&self) -> Vec<Entry> {
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return Vec::new();
+        };
+        if tab.syntax.as_deref() == Some("Markdown") {
+            return headings(&tab.content)
+                .into_iter()
+                .map(|heading| Entry {
+                    name: heading.title,
+                    kind: symbols::SymbolKind::Heading.label(),
+                    line: heading.line,
+                    depth: heading.level,
+                })
+                .collect();
+        }
+        let Some(path) = &tab.path else {
+            return Vec::new();
+        };
+        let lines: Vec<&str> = tab.content.lines().collect();
+        symbols::extract(path, &tab.content)
+            .into_iter()
+            .map(|symbol| {
+                let text = lines.get(symbol.line).copied().unwrap_or("");
+                Entry {
+                    depth: text.len() - text.trim_start().len(),
+                    kind: symbol.kind.label(),
+                    line: symbol.line,
+                    name: symbol.name,
+                }
+            })
+            .collect()
+    }
+
+    pub(crate) fn show_file_symbols(&mut self, ctx: &egui::Context) {
+        if !self.file_symbol_picker.open {
+            return;
+        }
+        let items = items(self.file_symbols());
+        if let Some(line) = self
+            .file_symbol_picker
+            .show(ctx, &tr("Go to Symbol in File"), items)
+        {
+            self.go_to_line(ctx, line);
+        }
+    }
+}
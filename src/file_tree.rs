@@ -0,0 +1,605 @@
+//! The file browser: the workspace folders as a tree in the sidebar. With
+//! more than one folder, each heads its own branch of the tree.
+//!
+//! The tree works from the keyboard as well as the mouse. Ctrl+Shift+E moves
+//! focus between the tree and the editor; while the tree has it, the arrows
+//! move and expand, Enter opens, F2 renames, Delete deletes and typing jumps
+//! to the next entry starting with what was typed.
+//!
+//! The filter box above the tree narrows it to the files whose path below
+//! its workspace folder fuzzy-matches, with the folders leading to them kept open.
+
+use crate::disk_check::DiskState;
+use crate::editing::editor_id;
+use crate::fuzzy;
+use crate::i18n::{tr, trf};
+use crate::project::{Entry, display_path, list_dir, walk_roots};
+use crate::{FileTab, TextEditorApp};
+use eframe::egui;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Seconds after which folder listings are read again, so files created
+/// outside the editor show up.
+const REFRESH_SECONDS: f64 = 2.0;
+
+/// Seconds between keystrokes before typing starts a new name.
+const JUMP_TIMEOUT: f64 = 1.0;
+
+/// One visible line of the tree.
+#[derive(Clone)]
+pub struct Row {
+    pub path: PathBuf,
+    pub depth: usize,
+    pub is_dir: bool,
+    /// A workspace folder heading its branch.
+    pub is_root: bool,
+}
+
+impl Row {
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+#[derive(Default)]
+pub struct FileTree {
+    expanded: HashSet<PathBuf>,
+    /// Folder listings read so far, folders first.
+    listings: HashMap<PathBuf, Vec<Entry>>,
+    pub filter: String,
+    /// Every file in the workspace, read once a filter is typed.
+    files: Option<Vec<PathBuf>>,
+    read_at: f64,
+    selected: Option<PathBuf>,
+    /// Whether the tree, rather than the editor, takes the keyboard.
+    pub focused: bool,
+    scroll_to_selected: bool,
+    jump: String,
+    jump_at: f64,
+    /// The file or folder waiting for the user to confirm its deletion.
+    pending_delete: Option<PathBuf>,
+}
+
+impl FileTree {
+    /// Forgets the folder listings so they are read again.
+    pub fn refresh(&mut self) {
+        self.listings.clear();
+        self.files = None;
+    }
+
+    fn listing(&mut self, dir: &Path) -> &[Entry] {
+        self.listings
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| list_dir(dir))
+    }
+
+    /// The visible rows of the workspace: the entries of `roots` and of
+    /// every expanded folder, or the matches of the filter and their
+    /// folders.
+    pub fn rows(&mut self, roots: &[PathBuf]) -> Vec<Row> {
+        let matches = (!self.filter.is_empty()).then(|| self.matches(roots));
+        let mut rows = Vec::new();
+        if let [root] = roots {
+            self.push_rows(root, 0, matches.as_ref(), &mut rows);
+            return rows;
+        }
+        for root in roots {
+            if matches
+                .as_ref()
+                .is_some_and(|matches| !matches.contains(root))
+            {
+                continue;
+            }
+            rows.push(Row {
+                path: root.clone(),
+                depth: 0,
+                is_dir: true,
+                is_root: true,
+            });
+            if self.is_expanded(root) {
+                self.push_rows(root, 1, matches.as_ref(), &mut rows);
+            }
+        }
+        rows
+    }
+
+    /// The files matching the filter and every folder above them.
+    fn matches(&mut self, roots: &[PathBuf]) -> HashSet<PathBuf> {
+        let files = self.files.get_or_insert_with(|| walk_roots(roots));
+        let mut matches = HashSet::new();
+        for file in files.iter() {
+            if fuzzy::score(&self.filter, &display_path(roots, file)).is_none() {
+                continue;
+            }
+            for path in file.ancestors() {
+                if !matches.insert(path.to_path_buf()) || roots.iter().any(|root| root == path) {
+                    break;
+                }
+            }
+        }
+        matches
+    }
+
+    fn push_rows(
+        &mut self,
+        dir: &Path,
+        depth: usize,
+        matches: Option<&HashSet<PathBuf>>,
+        rows: &mut Vec<Row>,
+    ) {
+        for entry in self.listing(dir).to_vec() {
+            let expanded = match matches {
+                Some(matches) if !matches.contains(&entry.path) => continue,
+                Some(_) => entry.is_dir,
+                None => entry.is_dir && self.expanded.contains(&entry.path),
+            };
+            rows.push(Row {
+                path: entry.path.clone(),
+                depth,
+                is_dir: entry.is_dir,
+                is_root: false,
+            });
+            if expanded {
+                self.push_rows(&entry.path, depth + 1, matches, rows);
+            }
+        }
+    }
+
+    fn is_expanded(&self, dir: &Path) -> bool {
+        !self.filter.is_empty() || self.expanded.contains(dir)
+    }
+
+    /// Expands or collapses `dir`; while filtering every folder shown stays
+    /// open.
+    pub fn expand(&mut self, dir: &Path) {
+        self.expanded.insert(dir.to_path_buf());
+    }
+
+    fn toggle(&mut self, dir: &Path) {
+        if self.filter.is_empty() && !self.expanded.remove(dir) {
+            self.expanded.insert(dir.to_path_buf());
+        }
+    }
+
+    fn select(&mut self, path: &Path) {
+        self.selected = Some(path.to_path_buf());
+        self.scroll_to_selected = true;
+    }
+
+    /// Expands the folders above `path` and selects it, if it lies in one
+    /// of `roots`.
+    pub fn reveal(&mut self, roots: &[PathBuf], path: &Path) -> bool {
+        let Some(root) = roots.iter().find(|root| path.starts_with(root)) else {
+            return false;
+        };
+        for dir in path.ancestors().skip(1) {
+            self.expanded.insert(dir.to_path_buf());
+            if dir == root {
+                break;
+            }
+        }
+        if !self.filter.is_empty() && !self.rows(roots).iter().any(|row| row.path == path) {
+            self.filter.clear();
+        }
+        self.select(path);
+        true
+    }
+
+    /// Moves the selection and expanded folders along with a rename.
+    pub fn renamed(&mut self, old: &Path, new: &Path) {
+        let moved = |path: &Path| match path.strip_prefix(old) {
+            Ok(rest) if rest.as_os_str().is_empty() => new.to_path_buf(),
+            Ok(rest) => new.join(rest),
+            Err(_) => path.to_path_buf(),
+        };
+        self.expanded = self.expanded.iter().map(|path| moved(path)).collect();
+        self.selected = self.selected.as_deref().map(moved);
+        self.refresh();
+    }
+
+    /// Selects the next row whose name starts with the text typed so far,
+    /// starting at the selection when the text grows and after it when a
+    /// new name is begun.
+    fn jump_to(&mut self, rows: &[Row], typed: &str, now: f64) {
+        if now - self.jump_at > JUMP_TIMEOUT {
+            self.jump.clear();
+        }
+        self.jump_at = now;
+        self.jump.push_str(&typed.to_lowercase());
+        let current = self
+            .selected
+            .as_ref()
+            .and_then(|path| rows.iter().position(|row| &row.path == path));
+        let start = match current {
+            Some(index) if self.jump.chars().count() == typed.chars().count() => index + 1,
+            Some(index) => index,
+            None => 0,
+        };
+        let found = (0..rows.len())
+            .map(|offset| &rows[(start + offset) % rows.len()])
+            .find(|row| row.name().to_lowercase().starts_with(&self.jump));
+        if let Some(row) = found {
+            self.select(&row.path.clone());
+        }
+    }
+}
+
+/// Flags the tabs of files at or below `path`, which was just deleted, as
+/// deleted and unsaved, so they show the deleted-file banner and ask before
+/// closing instead of the next save quietly recreating the file.
+fn mark_deleted(tabs: &mut HashMap<String, FileTab>, path: &Path) {
+    for tab in tabs.values_mut() {
+        if tab.path.as_ref().is_some_and(|p| p.starts_with(path)) {
+            tab.disk_state = DiskState::Deleted;
+            tab.saved_hash = None;
+        }
+    }
+}
+
+impl TextEditorApp {
+    /// Selects the active tab's file in the file browser.
+    pub(crate) fn reveal_active_file(&mut self) {
+        let Some(path) = self
+            .active_tab
+            .as_ref()
+            .and_then(|key| self.tabs[key].path.clone())
+        else {
+            return;
+        };
+        if !self.file_tree.reveal(&self.folders, &path) {
+            self.notifications
+                .info(tr("The active file is not in a workspace folder."));
+        }
+    }
+
+    /// Hands the keyboard back to the editor of the active tab.
+    fn focus_editor(&mut self, ctx: &egui::Context) {
+        self.file_tree.focused = false;
+        if let Some(key) = &self.active_tab {
+            ctx.memory_mut(|m| m.request_focus(editor_id(key)));
+        }
+    }
+
+    /// Moves the keyboard between the file browser and the editor.
+    pub(crate) fn toggle_file_tree_focus(&mut self, ctx: &egui::Context) {
+        if self.file_tree.focused {
+            self.focus_editor(ctx);
+            return;
+        }
+        if self.folders.is_empty() {
+            return;
+        }
+        if let Some(id) = ctx.memory(|m| m.focused()) {
+            ctx.memory_mut(|m| m.surrender_focus(id));
+        }
+        self.file_tree.focused = true;
+        let rows = self.file_tree.rows(&self.folders);
+        let active = self
+            .active_tab
+            .as_ref()
+            .and_then(|key| self.tabs[key].path.clone());
+        let selection_visible = |path: &Option<PathBuf>| {
+            path.as_ref()
+                .is_some_and(|path| rows.iter().any(|row| &row.path == path))
+        };
+        if selection_visible(&active) {
+            self.file_tree.select(&active.unwrap());
+        } else if !selection_visible(&self.file_tree.selected)
+            && let Some(row) = rows.first()
+        {
+            self.file_tree.select(&row.path);
+        }
+    }
+
+    /// The keys of the file browser while it has the keyboard.
+    pub(crate) fn handle_file_tree_keys(&mut self, ctx: &egui::Context) {
+        if !self.file_tree.focused || self.rename.open || self.file_tree.pending_delete.is_some() {
+            return;
+        }
+        // Clicking into the editor or any other field takes the keyboard.
+        if ctx.memory(|m| m.focused().is_some()) {
+            self.file_tree.focused = false;
+            return;
+        }
+        if self.folders.is_empty() {
+            self.file_tree.focused = false;
+            return;
+        }
+        use egui::Key;
+        let (key, typed, now) = ctx.input_mut(|i| {
+            let key = [
+                Key::ArrowUp,
+                Key::ArrowDown,
+                Key::ArrowLeft,
+                Key::ArrowRight,
+                Key::Home,
+                Key::End,
+                Key::Enter,
+                Key::F2,
+                Key::Delete,
+                Key::Escape,
+            ]
+            .into_iter()
+            .find(|key| i.consume_key(egui::Modifiers::NONE, *key));
+            let mut typed = String::new();
+            i.events.retain(|event| match event {
+                egui::Event::Text(text) => {
+                    typed.push_str(text);
+                    false
+                }
+                _ => true,
+            });
+            (key, typed, i.time)
+        });
+        let tree = &mut self.file_tree;
+        let rows = tree.rows(&self.folders);
+        if rows.is_empty() {
+            return;
+        }
+        if !typed.trim().is_empty() {
+            tree.jump_to(&rows, &typed, now);
+        }
+        let Some(key) = key else {
+            return;
+        };
+        let current = tree
+            .selected
+            .as_ref()
+            .and_then(|path| rows.iter().position(|row| &row.path == path));
+        let Some(index) = current else {
+            // Any key starts at the top when nothing is selected yet.
+            if key != Key::Escape {
+                tree.select(&rows[0].path);
+                return;
+            }
+            self.focus_editor(ctx);
+            return;
+        };
+        let row = rows[index].clone();
+        match key {
+            Key::ArrowUp => tree.select(&rows[index.saturating_sub(1)].path),
+            Key::ArrowDown => tree.select(&rows[(index + 1).min(rows.len() - 1)].path),
+            Key::Home => tree.select(&rows[0].path),
+            Key::End => tree.select(&rows[rows.len() - 1].path),
+            Key::ArrowRight if row.is_dir => {
+                if !tree.is_expanded(&row.path) {
+                    tree.toggle(&row.path);
+                } else if let Some(child) = rows.get(index + 1).filter(|r| r.depth > row.depth) {
+                    tree.select(&child.path);
+                }
+            }
+            Key::ArrowLeft => {
+                if row.is_dir && tree.filter.is_empty() && tree.expanded.contains(&row.path) {
+                    tree.toggle(&row.path);
+                } else if let Some(parent) = row.path.parent()
+                    && rows.iter().any(|r| r.path == parent)
+                {
+                    tree.select(parent);
+                }
+            }
+            Key::Enter if row.is_dir => tree.toggle(&row.path),
+            Key::Enter => {
+                self.activate_or_open(&row.path);
+                self.focus_editor(ctx);
+            }
+            // Workspace folders are removed from the workspace, not the disk.
+            Key::F2 | Key::Delete if row.is_root => {}
+            Key::F2 => self.start_rename_path(&row.path),
+            Key::Delete => tree.pending_delete = Some(row.path),
+            Key::Escape if !tree.filter.is_empty() => tree.filter.clear(),
+            Key::Escape => self.focus_editor(ctx),
+            _ => {}
+        }
+    }
+
+    /// The tree of the workspace folders in the sidebar.
+    pub(crate) fn file_tree_sidebar(&mut self, ui: &mut egui::Ui) {
+        let now = ui.input(|i| i.time);
+        let tree = &mut self.file_tree;
+        let favorites = &mut self.favorites;
+        if now - tree.read_at > REFRESH_SECONDS {
+            tree.refresh();
+            tree.read_at = now;
+        }
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut tree.filter)
+                .hint_text(tr("Filter files"))
+                .desired_width(f32::INFINITY),
+        );
+        let (escape, enter, down) = ui.input_mut(|i| {
+            (
+                i.key_pressed(egui::Key::Escape),
+                i.key_pressed(egui::Key::Enter),
+                response.has_focus() && i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+            )
+        });
+        if response.lost_focus() && escape {
+            tree.filter.clear();
+        }
+        let rows = tree.rows(&self.folders);
+        let mut clicked = None;
+        let mut removed = None;
+        let mut find_in = None;
+        // Down moves into the tree and Enter opens the first match.
+        if down && let Some(row) = rows.first() {
+            response.surrender_focus();
+            tree.focused = true;
+            tree.select(&row.path);
+        }
+        if response.lost_focus()
+            && enter
+            && !tree.filter.is_empty()
+            && let Some(row) = rows.iter().find(|row| !row.is_dir)
+        {
+            clicked = Some(row.clone());
+        }
+        egui::ScrollArea::vertical()
+            .id_source("file_tree")
+            .show(ui, |ui| {
+                for row in &rows {
+                    let selected = tree.selected.as_ref() == Some(&row.path);
+                    let mut label = egui::RichText::new(if row.is_dir {
+                        let icon = if tree.is_expanded(&row.path) {
+                            "▾"
+                        } else {
+                            "▸"
+                        };
+                        format!("{icon} {}", row.name())
+                    } else {
+                        row.name()
+                    });
+                    if row.is_root {
+                        label = label.strong();
+                    }
+                    let indent = row.depth as f32 * 12.0 + if row.is_dir { 0.0 } else { 14.0 };
+                    let response = ui
+                        .horizontal(|ui| {
+                            ui.add_space(indent);
+                            ui.selectable_label(selected, label)
+                        })
+                        .inner;
+                    if selected && tree.focused {
+                        let stroke = ui.visuals().selection.stroke;
+                        ui.painter()
+                            .rect_stroke(response.rect.expand(1.0), 2.0, stroke);
+                    }
+                    if selected && tree.scroll_to_selected {
+                        response.scroll_to_me(None);
+                        tree.scroll_to_selected = false;
+                    }
+                    let response = if row.is_root {
+                        response.on_hover_text(row.path.display().to_string())
+                    } else {
+                        response
+                    };
+                    if response.clicked() {
+                        clicked = Some(row.clone());
+                    }
+                    response.context_menu(|ui| {
+                        favorites.menu_item(ui, &row.path);
+                        if row.is_dir && ui.button(tr("Find in Folder…")).clicked() {
+                            find_in = Some(row.path.clone());
+                            ui.close_menu();
+                        }
+                        if row.is_root && ui.button(tr("Remove Folder from Workspace")).clicked() {
+                            removed = Some(row.path.clone());
+                            ui.close_menu();
+                        }
+                    });
+                }
+            });
+        if let Some(folder) = removed {
+            self.folders.retain(|f| *f != folder);
+        }
+        if let Some(folder) = find_in {
+            self.find_in_folder(&folder);
+        }
+        let Some(row) = clicked else {
+            return;
+        };
+        self.file_tree.selected = Some(row.path.clone());
+        if row.is_dir {
+            self.file_tree.toggle(&row.path);
+            self.file_tree.focused = true;
+        } else {
+            self.activate_or_open(&row.path);
+        }
+    }
+
+    /// Asks before deleting the file or folder chosen in the tree.
+    pub(crate) fn show_delete_confirm(&mut self, ctx: &egui::Context) {
+        let Some(path) = self.file_tree.pending_delete.clone() else {
+            return;
+        };
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let mut delete = false;
+        let mut cancel = false;
+        egui::Window::new(tr("Delete"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                let question = if path.is_dir() {
+                    trf(
+                        "Delete the folder {name} and everything in it?",
+                        &[("name", &name)],
+                    )
+                } else {
+                    trf("Delete {name}?", &[("name", &name)])
+                };
+                ui.label(question);
+                ui.weak(tr("This cannot be undone."));
+                ui.horizontal(|ui| {
+                    delete = ui.button(tr("Delete")).clicked();
+                    cancel = ui.button(tr("Cancel")).clicked();
+                });
+            });
+        cancel |= ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape));
+        if cancel {
+            self.file_tree.pending_delete = None;
+            return;
+        }
+        if !delete {
+            return;
+        }
+        self.file_tree.pending_delete = None;
+        let result = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        if let Err(err) = result {
+            self.notifications.error(trf(
+                "Could not delete {name}: {error}",
+                &[("name", &name), ("error", &err)],
+            ));
+            return;
+        }
+        // The selection moves to the entry after the deleted one.
+        let tree = &mut self.file_tree;
+        let rows = tree.rows(&self.folders);
+        let next = rows
+            .iter()
+            .skip_while(|row| row.path != path)
+            .find(|row| !row.path.starts_with(&path))
+            .or_else(|| rows.iter().take_while(|row| row.path != path).last())
+            .map(|row| row.path.clone());
+        tree.selected = next;
+        tree.expanded.retain(|dir| !dir.starts_with(&path));
+        tree.refresh();
+        mark_deleted(&mut self.tabs, &path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deleting_a_folder_flags_the_tabs_below_it() {
+        let tab = |path: &str| FileTab {
+            path: Some(PathBuf::from(path)),
+            content: "text".to_string(),
+            saved_hash: Some(crate::journal::content_hash("text")),
+            ..Default::default()
+        };
+        let mut tabs = HashMap::from([
+            ("a".to_string(), tab("/work/src/a.rs")),
+            ("b".to_string(), tab("/work/src/nested/b.rs")),
+            ("c".to_string(), tab("/work/srcs/c.rs")),
+        ]);
+        mark_deleted(&mut tabs, Path::new("/work/src"));
+        for key in ["a", "b"] {
+            assert!(tabs[key].disk_state == DiskState::Deleted);
+            assert!(tabs[key].is_modified());
+        }
+        assert!(tabs["c"].disk_state == DiskState::Present);
+        assert!(!tabs["c"].is_modified());
+    }
+}
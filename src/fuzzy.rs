@@ -0,0 +1,42 @@
+//! Small fuzzy matcher for pickers: the pattern must appear in the
+//! candidate as a case-insensitive subsequence. Consecutive matches and
+//! matches at word starts score higher.
+
+pub fn score(pattern: &str, candidate: &str) -> Option<i64> {
+    if pattern.is_empty() {
+        return Some(0);
+    }
+    let candidate: Vec<char> = candidate.chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous_match: Option<usize> = None;
+    for p in pattern.chars().flat_map(char::to_lowercase) {
+        let offset = candidate[next..]
+            .iter()
+            .position(|c| c.to_lowercase().eq(std::iter::once(p)))?;
+        let index = next + offset;
+        let at_word_start = index == 0
+            || !candidate[index - 1].is_alphanumeric()
+            || (candidate[index - 1].is_lowercase() && candidate[index].is_uppercase());
+        score += match previous_match {
+            Some(prev) if prev + 1 == index => 8,
+            _ if at_word_start => 6,
+            _ => 1,
+        };
+        score -= offset.min(10) as i64;
+        previous_match = Some(index);
+        next = index + 1;
+    }
+    Some(score)
+}
+
+/// Sorts `items` by descending score against `pattern`, dropping
+/// non-matches. Ties keep their original order.
+pub fn filter<T>(pattern: &str, items: Vec<T>, key: impl Fn(&T) -> &str) -> Vec<T> {
+    let mut scored: Vec<(i64, T)> = items
+        .into_iter()
+        .filter_map(|item| score(pattern, key(&item)).map(|s| (s, item)))
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, item)| item).collect()
+}
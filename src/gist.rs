@@ -0,0 +1,173 @@
+//! "Share as Gist": uploads the selection (or the whole buffer) to GitHub
+//! Gists using the token from the preferences and copies the link.
+
+use crate::TextEditorApp;
+use crate::clipboard;
+use crate::i18n::{tr, trf};
+use eframe::egui;
+use std::sync::mpsc::{Receiver, channel};
+use std::thread;
+use std::time::Duration;
+
+const GISTS_API: &str = "https://api.github.com/gists";
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Default)]
+pub struct GistShare {
+    pub show: bool,
+    file_name: String,
+    description: String,
+    public: bool,
+    content: String,
+    pending: Option<Receiver<Result<String, String>>>,
+    /// The URL of the created gist, or why it failed.
+    result: Option<Result<String, String>>,
+}
+
+fn upload(
+    token: &str,
+    file_name: &str,
+    description: &str,
+    public: bool,
+    content: &str,
+) -> Result<String, String> {
+    let mut files = serde_json::Map::new();
+    files.insert(
+        file_name.to_string(),
+        serde_json::json!({ "content": content }),
+    );
+    let body = serde_json::json!({
+        "description": description,
+        "public": public,
+        "files": files,
+    });
+    let response = ureq::post(GISTS_API)
+        .timeout(TIMEOUT)
+        .set("Authorization", &format!("Bearer {token}"))
+        .set("Accept", "application/vnd.github+json")
+        .set("User-Agent", "rusty-editor")
+        .send_json(body)
+        .map_err(|e| match e {
+            ureq::Error::Status(401, _) => tr("GitHub rejected the token"),
+            e => e.to_string(),
+        })?;
+    let json: serde_json::Value = response.into_json().map_err(|e| e.to_string())?;
+    json["html_url"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| tr("GitHub did not return a gist URL"))
+}
+
+impl TextEditorApp {
+    pub(crate) fn share_as_gist(&mut self, ctx: &egui::Context) {
+        let Some(range) = self.selection_or(ctx, |text, _| 0..text.len()) else {
+            return;
+        };
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        if tab.sensitive {
+            self.notifications.warning(trf(
+                "{name} is sensitive and is not shared; turn off privacy mode for it first",
+                &[("name", &tab.title)],
+            ));
+            return;
+        }
+        let gist = &mut self.gist;
+        gist.show = true;
+        gist.file_name = tab.title.clone();
+        gist.content = tab.content[range].to_string();
+        gist.result = None;
+    }
+
+    pub(crate) fn show_gist_window(&mut self, ctx: &egui::Context) {
+        let gist = &mut self.gist;
+        if let Some(pending) = &gist.pending
+            && let Ok(result) = pending.try_recv()
+        {
+            if let Ok(url) = &result {
+                clipboard::write_text(url);
+            }
+            gist.result = Some(result);
+            gist.pending = None;
+        }
+        if !gist.show {
+            return;
+        }
+        let token = self.settings.gist_token.trim().to_string();
+        let mut open = true;
+        let mut share = false;
+        egui::Window::new(tr("Share as Gist"))
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                egui::Grid::new("gist_form").num_columns(2).show(ui, |ui| {
+                    ui.label(tr("File name:"));
+                    ui.text_edit_singleline(&mut gist.file_name);
+                    ui.end_row();
+                    ui.label(tr("Description:"));
+                    ui.text_edit_singleline(&mut gist.description);
+                    ui.end_row();
+                    ui.label(tr("Visibility:"));
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut gist.public, false, tr("Secret"));
+                        ui.radio_value(&mut gist.public, true, tr("Public"));
+                    });
+                    ui.end_row();
+                });
+                ui.weak(trf(
+                    "{count} lines",
+                    &[("count", &gist.content.lines().count())],
+                ));
+
+                if token.is_empty() {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        tr("Set a GitHub token with the gist scope in Preferences first."),
+                    );
+                }
+                ui.horizontal(|ui| {
+                    let ready = !token.is_empty()
+                        && !gist.file_name.trim().is_empty()
+                        && gist.pending.is_none();
+                    if ui
+                        .add_enabled(ready, egui::Button::new(tr("Share")))
+                        .clicked()
+                    {
+                        share = true;
+                    }
+                    if gist.pending.is_some() {
+                        ui.spinner();
+                    }
+                });
+                match &gist.result {
+                    Some(Ok(url)) => {
+                        ui.hyperlink(url);
+                        ui.label(tr("Link copied to the clipboard."));
+                    }
+                    Some(Err(message)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, message);
+                    }
+                    None => {}
+                }
+            });
+        if share {
+            let (sender, receiver) = channel();
+            let ctx = ctx.clone();
+            let file_name = gist.file_name.trim().to_string();
+            let description = gist.description.clone();
+            let public = gist.public;
+            let content = gist.content.clone();
+            thread::spawn(move || {
+                let _ = sender.send(upload(&token, &file_name, &description, public, &content));
+                ctx.request_repaint();
+            });
+            gist.result = None;
+            gist.pending = Some(receiver);
+        }
+        if !open {
+            gist.show = false;
+        }
+    }
+}
@@ -0,0 +1,145 @@
+//! Thin wrappers around the `git` command line.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Runs `git` in `dir` and returns its stdout, or `None` if it failed.
+pub fn run(dir: &Path, args: &[&str]) -> Option<String> {
+    run_checked(dir, args).ok()
+}
+
+/// Like [`run`], but returns git's error message on failure.
+pub fn run_checked(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("Could not run git: {e}"))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// The working tree root of the repository containing `path`.
+pub fn repo_root(path: &Path) -> Option<PathBuf> {
+    let dir = if path.is_dir() { path } else { path.parent()? };
+    let root = run(dir, &["rev-parse", "--show-toplevel"])?;
+    Some(PathBuf::from(root.trim_end()))
+}
+
+/// `path` relative to `root`, with forward slashes as git expects.
+pub fn relative(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    Some(parts.join("/"))
+}
+
+/// The content of `file` (relative to `root`) at revision `rev`.
+pub fn show_file(root: &Path, rev: &str, file: &str) -> Option<String> {
+    run(root, &["show", &format!("{rev}:{file}")])
+}
+
+pub struct Commit {
+    pub hash: String,
+    pub short_hash: String,
+    pub author: String,
+    pub date: String,
+    pub subject: String,
+    /// The file's path, relative to the repository root, in this commit.
+    pub path: String,
+}
+
+/// Commits that touched `file`, newest first, following renames.
+pub fn file_log(root: &Path, file: &str) -> Vec<Commit> {
+    let Some(log) = run(
+        root,
+        &[
+            "log",
+            "--follow",
+            "--name-only",
+            "--date=short",
+            "--format=%x1e%H%x1f%h%x1f%an%x1f%ad%x1f%s",
+            "--",
+            file,
+        ],
+    ) else {
+        return Vec::new();
+    };
+    log.split('\x1e')
+        .filter_map(|entry| {
+            let mut lines = entry.lines();
+            let mut fields = lines.next()?.split('\x1f');
+            let mut field = || fields.next().map(str::to_string);
+            Some(Commit {
+                hash: field()?,
+                short_hash: field()?,
+                author: field()?,
+                date: field()?,
+                subject: field().unwrap_or_default(),
+                path: lines.find(|l| !l.is_empty()).unwrap_or(file).to_string(),
+            })
+        })
+        .collect()
+}
+
+/// The checked-out branch and how far it is from its upstream.
+#[derive(Clone, Default)]
+pub struct BranchStatus {
+    /// Branch name, or the short commit hash when detached.
+    pub head: String,
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+pub fn branch_status(root: &Path) -> Option<BranchStatus> {
+    let status = run(
+        root,
+        &[
+            "status",
+            "--porcelain=v2",
+            "--branch",
+            "--untracked-files=no",
+        ],
+    )?;
+    let mut branch = BranchStatus::default();
+    let mut oid = "";
+    for line in status.lines() {
+        if let Some(head) = line.strip_prefix("# branch.head ") {
+            branch.head = head.to_string();
+        } else if let Some(id) = line.strip_prefix("# branch.oid ") {
+            oid = id;
+        } else if let Some(counts) = line.strip_prefix("# branch.ab ") {
+            for count in counts.split_whitespace() {
+                if let Some(n) = count.strip_prefix('+') {
+                    branch.ahead = n.parse().unwrap_or(0);
+                } else if let Some(n) = count.strip_prefix('-') {
+                    branch.behind = n.parse().unwrap_or(0);
+                }
+            }
+        }
+    }
+    if branch.head == "(detached)" {
+        branch.head = oid.chars().take(7).collect();
+    }
+    Some(branch)
+}
+
+/// Local branch names, most recently committed first.
+pub fn local_branches(root: &Path) -> Vec<String> {
+    run(
+        root,
+        &[
+            "for-each-ref",
+            "--sort=-committerdate",
+            "--format=%(refname:short)",
+            "refs/heads",
+        ],
+    )
+    .map(|out| out.lines().map(str::to_string).collect())
+    .unwrap_or_default()
+}
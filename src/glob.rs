@@ -0,0 +1,63 @@
+//! Minimal shell-style glob matching (`*` and `?`), used for the
+//! user-editable file patterns in settings.
+
+/// Returns true if `name` matches `pattern` in its entirety.
+pub fn matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::matches;
+
+    #[test]
+    fn literal_and_wildcards() {
+        assert!(matches("Cargo.toml", "Cargo.toml"));
+        assert!(!matches("Cargo.toml", "Cargo.lock"));
+        assert!(matches("*.rs", "main.rs"));
+        assert!(!matches("*.rs", "main.rs.bak"));
+        assert!(matches("file?.txt", "file1.txt"));
+        assert!(!matches("file?.txt", "file.txt"));
+        assert!(matches("*", ""));
+        assert!(matches("a*b*c", "a-x-b-y-c"));
+        assert!(!matches("a*b*c", "a-x-c-y-b"));
+    }
+
+    #[test]
+    fn star_crosses_folders() {
+        assert!(matches("src/*", "src/ui/menu.rs"));
+        assert!(!matches("src/*", "tests/src.rs"));
+    }
+
+    #[test]
+    fn whole_name_must_match() {
+        assert!(!matches("rs", "main.rs"));
+        assert!(!matches(".env", "x.env"));
+        assert!(matches(".env*", ".env.local"));
+    }
+}
@@ -0,0 +1,203 @@
+//! Incremental syntax highlighting.
+//!
+//! Each tab owns a [`HighlightCache`] that remembers, per line, the syntect
+//! parser state at the start of the line together with the styled spans it
+//! produced. When the buffer changes only the lines from the first edit
+//! onwards are re-parsed, and parsing stops as soon as it reaches an
+//! unchanged line whose starting state matches the cached one.
+
+use crate::editing::char_to_byte;
+use eframe::egui;
+use egui::text::{LayoutJob, TextFormat};
+use std::ops::Range;
+use syntect::highlighting::{HighlightState, Highlighter, RangedHighlightIterator, Style, Theme};
+use syntect::parsing::{ParseState, ScopeStack, SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+struct CachedLine {
+    text: String,
+    parse_state: ParseState,
+    highlight_state: HighlightState,
+    spans: Vec<(Style, Range<usize>)>,
+}
+
+#[derive(Default)]
+pub struct HighlightCache {
+    /// Syntax and theme names the cached lines were produced with.
+    key: Option<(String, String)>,
+    lines: Vec<CachedLine>,
+}
+
+impl HighlightCache {
+    /// Brings the cache in sync with `text`, re-highlighting only what changed.
+    pub fn update(
+        &mut self,
+        text: &str,
+        syntax_set: &SyntaxSet,
+        syntax: &SyntaxReference,
+        theme: &Theme,
+    ) {
+        let key = (syntax.name.clone(), theme.name.clone().unwrap_or_default());
+        if self.key.as_ref() != Some(&key) {
+            self.lines.clear();
+            self.key = Some(key);
+        }
+
+        let new_lines: Vec<&str> = LinesWithEndings::from(text).collect();
+        let old_len = self.lines.len();
+        let new_len = new_lines.len();
+
+        let prefix = self
+            .lines
+            .iter()
+            .zip(&new_lines)
+            .take_while(|(old, new)| old.text == **new)
+            .count();
+        if prefix == old_len && prefix == new_len {
+            return;
+        }
+        let suffix = self.lines[prefix..]
+            .iter()
+            .rev()
+            .zip(new_lines[prefix..].iter().rev())
+            .take_while(|(old, new)| old.text == **new)
+            .count();
+
+        let highlighter = Highlighter::new(theme);
+        let (mut parse_state, mut highlight_state) = match self.lines.get(prefix) {
+            Some(line) => (line.parse_state.clone(), line.highlight_state.clone()),
+            None => match prefix.checked_sub(1).map(|i| &self.lines[i]) {
+                Some(last) => {
+                    let mut parse_state = last.parse_state.clone();
+                    let mut highlight_state = last.highlight_state.clone();
+                    highlight_line(
+                        &last.text,
+                        &mut parse_state,
+                        &mut highlight_state,
+                        syntax_set,
+                        &highlighter,
+                    );
+                    (parse_state, highlight_state)
+                }
+                None => (
+                    ParseState::new(syntax),
+                    HighlightState::new(&highlighter, ScopeStack::new()),
+                ),
+            },
+        };
+
+        let mut fresh = Vec::new();
+        let mut reuse_from = None;
+        for (i, line) in new_lines.iter().enumerate().skip(prefix) {
+            // Once we are inside the unchanged tail and the parser arrives in
+            // the same state as before, everything after this point is valid.
+            if i >= new_len - suffix {
+                let old = &self.lines[i + old_len - new_len];
+                if old.parse_state == parse_state && old.highlight_state == highlight_state {
+                    reuse_from = Some(i + old_len - new_len);
+                    break;
+                }
+            }
+            let line_parse_state = parse_state.clone();
+            let line_highlight_state = highlight_state.clone();
+            let spans = highlight_line(
+                line,
+                &mut parse_state,
+                &mut highlight_state,
+                syntax_set,
+                &highlighter,
+            );
+            fresh.push(CachedLine {
+                text: line.to_string(),
+                parse_state: line_parse_state,
+                highlight_state: line_highlight_state,
+                spans,
+            });
+        }
+
+        let tail = match reuse_from {
+            Some(j) => self.lines.split_off(j),
+            None => Vec::new(),
+        };
+        self.lines.truncate(prefix);
+        self.lines.extend(fresh);
+        self.lines.extend(tail);
+    }
+
+    /// Drops every cached line; the next [`Self::update`] starts over.
+    pub fn clear(&mut self) {
+        self.key = None;
+        self.lines = Vec::new();
+    }
+
+    /// Rough heap size of the cache in bytes. Parser states are counted by
+    /// their inline size only.
+    pub fn approx_bytes(&self) -> usize {
+        self.lines
+            .iter()
+            .map(|line| {
+                std::mem::size_of::<CachedLine>()
+                    + line.text.capacity()
+                    + line.spans.capacity() * std::mem::size_of::<(Style, Range<usize>)>()
+            })
+            .sum()
+    }
+
+    /// Builds an egui layout job from the cached spans. Characters past
+    /// `long_line_limit` are drawn on a `warning` background, and whole lines
+    /// can be tinted through `line_background`.
+    pub fn layout_job(
+        &self,
+        font_id: egui::FontId,
+        long_line_limit: Option<usize>,
+        warning: egui::Color32,
+        line_background: impl Fn(usize) -> Option<egui::Color32>,
+    ) -> LayoutJob {
+        let mut job = LayoutJob::default();
+        for (index, line) in self.lines.iter().enumerate() {
+            let background = line_background(index).unwrap_or_default();
+            let mut column = 0;
+            for (style, range) in &line.spans {
+                let text = &line.text[range.clone()];
+                let fg = style.foreground;
+                let format = TextFormat {
+                    background,
+                    ..TextFormat::simple(font_id.clone(), egui::Color32::from_rgb(fg.r, fg.g, fg.b))
+                };
+                let len = text.trim_end_matches(['\r', '\n']).chars().count();
+                match long_line_limit {
+                    Some(limit) if column + len > limit => {
+                        let split = char_to_byte(text, limit.saturating_sub(column));
+                        if split > 0 {
+                            job.append(&text[..split], 0.0, format.clone());
+                        }
+                        job.append(
+                            &text[split..],
+                            0.0,
+                            TextFormat {
+                                background: warning,
+                                ..format
+                            },
+                        );
+                    }
+                    _ => job.append(text, 0.0, format),
+                }
+                column += len;
+            }
+        }
+        job
+    }
+}
+
+fn highlight_line(
+    line: &str,
+    parse_state: &mut ParseState,
+    highlight_state: &mut HighlightState,
+    syntax_set: &SyntaxSet,
+    highlighter: &Highlighter,
+) -> Vec<(Style, Range<usize>)> {
+    let ops = parse_state.parse_line(line, syntax_set).unwrap_or_default();
+    RangedHighlightIterator::new(highlight_state, &ops, line, highlighter)
+        .map(|(style, _, range)| (style, range))
+        .collect()
+}
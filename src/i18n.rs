@@ -0,0 +1,106 @@
+//! UI translations.
+//!
+//! Strings are looked up by their English text, so anything a catalog does
+//! not cover falls back to English. A catalog is a JSON object mapping the
+//! English text to its translation, with the language's own name under
+//! `"_name"`. Placeholders such as `{path}` are filled in by [`trf`] after
+//! translation. Catalogs shipped with the editor live in `locales/`; more
+//! can be added by dropping `<code>.json` into `<config dir>/locales/`,
+//! where they also override a bundled catalog of the same code.
+
+use crate::settings::config_dir;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::sync::RwLock;
+
+/// Catalogs compiled into the binary, by language code.
+const BUNDLED: &[(&str, &str)] = &[("de", include_str!("../locales/de.json"))];
+
+const NAME_KEY: &str = "_name";
+
+/// Translations of the active language; empty for English.
+static ACTIVE: RwLock<Option<HashMap<String, String>>> = RwLock::new(None);
+
+fn parse(json: &str) -> Option<HashMap<String, String>> {
+    serde_json::from_str(json).ok()
+}
+
+/// User catalogs from the config directory, by language code.
+fn user_catalogs() -> Vec<(String, String)> {
+    let Some(entries) = config_dir().and_then(|dir| fs::read_dir(dir.join("locales")).ok()) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let code = path.file_stem()?.to_string_lossy().to_string();
+            Some((code, fs::read_to_string(&path).ok()?))
+        })
+        .collect()
+}
+
+fn catalog(code: &str) -> Option<HashMap<String, String>> {
+    user_catalogs()
+        .into_iter()
+        .find(|(c, _)| c == code)
+        .and_then(|(_, json)| parse(&json))
+        .or_else(|| {
+            BUNDLED
+                .iter()
+                .find(|(c, _)| *c == code)
+                .and_then(|(_, json)| parse(json))
+        })
+}
+
+/// Every language that can be picked, as `(code, name)`, English first.
+pub fn available() -> Vec<(String, String)> {
+    let mut languages: Vec<(String, String)> = BUNDLED
+        .iter()
+        .map(|(code, json)| (code.to_string(), json.to_string()))
+        .collect();
+    for (code, json) in user_catalogs() {
+        languages.retain(|(c, _)| *c != code);
+        languages.push((code, json));
+    }
+    let mut languages: Vec<(String, String)> = languages
+        .into_iter()
+        .filter_map(|(code, json)| {
+            let name = parse(&json)?
+                .remove(NAME_KEY)
+                .unwrap_or_else(|| code.clone());
+            Some((code, name))
+        })
+        .collect();
+    languages.sort_by(|a, b| a.1.cmp(&b.1));
+    languages.insert(0, ("en".to_string(), "English".to_string()));
+    languages
+}
+
+/// Switches the UI to the language `code`; unknown codes mean English.
+pub fn set_language(code: &str) {
+    let strings = if code == "en" { None } else { catalog(code) };
+    if let Ok(mut active) = ACTIVE.write() {
+        *active = strings;
+    }
+}
+
+/// The translation of `text` in the active language.
+pub fn tr(text: &str) -> String {
+    ACTIVE
+        .read()
+        .ok()
+        .and_then(|active| active.as_ref()?.get(text).cloned())
+        .unwrap_or_else(|| text.to_string())
+}
+
+/// Translates `template`, then replaces each `{name}` with its argument.
+pub fn trf(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut text = tr(template);
+    for (name, value) in args {
+        text = text.replace(&format!("{{{name}}}"), &value.to_string());
+    }
+    text
+}
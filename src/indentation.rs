@@ -0,0 +1,208 @@
+//! Indentation detected from a file's content: when a file is opened, the
+//! leading whitespace of its lines tells whether it is indented with tabs
+//! or spaces, and by how many. The result replaces the configured tab
+//! settings for that buffer, for the Tab key and everything that indents,
+//! and is shown in the status bar, where it can be changed. Edit >
+//! Indentation rewrites a buffer's indentation to tabs or spaces.
+
+use crate::i18n::{tr, trf};
+use crate::settings::Settings;
+use crate::{FileTab, TextEditorApp};
+use eframe::egui;
+use std::cmp::Reverse;
+
+/// Lines looked at; the start of a file is representative enough.
+const MAX_LINES: usize = 10_000;
+/// Indent widths that can be detected or picked.
+const WIDTHS: [usize; 4] = [2, 3, 4, 8];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Indentation {
+    Tabs,
+    Spaces(usize),
+}
+
+/// The indentation `text` uses, if it indents consistently enough to tell.
+/// The width of space indentation is the step most often taken between
+/// consecutive lines.
+pub fn detect(text: &str) -> Option<Indentation> {
+    let mut tab_lines = 0;
+    let mut space_lines = 0;
+    let mut steps = [0usize; 9];
+    let mut previous = 0;
+    for line in text.lines().take(MAX_LINES) {
+        let body = line.trim_start();
+        if body.is_empty() {
+            continue;
+        }
+        let indent = &line[..line.len() - body.len()];
+        if indent.starts_with('\t') {
+            tab_lines += 1;
+            continue;
+        }
+        // Mixed indentation, or the ` * ` lines of a block comment, which
+        // sit one column off.
+        if indent.contains('\t') || body.starts_with('*') {
+            continue;
+        }
+        let width = indent.len();
+        if width > 0 {
+            space_lines += 1;
+        }
+        let step = width.abs_diff(previous);
+        if (2..=8).contains(&step) {
+            steps[step] += 1;
+        }
+        previous = width;
+    }
+    if tab_lines == 0 && space_lines == 0 {
+        return None;
+    }
+    if tab_lines > space_lines {
+        return Some(Indentation::Tabs);
+    }
+    let width = (2..=8).max_by_key(|&width| (steps[width], Reverse(width)))?;
+    (steps[width] > 0).then_some(Indentation::Spaces(width))
+}
+
+/// `text` with the leading whitespace of every line rewritten to `target`.
+/// Indentation is measured in levels of `from` columns, with tab stops
+/// every `from` columns; each level becomes a tab or the target's number
+/// of spaces, and columns left over stay spaces.
+pub fn convert(text: &str, target: Indentation, from: usize) -> String {
+    let from = from.max(1);
+    let unit = match target {
+        Indentation::Tabs => "\t".to_string(),
+        Indentation::Spaces(width) => " ".repeat(width),
+    };
+    let mut out = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        let body = line.trim_start_matches([' ', '\t']);
+        let mut columns = 0;
+        for c in line[..line.len() - body.len()].chars() {
+            columns = if c == '\t' {
+                (columns / from + 1) * from
+            } else {
+                columns + 1
+            };
+        }
+        if body.trim_end().is_empty() {
+            // Whitespace-only lines keep what they have.
+            out.push_str(line);
+            continue;
+        }
+        out.push_str(&unit.repeat(columns / from));
+        out.extend(std::iter::repeat_n(' ', columns % from));
+        out.push_str(body);
+    }
+    out
+}
+
+impl TextEditorApp {
+    /// Rewrites the indentation of the active buffer to `target`, as one
+    /// edit, and has the buffer indent that way from now on. `None`
+    /// normalizes to the way the buffer indents already.
+    pub(crate) fn convert_indentation(&mut self, ctx: &egui::Context, target: Option<Indentation>) {
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        let options = tab.editor_options(&self.settings);
+        let target = target.unwrap_or(if options.insert_spaces {
+            Indentation::Spaces(options.tab_width)
+        } else {
+            Indentation::Tabs
+        });
+        let converted = convert(&tab.content, target, options.tab_width);
+        let Some(tab) = self.editable_tab() else {
+            return;
+        };
+        tab.indentation = Some(target);
+        if converted != tab.content {
+            let len = tab.content.len();
+            self.replace_range(ctx, 0..len, &converted);
+        }
+    }
+
+    /// Converts the active buffer to spaces at its current tab width.
+    pub(crate) fn convert_indentation_to_spaces(&mut self, ctx: &egui::Context) {
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        let width = tab.editor_options(&self.settings).tab_width;
+        self.convert_indentation(ctx, Some(Indentation::Spaces(width)));
+    }
+}
+
+/// The status bar entry showing how `tab` indents, with a menu to change it.
+/// Returns the indentation to convert the buffer to, if one was picked.
+pub fn status_menu(
+    ui: &mut egui::Ui,
+    tab: &mut FileTab,
+    settings: &Settings,
+) -> Option<Indentation> {
+    let mut convert_to = None;
+    let options = tab.editor_options(settings);
+    let label = if options.insert_spaces {
+        trf("Spaces: {width}", &[("width", &options.tab_width)])
+    } else {
+        trf("Tab Size: {width}", &[("width", &options.tab_width)])
+    };
+    let hover = if tab.indentation.is_some() {
+        tr("Detected from the file's content")
+    } else {
+        tr("From the settings")
+    };
+    ui.menu_button(label, |ui| {
+        ui.weak(hover);
+        ui.separator();
+        for width in WIDTHS {
+            let selected = tab.indentation == Some(Indentation::Spaces(width));
+            if ui
+                .radio(selected, trf("Spaces: {width}", &[("width", &width)]))
+                .clicked()
+            {
+                tab.indentation = Some(Indentation::Spaces(width));
+                ui.close_menu();
+            }
+        }
+        if ui
+            .radio(tab.indentation == Some(Indentation::Tabs), tr("Tabs"))
+            .clicked()
+        {
+            tab.indentation = Some(Indentation::Tabs);
+            ui.close_menu();
+        }
+        ui.separator();
+        if ui.button(tr("Detect from Content")).clicked() {
+            tab.indentation = detect(&tab.content);
+            ui.close_menu();
+        }
+        if ui
+            .add_enabled(
+                tab.indentation.is_some(),
+                egui::Button::new(tr("Use Settings")),
+            )
+            .clicked()
+        {
+            tab.indentation = None;
+            ui.close_menu();
+        }
+        ui.separator();
+        ui.menu_button(tr("Convert Indentation"), |ui| {
+            for width in WIDTHS {
+                if ui
+                    .button(trf("Spaces: {width}", &[("width", &width)]))
+                    .clicked()
+                {
+                    convert_to = Some(Indentation::Spaces(width));
+                    ui.close_menu();
+                }
+            }
+            if ui.button(tr("Tabs")).clicked() {
+                convert_to = Some(Indentation::Tabs);
+                ui.close_menu();
+            }
+        });
+    });
+    convert_to
+}
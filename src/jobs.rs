@@ -0,0 +1,166 @@
+//! Background jobs: long operations running on other threads (indexing,
+//! searching, extracting documents, git and cargo) report through a [`Job`]
+//! what they are doing. Running jobs show as a spinner in the status bar
+//! and in the Jobs popup, where cancellable ones can be stopped.
+
+use crate::TextEditorApp;
+use crate::i18n::{tr, trf};
+use eframe::egui;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How often the status bar redraws while jobs run, to show progress.
+const REFRESH: Duration = Duration::from_millis(200);
+
+#[derive(Default)]
+struct Progress {
+    fraction: Option<f32>,
+    detail: String,
+}
+
+struct JobState {
+    title: String,
+    cancellable: bool,
+    cancelled: AtomicBool,
+    finished: AtomicBool,
+    progress: Mutex<Progress>,
+}
+
+/// The worker's side of a job. Dropping it ends the job.
+pub struct Job(Arc<JobState>);
+
+impl Job {
+    pub fn progress(&self, done: usize, total: usize) {
+        if let Ok(mut progress) = self.0.progress.lock() {
+            progress.fraction = (total > 0).then(|| done as f32 / total as f32);
+        }
+    }
+
+    pub fn detail(&self, detail: impl Into<String>) {
+        if let Ok(mut progress) = self.0.progress.lock() {
+            progress.detail = detail.into();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Lets the UI thread notice a cancellation while the worker holds
+    /// the job.
+    pub fn cancel_token(&self) -> CancelToken {
+        CancelToken(self.0.clone())
+    }
+}
+
+impl Drop for Job {
+    fn drop(&mut self) {
+        self.0.finished.store(true, Ordering::Relaxed);
+    }
+}
+
+pub struct CancelToken(Arc<JobState>);
+
+impl CancelToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Default)]
+pub struct Jobs {
+    running: Vec<Arc<JobState>>,
+    popup_open: bool,
+}
+
+impl Jobs {
+    /// Registers a job; hand the returned [`Job`] to the thread doing it.
+    pub fn start(&mut self, title: impl Into<String>, cancellable: bool) -> Job {
+        let state = Arc::new(JobState {
+            title: title.into(),
+            cancellable,
+            cancelled: AtomicBool::new(false),
+            finished: AtomicBool::new(false),
+            progress: Mutex::new(Progress::default()),
+        });
+        self.running.push(state.clone());
+        Job(state)
+    }
+}
+
+impl TextEditorApp {
+    /// The spinner in the status bar while jobs run; clicking it opens the
+    /// Jobs popup.
+    pub(crate) fn jobs_button(&mut self, ui: &mut egui::Ui) {
+        let jobs = &mut self.jobs;
+        // A cancelled job is gone as far as the user is concerned, even if
+        // its worker takes a moment to notice.
+        jobs.running.retain(|job| {
+            !job.finished.load(Ordering::Relaxed) && !job.cancelled.load(Ordering::Relaxed)
+        });
+        if jobs.running.is_empty() {
+            jobs.popup_open = false;
+            return;
+        }
+        ui.ctx().request_repaint_after(REFRESH);
+        ui.spinner();
+        let text = match jobs.running.as_slice() {
+            [job] => job.title.clone(),
+            many => trf("{count} jobs", &[("count", &many.len())]),
+        };
+        if ui
+            .add(egui::Button::new(text).frame(false))
+            .on_hover_text(tr("Show background jobs"))
+            .clicked()
+        {
+            jobs.popup_open = !jobs.popup_open;
+        }
+        ui.separator();
+    }
+
+    pub(crate) fn show_jobs_window(&mut self, ctx: &egui::Context) {
+        let jobs = &mut self.jobs;
+        if !jobs.popup_open {
+            return;
+        }
+        egui::Window::new(tr("Background Jobs"))
+            .open(&mut jobs.popup_open)
+            .collapsible(false)
+            .default_width(360.0)
+            .anchor(egui::Align2::LEFT_BOTTOM, egui::vec2(8.0, -32.0))
+            .show(ctx, |ui| {
+                for job in &jobs.running {
+                    ui.horizontal(|ui| {
+                        ui.strong(&job.title);
+                        if job.cancellable {
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    if ui.button(tr("Cancel")).clicked() {
+                                        job.cancelled.store(true, Ordering::Relaxed);
+                                    }
+                                },
+                            );
+                        }
+                    });
+                    let (fraction, detail) = job
+                        .progress
+                        .lock()
+                        .map_or((None, String::new()), |p| (p.fraction, p.detail.clone()));
+                    match fraction {
+                        Some(fraction) => {
+                            ui.add(egui::ProgressBar::new(fraction).show_percentage());
+                        }
+                        None => {
+                            ui.spinner();
+                        }
+                    }
+                    if !detail.is_empty() {
+                        ui.weak(detail);
+                    }
+                    ui.separator();
+                }
+            });
+    }
+}
@@ -0,0 +1,164 @@
+//! Crash protection for untitled buffers.
+//!
+//! Every few seconds the content of each untitled tab that changed since the
+//! last flush is snapshotted to `<data dir>/journal/<id>.json`. Snapshots
+//! are removed once the buffer is saved to a real file or its tab is
+//! closed, and any that remain are reopened on the next start. Named files
+//! are not journaled.
+
+use crate::settings::data_dir;
+use crate::{FileTab, TextEditorApp, atomic_write};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const FLUSH_INTERVAL: Duration = Duration::from_secs(3);
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    title: String,
+    content: String,
+}
+
+pub struct Journal {
+    last_flush: Instant,
+    /// Content hash of the last snapshot written for each journal id.
+    written: HashMap<String, u64>,
+}
+
+impl Default for Journal {
+    fn default() -> Self {
+        Self {
+            last_flush: Instant::now(),
+            written: HashMap::new(),
+        }
+    }
+}
+
+fn journal_dir() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("journal"))
+}
+
+pub(crate) fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl Journal {
+    pub fn new_id() -> String {
+        uuid::Uuid::new_v4().to_string()
+    }
+
+    fn write(&mut self, id: &str, tab: &FileTab) {
+        let hash = content_hash(&tab.content);
+        if self.written.get(id) == Some(&hash) {
+            return;
+        }
+        let Some(dir) = journal_dir() else {
+            return;
+        };
+        if tab.content.is_empty() {
+            self.discard(id);
+            self.written.insert(id.to_string(), hash);
+            return;
+        }
+        let snapshot = Snapshot {
+            title: tab.title.clone(),
+            content: tab.content.clone(),
+        };
+        let Ok(json) = serde_json::to_string(&snapshot) else {
+            return;
+        };
+        // Snapshots hold buffer contents, so only the user may read them.
+        if fs::create_dir_all(&dir).is_ok()
+            && atomic_write::write_private(&dir.join(format!("{id}.json")), json.as_bytes()).is_ok()
+        {
+            self.written.insert(id.to_string(), hash);
+        }
+    }
+
+    /// Forgets the snapshot for `id`, e.g. after the buffer was saved.
+    pub fn discard(&mut self, id: &str) {
+        self.written.remove(id);
+        if let Some(dir) = journal_dir() {
+            let _ = fs::remove_file(dir.join(format!("{id}.json")));
+        }
+    }
+}
+
+impl TextEditorApp {
+    /// Flushes changed untitled buffers to the journal when the interval
+    /// has elapsed, and schedules the next check.
+    pub(crate) fn flush_journal(&mut self, ctx: &egui::Context) {
+        let has_untitled = self.tabs.values().any(|tab| tab.journal_id.is_some());
+        if !has_untitled {
+            return;
+        }
+        let elapsed = self.journal.last_flush.elapsed();
+        if elapsed < FLUSH_INTERVAL {
+            ctx.request_repaint_after(FLUSH_INTERVAL - elapsed);
+            return;
+        }
+        for tab in self.tabs.values() {
+            if let Some(id) = &tab.journal_id
+                && !tab.sensitive
+            {
+                self.journal.write(id, tab);
+            }
+        }
+        self.journal.last_flush = Instant::now();
+        ctx.request_repaint_after(FLUSH_INTERVAL);
+    }
+
+    /// Reopens untitled buffers left over from a previous run.
+    pub(crate) fn restore_journal(&mut self) {
+        let Some(entries) = journal_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+            return;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let Some(id) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+                continue;
+            };
+            let Some(snapshot) = fs::read_to_string(&path)
+                .ok()
+                .and_then(|json| serde_json::from_str::<Snapshot>(&json).ok())
+            else {
+                continue;
+            };
+
+            if let Some(n) = snapshot
+                .title
+                .strip_prefix("Untitled ")
+                .and_then(|n| n.parse::<usize>().ok())
+            {
+                self.new_file_counter = self.new_file_counter.max(n + 1);
+            }
+            let mut key = snapshot.title.clone();
+            while self.tabs.contains_key(&key) {
+                key.push('′');
+            }
+            self.journal
+                .written
+                .insert(id.clone(), content_hash(&snapshot.content));
+            let tab = FileTab {
+                title: key.clone(),
+                content: snapshot.content,
+                journal_id: Some(id),
+                ..Default::default()
+            };
+            self.tabs.insert(key.clone(), tab);
+            self.open_order.push(key.clone());
+            self.active_tab = Some(key);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! The JSON files the editor keeps its state in. A file that exists but
+//! does not parse is reported when it is loaded and never saved over, so a
+//! typo or a file from a newer version does not cost the user its contents.
+
+use crate::atomic_write;
+use crate::i18n::trf;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Reads the JSON file at `path`: `Ok(None)` if there is none, and an
+/// error message if it cannot be read or parsed.
+pub fn load<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, String> {
+    let json = match fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(message(path, &err)),
+    };
+    serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|err| message(path, &err))
+}
+
+/// The contents of the JSON file at `path`, or the default with a message
+/// to show if the file cannot be read.
+pub fn load_or_default<T: DeserializeOwned + Default>(path: Option<&Path>) -> (T, Option<String>) {
+    match path.map(load) {
+        Some(Ok(Some(value))) => (value, None),
+        Some(Err(message)) => (T::default(), Some(message)),
+        _ => (T::default(), None),
+    }
+}
+
+fn message(path: &Path, error: &dyn std::fmt::Display) -> String {
+    trf(
+        "{path} could not be read ({error}); it is left as it is and changes are not saved to it until it is fixed",
+        &[("path", &path.display()), ("error", error)],
+    )
+}
+
+/// Writes `value` to `path` as JSON, creating its directory. An existing
+/// file that does not parse as `T` is left alone and an error returned.
+pub fn save<T: Serialize + DeserializeOwned>(path: &Path, value: &T) -> io::Result<()> {
+    match fs::read_to_string(path) {
+        Ok(json) if serde_json::from_str::<T>(&json).is_err() => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} does not parse; not overwriting it", path.display()),
+            ));
+        }
+        Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+        _ => {}
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_string_pretty(value).map_err(io::Error::other)?;
+    atomic_write::write(path, json.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn unparseable_files_are_reported_and_kept() {
+        let dir = std::env::temp_dir().join(format!("rusty-editor-test-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("state.json");
+        assert_eq!(load::<Vec<String>>(&path), Ok(None));
+        save(&path, &vec!["a".to_string()]).unwrap();
+        assert_eq!(load(&path), Ok(Some(vec!["a".to_string()])));
+
+        fs::write(&path, "[\"a\", oops").unwrap();
+        let (value, message) = load_or_default::<Vec<String>>(Some(&path));
+        assert!(value.is_empty());
+        assert!(message.is_some());
+        assert!(save(&path, &Vec::<String>::new()).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[\"a\", oops");
+
+        // A file of another shape is not replaced either.
+        fs::write(&path, "[1, 2]").unwrap();
+        assert!(save(&path, &BTreeMap::<String, String>::new()).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
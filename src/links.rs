@@ -0,0 +1,226 @@
+//! Link helpers: "Paste as Markdown Link" wraps the selection in a link to
+//! the URL on the clipboard, "Insert Link to File…" picks a workspace file
+//! and "Insert Image…" an image on disk, both linked by their path relative
+//! to the current document. HTML documents get `<a>` and `<img>` tags, other
+//! files just the path.
+
+use crate::TextEditorApp;
+use crate::clipboard;
+use crate::editing::char_to_byte;
+use crate::i18n::tr;
+use crate::picker::{Picker, PickerItem};
+use crate::platform;
+use crate::project::{display_path, relative_path, walk_roots};
+use eframe::egui;
+use std::path::{Path, PathBuf};
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "svg", "webp", "avif", "bmp"];
+
+/// How a link is written in a document of `syntax`.
+#[derive(Clone, Copy, PartialEq)]
+enum LinkStyle {
+    Markdown,
+    Html,
+    Plain,
+}
+
+impl LinkStyle {
+    fn of(syntax: Option<&str>) -> Self {
+        match syntax {
+            Some("Markdown") => LinkStyle::Markdown,
+            Some("HTML") => LinkStyle::Html,
+            _ => LinkStyle::Plain,
+        }
+    }
+
+    fn link(self, label: &str, target: &str) -> String {
+        match self {
+            LinkStyle::Markdown => format!("[{label}]({})", escape_target(target)),
+            LinkStyle::Html => format!("<a href=\"{}\">{label}</a>", escape_target(target)),
+            LinkStyle::Plain => target.to_string(),
+        }
+    }
+
+    fn image(self, alt: &str, target: &str) -> String {
+        match self {
+            LinkStyle::Markdown => format!("![{alt}]({})", escape_target(target)),
+            LinkStyle::Html => format!(
+                "<img src=\"{}\" alt=\"{}\">",
+                escape_target(target),
+                alt.replace('"', "&quot;")
+            ),
+            LinkStyle::Plain => target.to_string(),
+        }
+    }
+}
+
+/// Encodes the characters that would end a link target early.
+fn escape_target(target: &str) -> String {
+    target
+        .replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29")
+        .replace('"', "%22")
+}
+
+/// Whether `text` looks like something a link can point to.
+fn is_url(text: &str) -> bool {
+    !text.contains(char::is_whitespace)
+        && (text.contains("://") || text.starts_with("mailto:") || text.starts_with("www."))
+}
+
+/// The file picker behind "Insert Link to File…".
+#[derive(Default)]
+pub struct LinkPicker {
+    pub picker: Picker,
+    /// The workspace files, listed when the picker opens.
+    files: Vec<PathBuf>,
+}
+
+impl TextEditorApp {
+    fn link_style(&self) -> LinkStyle {
+        LinkStyle::of(
+            self.active_tab
+                .as_ref()
+                .and_then(|key| self.tabs.get(key))
+                .and_then(|tab| tab.syntax.as_deref()),
+        )
+    }
+
+    /// How the current document refers to `path`: relative to its folder,
+    /// or to the first workspace folder while it is unsaved.
+    fn link_target(&self, path: &Path) -> String {
+        let dir = self
+            .active_tab
+            .as_ref()
+            .and_then(|key| self.tabs.get(key))
+            .and_then(|tab| tab.path.as_deref())
+            .and_then(Path::parent)
+            .map(Path::to_path_buf)
+            .or_else(|| self.folder_path());
+        match dir {
+            Some(dir) => relative_path(&dir, path),
+            None => path.to_string_lossy().into_owned(),
+        }
+    }
+
+    /// The selected text, used as the label of an inserted link.
+    fn selected_text(&self, ctx: &egui::Context) -> String {
+        let (Some(selection), Some(tab)) = (
+            self.selection(ctx),
+            self.active_tab.as_ref().and_then(|key| self.tabs.get(key)),
+        ) else {
+            return String::new();
+        };
+        let start = char_to_byte(&tab.content, selection.start);
+        let end = char_to_byte(&tab.content, selection.end);
+        tab.content[start..end].to_string()
+    }
+
+    /// Replaces the selection with the link `text`. Without a label, the
+    /// caret lands where it goes, ready for one.
+    fn insert_link(&mut self, ctx: &egui::Context, text: &str, label: &str) {
+        let Some(start) = self.selection(ctx).map(|s| s.start) else {
+            return;
+        };
+        self.insert_text(ctx, text);
+        let label_at = if text.starts_with('[') {
+            Some(1)
+        } else {
+            text.rfind("</a>")
+        };
+        if label.is_empty()
+            && let Some(offset) = label_at
+        {
+            let at = start + text[..offset].chars().count();
+            self.set_selection(ctx, at..at);
+        }
+    }
+
+    pub(crate) fn paste_as_markdown_link(&mut self, ctx: &egui::Context) {
+        let Some(url) = clipboard::read_text().map(|text| text.trim().to_string()) else {
+            return;
+        };
+        if !is_url(&url) {
+            self.notifications
+                .warning(tr("The clipboard does not hold a URL."));
+            return;
+        }
+        let label = self.selected_text(ctx);
+        let text = match self.link_style() {
+            LinkStyle::Html => LinkStyle::Html.link(&label, &url),
+            _ => LinkStyle::Markdown.link(&label, &url),
+        };
+        self.insert_link(ctx, &text, &label);
+    }
+
+    pub(crate) fn open_link_picker(&mut self) {
+        if self.folders.is_empty() {
+            self.notifications
+                .warning(tr("Open a folder to link to its files."));
+            return;
+        }
+        self.link_picker.files = walk_roots(&self.folders);
+        self.link_picker.picker = Picker::opened();
+    }
+
+    pub(crate) fn show_link_picker(&mut self, ctx: &egui::Context) {
+        if !self.link_picker.picker.open {
+            return;
+        }
+        let items = self
+            .link_picker
+            .files
+            .iter()
+            .map(|path| PickerItem {
+                value: path.clone(),
+                label: display_path(&self.folders, path),
+                detail: String::new(),
+            })
+            .collect();
+        let Some(path) = self
+            .link_picker
+            .picker
+            .show(ctx, &tr("Insert Link to File"), items)
+        else {
+            return;
+        };
+        self.link_picker.files.clear();
+        let mut label = self.selected_text(ctx);
+        if label.is_empty() {
+            label = path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+        }
+        let text = self.link_style().link(&label, &self.link_target(&path));
+        self.insert_link(ctx, &text, &label);
+    }
+
+    pub(crate) fn insert_image(&mut self, ctx: &egui::Context) {
+        let mut dialog = platform::file_dialog().add_filter(tr("Images"), IMAGE_EXTENSIONS);
+        if let Some(dir) = self
+            .active_tab
+            .as_ref()
+            .and_then(|key| self.tabs.get(key))
+            .and_then(|tab| tab.path.as_deref())
+            .and_then(Path::parent)
+        {
+            dialog = dialog.set_directory(dir);
+        }
+        let Some(path) = dialog.pick_file() else {
+            return;
+        };
+        let mut alt = self.selected_text(ctx);
+        if alt.is_empty() {
+            alt = path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+        }
+        let text = self.link_style().image(&alt, &self.link_target(&path));
+        self.insert_text(ctx, &text);
+    }
+}
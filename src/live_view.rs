@@ -0,0 +1,333 @@
+//! Live View: a small web server on localhost that shows the active buffer,
+//! highlighted, to anyone following along in a browser. The page receives
+//! updates over a WebSocket; the view is read-only. Sensitive tabs are not
+//! shown.
+//!
+//! The page lives under a random token, and requests must name the server
+//! by its loopback address, so other web pages the user visits can neither
+//! find the view nor reach it through DNS rebinding. WebSocket upgrades
+//! must also come from the page's own origin.
+
+use crate::TextEditorApp;
+use crate::i18n::{tr, trf};
+use crate::journal::content_hash;
+use base64::Engine;
+use eframe::egui;
+use sha1::{Digest, Sha1};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often a changed buffer is re-rendered and pushed to the browsers.
+const PUBLISH_INTERVAL: Duration = Duration::from_millis(300);
+/// How often a connection checks for a new snapshot.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+const PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Rusty Editor</title>
+<style>
+body { margin: 0; font-family: sans-serif; }
+#status { padding: 4px 8px; font-size: 12px; background: #eee; color: #555; }
+#view pre { margin: 0; padding: 8px; min-height: 100vh; font-size: 14px; }
+</style>
+</head>
+<body>
+<div id="status">Connecting…</div>
+<div id="view"></div>
+<script>
+const status = document.getElementById("status");
+const socket = new WebSocket(`ws://${location.host}${location.pathname}ws`);
+socket.onmessage = (event) => {
+  const update = JSON.parse(event.data);
+  document.title = update.title;
+  status.textContent = update.title;
+  document.getElementById("view").innerHTML = update.html;
+};
+socket.onclose = () => { status.textContent = "Disconnected"; };
+</script>
+</body>
+</html>
+"#;
+
+/// What the browsers currently show. `version` goes up with every change.
+#[derive(Default)]
+struct Snapshot {
+    version: u64,
+    json: String,
+}
+
+struct Server {
+    port: u16,
+    /// The secret path segment the page and WebSocket are served under.
+    token: String,
+    snapshot: Arc<Mutex<Snapshot>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+#[derive(Default)]
+pub struct LiveView {
+    server: Option<Server>,
+    last_publish: Option<Instant>,
+    /// Tab key, content hash and sensitivity of the last published snapshot.
+    published: Option<(String, u64, bool)>,
+}
+
+impl LiveView {
+    pub fn url(&self) -> Option<String> {
+        self.server
+            .as_ref()
+            .map(|server| format!("http://127.0.0.1:{}/{}/", server.port, server.token))
+    }
+}
+
+/// Whether a `Host` header names this server by a loopback address.
+fn is_local_host(host: &str, port: u16) -> bool {
+    host == format!("127.0.0.1:{port}") || host == format!("localhost:{port}")
+}
+
+/// Whether an `Origin` header is the Live View page itself.
+fn is_local_origin(origin: &str, port: u16) -> bool {
+    origin
+        .strip_prefix("http://")
+        .is_some_and(|host| is_local_host(host, port))
+}
+
+fn start(snapshot: Arc<Mutex<Snapshot>>, stop: Arc<AtomicBool>, token: String) -> io::Result<u16> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    listener.set_nonblocking(true)?;
+    let port = listener.local_addr()?.port();
+    thread::spawn(move || {
+        while !stop.load(Ordering::Relaxed) {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    let snapshot = snapshot.clone();
+                    let stop = stop.clone();
+                    let token = token.clone();
+                    thread::spawn(move || {
+                        let _ = serve(stream, port, &token, &snapshot, &stop);
+                    });
+                }
+                Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                    thread::sleep(POLL_INTERVAL);
+                }
+                Err(_) => return,
+            }
+        }
+    });
+    Ok(port)
+}
+
+/// Answers one connection: the page for `/<token>/`, a WebSocket for
+/// `/<token>/ws`.
+fn serve(
+    mut stream: TcpStream,
+    port: u16,
+    token: &str,
+    snapshot: &Mutex<Snapshot>,
+    stop: &AtomicBool,
+) -> io::Result<()> {
+    stream.set_nonblocking(false)?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let (mut key, mut host, mut origin) = (None, None, None);
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            let value = Some(value.trim().to_string());
+            match name.trim().to_ascii_lowercase().as_str() {
+                "sec-websocket-key" => key = value,
+                "host" => host = value,
+                "origin" => origin = value,
+                _ => {}
+            }
+        }
+    }
+
+    if !host.is_some_and(|host| is_local_host(&host, port)) {
+        return write!(
+            stream,
+            "HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+        );
+    }
+    let page = format!("/{token}/");
+    let socket = format!("/{token}/ws");
+    match key {
+        Some(key)
+            if path == socket && origin.is_some_and(|origin| is_local_origin(&origin, port)) =>
+        {
+            let mut hasher = Sha1::new();
+            hasher.update(key.as_bytes());
+            hasher.update(WEBSOCKET_GUID.as_bytes());
+            let accept = base64::engine::general_purpose::STANDARD.encode(hasher.finalize());
+            write!(
+                stream,
+                "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\
+                 Connection: Upgrade\r\nSec-WebSocket-Accept: {accept}\r\n\r\n"
+            )?;
+            let mut sent = 0;
+            while !stop.load(Ordering::Relaxed) {
+                let update = snapshot
+                    .lock()
+                    .ok()
+                    .filter(|s| s.version != sent)
+                    .map(|s| (s.version, s.json.clone()));
+                if let Some((version, json)) = update {
+                    // A failed write means the browser went away.
+                    write_text_frame(&mut stream, &json)?;
+                    sent = version;
+                }
+                thread::sleep(POLL_INTERVAL);
+            }
+            Ok(())
+        }
+        _ if path == page => write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{PAGE}",
+            PAGE.len()
+        ),
+        _ => write!(
+            stream,
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+        ),
+    }
+}
+
+/// Sends `text` as a single unmasked WebSocket text frame.
+fn write_text_frame(stream: &mut TcpStream, text: &str) -> io::Result<()> {
+    let len = text.len();
+    let mut frame = vec![0x81];
+    if len < 126 {
+        frame.push(len as u8);
+    } else if len <= u16::MAX as usize {
+        frame.push(126);
+        frame.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        frame.push(127);
+        frame.extend_from_slice(&(len as u64).to_be_bytes());
+    }
+    frame.extend_from_slice(text.as_bytes());
+    stream.write_all(&frame)
+}
+
+impl TextEditorApp {
+    pub(crate) fn toggle_live_view(&mut self) {
+        let live = &mut self.live_view;
+        if live.server.take().is_some() {
+            self.notifications.info(tr("Live View stopped"));
+            return;
+        }
+        let snapshot = Arc::new(Mutex::new(Snapshot::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        match start(snapshot.clone(), stop.clone(), token.clone()) {
+            Ok(port) => {
+                live.server = Some(Server {
+                    port,
+                    token,
+                    snapshot,
+                    stop,
+                });
+                live.published = None;
+                let url = live.url().unwrap_or_default();
+                self.notifications
+                    .info(trf("Live View running at {url}", &[("url", &url)]));
+            }
+            Err(err) => self.notifications.error(trf(
+                "Could not start Live View: {error}",
+                &[("error", &err)],
+            )),
+        }
+    }
+
+    /// Renders the active buffer for the browsers when it changed, at most
+    /// every [`PUBLISH_INTERVAL`].
+    pub(crate) fn publish_live_view(&mut self, ctx: &egui::Context) {
+        let live = &mut self.live_view;
+        let Some(server) = &live.server else {
+            return;
+        };
+        if let Some(last) = live.last_publish
+            && last.elapsed() < PUBLISH_INTERVAL
+        {
+            ctx.request_repaint_after(PUBLISH_INTERVAL - last.elapsed());
+            return;
+        }
+        let Some((key, tab)) = self
+            .active_tab
+            .as_ref()
+            .and_then(|key| Some((key, self.tabs.get(key)?)))
+        else {
+            return;
+        };
+        let state = (key.clone(), content_hash(&tab.content), tab.sensitive);
+        if live.published.as_ref() == Some(&state) {
+            return;
+        }
+        let syntax = tab
+            .syntax
+            .as_deref()
+            .and_then(|name| self.syntax_set.find_syntax_by_name(name))
+            .unwrap_or_else(|| self.syntax_set.find_syntax_plain_text());
+        let html = if tab.sensitive {
+            format!("<p><em>{}</em></p>", tr("This buffer is marked sensitive."))
+        } else {
+            match syntect::html::highlighted_html_for_string(
+                &tab.content,
+                &self.syntax_set,
+                syntax,
+                &self.theme,
+            ) {
+                Ok(html) => html,
+                Err(_) => return,
+            }
+        };
+        let json = serde_json::json!({ "title": tab.title, "html": html }).to_string();
+        if let Ok(mut snapshot) = server.snapshot.lock() {
+            snapshot.version += 1;
+            snapshot.json = json;
+        }
+        live.published = Some(state);
+        live.last_publish = Some(Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_loopback_hosts_are_served() {
+        assert!(is_local_host("127.0.0.1:8123", 8123));
+        assert!(is_local_host("localhost:8123", 8123));
+        assert!(!is_local_host("127.0.0.1:8124", 8123));
+        assert!(!is_local_host("attacker.example:8123", 8123));
+    }
+
+    #[test]
+    fn websockets_must_come_from_the_page() {
+        assert!(is_local_origin("http://127.0.0.1:8123", 8123));
+        assert!(is_local_origin("http://localhost:8123", 8123));
+        assert!(!is_local_origin("https://attacker.example", 8123));
+        assert!(!is_local_origin("http://attacker.example:8123", 8123));
+        assert!(!is_local_origin("null", 8123));
+    }
+}
@@ -1,12 +1,114 @@
+mod align;
+mod archive;
+mod atomic_write;
+mod audit_log;
+mod block_end;
+mod bottom_panel;
+mod branch;
+mod breadcrumbs;
+mod change_bars;
+mod cheat_sheet;
+mod checkpoints;
+mod cli;
+mod clipboard;
+mod closed_tabs;
+mod commands;
+mod conflicts;
+mod continuation;
+mod counterpart;
+mod diff;
+mod disk_check;
+mod documents;
+mod editing;
+mod editor_view;
+mod elevated_save;
+mod emmet;
+mod expand_selection;
+mod favorites;
+mod file_history;
+mod file_symbols;
+mod file_tree;
+mod fuzzy;
+mod gist;
+mod git;
+mod glob;
+mod highlight;
+mod i18n;
+mod indentation;
+mod jobs;
+mod journal;
+mod json_file;
+mod links;
+mod live_view;
+mod markdown_table;
+mod menu_bar;
+mod merge;
+mod motion;
+mod new_project;
+mod notes;
+mod notifications;
+mod open_editors;
+mod outline;
+mod paste;
+mod path_completion;
+mod picker;
+mod platform;
+mod power;
+mod preferences;
+mod privacy;
+mod problems;
+mod profiler;
+mod profiles;
+mod project;
+mod project_search;
+mod prose;
+mod quit;
+mod reformat;
+mod rename;
+mod rename_symbol;
+mod repl;
+mod resources;
+mod run;
+mod scopes;
+mod search_filters;
+mod search_history;
+mod settings;
+mod soft_wrap;
+mod stale_save;
+mod status_bar;
+mod status_items;
+mod symbol_index;
+mod symbols;
+mod syntax;
+mod tab_groups;
+mod tags;
+mod tail;
+mod templates;
+mod terminal;
+mod theme_editor;
+mod transform;
+mod trust;
+mod ui_scale;
+mod unicode_picker;
+mod unicode_search;
+mod unicode_warnings;
+mod update;
+mod url_open;
+mod window_title;
+mod word_completion;
+mod word_highlight;
+mod wrap;
+
+use disk_check::DiskState;
 use eframe::egui;
-use rfd::FileDialog;
+use highlight::HighlightCache;
+use i18n::{tr, trf};
+use settings::Settings;
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
-use syntect::easy::HighlightLines;
-use syntect::highlighting::{ThemeSet, Style};
+use syntect::highlighting::ThemeSet;
 use syntect::parsing::SyntaxSet;
-use syntect::util::LinesWithEndings;
 
 #[derive(Default)]
 struct FileTab {
@@ -14,88 +116,401 @@ struct FileTab {
     title: String,
     content: String,
     syntax: Option<String>,
-    last_find: Option<usize>,
+    highlight: HighlightCache,
+    /// Whether the file on disk starts with a UTF-8 byte order mark. The
+    /// mark itself is never part of `content`.
+    bom: bool,
+    /// Set for untitled buffers, which are journaled to survive crashes.
+    journal_id: Option<String>,
+    /// Views that cannot be edited or saved, such as old revisions.
+    read_only: bool,
+    /// The archive and entry name for tabs opened from an archive.
+    archive_entry: Option<(PathBuf, String)>,
+    /// Set while the tab tails its file; the buffer is then read-only.
+    follow: Option<tail::Follow>,
+    /// Whether the file behind `path` still exists.
+    disk_state: disk_check::DiskState,
+    /// Hash of the content as last loaded or saved; `None` if it never was.
+    saved_hash: Option<u64>,
+    /// The content as last loaded or saved, for the change bars.
+    saved_content: Option<String>,
+    /// The file as last loaded or saved, to notice other programs
+    /// changing it.
+    disk_stamp: Option<stale_save::DiskStamp>,
+    /// Laid out for reading and writing text rather than code.
+    prose: bool,
+    /// Holds secrets: kept out of the journal, Live View and everything
+    /// else listed in [`crate::privacy`].
+    sensitive: bool,
+    /// How the file indents, detected when it was opened or picked in the
+    /// status bar; overrides the configured tab settings.
+    indentation: Option<indentation::Indentation>,
+    /// The caret's character index and the scroll position as last shown,
+    /// remembered when the tab is closed.
+    caret: usize,
+    scroll_offset: egui::Vec2,
+    /// Set on a reopened tab until the editor has scrolled back to
+    /// `scroll_offset`.
+    restore_scroll: bool,
+}
+
+impl FileTab {
+    /// Whether the buffer has edits that are not on disk.
+    fn is_modified(&self) -> bool {
+        if self.read_only {
+            return false;
+        }
+        match self.saved_hash {
+            Some(hash) => journal::content_hash(&self.content) != hash,
+            None => !self.content.is_empty(),
+        }
+    }
+
+    fn mark_saved(&mut self) {
+        self.saved_hash = Some(journal::content_hash(&self.content));
+        self.saved_content = Some(self.content.clone());
+    }
+
+    /// The editor options for this tab's file type. Untitled tabs match file
+    /// name patterns by their title.
+    fn editor_options(&self, settings: &Settings) -> settings::EditorOptions {
+        let file_name = self.path.as_deref().and_then(Path::file_name).map_or_else(
+            || self.title.clone(),
+            |name| name.to_string_lossy().to_string(),
+        );
+        let mut options = settings.editor_options(self.syntax.as_deref(), &file_name);
+        match self.indentation {
+            Some(indentation::Indentation::Tabs) => options.insert_spaces = false,
+            Some(indentation::Indentation::Spaces(width)) => {
+                options.insert_spaces = true;
+                options.tab_width = width;
+            }
+            None => {}
+        }
+        options
+    }
 }
 
+const UTF8_BOM: char = '\u{feff}';
+
 pub struct TextEditorApp {
     tabs: HashMap<String, FileTab>,
     open_order: Vec<String>,
     active_tab: Option<String>,
 
-    folder_path: Option<PathBuf>,
-    file_list: Vec<PathBuf>,
+    /// The workspace folders shown in the sidebar, the one opened with
+    /// Open Folder first.
+    folders: Vec<PathBuf>,
+    file_tree: file_tree::FileTree,
+    favorites: favorites::Favorites,
+    tab_groups: tab_groups::TabGroups,
+    closed_tabs: closed_tabs::ClosedTabs,
+    audit_log: audit_log::AuditLog,
+    updates: update::Updates,
+    theme_editor: theme_editor::ThemeEditor,
+    cheat_sheet: cheat_sheet::CheatSheet,
+    trust: trust::Trust,
 
     syntax_set: SyntaxSet,
+    theme_set: ThemeSet,
     theme: syntect::highlighting::Theme,
 
     new_file_counter: usize,
 
-    show_rename: bool,
-    rename_input: String,
+    rename: rename::RenameDialog,
 
     show_find: bool,
     find_input: String,
     found_count: usize,
+    find_scope: scopes::SearchScope,
+    /// Find and Replace ignore differences in Unicode normalization.
+    find_normalize: bool,
+    search_history: search_history::SearchHistory,
+    project_search: project_search::ProjectSearch,
+    backlinks: notes::Backlinks,
+    folds: outline::Folds,
+    word_completion: word_completion::WordCompletion,
+    path_completion: path_completion::PathCompletion,
+    word_highlight: word_highlight::WordHighlight,
+    change_bars: change_bars::ChangeBars,
+    checkpoints: checkpoints::Checkpoints,
+    resources: resources::Resources,
+    profiler: profiler::Profiler,
+    selection_steps: expand_selection::SelectionSteps,
+    rename_symbol: rename_symbol::RenameSymbol,
+    notifications: notifications::Notifications,
+    status_items: status_items::StatusItems,
+    jobs: jobs::Jobs,
+    disk_check: disk_check::DiskCheck,
+    profiles: profiles::Profiles,
+    saved_search_name: String,
 
     show_replace: bool,
     replace_find_input: String,
     replace_with_input: String,
-    
+
     // Added: theme state
     dark_mode: bool,
-    
+
     // Added: sidebar width state
     sidebar_width: f32,
+
+    settings: Settings,
+    show_preferences: bool,
+    show_about: bool,
+
+    palette: picker::Picker,
+    template_picker: picker::Picker,
+    wrapped_rows: soft_wrap::WrappedRows,
+    align: align::AlignDialog,
+    unicode_picker: unicode_picker::UnicodePicker,
+    diff_view: Option<diff::DiffView>,
+    merge_view: Option<merge::MergeView>,
+    /// Set when started with `--diff` or `--merge`: closing that view
+    /// closes the editor.
+    quit_with_view: bool,
+    journal: journal::Journal,
+    symbol_index: Option<symbol_index::SymbolIndex>,
+    symbol_picker: picker::Picker,
+    file_symbol_picker: picker::Picker,
+    link_picker: links::LinkPicker,
+    problems: problems::Problems,
+    file_history: Option<file_history::FileHistory>,
+    branch: branch::BranchIndicator,
+    url_opener: url_open::UrlOpener,
+    gist: gist::GistShare,
+    archives: Vec<archive::OpenArchive>,
+    documents: documents::DocumentExtractor,
+    bottom_panel: bottom_panel::BottomPanel,
+    run_output: run::RunOutput,
+    console: repl::Console,
+    ui_scale: ui_scale::UiScale,
+    last_window_title: String,
+    quit: quit::QuitConfirm,
+    live_view: live_view::LiveView,
+    new_project: new_project::NewProject,
+    elevated_save: elevated_save::ElevatedSave,
+    stale_save: stale_save::StaleSave,
+    /// Set by commands that move the caret, so the editor scrolls to it.
+    scroll_to_caret: bool,
 }
 
+const LIGHT_THEME: &str = "InspiredGitHub";
+const DARK_THEME: &str = "base16-ocean.dark";
+
 impl Default for TextEditorApp {
     fn default() -> Self {
         let syntax_set = SyntaxSet::load_defaults_newlines();
-        let theme = ThemeSet::load_defaults().themes["InspiredGitHub"].clone();
-        Self {
+        let theme_set = ThemeSet::load_defaults();
+        let theme = theme_set.themes[LIGHT_THEME].clone();
+        let (settings, settings_error) = Settings::load();
+        let (profiles, profiles_error) = profiles::Profiles::load();
+        let (trust, trust_error) = trust::Trust::load();
+        let (favorites, favorites_error) = favorites::Favorites::load();
+        let (tab_groups, tab_groups_error) = tab_groups::TabGroups::load();
+        let (search_history, search_history_error) = search_history::SearchHistory::load();
+        let mut app = Self {
             tabs: HashMap::new(),
             open_order: Vec::new(),
             active_tab: None,
-            folder_path: None,
-            file_list: Vec::new(),
+            folders: Vec::new(),
+            file_tree: file_tree::FileTree::default(),
+            favorites,
+            tab_groups,
+            closed_tabs: closed_tabs::ClosedTabs::default(),
+            audit_log: audit_log::AuditLog::default(),
+            updates: update::Updates::default(),
+            theme_editor: theme_editor::ThemeEditor::load(),
+            cheat_sheet: cheat_sheet::CheatSheet::default(),
+            trust,
             syntax_set,
+            theme_set,
             theme,
             new_file_counter: 1,
-            show_rename: false,
-            rename_input: String::new(),
+            rename: rename::RenameDialog::default(),
             show_find: false,
             find_input: String::new(),
             found_count: 0,
+            find_scope: scopes::SearchScope::default(),
+            find_normalize: false,
+            search_history,
+            project_search: project_search::ProjectSearch::default(),
+            backlinks: notes::Backlinks::default(),
+            folds: outline::Folds::default(),
+            word_completion: word_completion::WordCompletion::default(),
+            path_completion: path_completion::PathCompletion::default(),
+            word_highlight: word_highlight::WordHighlight::default(),
+            change_bars: change_bars::ChangeBars::default(),
+            checkpoints: checkpoints::Checkpoints::default(),
+            resources: resources::Resources::default(),
+            profiler: profiler::Profiler::default(),
+            selection_steps: expand_selection::SelectionSteps::default(),
+            rename_symbol: rename_symbol::RenameSymbol::default(),
+            notifications: notifications::Notifications::default(),
+            status_items: status_items::StatusItems::default(),
+            jobs: jobs::Jobs::default(),
+            disk_check: disk_check::DiskCheck::default(),
+            profiles,
+            saved_search_name: String::new(),
             show_replace: false,
             replace_find_input: String::new(),
             replace_with_input: String::new(),
-            dark_mode: false, // Default to light mode
+            dark_mode: false,     // Default to light mode
             sidebar_width: 200.0, // Default sidebar width
+            settings,
+            show_preferences: false,
+            show_about: false,
+            palette: picker::Picker::default(),
+            template_picker: picker::Picker::default(),
+            wrapped_rows: soft_wrap::WrappedRows::default(),
+            align: align::AlignDialog::default(),
+            unicode_picker: unicode_picker::UnicodePicker::default(),
+            diff_view: None,
+            merge_view: None,
+            quit_with_view: false,
+            journal: journal::Journal::default(),
+            symbol_index: None,
+            symbol_picker: picker::Picker::default(),
+            file_symbol_picker: picker::Picker::default(),
+            link_picker: links::LinkPicker::default(),
+            problems: problems::Problems::default(),
+            file_history: None,
+            branch: branch::BranchIndicator::default(),
+            url_opener: url_open::UrlOpener::default(),
+            gist: gist::GistShare::default(),
+            archives: Vec::new(),
+            documents: documents::DocumentExtractor::default(),
+            bottom_panel: bottom_panel::BottomPanel::default(),
+            run_output: run::RunOutput::default(),
+            console: repl::Console::default(),
+            ui_scale: ui_scale::UiScale::default(),
+            last_window_title: String::new(),
+            quit: quit::QuitConfirm::default(),
+            live_view: live_view::LiveView::default(),
+            new_project: new_project::NewProject::default(),
+            elevated_save: elevated_save::ElevatedSave::default(),
+            stale_save: stale_save::StaleSave::default(),
+            scroll_to_caret: false,
+        };
+        for message in [
+            settings_error,
+            profiles_error,
+            trust_error,
+            favorites_error,
+            tab_groups_error,
+            search_history_error,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            app.notifications.error(message);
         }
+        app
     }
 }
 
 impl TextEditorApp {
     fn open_file(&mut self, path: &Path) {
-        if let Ok(content) = fs::read_to_string(path) {
-            let file_name = path.file_name().unwrap().to_string_lossy().to_string();
-            let syntax = self
-                .syntax_set
-                .find_syntax_for_file(path)
-                .ok()
-                .flatten()
-                .map(|s| s.name.clone());
-
-            let tab = FileTab {
-                path: Some(path.to_path_buf()),
-                title: file_name.clone(),
-                content,
-                syntax,
-                last_find: None,
-            };
-            self.tabs.insert(file_name.clone(), tab);
-            self.open_order.push(file_name.clone());
-            self.active_tab = Some(file_name);
+        if archive::kind(path).is_some() {
+            self.open_archive(path);
+            return;
+        }
+        if documents::is_document(path) {
+            self.documents.queued.push(path.to_path_buf());
+            return;
+        }
+        let mut content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) => {
+                self.notifications.error(trf(
+                    "Could not open {path}: {error}",
+                    &[("path", &path.display()), ("error", &err)],
+                ));
+                return;
+            }
+        };
+        let file_name = path.file_name().unwrap().to_string_lossy().to_string();
+        let bom = content.starts_with(UTF8_BOM);
+        if bom {
+            content.remove(0);
+        }
+        let syntax = self.detect_syntax(Some(path), &content);
+        let indentation = if self.settings.detect_indentation {
+            indentation::detect(&content)
+        } else {
+            None
+        };
+
+        let mut tab = FileTab {
+            path: Some(path.to_path_buf()),
+            title: file_name.clone(),
+            content,
+            syntax,
+            bom,
+            prose: self.settings.prose_by_default && prose::is_prose_file(path),
+            sensitive: self.settings.is_sensitive(path),
+            disk_stamp: stale_save::DiskStamp::read(path),
+            indentation,
+            ..Default::default()
+        };
+        tab.mark_saved();
+        self.tabs.insert(file_name.clone(), tab);
+        self.open_order.push(file_name.clone());
+        self.active_tab = Some(file_name);
+    }
+
+    fn open_folder(&mut self, folder: PathBuf) {
+        if let Err(err) = fs::read_dir(&folder) {
+            self.notifications.error(trf(
+                "Could not open {path}: {error}",
+                &[("path", &folder.display()), ("error", &err)],
+            ));
+            return;
+        }
+        self.file_tree = file_tree::FileTree::default();
+        self.folders = vec![folder];
+    }
+
+    /// Adds `folder` next to the ones already open.
+    fn add_workspace_folder(&mut self, folder: PathBuf) {
+        if self.folders.contains(&folder) {
+            return;
+        }
+        if let Err(err) = fs::read_dir(&folder) {
+            self.notifications.error(trf(
+                "Could not open {path}: {error}",
+                &[("path", &folder.display()), ("error", &err)],
+            ));
+            return;
+        }
+        // A lone folder shows no heading of its own; it gets one open now.
+        if let [first] = self.folders.as_slice() {
+            self.file_tree.expand(first);
+        }
+        self.file_tree.expand(&folder);
+        self.folders.push(folder);
+    }
+
+    /// The first workspace folder, which features working on a single
+    /// folder use.
+    fn folder_path(&self) -> Option<PathBuf> {
+        self.folders.first().cloned()
+    }
+
+    fn detect_syntax(&self, path: Option<&Path>, content: &str) -> Option<String> {
+        syntax::detect(&self.settings, &self.syntax_set, path, content)
+    }
+
+    /// Re-runs syntax detection for every open tab, e.g. after the
+    /// file associations changed.
+    fn redetect_syntaxes(&mut self) {
+        let keys: Vec<String> = self.tabs.keys().cloned().collect();
+        for key in keys {
+            let tab = &self.tabs[&key];
+            let syntax = self.detect_syntax(tab.path.as_deref(), &tab.content);
+            if let Some(tab) = self.tabs.get_mut(&key) {
+                tab.syntax = syntax;
+            }
         }
     }
 
@@ -105,295 +520,523 @@ impl TextEditorApp {
         let tab = FileTab {
             path: None,
             title: title.clone(),
-            content: String::new(),
-            syntax: None,
-            last_find: None,
+            journal_id: Some(journal::Journal::new_id()),
+            ..Default::default()
         };
         self.tabs.insert(title.clone(), tab);
         self.open_order.push(title.clone());
         self.active_tab = Some(title);
     }
 
-    fn save_active(&mut self) {
-        if let Some(tab_name) = &self.active_tab {
-            if let Some(tab) = self.tabs.get_mut(tab_name) {
-                let target_path = if let Some(ref path) = tab.path {
-                    Some(path.clone())
-                } else {
-                    FileDialog::new().set_file_name(&tab.title).save_file()
-                };
+    /// `title`, primed until it no longer collides with an open tab's key.
+    fn unique_key(&self, title: &str) -> String {
+        let mut key = title.to_string();
+        while self.tabs.contains_key(&key) {
+            key.push('′');
+        }
+        key
+    }
 
-                if let Some(path) = target_path {
-                    if fs::write(&path, &tab.content).is_ok() {
-                        tab.path = Some(path);
-                    }
+    /// Adds `tab` under a key derived from its title and activates it.
+    fn add_tab(&mut self, mut tab: FileTab) {
+        let key = self.unique_key(&tab.title);
+        tab.title = key.clone();
+        self.tabs.insert(key.clone(), tab);
+        self.open_order.push(key.clone());
+        self.active_tab = Some(key);
+    }
+
+    fn close_tab(&mut self, key: &str) {
+        if let Some(tab) = self.tabs.remove(key) {
+            self.closed_tabs.push(&tab);
+            if let Some(id) = tab.journal_id {
+                self.journal.discard(&id);
+            }
+        }
+        self.checkpoints.remove_tab(key);
+        self.tab_groups.forget_tab(key);
+        self.open_order.retain(|n| n != key);
+        if self.active_tab.as_deref() == Some(key) {
+            self.active_tab = self.open_order.last().cloned();
+        }
+    }
+
+    /// Saves the active tab, asking for a path if it has none. Returns
+    /// whether the buffer is now on disk; failures are shown as a toast.
+    fn save_active(&mut self) -> bool {
+        if let Some(tab_name) = &self.active_tab
+            && let Some(tab) = self.tabs.get_mut(tab_name)
+            && !tab.read_only
+            && tab.follow.is_none()
+        {
+            if let Some((archive, entry)) = &tab.archive_entry {
+                if let Err(err) = archive::write_zip_entry(archive, entry, tab.content.as_bytes()) {
+                    self.notifications.error(trf(
+                        "Could not save {entry} into the archive: {error}",
+                        &[("entry", entry), ("error", &err)],
+                    ));
+                    return false;
+                }
+                tab.mark_saved();
+                return true;
+            }
+            let target_path = if let Some(ref path) = tab.path {
+                Some(path.clone())
+            } else {
+                platform::file_dialog()
+                    .set_file_name(&tab.title)
+                    .save_file()
+            };
+
+            if tab.bom && self.settings.strip_bom_on_save {
+                tab.bom = false;
+            }
+            let mut bytes = Vec::with_capacity(tab.content.len() + UTF8_BOM.len_utf8());
+            if tab.bom {
+                bytes.extend_from_slice(UTF8_BOM.encode_utf8(&mut [0; 4]).as_bytes());
+            }
+            bytes.extend_from_slice(tab.content.as_bytes());
+
+            let Some(path) = target_path else {
+                return false;
+            };
+            let key = tab_name.clone();
+            if tab.path.is_some() && self.stale_save.blocks(&key, &path, tab.disk_stamp.as_ref()) {
+                return false;
+            }
+            let hash = journal::content_hash(&tab.content);
+            let sensitive = tab.sensitive;
+            let audit = self.audit_snapshot(&path, sensitive);
+            if let Err(err) = atomic_write::write(&path, &bytes) {
+                if err.kind() == std::io::ErrorKind::PermissionDenied {
+                    self.offer_elevated_save(key, path, bytes, hash, audit);
+                } else {
+                    self.notifications.error(trf(
+                        "Could not save {path}: {error}",
+                        &[("path", &path.display()), ("error", &err)],
+                    ));
                 }
+                return false;
+            }
+            self.record_save(audit, &bytes);
+            self.finish_save(&key, path, hash);
+            return true;
+        }
+        false
+    }
+
+    /// Records that the tab `key` was written to `path` with content hashing
+    /// to `hash`.
+    fn finish_save(&mut self, key: &str, path: PathBuf, hash: u64) {
+        let Some(tab) = self.tabs.get_mut(key) else {
+            return;
+        };
+        let newly_named = tab.path.is_none();
+        if let Some(index) = &mut self.symbol_index {
+            index.reindex(&path, &tab.content);
+        }
+        tab.disk_stamp = stale_save::DiskStamp::read(&path);
+        tab.path = Some(path);
+        tab.disk_state = DiskState::Present;
+        tab.saved_hash = Some(hash);
+        // A save finishing in the background may have been overtaken by
+        // further typing; its text is then unknown.
+        tab.saved_content =
+            (journal::content_hash(&tab.content) == hash).then(|| tab.content.clone());
+        if let Some(id) = tab.journal_id.take() {
+            self.journal.discard(&id);
+        }
+        if newly_named {
+            let tab = &self.tabs[key];
+            let syntax = self.detect_syntax(tab.path.as_deref(), &tab.content);
+            if let Some(tab) = self.tabs.get_mut(key) {
+                tab.syntax = syntax;
             }
         }
     }
-    
+
+    /// Saves the active tab under a newly chosen path and names the tab
+    /// after it.
+    fn save_active_as(&mut self) -> bool {
+        let Some(key) = self.active_tab.clone() else {
+            return false;
+        };
+        let tab = &self.tabs[&key];
+        if tab.read_only || tab.follow.is_some() || tab.archive_entry.is_some() {
+            return false;
+        }
+        let Some(path) = platform::file_dialog()
+            .set_file_name(&tab.title)
+            .save_file()
+        else {
+            return false;
+        };
+        let previous = self
+            .tabs
+            .get_mut(&key)
+            .and_then(|tab| tab.path.replace(path.clone()));
+        if !self.save_active() {
+            if let Some(tab) = self.tabs.get_mut(&key) {
+                tab.path = previous;
+            }
+            return false;
+        }
+        let syntax = self.detect_syntax(Some(&path), &self.tabs[&key].content);
+        if let Some(tab) = self.tabs.get_mut(&key) {
+            tab.syntax = syntax;
+        }
+        if let Some(name) = path.file_name() {
+            self.retitle_tab(&key, &name.to_string_lossy());
+        }
+        true
+    }
+
+    /// Moves the tab stored under `key` to a key derived from `title`.
+    fn retitle_tab(&mut self, key: &str, title: &str) {
+        let Some(mut tab) = self.tabs.remove(key) else {
+            return;
+        };
+        let new_key = self.unique_key(title);
+        tab.title = new_key.clone();
+        self.tabs.insert(new_key.clone(), tab);
+        for name in &mut self.open_order {
+            if name == key {
+                *name = new_key.clone();
+            }
+        }
+        if self.active_tab.as_deref() == Some(key) {
+            self.active_tab = Some(new_key);
+        }
+    }
+
     // New method to toggle theme
     fn toggle_theme(&mut self, ctx: &egui::Context) {
         self.dark_mode = !self.dark_mode;
-        if self.dark_mode {
-            ctx.set_visuals(egui::Visuals::dark());
-        } else {
-            ctx.set_visuals(egui::Visuals::light());
+        if !self.settings.color_theme.is_empty() {
+            self.settings.color_theme.clear();
+            self.settings.save();
         }
+        self.apply_theme(ctx);
     }
 }
 
 impl eframe::App for TextEditorApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Set visuals based on current theme
-        if self.dark_mode {
-            ctx.set_visuals(egui::Visuals::dark());
-        } else {
-            ctx.set_visuals(egui::Visuals::light());
-        }
-        
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        platform::remember_main_window(frame);
+        self.profiler.begin_frame();
+        self.apply_ui_scale(ctx);
+        self.apply_power_mode(ctx);
+        self.track_tab_activity(ctx);
+        self.intercept_close(ctx);
+
         egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
-            ui.horizontal(|ui| {
-                if ui.button("New File").clicked() {
-                    self.create_new_file();
-                }
-                if ui.button("Open File").clicked() {
-                    if let Some(path) = FileDialog::new().pick_file() {
-                        self.open_file(&path);
-                    }
-                }
-                if ui.button("Open Folder").clicked() {
-                    if let Some(folder) = FileDialog::new().pick_folder() {
-                        self.folder_path = Some(folder.clone());
-                        self.file_list = fs::read_dir(&folder)
-                            .unwrap()
-                            .filter_map(Result::ok)
-                            .map(|e| e.path())
-                            .filter(|p| p.is_file())
-                            .collect();
-                    }
-                }
-                if ui.button("Save").clicked() {
-                    self.save_active();
-                }
-                if ui.button("Rename").clicked() {
-                    if let Some(tab_name) = &self.active_tab {
-                        if let Some(tab) = self.tabs.get(tab_name) {
-                            self.rename_input = tab.title.clone();
-                            self.show_rename = true;
-                        }
-                    }
-                }
-                if ui.button("Find").clicked() {
-                    self.show_find = true;
-                }
-                if ui.button("Replace").clicked() {
-                    self.show_replace = true;
-                }
-                
-                // Add theme toggle button
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    let theme_text = if self.dark_mode { "Light Theme" } else { "Dark Theme" };
-                    if ui.button(theme_text).clicked() {
-                        self.toggle_theme(ctx);
-                    }
-                });
-            });
-        });
-        
-        // Add sidebar width control panel
-        egui::TopBottomPanel::top("sidebar_controls").show(ctx, |ui| {
-            ui.horizontal(|ui| {
-                ui.label("Sidebar Width:");
-                if ui.button("Small (150px)").clicked() {
-                    self.sidebar_width = 150.0;
-                }
-                if ui.button("Medium (250px)").clicked() {
-                    self.sidebar_width = 250.0;
-                }
-                if ui.button("Large (350px)").clicked() {
-                    self.sidebar_width = 350.0;
-                }
-            });
+            self.menu_bar(ctx, ui);
         });
 
         // Side panel with fixed width based on sidebar_width
         egui::SidePanel::left("file_browser")
             .exact_width(self.sidebar_width) // Use exact width from current sidebar_width
             .show(ctx, |ui| {
-                ui.heading("Files");
-                
-                if let Some(folder) = &self.folder_path {
-                    ui.label(folder.display().to_string());
+                self.favorites_sidebar(ui);
+                self.open_editors_sidebar(ui);
+                ui.heading(tr("Files"));
+
+                if !self.folders.is_empty() {
+                    if let [folder] = self.folders.as_slice() {
+                        ui.label(folder.display().to_string());
+                    }
                     ui.separator();
-                    
-                    egui::ScrollArea::vertical().show(ui, |ui| {
-                        for path in self.file_list.clone() {
-                            if let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) {
-                                if ui.button(&name).clicked() {
-                                    self.open_file(&path);
-                                }
-                            }
-                        }
-                    });
+                    self.file_tree_sidebar(ui);
                 } else {
-                    ui.label("No folder opened");
+                    ui.label(tr("No folder opened"));
                 }
+                self.archive_sidebar(ui);
+                self.outline_sidebar(ui);
             });
 
+        self.sync_symbol_index(ctx);
+        self.poll_documents(ctx);
+        self.poll_followed_files(ctx);
+        self.check_open_files(ctx);
+        self.poll_status_commands(ctx);
+        self.poll_updates(ctx);
+        self.show_status_bar(ctx);
+        self.show_bottom_panel(ctx);
+        self.handle_file_tree_keys(ctx);
+        self.handle_shortcuts(ctx);
+        self.handle_caret_motion(ctx);
+        self.handle_path_completion(ctx);
+        self.handle_word_completion(ctx);
+        self.handle_table_keys(ctx);
+        self.handle_emmet(ctx);
+        self.handle_block_end(ctx);
+        self.handle_continuation(ctx);
+        self.handle_tag_close(ctx);
+        self.handle_tab_key(ctx);
+        self.handle_paste_variants(ctx);
+        self.reveal_caret(ctx);
+
         egui::CentralPanel::default().show(ctx, |ui| {
             egui::TopBottomPanel::top("tabs").show_inside(ui, |ui| {
                 let mut tab_to_close: Option<String> = None;
+                let mut group_action = None;
                 ui.horizontal_wrapped(|ui| {
                     for tab_name in &self.open_order {
+                        let tab = &self.tabs[tab_name];
                         let is_active = Some(tab_name) == self.active_tab.as_ref();
-                        ui.horizontal(|ui| {
-                            if ui.selectable_label(is_active, tab_name).clicked() {
+                        let label = if tab.disk_state == DiskState::Present {
+                            egui::RichText::new(tab_name)
+                        } else {
+                            egui::RichText::new(trf("{name} (deleted)", &[("name", tab_name)]))
+                                .strikethrough()
+                        };
+                        let group = self
+                            .tab_groups
+                            .group_of(tab_name, tab)
+                            .map(|group| (group.name.clone(), group.color32()));
+                        let response = ui.horizontal(|ui| {
+                            let mut response = ui.selectable_label(is_active, label);
+                            if let Some((name, _)) = &group {
+                                response = response.on_hover_text(name);
+                            }
+                            if response.clicked() {
                                 self.active_tab = Some(tab_name.clone());
                             }
+                            response.context_menu(|ui| {
+                                if let Some(action) = self.tab_groups.menu(ui, tab_name, tab) {
+                                    group_action = Some(action);
+                                }
+                            });
                             if ui.button("×").clicked() {
                                 tab_to_close = Some(tab_name.clone());
                             }
                         });
+                        if let Some((_, color)) = group {
+                            let rect = response.response.rect;
+                            ui.painter().hline(
+                                rect.x_range(),
+                                rect.bottom() + 1.0,
+                                egui::Stroke::new(3.0, color),
+                            );
+                        }
                     }
                 });
                 if let Some(to_close) = tab_to_close {
-                    self.tabs.remove(&to_close);
-                    self.open_order.retain(|n| n != &to_close);
-                    if self.active_tab.as_ref() == Some(&to_close) {
-                        self.active_tab = self.open_order.last().cloned();
-                    }
+                    self.close_tab(&to_close);
+                }
+                if let Some(action) = group_action {
+                    self.apply_group_action(action);
                 }
             });
 
-            if let Some(tab_name) = &self.active_tab {
-                if let Some(tab) = self.tabs.get_mut(tab_name) {
-                    ui.add_sized(
-                        ui.available_size(),
-                        egui::TextEdit::multiline(&mut tab.content)
-                            .font(egui::TextStyle::Monospace)
-                            .code_editor(),
-                    );
-                }
+            let following = self
+                .active_tab
+                .as_ref()
+                .and_then(|key| self.tabs.get(key))
+                .is_some_and(|tab| tab.follow.is_some());
+            if following {
+                self.show_follow_view(ui);
+            } else if self.active_tab.is_some() {
+                self.deleted_file_banner(ui);
+                self.breadcrumb_bar(ui);
+                self.show_editor(ui);
             } else {
-                ui.label("No file opened");
+                ui.label(tr("No file opened"));
             }
         });
 
-        let mut show_rename = self.show_rename;
-        if show_rename {
-            egui::Window::new("Rename File")
+        let mut find_next = false;
+        let mut show_find = self.show_find;
+        if show_find {
+            egui::Window::new(tr("Find"))
                 .collapsible(false)
                 .resizable(false)
                 .default_size((300.0, 120.0))
-                .open(&mut show_rename)
+                .open(&mut show_find)
                 .show(ctx, |ui| {
-                    ui.label("New name:");
-                    ui.text_edit_singleline(&mut self.rename_input);
-                    ui.horizontal(|ui| {
-                        if ui.button("OK").clicked() {
-                            if let Some(tab_name) = &self.active_tab {
-                                if let Some(tab) = self.tabs.get_mut(tab_name) {
-                                    let new_title = self.rename_input.trim();
-                                    if !new_title.is_empty() {
-                                        if let Some(old_path) = &tab.path {
-                                            let new_path = old_path.with_file_name(new_title);
-                                            if fs::rename(old_path, &new_path).is_ok() {
-                                                tab.path = Some(new_path);
-                                            }
-                                        }
-                                        let old_key = tab_name.clone();
-                                        let mut updated_tab = self.tabs.remove(&old_key).unwrap();
-                                        updated_tab.title = new_title.to_string();
-                                        self.tabs.insert(new_title.to_string(), updated_tab);
-                                        for name in &mut self.open_order {
-                                            if name == &old_key {
-                                                *name = new_title.to_string();
-                                            }
-                                        }
-                                        self.active_tab = Some(new_title.to_string());
-                                    }
-                                }
+                    ui.label(tr("Find:"));
+                    search_history::history_field(
+                        ui,
+                        "find_input",
+                        &mut self.find_input,
+                        &self.search_history.searches,
+                    );
+                    egui::ComboBox::from_id_source("find_scope")
+                        .selected_text(tr(self.find_scope.label()))
+                        .show_ui(ui, |ui| {
+                            for scope in scopes::SearchScope::ALL {
+                                ui.selectable_value(&mut self.find_scope, scope, tr(scope.label()));
                             }
-                            self.show_rename = false;
+                        });
+                    unicode_search::checkbox(ui, &mut self.find_normalize);
+                    ui.horizontal(|ui| {
+                        if ui.button(tr("Count occurrences")).clicked() {
+                            self.found_count = self.find_matches(&self.find_input).len();
+                            self.search_history.remember_search(&self.find_input);
                         }
-                        if ui.button("Cancel").clicked() {
-                            self.show_rename = false;
+                        if ui.button(tr("Find Next")).clicked() {
+                            find_next = true;
                         }
                     });
+                    ui.label(trf("Found: {count}", &[("count", &self.found_count)]));
                 });
-            self.show_rename = show_rename;
+            self.show_find = show_find;
         }
-
-        if self.show_find {
-            egui::Window::new("Find")
-                .collapsible(false)
-                .resizable(false)
-                .default_size((300.0, 120.0))
-                .open(&mut self.show_find)
-                .show(ctx, |ui| {
-                    ui.label("Find:");
-                    ui.text_edit_singleline(&mut self.find_input);
-                    if ui.button("Count occurrences").clicked() {
-                        if let Some(tab_name) = &self.active_tab {
-                            if let Some(tab) = self.tabs.get(tab_name) {
-                                self.found_count = tab.content.matches(&self.find_input).count();
-                            }
-                        }
-                    }
-                    ui.label(format!("Found: {}", self.found_count));
-                });
+        if find_next {
+            self.search_history.remember_search(&self.find_input);
+            self.find_next(ctx);
         }
 
         let mut show_replace = self.show_replace;
         if show_replace {
-            egui::Window::new("Find & Replace")
+            egui::Window::new(tr("Find & Replace"))
                 .collapsible(false)
                 .resizable(false)
                 .default_size((350.0, 160.0))
                 .open(&mut show_replace)
                 .show(ctx, |ui| {
-                    ui.label("Find:");
-                    ui.text_edit_singleline(&mut self.replace_find_input);
-                    ui.label("Replace with:");
-                    ui.text_edit_singleline(&mut self.replace_with_input);
+                    ui.label(tr("Find:"));
+                    search_history::history_field(
+                        ui,
+                        "replace_find_input",
+                        &mut self.replace_find_input,
+                        &self.search_history.searches,
+                    );
+                    ui.label(tr("Replace with:"));
+                    search_history::history_field(
+                        ui,
+                        "replace_with_input",
+                        &mut self.replace_with_input,
+                        &self.search_history.replacements,
+                    );
+                    unicode_search::checkbox(ui, &mut self.find_normalize);
                     ui.horizontal(|ui| {
-                        if ui.button("Replace All").clicked() {
-                            if let Some(tab_name) = &self.active_tab {
-                                if let Some(tab) = self.tabs.get_mut(tab_name) {
-                                    tab.content = tab
-                                        .content
-                                        .replace(&self.replace_find_input, &self.replace_with_input);
-                                }
+                        if ui.button(tr("Replace All")).clicked() {
+                            self.search_history
+                                .remember_search(&self.replace_find_input);
+                            self.search_history
+                                .remember_replacement(&self.replace_with_input);
+                            if let Some(tab_name) = &self.active_tab
+                                && let Some(tab) = self.tabs.get_mut(tab_name)
+                                && !tab.read_only
+                            {
+                                tab.content = if self.find_normalize {
+                                    let ranges = unicode_search::find(
+                                        &tab.content,
+                                        &self.replace_find_input,
+                                        true,
+                                    );
+                                    project_search::replace_ranges(
+                                        &tab.content,
+                                        &ranges,
+                                        &self.replace_with_input,
+                                    )
+                                } else {
+                                    tab.content
+                                        .replace(&self.replace_find_input, &self.replace_with_input)
+                                };
                             }
                         }
-                        if ui.button("Close").clicked() {
+                        self.saved_searches_menu(ui);
+                        if ui.button(tr("Close")).clicked() {
                             self.show_replace = false;
                         }
                     });
                 });
             self.show_replace = show_replace;
         }
-    }
 
+        if self.show_preferences {
+            self.show_preferences_window(ctx);
+        }
+
+        if self.show_about {
+            self.show_about_window(ctx);
+        }
+
+        self.show_file_history_window(ctx);
+        self.show_open_url_window(ctx);
+        self.show_gist_window(ctx);
+        self.show_diff_window(ctx);
+        self.show_merge_window(ctx);
+        self.show_rename_window(ctx);
+        self.show_replace_preview(ctx);
+        self.show_elevated_save_window(ctx);
+        self.show_new_project_window(ctx);
+        self.show_unicode_picker(ctx);
+        self.show_align_window(ctx);
+        self.show_checkpoints_window(ctx);
+        self.show_tab_groups_window(ctx);
+        self.show_trust_prompt(ctx);
+        self.show_update_window(ctx);
+        self.show_theme_editor(ctx);
+        self.show_cheat_sheet(ctx);
+        self.show_resources_window(ctx);
+        self.show_profiler_window(ctx);
+        self.show_jobs_window(ctx);
+        self.show_path_completion(ctx);
+        self.show_toasts(ctx);
+        self.show_quit_confirm(ctx);
+        self.show_delete_confirm(ctx);
+        self.show_stale_save_dialog(ctx);
 
+        self.show_command_palette(ctx);
+        self.show_template_picker(ctx);
+        self.show_workspace_symbols(ctx);
+        self.show_file_symbols(ctx);
+        self.show_link_picker(ctx);
+        self.show_rename_occurrences(ctx);
+        self.show_branch_picker(ctx);
+
+        self.flush_journal(ctx);
+        self.update_window_title(ctx);
+        self.publish_live_view(ctx);
+        self.profiler.end_frame();
+    }
 }
 
 fn main() {
-    let options = eframe::NativeOptions::default();
-    eframe::run_native(
-        "rusty-editor",
+    let args: Vec<String> = std::env::args().collect();
+    settings::init_portable(&args);
+    let locations = cli::locations(&args);
+    let diff_files = cli::diff_files(&args);
+    let merge_files = cli::merge_files(&args);
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_title(window_title::APP_NAME)
+            .with_app_id(window_title::APP_ID)
+            .with_icon(window_title::icon()),
+        // The theme is the app's own; visuals are only set when it changes.
+        follow_system_theme: false,
+        ..Default::default()
+    };
+    let _ = eframe::run_native(
+        window_title::APP_ID,
         options,
-        Box::new(|cc| {
+        Box::new(move |cc| {
             let mut app = TextEditorApp::default();
-            // Apply initial theme
-            if app.dark_mode {
-                cc.egui_ctx.set_visuals(egui::Visuals::dark());
-            } else {
-                cc.egui_ctx.set_visuals(egui::Visuals::light());
+            i18n::set_language(&app.settings.language);
+            app.restore_journal();
+            for location in locations {
+                if location.path.is_dir() {
+                    if app.folders.is_empty() {
+                        app.open_folder(location.path);
+                    } else {
+                        app.add_workspace_folder(location.path);
+                    }
+                } else {
+                    app.open_at(&cc.egui_ctx, &location.path, location.line, location.column);
+                }
             }
+            if let Some([left, right]) = &diff_files {
+                app.open_diff_files(left, right);
+            }
+            if let Some([base, ours, theirs, output]) = &merge_files {
+                app.open_merge_files(base, ours, theirs, output);
+            }
+            // Apply initial theme
+            app.apply_theme(&cc.egui_ctx);
             Box::new(app)
         }),
     );
 }
-
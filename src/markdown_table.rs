@@ -0,0 +1,321 @@
+//! Markdown table helpers: reformat the table under the caret so its pipes
+//! line up, move between cells with Tab/Shift+Tab (adding a row past the
+//! last cell) and insert rows and columns.
+
+use crate::TextEditorApp;
+use crate::editing::{byte_to_char, char_to_byte};
+use crate::motion::take_key;
+use eframe::egui;
+use std::ops::Range;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Align {
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+enum Row {
+    Cells(Vec<String>),
+    /// The `| --- | :-: |` line below the header.
+    Separator(Vec<Align>),
+}
+
+#[derive(Clone, Copy)]
+pub enum TableEdit {
+    Format,
+    NextCell,
+    PreviousCell,
+    InsertRow,
+    InsertColumn,
+}
+
+fn is_table_line(line: &str) -> bool {
+    line.trim_start().starts_with('|')
+}
+
+/// The cells of a table line, without the outer pipes. Escaped pipes stay
+/// inside their cell.
+fn cells(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = line
+        .strip_suffix('|')
+        .filter(|rest| !rest.ends_with('\\'))
+        .unwrap_or(line);
+    let mut cells = vec![String::new()];
+    let mut escaped = false;
+    for c in line.chars() {
+        if c == '|' && !escaped {
+            cells.push(String::new());
+        } else if let Some(cell) = cells.last_mut() {
+            cell.push(c);
+        }
+        escaped = c == '\\';
+    }
+    cells
+        .into_iter()
+        .map(|cell| cell.trim().to_string())
+        .collect()
+}
+
+fn parse_row(line: &str) -> Row {
+    let cells = cells(line);
+    let is_separator = cells.iter().all(|cell| {
+        let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+        !dashes.is_empty() && dashes.chars().all(|c| c == '-')
+    });
+    if !is_separator {
+        return Row::Cells(cells);
+    }
+    Row::Separator(
+        cells
+            .iter()
+            .map(|cell| match (cell.starts_with(':'), cell.ends_with(':')) {
+                (true, true) => Align::Center,
+                (true, false) => Align::Left,
+                (false, true) => Align::Right,
+                (false, false) => Align::None,
+            })
+            .collect(),
+    )
+}
+
+fn width(text: &str) -> usize {
+    text.chars().count()
+}
+
+fn pad(cell: &str, width: usize, align: Align) -> String {
+    let room = width.saturating_sub(self::width(cell));
+    let (left, right) = match align {
+        Align::Right => (room, 0),
+        Align::Center => (room / 2, room - room / 2),
+        Align::None | Align::Left => (0, room),
+    };
+    format!("{}{cell}{}", " ".repeat(left), " ".repeat(right))
+}
+
+fn separator_cell(width: usize, align: Align) -> String {
+    match align {
+        Align::None => "-".repeat(width),
+        Align::Left => format!(":{}", "-".repeat(width - 1)),
+        Align::Right => format!("{}:", "-".repeat(width - 1)),
+        Align::Center => format!(":{}:", "-".repeat(width - 2)),
+    }
+}
+
+/// Renders `rows` with every column padded to its widest cell. Returns the
+/// text and each column's width.
+fn format(rows: &[Row], indent: &str) -> (String, Vec<usize>) {
+    let columns = rows
+        .iter()
+        .map(|row| match row {
+            Row::Cells(cells) => cells.len(),
+            Row::Separator(aligns) => aligns.len(),
+        })
+        .max()
+        .unwrap_or(0);
+    let aligns: Vec<Align> = rows
+        .iter()
+        .find_map(|row| match row {
+            Row::Separator(aligns) => Some(aligns.clone()),
+            Row::Cells(_) => None,
+        })
+        .unwrap_or_default();
+    let align = |column: usize| aligns.get(column).copied().unwrap_or(Align::None);
+    let mut widths = vec![3; columns];
+    for row in rows {
+        if let Row::Cells(cells) = row {
+            for (width, cell) in widths.iter_mut().zip(cells) {
+                *width = (*width).max(self::width(cell));
+            }
+        }
+    }
+    let lines: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            let cells: Vec<String> = (0..columns)
+                .map(|column| match row {
+                    Row::Cells(cells) => pad(
+                        cells.get(column).map_or("", String::as_str),
+                        widths[column],
+                        align(column),
+                    ),
+                    Row::Separator(_) => separator_cell(widths[column], align(column)),
+                })
+                .collect();
+            format!("{indent}| {} |", cells.join(" | "))
+        })
+        .collect();
+    (lines.join("\n"), widths)
+}
+
+/// Byte range of the table lines around byte offset `pos`.
+fn table_at(text: &str, pos: usize) -> Option<Range<usize>> {
+    let line_start = text[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = text[pos..].find('\n').map_or(text.len(), |i| pos + i);
+    if !is_table_line(&text[line_start..line_end]) {
+        return None;
+    }
+    let mut start = line_start;
+    while start > 0 {
+        let previous = text[..start - 1].rfind('\n').map_or(0, |i| i + 1);
+        if !is_table_line(&text[previous..start - 1]) {
+            break;
+        }
+        start = previous;
+    }
+    let mut end = line_end;
+    while end < text.len() {
+        let next = text[end + 1..]
+            .find('\n')
+            .map_or(text.len(), |i| end + 1 + i);
+        if !is_table_line(&text[end + 1..next]) {
+            break;
+        }
+        end = next;
+    }
+    Some(start..end)
+}
+
+impl TextEditorApp {
+    fn in_markdown_tab(&self) -> bool {
+        self.active_tab
+            .as_ref()
+            .and_then(|key| self.tabs.get(key))
+            .is_some_and(|tab| tab.syntax.as_deref() == Some("Markdown"))
+    }
+
+    /// Tab and Shift+Tab move between cells while the caret is in a table.
+    pub(crate) fn handle_table_keys(&mut self, ctx: &egui::Context) {
+        if !self.in_markdown_tab() || !self.editor_has_focus(ctx) {
+            return;
+        }
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        let Some(caret) = self
+            .selection(ctx)
+            .map(|s| char_to_byte(&tab.content, s.start))
+        else {
+            return;
+        };
+        if table_at(&tab.content, caret).is_none() {
+            return;
+        }
+        let plain = |m: egui::Modifiers| !m.alt && !m.command && !m.ctrl;
+        if let Some(shift) = take_key(ctx, egui::Key::Tab, plain) {
+            let edit = if shift {
+                TableEdit::PreviousCell
+            } else {
+                TableEdit::NextCell
+            };
+            self.edit_table(ctx, edit);
+        }
+    }
+
+    /// Applies `edit` to the table under the caret and reformats it.
+    pub(crate) fn edit_table(&mut self, ctx: &egui::Context, edit: TableEdit) {
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        let Some(caret) = self
+            .selection(ctx)
+            .map(|s| char_to_byte(&tab.content, s.start))
+        else {
+            return;
+        };
+        let text = &tab.content;
+        let Some(range) = table_at(text, caret) else {
+            return;
+        };
+        let table = &text[range.clone()];
+        let indent: String = table.chars().take_while(|c| c.is_whitespace()).collect();
+        let mut rows: Vec<Row> = table.split('\n').map(parse_row).collect();
+
+        // Where the caret is: its row and the cell it is in.
+        let before = &text[range.start..caret];
+        let mut row = before.matches('\n').count();
+        let line = &before[before.rfind('\n').map_or(0, |i| i + 1)..];
+        let mut column = line.matches('|').count().saturating_sub(1);
+        let columns = rows
+            .iter()
+            .map(|row| match row {
+                Row::Cells(cells) => cells.len(),
+                Row::Separator(aligns) => aligns.len(),
+            })
+            .max()
+            .unwrap_or(1);
+        column = column.min(columns - 1);
+        let is_separator =
+            |rows: &[Row], row: usize| matches!(rows.get(row), Some(Row::Separator(_)));
+
+        match edit {
+            TableEdit::Format => {}
+            TableEdit::NextCell => {
+                if column + 1 < columns {
+                    column += 1;
+                } else {
+                    column = 0;
+                    row += 1;
+                    if is_separator(&rows, row) {
+                        row += 1;
+                    }
+                    if row >= rows.len() {
+                        rows.push(Row::Cells(vec![String::new(); columns]));
+                    }
+                }
+            }
+            TableEdit::PreviousCell => {
+                if column > 0 {
+                    column -= 1;
+                } else if row > 0 {
+                    row -= 1;
+                    if is_separator(&rows, row) && row > 0 {
+                        row -= 1;
+                    }
+                    column = columns - 1;
+                }
+            }
+            TableEdit::InsertRow => {
+                row += 1;
+                if is_separator(&rows, row) {
+                    row += 1;
+                }
+                rows.insert(row, Row::Cells(vec![String::new(); columns]));
+                column = 0;
+            }
+            TableEdit::InsertColumn => {
+                column += 1;
+                for row in &mut rows {
+                    match row {
+                        Row::Cells(cells) => {
+                            cells.resize(columns, String::new());
+                            cells.insert(column, String::new());
+                        }
+                        Row::Separator(aligns) => {
+                            aligns.resize(columns, Align::None);
+                            aligns.insert(column, Align::None);
+                        }
+                    }
+                }
+            }
+        }
+
+        let (formatted, widths) = format(&rows, &indent);
+        // The caret goes to the start of the target cell's text.
+        let line_start: usize = formatted
+            .split('\n')
+            .take(row)
+            .map(|line| width(line) + 1)
+            .sum();
+        let cell_offset =
+            width(&indent) + 2 + widths[..column].iter().map(|w| w + 3).sum::<usize>();
+        let target = byte_to_char(text, range.start) + line_start + cell_offset;
+        if formatted != table {
+            self.replace_range(ctx, range, &formatted);
+        }
+        self.set_selection(ctx, target..target);
+    }
+}
@@ -0,0 +1,173 @@
+//! The application menu bar. Menus are built from the command registry so
+//! every entry shows its shortcut and runs through [`TextEditorApp::run_command`].
+
+use crate::TextEditorApp;
+use crate::commands::Command;
+use crate::i18n::{tr, trf};
+use crate::transform::Transform;
+use eframe::egui;
+
+const SIDEBAR_WIDTHS: [(&str, f32); 3] = [
+    ("Small (150px)", 150.0),
+    ("Medium (250px)", 250.0),
+    ("Large (350px)", 350.0),
+];
+
+impl TextEditorApp {
+    pub(crate) fn menu_bar(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        egui::menu::bar(ui, |ui| {
+            ui.menu_button(tr("File"), |ui| {
+                self.command_button(ui, Command::NewFile);
+                self.command_button(ui, Command::NewFileFromTemplate);
+                self.command_button(ui, Command::NewProject);
+                self.command_button(ui, Command::OpenFile);
+                self.command_button(ui, Command::OpenFolder);
+                self.command_button(ui, Command::AddFolderToWorkspace);
+                self.command_button(ui, Command::OpenUrl);
+                self.command_button(ui, Command::ReopenClosedTab);
+                self.reopen_closed_menu(ctx, ui);
+                ui.separator();
+                self.command_button(ui, Command::Save);
+                self.command_button(ui, Command::SaveAs);
+                self.command_button(ui, Command::Rename);
+                self.command_button(ui, Command::ToggleSensitive);
+                self.command_button(ui, Command::AddToFavorites);
+                ui.separator();
+                self.profiles_menu(ctx, ui);
+                self.command_button(ui, Command::Preferences);
+            });
+            ui.menu_button(tr("Edit"), |ui| {
+                self.command_button(ui, Command::HardWrap);
+                self.command_button(ui, Command::AlignSelection);
+                self.command_button(ui, Command::ExpandSelection);
+                self.command_button(ui, Command::ShrinkSelection);
+                self.command_button(ui, Command::RenameOccurrences);
+                self.command_button(ui, Command::SelectTagContents);
+                ui.separator();
+                self.command_button(ui, Command::PasteAndIndent);
+                self.command_button(ui, Command::PasteAsColumn);
+                self.command_button(ui, Command::PastePlainText);
+                self.command_button(ui, Command::PasteAsMarkdownLink);
+                ui.separator();
+                ui.menu_button(tr("Transform"), |ui| {
+                    for transform in Transform::ALL {
+                        self.command_button(ui, Command::Transform(transform));
+                    }
+                });
+                ui.menu_button(tr("Insert"), |ui| {
+                    self.command_button(ui, Command::InsertDateTime);
+                    self.command_button(ui, Command::InsertTimestamp);
+                    self.command_button(ui, Command::InsertUuid);
+                    self.command_button(ui, Command::InsertUnicode);
+                    ui.separator();
+                    self.command_button(ui, Command::InsertLinkToFile);
+                    self.command_button(ui, Command::InsertImage);
+                });
+                ui.menu_button(tr("Reformat"), |ui| {
+                    self.command_button(ui, Command::PrettyPrint);
+                    self.command_button(ui, Command::Minify);
+                });
+                ui.menu_button(tr("Indentation"), |ui| {
+                    self.command_button(ui, Command::ConvertIndentationToSpaces);
+                    self.command_button(ui, Command::ConvertIndentationToTabs);
+                    self.command_button(ui, Command::Reindent);
+                });
+                ui.menu_button(tr("Table"), |ui| {
+                    self.command_button(ui, Command::FormatTable);
+                    self.command_button(ui, Command::InsertTableRow);
+                    self.command_button(ui, Command::InsertTableColumn);
+                });
+                ui.separator();
+                self.command_button(ui, Command::RemoveSuspicious);
+                self.command_button(ui, Command::EscapeSuspicious);
+                ui.separator();
+                self.command_button(ui, Command::CompareWithClipboard);
+                self.command_button(ui, Command::Checkpoints);
+            });
+            ui.menu_button(tr("View"), |ui| {
+                self.command_button(ui, Command::ShowCommandPalette);
+                self.command_button(ui, Command::ToggleTheme);
+                self.color_theme_menu(ctx, ui);
+                self.command_button(ui, Command::EditTheme);
+                self.command_button(ui, Command::ToggleProseMode);
+                self.command_button(ui, Command::ToggleFold);
+                self.command_button(ui, Command::UnfoldAll);
+                self.command_button(ui, Command::TabGroups);
+                ui.menu_button(tr("Sidebar Width"), |ui| {
+                    for (label, width) in SIDEBAR_WIDTHS {
+                        if ui.radio(self.sidebar_width == width, tr(label)).clicked() {
+                            self.sidebar_width = width;
+                            ui.close_menu();
+                        }
+                    }
+                });
+                ui.separator();
+                self.command_button(ui, Command::ToggleProblems);
+                self.command_button(ui, Command::ToggleOutput);
+                self.command_button(ui, Command::ToggleConsole);
+                self.command_button(ui, Command::ToggleBacklinks);
+                self.command_button(ui, Command::ToggleAuditLog);
+                self.command_button(ui, Command::FocusFileBrowser);
+                self.command_button(ui, Command::RevealActiveFile);
+            });
+            ui.menu_button(tr("Search"), |ui| {
+                self.command_button(ui, Command::Find);
+                self.command_button(ui, Command::Replace);
+                self.command_button(ui, Command::FindInFiles);
+                self.command_button(ui, Command::FindAllReferences);
+                ui.separator();
+                self.command_button(ui, Command::GoToWorkspaceSymbol);
+                self.command_button(ui, Command::GoToSymbolInFile);
+                self.command_button(ui, Command::SwitchToCounterpart);
+                self.command_button(ui, Command::NextConflict);
+                self.command_button(ui, Command::PreviousConflict);
+                self.command_button(ui, Command::NextHeading);
+                self.command_button(ui, Command::PreviousHeading);
+                self.command_button(ui, Command::GoToMatchingTag);
+            });
+            ui.menu_button(tr("Tools"), |ui| {
+                self.command_button(ui, Command::RunCurrentFile);
+                self.command_button(ui, Command::StopRun);
+                self.command_button(ui, Command::EvaluateSelection);
+                ui.separator();
+                self.command_button(ui, Command::RunCargoCheck);
+                self.command_button(ui, Command::ScanTodos);
+                ui.separator();
+                self.command_button(ui, Command::OpenTerminalInProject);
+                self.command_button(ui, Command::OpenTerminalInFileFolder);
+                ui.separator();
+                self.command_button(ui, Command::ShowFileHistory);
+                self.command_button(ui, Command::ToggleFollow);
+                ui.separator();
+                self.command_button(ui, Command::ShareAsGist);
+                self.command_button(ui, Command::ToggleLiveView);
+            });
+            ui.menu_button(tr("Help"), |ui| {
+                self.command_button(ui, Command::ShowCommandPalette);
+                self.command_button(ui, Command::ShowResources);
+                self.command_button(ui, Command::ToggleProfiler);
+                self.command_button(ui, Command::KeyboardShortcuts);
+                ui.separator();
+                self.command_button(ui, Command::CheckForUpdates);
+                self.command_button(ui, Command::About);
+            });
+        });
+    }
+
+    pub(crate) fn show_about_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_about;
+        egui::Window::new(tr("About Rusty Editor"))
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.heading("rusty-editor");
+                ui.label(trf(
+                    "Version {version}",
+                    &[("version", &env!("CARGO_PKG_VERSION"))],
+                ));
+                ui.label(tr("A work-in-progress text editor written in Rust."));
+            });
+        self.show_about = open;
+    }
+}
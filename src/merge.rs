@@ -0,0 +1,519 @@
+//! The three-way merge view, for use as git's mergetool: base, ours and
+//! theirs side by side above an editable result. Changes made on one side
+//! only are merged into the result right away; changes on both sides
+//! become conflict markers there, to be resolved with the Accept buttons
+//! or by hand.
+
+use crate::change_bars::{Hunk, hunks, line_bytes};
+use crate::conflicts::{Resolution, find_conflicts};
+use crate::i18n::{tr, trf};
+use crate::platform;
+use crate::{TextEditorApp, UTF8_BOM, atomic_write};
+use eframe::egui;
+use egui::text::{CCursor, LayoutJob};
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// Which side changed a chunk of the base.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ChunkKind {
+    Ours,
+    Theirs,
+    /// Both sides, in the same way.
+    Same,
+    Conflict,
+}
+
+/// A run of base lines changed on at least one side, with the lines that
+/// replace it on each. Lines are 0-based.
+pub struct Chunk {
+    pub kind: ChunkKind,
+    pub base: Range<usize>,
+    pub ours: Range<usize>,
+    pub theirs: Range<usize>,
+}
+
+/// Lines `lines` of `text`.
+fn slice<'a>(text: &'a str, lines: &Range<usize>) -> &'a str {
+    &text[line_bytes(text, lines)]
+}
+
+/// The chunks of a three-way merge. Changes on both sides count as one
+/// chunk when they overlap or touch in the base, as git does.
+pub fn chunks(base: &str, ours: &str, theirs: &str) -> Vec<Chunk> {
+    let mut changes: Vec<(bool, Hunk)> = hunks(base, ours)
+        .into_iter()
+        .map(|hunk| (true, hunk))
+        .chain(hunks(base, theirs).into_iter().map(|hunk| (false, hunk)))
+        .collect();
+    changes.sort_by_key(|(_, hunk)| (hunk.saved.start, hunk.saved.end));
+    let mut chunks = Vec::new();
+    // How many lines each side has gained before the current position.
+    let (mut ours_shift, mut theirs_shift) = (0isize, 0isize);
+    let mut changes = changes.into_iter().peekable();
+    while let Some((first_ours, first)) = changes.next() {
+        let mut base_range = first.saved.clone();
+        let mut members = vec![(first_ours, first)];
+        while let Some((_, next)) = changes.peek()
+            && next.saved.start <= base_range.end
+        {
+            let (side, next) = changes.next().expect("peeked");
+            base_range.end = base_range.end.max(next.saved.end);
+            members.push((side, next));
+        }
+        let growth = |ours: bool| {
+            members
+                .iter()
+                .filter(|(side, _)| *side == ours)
+                .map(|(_, hunk)| hunk.current.len() as isize - hunk.saved.len() as isize)
+                .sum::<isize>()
+        };
+        let shifted = |shift: isize, growth: isize| {
+            (base_range.start as isize + shift) as usize
+                ..(base_range.end as isize + shift + growth) as usize
+        };
+        let (ours_growth, theirs_growth) = (growth(true), growth(false));
+        let ours_range = shifted(ours_shift, ours_growth);
+        let theirs_range = shifted(theirs_shift, theirs_growth);
+        ours_shift += ours_growth;
+        theirs_shift += theirs_growth;
+        let changed_ours = members.iter().any(|(side, _)| *side);
+        let changed_theirs = members.iter().any(|(side, _)| !*side);
+        let kind = match (changed_ours, changed_theirs) {
+            (true, false) => ChunkKind::Ours,
+            (false, true) => ChunkKind::Theirs,
+            _ if slice(ours, &ours_range) == slice(theirs, &theirs_range) => ChunkKind::Same,
+            _ => ChunkKind::Conflict,
+        };
+        chunks.push(Chunk {
+            kind,
+            base: base_range,
+            ours: ours_range,
+            theirs: theirs_range,
+        });
+    }
+    chunks
+}
+
+/// Appends `lines`, ending them with a line break so a marker can follow.
+fn push_lines(result: &mut String, lines: &str) {
+    result.push_str(lines);
+    if !lines.is_empty() && !lines.ends_with('\n') {
+        result.push('\n');
+    }
+}
+
+/// The merged text: one-sided changes applied, conflicts between markers.
+fn merged(base: &str, ours: &str, theirs: &str, chunks: &[Chunk]) -> String {
+    let mut result = String::new();
+    let mut line = 0;
+    for chunk in chunks {
+        result.push_str(slice(base, &(line..chunk.base.start)));
+        match chunk.kind {
+            ChunkKind::Ours => result.push_str(slice(ours, &chunk.ours)),
+            ChunkKind::Theirs | ChunkKind::Same => result.push_str(slice(theirs, &chunk.theirs)),
+            ChunkKind::Conflict => {
+                if !result.is_empty() && !result.ends_with('\n') {
+                    result.push('\n');
+                }
+                result.push_str("<<<<<<< ours\n");
+                push_lines(&mut result, slice(ours, &chunk.ours));
+                result.push_str("=======\n");
+                push_lines(&mut result, slice(theirs, &chunk.theirs));
+                result.push_str(">>>>>>> theirs\n");
+            }
+        }
+        line = chunk.base.end;
+    }
+    result.push_str(slice(base, &(line..usize::MAX)));
+    result
+}
+
+/// One of the read-only panes: its title, lines and their tint.
+struct Pane {
+    title: String,
+    lines: Vec<String>,
+    kinds: Vec<Option<ChunkKind>>,
+}
+
+impl Pane {
+    fn new(
+        title: String,
+        text: &str,
+        ranges: impl Iterator<Item = (ChunkKind, Range<usize>)>,
+    ) -> Self {
+        let lines: Vec<String> = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| format!("{:>5}  {line}", i + 1))
+            .collect();
+        let mut kinds = vec![None; lines.len()];
+        for (kind, range) in ranges {
+            for line in range {
+                if let Some(slot) = kinds.get_mut(line) {
+                    *slot = Some(kind);
+                }
+            }
+        }
+        Self {
+            title,
+            lines,
+            kinds,
+        }
+    }
+}
+
+fn chunk_color(kind: ChunkKind, dark_mode: bool) -> egui::Color32 {
+    let alpha = if dark_mode { 50 } else { 36 };
+    match kind {
+        ChunkKind::Ours => egui::Color32::from_rgba_unmultiplied(40, 160, 90, alpha),
+        ChunkKind::Theirs => egui::Color32::from_rgba_unmultiplied(60, 120, 220, alpha),
+        ChunkKind::Same => egui::Color32::from_rgba_unmultiplied(128, 128, 128, alpha),
+        ChunkKind::Conflict => egui::Color32::from_rgba_unmultiplied(220, 60, 60, alpha),
+    }
+}
+
+pub struct MergeView {
+    ours: String,
+    theirs: String,
+    chunks: Vec<Chunk>,
+    panes: [Pane; 3],
+    result: String,
+    /// Where the result is saved; asked for when `None`.
+    output: Option<PathBuf>,
+    /// The conflict shown, counted among those left in the result.
+    current: usize,
+    /// Set for one frame to scroll everything to the current conflict.
+    scroll: bool,
+}
+
+impl MergeView {
+    pub fn new(titles: [String; 3], base: &str, ours: &str, theirs: &str) -> Self {
+        let chunks = chunks(base, ours, theirs);
+        let result = merged(base, ours, theirs, &chunks);
+        let [base_title, ours_title, theirs_title] = titles;
+        let panes = [
+            Pane::new(
+                base_title,
+                base,
+                chunks.iter().map(|c| (c.kind, c.base.clone())),
+            ),
+            Pane::new(
+                ours_title,
+                ours,
+                chunks
+                    .iter()
+                    .filter(|c| c.kind != ChunkKind::Theirs)
+                    .map(|c| (c.kind, c.ours.clone())),
+            ),
+            Pane::new(
+                theirs_title,
+                theirs,
+                chunks
+                    .iter()
+                    .filter(|c| c.kind != ChunkKind::Ours)
+                    .map(|c| (c.kind, c.theirs.clone())),
+            ),
+        ];
+        Self {
+            ours: ours.to_string(),
+            theirs: theirs.to_string(),
+            chunks,
+            panes,
+            result,
+            output: None,
+            current: 0,
+            scroll: true,
+        }
+    }
+
+    /// The chunk behind conflict `index` of the result, found by its text
+    /// since earlier conflicts may have been resolved by hand.
+    fn chunk_of(&self, index: usize) -> Option<&Chunk> {
+        let conflict = find_conflicts(&self.result).into_iter().nth(index)?;
+        let ours = &self.result[conflict.ours.clone()];
+        let theirs = &self.result[conflict.theirs.clone()];
+        let trimmed = |text: &str| text.trim_end_matches('\n').to_string();
+        self.chunks.iter().find(|chunk| {
+            chunk.kind == ChunkKind::Conflict
+                && trimmed(slice(&self.ours, &chunk.ours)) == trimmed(ours)
+                && trimmed(slice(&self.theirs, &chunk.theirs)) == trimmed(theirs)
+        })
+    }
+
+    fn resolve_current(&mut self, resolution: Resolution) {
+        if let Some(conflict) = find_conflicts(&self.result).into_iter().nth(self.current) {
+            let text = conflict.resolved_text(&self.result, resolution);
+            self.result.replace_range(conflict.range, &text);
+            self.scroll = true;
+        }
+    }
+
+    /// Draws the view; returns whether Save was clicked.
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let conflicts = find_conflicts(&self.result);
+        if self.current >= conflicts.len() {
+            self.current = conflicts.len().saturating_sub(1);
+        }
+        let mut save = false;
+        ui.horizontal(|ui| {
+            if conflicts.is_empty() {
+                ui.label(tr("No conflicts left"));
+            } else {
+                ui.label(trf(
+                    "Conflict {current} of {count}",
+                    &[
+                        ("current", &(self.current + 1)),
+                        ("count", &conflicts.len()),
+                    ],
+                ));
+                if ui
+                    .button("⏶")
+                    .on_hover_text(tr("Previous Conflict"))
+                    .clicked()
+                {
+                    self.current = (self.current + conflicts.len() - 1) % conflicts.len();
+                    self.scroll = true;
+                }
+                if ui.button("⏷").on_hover_text(tr("Next Conflict")).clicked() {
+                    self.current = (self.current + 1) % conflicts.len();
+                    self.scroll = true;
+                }
+                ui.separator();
+                for resolution in Resolution::ALL {
+                    if ui.button(tr(resolution.label())).clicked() {
+                        self.resolve_current(resolution);
+                    }
+                }
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                save = ui.button(tr("Save Result")).clicked();
+            });
+        });
+        ui.separator();
+
+        let scroll = std::mem::take(&mut self.scroll);
+        let chunk = self.chunk_of(self.current);
+        let targets = chunk.map(|c| [c.base.start, c.ours.start, c.theirs.start]);
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        let dark_mode = ui.visuals().dark_mode;
+        let pane_height = ui.available_height() * 0.45;
+        ui.columns(3, |columns| {
+            for (i, (ui, pane)) in columns.iter_mut().zip(&self.panes).enumerate() {
+                ui.strong(&pane.title);
+                let mut area = egui::ScrollArea::both()
+                    .id_source(("merge_pane", i))
+                    .max_height(pane_height)
+                    .auto_shrink([false, false]);
+                if scroll && let Some(targets) = targets {
+                    let line = targets[i].saturating_sub(3);
+                    area = area.vertical_scroll_offset(line as f32 * row_height);
+                }
+                area.show_rows(ui, row_height, pane.lines.len(), |ui, range| {
+                    for line in range {
+                        let bg = pane.kinds[line].map_or(egui::Color32::TRANSPARENT, |kind| {
+                            chunk_color(kind, dark_mode)
+                        });
+                        ui.add(
+                            egui::Label::new(
+                                egui::RichText::new(&pane.lines[line])
+                                    .monospace()
+                                    .background_color(bg),
+                            )
+                            .wrap(false),
+                        );
+                    }
+                });
+            }
+        });
+        ui.separator();
+        ui.strong(tr("Result"));
+        let mut layouter = |ui: &egui::Ui, text: &str, wrap_width: f32| {
+            let conflicts = find_conflicts(text);
+            let font = egui::TextStyle::Monospace.resolve(ui.style());
+            let color = ui.visuals().text_color();
+            let mut job = LayoutJob::default();
+            for (line, content) in text.split_inclusive('\n').enumerate() {
+                let background = conflicts
+                    .iter()
+                    .find_map(|c| c.line_color(line, dark_mode))
+                    .unwrap_or_default();
+                job.append(
+                    content,
+                    0.0,
+                    egui::TextFormat {
+                        background,
+                        ..egui::TextFormat::simple(font.clone(), color)
+                    },
+                );
+            }
+            job.wrap.max_width = wrap_width;
+            ui.fonts(|f| f.layout_job(job))
+        };
+        egui::ScrollArea::both()
+            .id_source("merge_result")
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                let output = egui::TextEdit::multiline(&mut self.result)
+                    .code_editor()
+                    .desired_width(f32::INFINITY)
+                    .min_size(ui.available_size())
+                    .layouter(&mut layouter)
+                    .show(ui);
+                if scroll && let Some(conflict) = conflicts.get(self.current) {
+                    let rect = output
+                        .galley
+                        .pos_from_ccursor(CCursor::new(
+                            self.result[..conflict.range.start].chars().count(),
+                        ))
+                        .translate(output.galley_pos.to_vec2());
+                    ui.scroll_to_rect(rect, Some(egui::Align::Center));
+                }
+            });
+        save
+    }
+}
+
+impl TextEditorApp {
+    /// Opens the merge view on files from disk, as asked for with
+    /// `--merge`. The result goes to `output`, and closing the view then
+    /// closes the editor.
+    pub(crate) fn open_merge_files(
+        &mut self,
+        base: &Path,
+        ours: &Path,
+        theirs: &Path,
+        output: &Path,
+    ) {
+        let read = |path: &Path| {
+            fs::read(path).map(|bytes| {
+                let text = String::from_utf8_lossy(&bytes);
+                text.strip_prefix(UTF8_BOM).unwrap_or(&text).to_string()
+            })
+        };
+        match (read(base), read(ours), read(theirs)) {
+            (Ok(base_text), Ok(ours_text), Ok(theirs_text)) => {
+                let titles = [
+                    trf("Base: {name}", &[("name", &base.display())]),
+                    trf("Ours: {name}", &[("name", &ours.display())]),
+                    trf("Theirs: {name}", &[("name", &theirs.display())]),
+                ];
+                let mut view = MergeView::new(titles, &base_text, &ours_text, &theirs_text);
+                view.output = Some(output.to_path_buf());
+                self.merge_view = Some(view);
+                self.quit_with_view = true;
+            }
+            (Err(err), ..) | (_, Err(err), _) | (.., Err(err)) => self.notifications.error(trf(
+                "Could not read the files to merge: {error}",
+                &[("error", &err)],
+            )),
+        }
+    }
+
+    fn save_merge_result(&mut self) {
+        let Some(view) = &mut self.merge_view else {
+            return;
+        };
+        if view.output.is_none() {
+            view.output = platform::file_dialog().save_file();
+        }
+        let Some(path) = view.output.clone() else {
+            return;
+        };
+        let audit = self.audit_snapshot(&path, false);
+        let Some(view) = &self.merge_view else {
+            return;
+        };
+        let result = view.result.clone();
+        if let Err(err) = atomic_write::write(&path, result.as_bytes()) {
+            self.notifications.error(trf(
+                "Could not save {path}: {error}",
+                &[("path", &path.display()), ("error", &err)],
+            ));
+            return;
+        }
+        self.record_save(audit, result.as_bytes());
+        let left = find_conflicts(&result).len();
+        if left > 0 {
+            self.notifications.warning(trf(
+                "Saved with {count} unresolved conflicts",
+                &[("count", &left)],
+            ));
+        } else {
+            self.notifications.info(tr("Merge result saved"));
+        }
+    }
+
+    pub(crate) fn show_merge_window(&mut self, ctx: &egui::Context) {
+        let Some(view) = &mut self.merge_view else {
+            return;
+        };
+        let mut open = true;
+        let mut save = false;
+        egui::Window::new(tr("Merge"))
+            .default_size((1000.0, 700.0))
+            .open(&mut open)
+            .show(ctx, |ui| save = view.ui(ui));
+        if save {
+            self.save_merge_result();
+        }
+        if !open {
+            self.merge_view = None;
+            if std::mem::take(&mut self.quit_with_view) {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merge(base: &str, ours: &str, theirs: &str) -> String {
+        merged(base, ours, theirs, &chunks(base, ours, theirs))
+    }
+
+    #[test]
+    fn one_sided_changes_are_merged() {
+        let base = "a\nb\nc\nd\n";
+        let ours = "A\nb\nc\nd\n";
+        let theirs = "a\nb\nc\nD\n";
+        let found = chunks(base, ours, theirs);
+        assert_eq!(found.len(), 2);
+        assert!(found[0].kind == ChunkKind::Ours);
+        assert!(found[1].kind == ChunkKind::Theirs);
+        assert_eq!(merge(base, ours, theirs), "A\nb\nc\nD\n");
+    }
+
+    #[test]
+    fn identical_changes_are_not_conflicts() {
+        let base = "a\nb\nc\n";
+        let both = "a\nB\nc\n";
+        let found = chunks(base, both, both);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].kind == ChunkKind::Same);
+        assert_eq!(merge(base, both, both), both);
+    }
+
+    #[test]
+    fn overlapping_changes_become_conflict_markers() {
+        let base = "a\nb\nc\n";
+        let ours = "a\nours\nc\n";
+        let theirs = "a\ntheirs\nc\n";
+        assert!(chunks(base, ours, theirs)[0].kind == ChunkKind::Conflict);
+        assert_eq!(
+            merge(base, ours, theirs),
+            "a\n<<<<<<< ours\nours\n=======\ntheirs\n>>>>>>> theirs\nc\n"
+        );
+    }
+
+    #[test]
+    fn insertions_shift_later_chunks() {
+        let base = "a\nb\nc\n";
+        let ours = "new\na\nb\nc\n";
+        let theirs = "a\nb\nC\n";
+        assert_eq!(merge(base, ours, theirs), "new\na\nb\nC\n");
+    }
+}
@@ -0,0 +1,269 @@
+//! Caret motions that replace or extend egui's built-in ones: Smart Home,
+//! word-wise movement with configurable word boundaries, Alt+Up/Down/Home/End
+//! by logical line where soft wrap splits lines into several rows and, in
+//! prose mode, paragraph-wise movement. macOS uses its own modifiers for
+//! these, see [`crate::platform`].
+//!
+//! All positions are character indices into the buffer.
+
+use crate::TextEditorApp;
+use crate::platform;
+use crate::prose::{next_paragraph, prev_paragraph};
+use eframe::egui;
+use egui::text::{CCursor, CCursorRange};
+
+/// Which positions inside an identifier count as word boundaries.
+#[derive(Clone, Copy)]
+pub struct WordRules {
+    /// `foo_bar` is two words.
+    pub underscore: bool,
+    /// `fooBar` and `HTTPServer` are two words.
+    pub camel_case: bool,
+}
+
+impl WordRules {
+    const SUB_WORD: Self = Self {
+        underscore: true,
+        camel_case: true,
+    };
+}
+
+/// Smart Home: jumps to the first non-whitespace character of the line, or
+/// to column 0 if the caret is already there.
+pub fn smart_home(chars: &[char], pos: usize) -> usize {
+    let line_start = chars[..pos]
+        .iter()
+        .rposition(|&c| c == '\n')
+        .map_or(0, |i| i + 1);
+    let first_non_ws = chars[line_start..]
+        .iter()
+        .position(|&c| c == '\n' || !c.is_whitespace())
+        .map_or(chars.len(), |i| line_start + i);
+    if pos == first_non_ws {
+        line_start
+    } else {
+        first_non_ws
+    }
+}
+
+fn line_start(chars: &[char], pos: usize) -> usize {
+    chars[..pos]
+        .iter()
+        .rposition(|&c| c == '\n')
+        .map_or(0, |i| i + 1)
+}
+
+fn line_end(chars: &[char], pos: usize) -> usize {
+    chars[pos..]
+        .iter()
+        .position(|&c| c == '\n')
+        .map_or(chars.len(), |i| pos + i)
+}
+
+/// The same column on the logical line above or below, clamped to its end.
+fn logical_line(chars: &[char], pos: usize, down: bool) -> usize {
+    let start = line_start(chars, pos);
+    let column = pos - start;
+    let target_start = if down {
+        let end = line_end(chars, pos);
+        if end == chars.len() {
+            return end;
+        }
+        end + 1
+    } else {
+        if start == 0 {
+            return 0;
+        }
+        line_start(chars, start - 1)
+    };
+    (target_start + column).min(line_end(chars, target_start))
+}
+
+pub fn next_word(chars: &[char], pos: usize, rules: WordRules) -> usize {
+    let mut i = pos;
+    while i < chars.len() && is_gap(chars[i], rules) {
+        i += 1;
+    }
+    if i < chars.len() {
+        i += 1;
+        while i < chars.len() && !is_gap(chars[i], rules) && !is_boundary(chars, i, rules) {
+            i += 1;
+        }
+    }
+    i
+}
+
+pub fn prev_word(chars: &[char], pos: usize, rules: WordRules) -> usize {
+    let mut i = pos;
+    while i > 0 && is_gap(chars[i - 1], rules) {
+        i -= 1;
+    }
+    if i > 0 {
+        i -= 1;
+        while i > 0 && !is_gap(chars[i - 1], rules) && !is_boundary(chars, i, rules) {
+            i -= 1;
+        }
+    }
+    i
+}
+
+/// Characters skipped over between words.
+fn is_gap(c: char, rules: WordRules) -> bool {
+    c.is_whitespace() || (rules.underscore && c == '_')
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Whether a word boundary lies between `chars[i - 1]` and `chars[i]`.
+fn is_boundary(chars: &[char], i: usize, rules: WordRules) -> bool {
+    let (prev, cur) = (chars[i - 1], chars[i]);
+    if is_word_char(prev) != is_word_char(cur) {
+        return true;
+    }
+    if !rules.camel_case || !prev.is_alphanumeric() || !cur.is_alphanumeric() {
+        return false;
+    }
+    let lower_to_upper = (prev.is_lowercase() || prev.is_ascii_digit()) && cur.is_uppercase();
+    let acronym_end = prev.is_uppercase()
+        && cur.is_uppercase()
+        && chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+    lower_to_upper || acronym_end
+}
+
+enum Motion {
+    SmartHome,
+    Word(WordRules, bool),
+    Paragraph(bool),
+    LineStart,
+    LineEnd,
+    LogicalLine(bool),
+}
+
+/// Removes key presses of `key` whose modifiers satisfy `accept` from this
+/// frame's input and returns whether Shift was held for the last one.
+pub(crate) fn take_key(
+    ctx: &egui::Context,
+    key: egui::Key,
+    accept: impl Fn(egui::Modifiers) -> bool,
+) -> Option<bool> {
+    ctx.input_mut(|i| {
+        let mut shift = None;
+        i.events.retain(|event| match event {
+            egui::Event::Key {
+                key: k,
+                pressed: true,
+                modifiers,
+                ..
+            } if *k == key && accept(*modifiers) => {
+                shift = Some(modifiers.shift);
+                false
+            }
+            _ => true,
+        });
+        shift
+    })
+}
+
+/// Removes typed `text` from this frame's input and returns whether it was
+/// there.
+pub(crate) fn take_text(ctx: &egui::Context, text: &str) -> bool {
+    ctx.input_mut(|i| {
+        let before = i.events.len();
+        i.events
+            .retain(|event| !matches!(event, egui::Event::Text(t) if t == text));
+        i.events.len() != before
+    })
+}
+
+impl TextEditorApp {
+    /// Handles the caret motions we implement ourselves before the editor
+    /// widget gets to see the key presses.
+    pub(crate) fn handle_caret_motion(&mut self, ctx: &egui::Context) {
+        let Some(key) = self.active_tab.clone() else {
+            return;
+        };
+        if !self.editor_has_focus(ctx) {
+            return;
+        }
+
+        let configured = WordRules {
+            underscore: self.settings.word_underscore_boundary,
+            camel_case: self.settings.word_camel_case_boundary,
+        };
+        let custom_words = configured.underscore || configured.camel_case;
+        let plain = |m: egui::Modifiers| !m.alt && !m.command && !m.ctrl;
+        let word = platform::is_word_motion;
+        let alt = platform::is_sub_word_motion;
+
+        // On a wrapped row Home goes to the start of the row, which egui does.
+        let on_continuation = self
+            .cursor_range(ctx)
+            .is_some_and(|cursor| self.wrapped_rows.is_continuation(cursor.primary.index));
+
+        let mut motions = Vec::new();
+        if self.settings.smart_home
+            && !on_continuation
+            && let Some(shift) = take_key(ctx, egui::Key::Home, plain)
+        {
+            motions.push((Motion::SmartHome, shift));
+        }
+        for (key, motion) in [
+            (egui::Key::Home, Motion::LineStart),
+            (egui::Key::End, Motion::LineEnd),
+            (egui::Key::ArrowUp, Motion::LogicalLine(false)),
+            (egui::Key::ArrowDown, Motion::LogicalLine(true)),
+        ] {
+            if let Some(shift) = take_key(ctx, key, alt) {
+                motions.push((motion, shift));
+            }
+        }
+        for (key, forward) in [(egui::Key::ArrowRight, true), (egui::Key::ArrowLeft, false)] {
+            if let Some(shift) = take_key(ctx, key, alt) {
+                motions.push((Motion::Word(WordRules::SUB_WORD, forward), shift));
+            }
+            if custom_words && let Some(shift) = take_key(ctx, key, word) {
+                motions.push((Motion::Word(configured, forward), shift));
+            }
+        }
+        if self.tabs[&key].prose {
+            for (key, forward) in [(egui::Key::ArrowDown, true), (egui::Key::ArrowUp, false)] {
+                if let Some(shift) = take_key(ctx, key, word) {
+                    motions.push((Motion::Paragraph(forward), shift));
+                }
+            }
+        }
+        if motions.is_empty() {
+            return;
+        }
+
+        let Some(mut cursor) = self.cursor_range(ctx) else {
+            return;
+        };
+        let chars: Vec<char> = self.tabs[&key].content.chars().collect();
+        for (motion, extend) in motions {
+            let pos = cursor.primary.index.min(chars.len());
+            let target = match motion {
+                Motion::SmartHome => smart_home(&chars, pos),
+                Motion::Word(rules, true) => next_word(&chars, pos, rules),
+                Motion::Word(rules, false) => prev_word(&chars, pos, rules),
+                Motion::Paragraph(true) => next_paragraph(&chars, pos),
+                Motion::Paragraph(false) => prev_paragraph(&chars, pos),
+                Motion::LineStart if self.settings.smart_home => smart_home(&chars, pos),
+                Motion::LineStart => line_start(&chars, pos),
+                Motion::LineEnd => line_end(&chars, pos),
+                Motion::LogicalLine(down) => logical_line(&chars, pos, down),
+            };
+            cursor = if extend {
+                CCursorRange {
+                    primary: CCursor::new(target),
+                    secondary: cursor.secondary,
+                }
+            } else {
+                CCursorRange::one(CCursor::new(target))
+            };
+        }
+        self.set_cursor_range(ctx, cursor);
+    }
+}
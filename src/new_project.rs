@@ -0,0 +1,237 @@
+//! "New Project…": creates a project folder from a template set and opens
+//! it in the sidebar. Besides `cargo new` and a plain git repository with a
+//! README, every folder in `project-templates` in the config directory is a
+//! template set whose files are copied with the placeholders of
+//! [`crate::templates`] filled in.
+
+use crate::i18n::{tr, trf};
+use crate::platform;
+use crate::settings::config_dir;
+use crate::{TextEditorApp, git, templates};
+use eframe::egui;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::mpsc::{Receiver, channel};
+use std::thread;
+
+const PROJECT_TEMPLATES_DIR: &str = "project-templates";
+
+#[derive(Clone, PartialEq)]
+pub enum Scaffold {
+    CargoBinary,
+    CargoLibrary,
+    GitReadme,
+    /// A folder of files in the project templates directory.
+    User(PathBuf),
+}
+
+impl Scaffold {
+    fn label(&self) -> String {
+        match self {
+            Scaffold::CargoBinary => tr("Cargo binary (cargo new)"),
+            Scaffold::CargoLibrary => tr("Cargo library (cargo new --lib)"),
+            Scaffold::GitReadme => tr("Git repository with README"),
+            Scaffold::User(dir) => dir
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+fn scaffolds() -> Vec<Scaffold> {
+    let mut all = vec![
+        Scaffold::CargoBinary,
+        Scaffold::CargoLibrary,
+        Scaffold::GitReadme,
+    ];
+    if let Some(entries) =
+        config_dir().and_then(|dir| fs::read_dir(dir.join(PROJECT_TEMPLATES_DIR)).ok())
+    {
+        let mut user: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+        user.sort();
+        all.extend(user.into_iter().map(Scaffold::User));
+    }
+    all
+}
+
+pub struct NewProject {
+    pub show: bool,
+    parent: String,
+    name: String,
+    scaffold: Scaffold,
+    pending: Option<(PathBuf, Receiver<Result<(), String>>)>,
+}
+
+impl Default for NewProject {
+    fn default() -> Self {
+        Self {
+            show: false,
+            parent: dirs::home_dir()
+                .map(|home| home.display().to_string())
+                .unwrap_or_default(),
+            name: String::new(),
+            scaffold: Scaffold::CargoBinary,
+            pending: None,
+        }
+    }
+}
+
+fn run(dir: &Path, program: &str, args: &[&str]) -> Result<(), String> {
+    let output = Command::new(program)
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("Could not run {program}: {e}"))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Copies the template set `from` into `to`, expanding placeholders in
+/// text files.
+fn copy_template(from: &Path, to: &Path, author: &str) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let source = entry.path();
+        let target = to.join(entry.file_name());
+        if source.is_dir() {
+            copy_template(&source, &target, author)?;
+            continue;
+        }
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        match fs::read_to_string(&source) {
+            Ok(text) => fs::write(&target, templates::expand(&text, &file_name, author))?,
+            Err(_) => {
+                fs::copy(&source, &target)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn scaffold(parent: &Path, name: &str, scaffold: &Scaffold, author: &str) -> Result<(), String> {
+    let target = parent.join(name);
+    match scaffold {
+        Scaffold::CargoBinary => run(parent, "cargo", &["new", "--bin", name]),
+        Scaffold::CargoLibrary => run(parent, "cargo", &["new", "--lib", name]),
+        Scaffold::GitReadme => {
+            fs::create_dir(&target).map_err(|e| e.to_string())?;
+            fs::write(target.join("README.md"), format!("# {name}\n"))
+                .map_err(|e| e.to_string())?;
+            git::run_checked(&target, &["init"]).map(|_| ())
+        }
+        Scaffold::User(dir) => {
+            if target.exists() {
+                return Err(trf("{path} already exists", &[("path", &target.display())]));
+            }
+            copy_template(dir, &target, author).map_err(|e| e.to_string())
+        }
+    }
+}
+
+impl TextEditorApp {
+    pub(crate) fn show_new_project_window(&mut self, ctx: &egui::Context) {
+        if let Some((_, receiver)) = &self.new_project.pending
+            && let Ok(result) = receiver.try_recv()
+            && let Some((path, _)) = self.new_project.pending.take()
+        {
+            match result {
+                Ok(()) => {
+                    self.new_project.show = false;
+                    // The user just made it.
+                    self.trust.set(&path, true);
+                    self.open_folder(path);
+                    self.problems.clear();
+                    self.run_todo_scan(ctx);
+                }
+                Err(err) => self.notifications.error(trf(
+                    "Could not create the project: {error}",
+                    &[("error", &err)],
+                )),
+            }
+        }
+        if !self.new_project.show {
+            return;
+        }
+        let project = &mut self.new_project;
+        let mut open = true;
+        let mut create = false;
+        egui::Window::new(tr("New Project"))
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                egui::Grid::new("new_project_form")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label(tr("Location:"));
+                        ui.horizontal(|ui| {
+                            ui.text_edit_singleline(&mut project.parent);
+                            if ui.button(tr("Browse…")).clicked()
+                                && let Some(folder) = platform::file_dialog().pick_folder()
+                            {
+                                project.parent = folder.display().to_string();
+                            }
+                        });
+                        ui.end_row();
+                        ui.label(tr("Name:"));
+                        ui.text_edit_singleline(&mut project.name);
+                        ui.end_row();
+                        ui.label(tr("Template:"));
+                        ui.vertical(|ui| {
+                            for scaffold in scaffolds() {
+                                let label = scaffold.label();
+                                ui.radio_value(&mut project.scaffold, scaffold, label);
+                            }
+                        });
+                        ui.end_row();
+                    });
+                let name = project.name.trim();
+                let valid = !name.is_empty()
+                    && !name.contains(['/', '\\'])
+                    && Path::new(project.parent.trim()).is_dir();
+                ui.separator();
+                if project.pending.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label(tr("Creating…"));
+                    });
+                } else {
+                    create = ui
+                        .add_enabled(valid, egui::Button::new(tr("Create")))
+                        .clicked();
+                }
+            });
+        if !open {
+            project.show = false;
+        }
+        if create {
+            let author = self.template_author();
+            let project = &mut self.new_project;
+            let parent = PathBuf::from(project.parent.trim());
+            let name = project.name.trim().to_string();
+            let kind = project.scaffold.clone();
+            let (sender, receiver) = channel();
+            project.pending = Some((parent.join(&name), receiver));
+            let job = self
+                .jobs
+                .start(trf("Creating project {name}", &[("name", &name)]), false);
+            let ctx = ctx.clone();
+            thread::spawn(move || {
+                let _job = job;
+                let _ = sender.send(scaffold(&parent, &name, &kind, &author));
+                ctx.request_repaint();
+            });
+        }
+    }
+}
@@ -0,0 +1,241 @@
+//! `[[wikilink]]` support for Markdown notes: Ctrl+click on a link opens the
+//! note it names inside the opened folder, creating it if it does not exist
+//! yet, and the Backlinks tab lists the notes that link to the current one.
+//!
+//! A link names a note by file name without extension (`[[Ideas]]`), or by
+//! path relative to the folder (`[[projects/Ideas]]`). Text after `|` is a
+//! display alias and after `#` a heading; both are ignored when resolving.
+
+use crate::TextEditorApp;
+use crate::i18n::{tr, trf};
+use crate::project::walk_files;
+use eframe::egui;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, channel};
+use std::thread;
+
+/// A line of another note that links to the current one.
+pub struct Backlink {
+    pub path: PathBuf,
+    pub line: usize,
+    pub text: String,
+}
+
+#[derive(Default)]
+pub struct Backlinks {
+    /// The note the results are for.
+    note: Option<PathBuf>,
+    results: Vec<Backlink>,
+    receiver: Option<Receiver<Vec<Backlink>>>,
+}
+
+fn is_note(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
+}
+
+/// The wikilinks of `line`: the byte range of each `[[...]]` and the note
+/// it names.
+pub fn wikilinks(line: &str) -> Vec<(Range<usize>, &str)> {
+    let mut links = Vec::new();
+    let mut from = 0;
+    while let Some(open) = line[from..].find("[[").map(|i| from + i) {
+        let Some(close) = line[open + 2..].find("]]").map(|i| open + 2 + i) else {
+            break;
+        };
+        let inner = &line[open + 2..close];
+        let target = inner.split(['|', '#']).next().unwrap_or("").trim();
+        if !target.is_empty() && !inner.contains('\n') {
+            links.push((open..close + 2, target));
+        }
+        from = close + 2;
+    }
+    links
+}
+
+/// The note named by the wikilink around byte offset `pos` of `text`.
+pub fn wikilink_at(text: &str, pos: usize) -> Option<&str> {
+    let line_start = text[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = text[pos..].find('\n').map_or(text.len(), |i| pos + i);
+    wikilinks(&text[line_start..line_end])
+        .into_iter()
+        .find(|(range, _)| range.contains(&(pos - line_start)))
+        .map(|(_, target)| target)
+}
+
+/// Whether the link `target` names the note at `path`.
+fn names(target: &str, root: &Path, path: &Path) -> bool {
+    let target = target.trim_end_matches(".md");
+    if target.contains('/') {
+        path.strip_prefix(root)
+            .is_ok_and(|relative| relative.with_extension("") == Path::new(target))
+    } else {
+        path.file_stem()
+            .is_some_and(|stem| stem.to_string_lossy().eq_ignore_ascii_case(target))
+    }
+}
+
+/// The note `target` names below `root`, or where a new one goes.
+fn resolve(root: &Path, target: &str) -> (PathBuf, bool) {
+    let existing = walk_files(root)
+        .into_iter()
+        .find(|path| is_note(path) && names(target, root, path));
+    match existing {
+        Some(path) => (path, true),
+        None => {
+            let target = target.trim_end_matches(".md");
+            (root.join(format!("{target}.md")), false)
+        }
+    }
+}
+
+/// Every line of the other notes below `root` that links to `note`.
+fn find_backlinks(root: &Path, note: &Path) -> Vec<Backlink> {
+    let mut backlinks = Vec::new();
+    for path in walk_files(root) {
+        if !is_note(&path) || path == note {
+            continue;
+        }
+        let Ok(text) = fs::read_to_string(&path) else {
+            continue;
+        };
+        for (line, content) in text.lines().enumerate() {
+            if wikilinks(content)
+                .iter()
+                .any(|(_, target)| names(target, root, note))
+            {
+                backlinks.push(Backlink {
+                    path: path.clone(),
+                    line,
+                    text: content.trim().to_string(),
+                });
+            }
+        }
+    }
+    backlinks
+}
+
+impl Backlinks {
+    pub fn is_running(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    fn start(&mut self, ctx: &egui::Context, root: PathBuf, note: PathBuf) {
+        let (sender, receiver) = channel();
+        self.note = Some(note.clone());
+        self.results.clear();
+        self.receiver = Some(receiver);
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let _ = sender.send(find_backlinks(&root, &note));
+            ctx.request_repaint();
+        });
+    }
+
+    fn poll(&mut self) {
+        if let Some(receiver) = &self.receiver
+            && let Ok(results) = receiver.try_recv()
+        {
+            self.results = results;
+            self.receiver = None;
+        }
+    }
+}
+
+impl TextEditorApp {
+    /// The folder wikilinks resolve in: the opened folder, or else the
+    /// directory of the active file.
+    fn notes_root(&self) -> Option<PathBuf> {
+        self.folder_path().or_else(|| {
+            let tab = self.tabs.get(self.active_tab.as_ref()?)?;
+            Some(tab.path.as_deref()?.parent()?.to_path_buf())
+        })
+    }
+
+    /// Opens the note `target` names, creating it first if needed.
+    pub(crate) fn follow_wikilink(&mut self, ctx: &egui::Context, target: &str) {
+        let Some(root) = self.notes_root() else {
+            self.notifications
+                .warning(tr("Open a folder to follow links between notes."));
+            return;
+        };
+        let (path, exists) = resolve(&root, target);
+        if !exists {
+            let title = target.rsplit('/').next().unwrap_or(target);
+            let created = path
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|()| fs::write(&path, format!("# {title}\n")));
+            if let Err(err) = created {
+                self.notifications.error(trf(
+                    "Could not create {path}: {error}",
+                    &[("path", &path.display()), ("error", &err)],
+                ));
+                return;
+            }
+            self.notifications
+                .info(trf("Created note {path}", &[("path", &path.display())]));
+        }
+        self.open_at(ctx, &path, 0, 0);
+    }
+
+    pub(crate) fn poll_backlinks(&mut self) {
+        self.backlinks.poll();
+    }
+
+    /// The Backlinks tab of the bottom panel.
+    pub(crate) fn backlinks_ui(&mut self, ui: &mut egui::Ui) {
+        let ctx = ui.ctx().clone();
+        let note = self
+            .active_tab
+            .as_ref()
+            .and_then(|key| self.tabs[key].path.clone())
+            .filter(|path| is_note(path));
+        let (Some(note), Some(root)) = (note, self.notes_root()) else {
+            ui.weak(tr(
+                "Open a saved Markdown note to see the notes linking to it.",
+            ));
+            return;
+        };
+        let backlinks = &mut self.backlinks;
+        let mut refresh = backlinks.note.as_ref() != Some(&note) && !backlinks.is_running();
+        ui.horizontal(|ui| {
+            let name = note.file_stem().unwrap_or_default().to_string_lossy();
+            ui.label(trf("Notes linking to {name}", &[("name", &name)]));
+            refresh |= ui.button(tr("Refresh")).clicked();
+            if backlinks.is_running() {
+                ui.spinner();
+            }
+        });
+        if refresh {
+            backlinks.start(&ctx, root.clone(), note);
+        }
+        ui.separator();
+
+        let mut jump = None;
+        if backlinks.results.is_empty() && !backlinks.is_running() {
+            ui.weak(tr("No backlinks."));
+        }
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                for backlink in &backlinks.results {
+                    let path = backlink.path.strip_prefix(&root).unwrap_or(&backlink.path);
+                    let label = format!(
+                        "{}:{}  {}",
+                        path.display(),
+                        backlink.line + 1,
+                        backlink.text
+                    );
+                    if ui.link(label).clicked() {
+                        jump = Some((backlink.path.clone(), backlink.line));
+                    }
+                }
+            });
+        if let Some((path, line)) = jump {
+            self.open_at(&ctx, &path, line, 0);
+        }
+    }
+}
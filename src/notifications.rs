@@ -0,0 +1,177 @@
+//! User-visible notifications. Failures and the results of background work
+//! pop up as dismissible toasts in the bottom-right corner and are kept in
+//! the Notifications tab of the bottom panel.
+
+use crate::TextEditorApp;
+use crate::bottom_panel::BottomTab;
+use crate::i18n::{tr, trf};
+use chrono::{DateTime, Local};
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+/// Entries kept in the log; older ones are dropped.
+const MAX_LOG: usize = 200;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Level {
+    fn icon(self) -> &'static str {
+        match self {
+            Level::Info => "ℹ",
+            Level::Warning => "⚠",
+            Level::Error => "⊗",
+        }
+    }
+
+    fn color(self, visuals: &egui::Visuals) -> egui::Color32 {
+        match self {
+            Level::Info => visuals.text_color(),
+            Level::Warning => visuals.warn_fg_color,
+            Level::Error => visuals.error_fg_color,
+        }
+    }
+
+    /// How long the toast stays up unless dismissed earlier.
+    fn toast_lifetime(self) -> Duration {
+        match self {
+            Level::Info => Duration::from_secs(4),
+            Level::Warning | Level::Error => Duration::from_secs(10),
+        }
+    }
+}
+
+struct Notification {
+    level: Level,
+    message: String,
+    time: DateTime<Local>,
+    shown_at: Instant,
+    /// Whether the toast is still up; the log entry outlives it.
+    toast: bool,
+}
+
+#[derive(Default)]
+pub struct Notifications {
+    /// Oldest first.
+    log: Vec<Notification>,
+    /// Entries added since the log was last looked at.
+    unread: usize,
+}
+
+impl Notifications {
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.push(Level::Info, message.into());
+    }
+
+    pub fn warning(&mut self, message: impl Into<String>) {
+        self.push(Level::Warning, message.into());
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.push(Level::Error, message.into());
+    }
+
+    fn push(&mut self, level: Level, message: String) {
+        self.log.push(Notification {
+            level,
+            message,
+            time: Local::now(),
+            shown_at: Instant::now(),
+            toast: true,
+        });
+        if self.log.len() > MAX_LOG {
+            self.log.remove(0);
+        }
+        self.unread += 1;
+    }
+}
+
+impl TextEditorApp {
+    pub(crate) fn show_toasts(&mut self, ctx: &egui::Context) {
+        let log = &mut self.notifications.log;
+        let mut next_expiry: Option<Duration> = None;
+        for n in log.iter_mut().filter(|n| n.toast) {
+            let remaining = n
+                .level
+                .toast_lifetime()
+                .saturating_sub(n.shown_at.elapsed());
+            if remaining.is_zero() {
+                n.toast = false;
+            } else {
+                next_expiry = Some(next_expiry.map_or(remaining, |d| d.min(remaining)));
+            }
+        }
+        let Some(next_expiry) = next_expiry else {
+            return;
+        };
+        ctx.request_repaint_after(next_expiry);
+
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-12.0, -36.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                for n in log.iter_mut().filter(|n| n.toast) {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.set_max_width(360.0);
+                        ui.horizontal(|ui| {
+                            ui.colored_label(n.level.color(ui.visuals()), n.level.icon());
+                            ui.add(egui::Label::new(&n.message).wrap(true));
+                            if ui.small_button("×").on_hover_text(tr("Dismiss")).clicked() {
+                                n.toast = false;
+                            }
+                        });
+                    });
+                }
+            });
+    }
+
+    /// Status bar bell with the number of unread notifications.
+    pub(crate) fn notifications_button(&mut self, ui: &mut egui::Ui) {
+        let unread = self.notifications.unread;
+        let text = if unread > 0 {
+            format!("🔔 {unread}")
+        } else {
+            "🔔".to_string()
+        };
+        if ui
+            .add(egui::Button::new(text).frame(false))
+            .on_hover_text(tr("Notifications"))
+            .clicked()
+        {
+            self.bottom_panel.toggle(BottomTab::Notifications);
+        }
+    }
+
+    /// The Notifications tab of the bottom panel, newest first.
+    pub(crate) fn notifications_ui(&mut self, ui: &mut egui::Ui) {
+        let notifications = &mut self.notifications;
+        notifications.unread = 0;
+        ui.horizontal(|ui| {
+            ui.label(trf(
+                "{count} notifications",
+                &[("count", &notifications.log.len())],
+            ));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button(tr("Clear")).clicked() {
+                    notifications.log.clear();
+                }
+            });
+        });
+        ui.separator();
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                for n in notifications.log.iter().rev() {
+                    ui.horizontal(|ui| {
+                        ui.colored_label(n.level.color(ui.visuals()), n.level.icon());
+                        ui.weak(n.time.format("%H:%M:%S").to_string());
+                        ui.label(&n.message);
+                    });
+                }
+            });
+    }
+}
@@ -0,0 +1,53 @@
+//! The Open Editors section of the sidebar: every open tab in one list, for
+//! when the tab bar wraps onto more rows than fit. A bullet marks unsaved
+//! changes; picking a tab also reveals its file in the tree.
+
+use crate::TextEditorApp;
+use crate::i18n::tr;
+use eframe::egui;
+
+impl TextEditorApp {
+    pub(crate) fn open_editors_sidebar(&mut self, ui: &mut egui::Ui) {
+        if self.open_order.is_empty() {
+            return;
+        }
+        let mut picked = None;
+        let mut close = None;
+        egui::CollapsingHeader::new(tr("Open Editors"))
+            .id_source("open_editors")
+            .default_open(true)
+            .show(ui, |ui| {
+                for key in &self.open_order {
+                    let tab = &self.tabs[key];
+                    let active = self.active_tab.as_ref() == Some(key);
+                    ui.horizontal(|ui| {
+                        if ui.small_button("×").on_hover_text(tr("Close")).clicked() {
+                            close = Some(key.clone());
+                        }
+                        let label = if tab.is_modified() {
+                            format!("• {key}")
+                        } else {
+                            key.clone()
+                        };
+                        let mut response = ui.selectable_label(active, label);
+                        if let Some(path) = &tab.path {
+                            response = response.on_hover_text(path.display().to_string());
+                        }
+                        if response.clicked() {
+                            picked = Some(key.clone());
+                        }
+                    });
+                }
+            });
+        ui.separator();
+        if let Some(key) = close {
+            self.close_tab(&key);
+        }
+        if let Some(key) = picked {
+            if let Some(path) = &self.tabs[&key].path {
+                self.file_tree.reveal(&self.folders, path);
+            }
+            self.active_tab = Some(key);
+        }
+    }
+}
@@ -0,0 +1,304 @@
+//! The heading outline of Markdown files: a tree of sections shown in the
+//! sidebar, folding of sections, and jumping to the next or previous
+//! heading.
+//!
+//! egui's text editor cannot leave lines out, so a folded section is laid
+//! out with zero height and no color instead. Moving the caret into it
+//! unfolds it again.
+
+use crate::TextEditorApp;
+use crate::editing::char_to_byte;
+use crate::i18n::{tr, trf};
+use crate::symbols::markdown_heading;
+use eframe::egui;
+use egui::text::{LayoutJob, LayoutSection};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+/// A heading and the section it starts. Lines are 0-based; `end` is the
+/// first line after the section.
+pub struct Heading {
+    pub level: usize,
+    pub title: String,
+    pub line: usize,
+    pub end: usize,
+}
+
+impl Heading {
+    /// Identifies the heading across edits that move it to another line.
+    fn key(&self) -> (usize, String) {
+        (self.level, self.title.clone())
+    }
+}
+
+/// Folded sections per tab key, by heading.
+#[derive(Default)]
+pub struct Folds {
+    folded: HashMap<String, HashSet<(usize, String)>>,
+}
+
+impl Folds {
+    pub fn of(&self, tab_key: &str) -> Option<&HashSet<(usize, String)>> {
+        self.folded.get(tab_key).filter(|folded| !folded.is_empty())
+    }
+
+    fn toggle(&mut self, tab_key: &str, heading: &Heading) {
+        let folded = self.folded.entry(tab_key.to_string()).or_default();
+        if !folded.remove(&heading.key()) {
+            folded.insert(heading.key());
+        }
+    }
+
+    fn unfold(&mut self, tab_key: &str, heading: &Heading) {
+        if let Some(folded) = self.folded.get_mut(tab_key) {
+            folded.remove(&heading.key());
+        }
+    }
+}
+
+/// The ATX headings of `text`, outside fenced code blocks.
+pub fn headings(text: &str) -> Vec<Heading> {
+    let mut headings: Vec<Heading> = Vec::new();
+    let mut in_fence = false;
+    let mut count = 0;
+    for (line, content) in text.split('\n').enumerate() {
+        count = line + 1;
+        let content = content.trim_start();
+        if content.starts_with("```") || content.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        let Some((level, title)) = markdown_heading(content) else {
+            continue;
+        };
+        for open in headings.iter_mut().rev() {
+            if open.end != usize::MAX {
+                continue;
+            }
+            if open.level >= level {
+                open.end = line;
+            }
+        }
+        headings.push(Heading {
+            level,
+            title: title.to_string(),
+            line,
+            end: usize::MAX,
+        });
+    }
+    for heading in &mut headings {
+        heading.end = heading.end.min(count);
+    }
+    headings
+}
+
+/// Byte ranges hidden by the folded sections of `text`: from the end of
+/// each folded heading line to the end of its section, outermost only.
+pub fn hidden_ranges(text: &str, folded: &HashSet<(usize, String)>) -> Vec<Range<usize>> {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let line_end = |line: usize| {
+        line_starts
+            .get(line + 1)
+            .map_or(text.len(), |next| next - 1)
+    };
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for heading in headings(text) {
+        if heading.end <= heading.line + 1 || !folded.contains(&heading.key()) {
+            continue;
+        }
+        let range = line_end(heading.line)..line_end(heading.end - 1);
+        if ranges.last().is_none_or(|last| last.end < range.start) {
+            ranges.push(range);
+        }
+    }
+    ranges
+}
+
+/// Lays out the `hidden` ranges of a buffer with zero height and no color.
+pub fn hide(job: &mut LayoutJob, hidden: &[Range<usize>]) {
+    if hidden.is_empty() {
+        return;
+    }
+    let mut sections = Vec::with_capacity(job.sections.len() + hidden.len() * 2);
+    for section in job.sections.drain(..) {
+        let range = section.byte_range.clone();
+        let mut start = range.start;
+        let mut leading_space = section.leading_space;
+        let mut push = |part: Range<usize>, format: egui::TextFormat| {
+            if !part.is_empty() {
+                sections.push(LayoutSection {
+                    leading_space: std::mem::take(&mut leading_space),
+                    byte_range: part,
+                    format,
+                });
+            }
+        };
+        for folded in hidden
+            .iter()
+            .filter(|h| h.start < range.end && h.end > range.start)
+        {
+            let from = folded.start.max(start);
+            let to = folded.end.min(range.end);
+            push(start..from, section.format.clone());
+            push(
+                from..to,
+                egui::TextFormat {
+                    font_id: egui::FontId::new(1.0, section.format.font_id.family.clone()),
+                    line_height: Some(0.0),
+                    color: egui::Color32::TRANSPARENT,
+                    background: egui::Color32::TRANSPARENT,
+                    ..section.format.clone()
+                },
+            );
+            start = to;
+        }
+        push(start..range.end, section.format);
+    }
+    job.sections = sections;
+}
+
+impl TextEditorApp {
+    /// The headings of the active tab, if it is a Markdown file.
+    fn active_headings(&self) -> Option<(String, Vec<Heading>)> {
+        let key = self.active_tab.clone()?;
+        let tab = self.tabs.get(&key)?;
+        (tab.syntax.as_deref() == Some("Markdown")).then(|| (key, headings(&tab.content)))
+    }
+
+    fn caret_line(&self, ctx: &egui::Context) -> Option<usize> {
+        let tab = self.tabs.get(self.active_tab.as_ref()?)?;
+        let caret = char_to_byte(&tab.content, self.selection(ctx)?.start);
+        Some(tab.content[..caret].matches('\n').count())
+    }
+
+    /// Moves the caret to the next or previous heading.
+    pub(crate) fn go_to_heading(&mut self, ctx: &egui::Context, forward: bool) {
+        let (Some((_, headings)), Some(line)) = (self.active_headings(), self.caret_line(ctx))
+        else {
+            return;
+        };
+        let target = if forward {
+            headings.iter().find(|h| h.line > line)
+        } else {
+            headings.iter().rev().find(|h| h.line < line)
+        };
+        if let Some(heading) = target {
+            self.go_to_line(ctx, heading.line);
+        }
+    }
+
+    /// Folds or unfolds the innermost section around the caret.
+    pub(crate) fn toggle_fold_at_caret(&mut self, ctx: &egui::Context) {
+        let (Some((key, headings)), Some(line)) = (self.active_headings(), self.caret_line(ctx))
+        else {
+            return;
+        };
+        if let Some(heading) = headings
+            .iter()
+            .rev()
+            .find(|h| h.line <= line && line < h.end)
+        {
+            self.folds.toggle(&key, heading);
+            // Keep the caret visible on the heading line.
+            self.go_to_line(ctx, heading.line);
+        }
+    }
+
+    pub(crate) fn unfold_all(&mut self) {
+        if let Some(key) = &self.active_tab {
+            self.folds.folded.remove(key);
+        }
+    }
+
+    /// Unfolds the sections that hide the caret, e.g. after a search hit or
+    /// arrow keys moved it there.
+    pub(crate) fn reveal_caret(&mut self, ctx: &egui::Context) {
+        let Some(key) = self.active_tab.clone() else {
+            return;
+        };
+        if self.folds.of(&key).is_none() {
+            return;
+        }
+        let (Some((_, headings)), Some(line)) = (self.active_headings(), self.caret_line(ctx))
+        else {
+            return;
+        };
+        let folded = self.folds.of(&key).cloned().unwrap_or_default();
+        for heading in headings
+            .iter()
+            .filter(|h| h.line < line && line < h.end && folded.contains(&h.key()))
+        {
+            self.folds.unfold(&key, heading);
+        }
+    }
+
+    /// The Outline section of the sidebar for Markdown tabs.
+    pub(crate) fn outline_sidebar(&mut self, ui: &mut egui::Ui) {
+        let Some((key, headings)) = self.active_headings() else {
+            return;
+        };
+        if headings.is_empty() {
+            return;
+        }
+        ui.separator();
+        ui.strong(tr("Outline"));
+        let folded = self.folds.of(&key).cloned().unwrap_or_default();
+        let mut toggle = None;
+        let mut jump = None;
+        egui::ScrollArea::vertical()
+            .id_source("outline")
+            .show(ui, |ui| {
+                // Headings inside a folded section are left out.
+                let mut hidden_until = 0;
+                for (index, heading) in headings.iter().enumerate() {
+                    if heading.line < hidden_until {
+                        continue;
+                    }
+                    let is_folded = folded.contains(&heading.key());
+                    if is_folded {
+                        hidden_until = heading.end;
+                    }
+                    ui.horizontal(|ui| {
+                        ui.add_space((heading.level - 1) as f32 * 10.0);
+                        if heading.end > heading.line + 1 {
+                            let icon = if is_folded { "▸" } else { "▾" };
+                            let hint = if is_folded {
+                                tr("Unfold section")
+                            } else {
+                                tr("Fold section")
+                            };
+                            if ui.small_button(icon).on_hover_text(hint).clicked() {
+                                toggle = Some(index);
+                            }
+                        }
+                        let title = if is_folded {
+                            trf(
+                                "{title} ({count} lines)",
+                                &[
+                                    ("title", &heading.title),
+                                    ("count", &(heading.end - heading.line - 1)),
+                                ],
+                            )
+                        } else {
+                            heading.title.clone()
+                        };
+                        if ui.link(title).clicked() {
+                            jump = Some(heading.line);
+                        }
+                    });
+                }
+            });
+        if let Some(index) = toggle {
+            self.folds.toggle(&key, &headings[index]);
+            self.go_to_line(ui.ctx(), headings[index].line);
+        }
+        if let Some(line) = jump {
+            self.go_to_line(ui.ctx(), line);
+        }
+    }
+}
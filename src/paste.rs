@@ -0,0 +1,218 @@
+//! Paste variants: "Paste and Indent" re-indents a multi-line block to the
+//! insertion context, and "Paste as Column" puts one clipboard line on each
+//! consecutive line at the caret's column, the way a column of carets would.
+//! File types can ask for pasted typography (smart quotes, dashes,
+//! non-breaking spaces) to become ASCII; "Paste as Plain Text" bypasses that.
+
+use crate::TextEditorApp;
+use crate::clipboard;
+use crate::editing::char_to_byte;
+use eframe::egui;
+
+/// Strips the block's own indentation and re-indents every line after the
+/// first with `indent`. The first line lands at the caret as-is.
+pub fn reindent(block: &str, indent: &str) -> String {
+    let mut lines = block.split('\n');
+    let first = lines.next().unwrap_or_default().trim_start();
+    let rest: Vec<&str> = lines.collect();
+    let common = rest
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| leading_whitespace(line).chars().count())
+        .min()
+        .unwrap_or(0);
+
+    let mut out = first.to_string();
+    for line in rest {
+        out.push('\n');
+        if line.trim().is_empty() {
+            continue;
+        }
+        let strip = leading_whitespace(line)
+            .char_indices()
+            .nth(common)
+            .map_or(leading_whitespace(line).len(), |(i, _)| i);
+        out.push_str(indent);
+        out.push_str(&line[strip..]);
+    }
+    out
+}
+
+/// Inserts the lines of `block` at the character column of byte offset
+/// `caret` on consecutive lines, padding short lines and appending new ones
+/// as needed. Returns the new text and the character index after the last
+/// inserted piece.
+pub fn column_paste(text: &str, caret: usize, block: &str) -> (String, usize) {
+    let row = text[..caret].matches('\n').count();
+    let line_start = text[..caret].rfind('\n').map_or(0, |i| i + 1);
+    let column = text[line_start..caret].chars().count();
+
+    let mut lines: Vec<String> = text.split('\n').map(str::to_string).collect();
+    let mut end = 0;
+    for (k, piece) in block.lines().enumerate() {
+        let target = row + k;
+        if target >= lines.len() {
+            lines.push(String::new());
+        }
+        let line = &mut lines[target];
+        let len = line.chars().count();
+        if len < column {
+            line.extend(std::iter::repeat_n(' ', column - len));
+        }
+        line.insert_str(char_to_byte(line, column), piece);
+        end = lines[..target]
+            .iter()
+            .map(|l| l.chars().count() + 1)
+            .sum::<usize>()
+            + column
+            + piece.chars().count();
+    }
+    (lines.join("\n"), end)
+}
+
+/// Replaces the typographic characters word processors and web pages put in
+/// place of ASCII punctuation.
+pub fn to_ascii(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\u{2018}' | '\u{2019}' | '\u{201a}' | '\u{201b}' | '\u{2032}' => out.push('\''),
+            '\u{201c}' | '\u{201d}' | '\u{201e}' | '\u{201f}' | '\u{2033}' => out.push('"'),
+            // En dash and minus sign.
+            '\u{2013}' | '\u{2212}' => out.push('-'),
+            // Often a mangled `--` option.
+            '\u{2014}' => out.push_str("--"),
+            '\u{2026}' => out.push_str("..."),
+            '\u{a0}' | '\u{2007}' | '\u{202f}' => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// The spaces and tabs `line` starts with; on a blank line, up to the
+/// line break.
+fn leading_whitespace(line: &str) -> &str {
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// The indentation of the line holding byte offset `pos` of `text`.
+fn indent_at(text: &str, pos: usize) -> &str {
+    let line_start = text[..pos].rfind('\n').map_or(0, |i| i + 1);
+    leading_whitespace(&text[line_start..])
+}
+
+impl TextEditorApp {
+    /// Whether pastes into the active tab are turned into ASCII. Prose tabs
+    /// keep their typography.
+    fn ascii_paste(&self) -> bool {
+        self.active_tab
+            .as_ref()
+            .and_then(|key| self.tabs.get(key))
+            .is_some_and(|tab| !tab.prose && tab.editor_options(&self.settings).ascii_paste)
+    }
+
+    /// Turns Ctrl+Shift+V into "Paste and Indent" and Ctrl+Alt+V into
+    /// "Paste as Column" while the editor has focus, and plain pastes into
+    /// ASCII where the file type asks for it.
+    pub(crate) fn handle_paste_variants(&mut self, ctx: &egui::Context) {
+        if !self.editor_has_focus(ctx) {
+            return;
+        }
+        let ascii = self.ascii_paste();
+        let pasted = ctx.input_mut(|i| {
+            let modifiers = i.modifiers;
+            let variant = modifiers.shift || modifiers.alt;
+            let index = i.events.iter().position(|e| match e {
+                egui::Event::Paste(text) => variant || (ascii && to_ascii(text) != *text),
+                _ => false,
+            })?;
+            match i.events.remove(index) {
+                egui::Event::Paste(text) => Some((text, modifiers)),
+                _ => None,
+            }
+        });
+        let Some((text, modifiers)) = pasted else {
+            return;
+        };
+        let text = if ascii { to_ascii(&text) } else { text };
+        if modifiers.alt {
+            self.paste_as_column(ctx, &text);
+        } else if modifiers.shift {
+            self.paste_and_indent(ctx, &text);
+        } else {
+            self.insert_text(ctx, &text);
+        }
+    }
+
+    pub(crate) fn paste_and_indent(&mut self, ctx: &egui::Context, block: &str) {
+        let Some(range) = self.selection_or(ctx, |_, pos| pos..pos) else {
+            return;
+        };
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        let text = reindent(block, indent_at(&tab.content, range.start));
+        self.insert_text(ctx, &text);
+    }
+
+    pub(crate) fn paste_as_column(&mut self, ctx: &egui::Context, block: &str) {
+        let Some(range) = self.selection_or(ctx, |_, pos| pos..pos) else {
+            return;
+        };
+        let Some(tab) = self.editable_tab() else {
+            return;
+        };
+        let (text, end) = column_paste(&tab.content, range.start, block);
+        tab.content = text;
+        self.set_selection(ctx, end..end);
+    }
+
+    /// Menu entry points, reading the system clipboard directly.
+    pub(crate) fn paste_from_clipboard(&mut self, ctx: &egui::Context, as_column: bool) {
+        let Some(block) = clipboard::read_text() else {
+            return;
+        };
+        let block = if self.ascii_paste() {
+            to_ascii(&block)
+        } else {
+            block
+        };
+        if as_column {
+            self.paste_as_column(ctx, &block);
+        } else {
+            self.paste_and_indent(ctx, &block);
+        }
+    }
+
+    /// Inserts the clipboard exactly as copied.
+    pub(crate) fn paste_plain_text(&mut self, ctx: &egui::Context) {
+        if let Some(text) = clipboard::read_text() {
+            self.insert_text(ctx, &text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indents_to_the_current_line() {
+        let text = "fn main() {\n    let x = 1;\n}\n";
+        let pos = text.find("let").unwrap();
+        assert_eq!(indent_at(text, pos), "    ");
+        assert_eq!(reindent("a\n  b", indent_at(text, pos)), "a\n    b");
+    }
+
+    #[test]
+    fn a_blank_line_has_no_indent() {
+        let text = "fn main() {\n\n    let x = 1;\n}\n";
+        let pos = text.find("\n\n").unwrap() + 1;
+        assert_eq!(indent_at(text, pos), "");
+        assert_eq!(reindent("a\nb\nc", indent_at(text, pos)), "a\nb\nc");
+
+        let text = "\t\n\tnext";
+        assert_eq!(indent_at(text, 1), "\t");
+    }
+}
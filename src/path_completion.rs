@@ -0,0 +1,273 @@
+//! File path completion in buffers: typing a relative path (after `./` or
+//! `../`, or in the string of an include or import statement or a Markdown
+//! link) lists the matching files and folders of the workspace below the
+//! caret. Up and Down pick one, Tab or Enter inserts it, Escape hides the
+//! list until the caret moves.
+//!
+//! Paths are resolved from the folder of the file being edited, or from the
+//! first workspace folder for unsaved buffers.
+
+use crate::TextEditorApp;
+use crate::editing::{char_to_byte, editor_id};
+use crate::motion::take_key;
+use crate::project::{list_dir, normalize, walk_roots};
+use eframe::egui;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How long the listing of the workspace files is reused.
+const FILES_TTL: Duration = Duration::from_secs(10);
+/// Entries shown at most.
+const MAX_CANDIDATES: usize = 20;
+/// Statements whose quoted strings name files even without a leading `./`.
+const IMPORT_KEYWORDS: &[&str] = &[
+    "#include", "@import", "import", "from", "require", "include", "source",
+];
+
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '"' | '\'' | '`' | '(' | '<' | '[' | '=' | ',')
+}
+
+/// The path being typed at the end of `line`, the text before the caret on
+/// its line.
+fn typed_path(line: &str) -> Option<&str> {
+    let start = line
+        .char_indices()
+        .rev()
+        .find(|(_, c)| is_delimiter(*c))
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    let token = &line[start..];
+    if token.starts_with("./") || token.starts_with("../") {
+        return Some(token);
+    }
+    if token.contains("://") {
+        return None;
+    }
+    let before = &line[..start];
+    let quoted = before.ends_with(['"', '\'', '`', '<']);
+    let statement = line.trim_start();
+    let import = quoted
+        && IMPORT_KEYWORDS
+            .iter()
+            .any(|keyword| statement.starts_with(keyword));
+    (import || before.ends_with("](")).then_some(token)
+}
+
+/// Names in the folder `token` leads to that start with its last segment,
+/// folders with a trailing `/`.
+fn candidates(base: &Path, token: &str, files: &[PathBuf]) -> Vec<String> {
+    let (dir, prefix) = match token.rsplit_once('/') {
+        Some((dir, prefix)) => (base.join(format!("{dir}/")), prefix),
+        None => (base.to_path_buf(), token),
+    };
+    let dir = normalize(&dir);
+    let prefix = prefix.to_lowercase();
+    let mut names = BTreeSet::new();
+    let listed = files.iter().any(|file| file.starts_with(&dir));
+    if listed {
+        for file in files {
+            let Ok(rest) = file.strip_prefix(&dir) else {
+                continue;
+            };
+            let mut components = rest.components();
+            let Some(name) = components.next() else {
+                continue;
+            };
+            let name = name.as_os_str().to_string_lossy();
+            if components.next().is_some() {
+                names.insert(format!("{name}/"));
+            } else {
+                names.insert(name.to_string());
+            }
+        }
+    } else {
+        // Outside the workspace, or no folder is open.
+        for entry in list_dir(&dir) {
+            let name = entry.path.file_name().unwrap_or_default().to_string_lossy();
+            if name.starts_with('.') {
+                continue;
+            }
+            if entry.is_dir {
+                names.insert(format!("{name}/"));
+            } else {
+                names.insert(name.to_string());
+            }
+        }
+    }
+    names
+        .into_iter()
+        .filter(|name| {
+            let lower = name.to_lowercase();
+            lower.starts_with(&prefix) && lower != prefix
+        })
+        .take(MAX_CANDIDATES)
+        .collect()
+}
+
+struct Popup {
+    tab: String,
+    /// Character index of the caret.
+    caret: usize,
+    /// Characters of the last path segment, replaced by the chosen entry.
+    typed: usize,
+    candidates: Vec<String>,
+    selected: usize,
+    /// Below the caret, in screen coordinates.
+    anchor: egui::Pos2,
+    /// Where the list was drawn, so clicking it does not hide it.
+    rect: Option<egui::Rect>,
+}
+
+#[derive(Default)]
+pub struct PathCompletion {
+    /// The workspace files, their roots and when they were listed.
+    files: Option<(Vec<PathBuf>, Vec<PathBuf>, Instant)>,
+    popup: Option<Popup>,
+    /// Tab and caret where Escape hid the list.
+    dismissed: Option<(String, usize)>,
+}
+
+impl PathCompletion {
+    fn files(&mut self, roots: &[PathBuf]) -> &[PathBuf] {
+        let fresh = self
+            .files
+            .as_ref()
+            .is_some_and(|(_, listed, at)| listed.as_slice() == roots && at.elapsed() < FILES_TTL);
+        if !fresh {
+            self.files = Some((walk_roots(roots), roots.to_vec(), Instant::now()));
+        }
+        self.files.as_ref().map_or(&[], |(files, _, _)| files)
+    }
+
+    /// Lists the completions for the caret at character index `caret` of
+    /// `text`, or hides the list when nothing is being typed there.
+    pub fn update(
+        &mut self,
+        tab_key: &str,
+        base: Option<&Path>,
+        roots: &[PathBuf],
+        text: &str,
+        caret: usize,
+        anchor: egui::Pos2,
+    ) {
+        let previous = self.popup.take();
+        let here = (tab_key.to_string(), caret);
+        if self.dismissed.as_ref() == Some(&here) {
+            return;
+        }
+        self.dismissed = None;
+        let Some(base) = base.or(roots.first().map(PathBuf::as_path)) else {
+            return;
+        };
+        let byte = char_to_byte(text, caret);
+        let line_start = text[..byte].rfind('\n').map_or(0, |i| i + 1);
+        let Some(token) = typed_path(&text[line_start..byte]) else {
+            return;
+        };
+        let candidates = candidates(base, token, self.files(roots));
+        if candidates.is_empty() {
+            return;
+        }
+        let typed = token.rsplit('/').next().unwrap_or(token).chars().count();
+        let (selected, rect) = previous
+            .filter(|p| p.candidates == candidates)
+            .map_or((0, None), |p| (p.selected, p.rect));
+        self.popup = Some(Popup {
+            tab: tab_key.to_string(),
+            caret,
+            typed,
+            candidates,
+            selected,
+            anchor,
+            rect,
+        });
+    }
+
+    pub fn hide(&mut self) {
+        self.popup = None;
+    }
+}
+
+impl TextEditorApp {
+    /// Replaces the typed path segment with `choice`.
+    fn accept_path(&mut self, ctx: &egui::Context, choice: usize) {
+        let Some(popup) = self.path_completion.popup.take() else {
+            return;
+        };
+        if self.active_tab.as_ref() != Some(&popup.tab) {
+            return;
+        }
+        let start = popup.caret - popup.typed;
+        self.set_selection(ctx, start..popup.caret);
+        self.insert_text(ctx, &popup.candidates[choice]);
+        ctx.memory_mut(|m| m.request_focus(editor_id(&popup.tab)));
+    }
+
+    /// Up, Down, Tab, Enter and Escape while the list is shown.
+    pub(crate) fn handle_path_completion(&mut self, ctx: &egui::Context) {
+        let Some(popup) = &mut self.path_completion.popup else {
+            return;
+        };
+        let caret = popup.caret;
+        let pointer_on_list = popup
+            .rect
+            .zip(ctx.pointer_latest_pos())
+            .is_some_and(|(rect, pos)| rect.contains(pos));
+        if self.active_tab.as_ref() != Some(&popup.tab)
+            || self.selection(ctx) != Some(caret..caret)
+            || !(self.editor_has_focus(ctx) || pointer_on_list)
+        {
+            self.path_completion.hide();
+            return;
+        }
+        let Some(popup) = &mut self.path_completion.popup else {
+            return;
+        };
+        let plain = |m: egui::Modifiers| m.is_none();
+        let count = popup.candidates.len();
+        if take_key(ctx, egui::Key::ArrowDown, plain).is_some() {
+            popup.selected = (popup.selected + 1) % count;
+        }
+        if take_key(ctx, egui::Key::ArrowUp, plain).is_some() {
+            popup.selected = (popup.selected + count - 1) % count;
+        }
+        if take_key(ctx, egui::Key::Escape, plain).is_some() {
+            self.path_completion.dismissed = Some((popup.tab.clone(), caret));
+            self.path_completion.hide();
+            return;
+        }
+        let selected = popup.selected;
+        if take_key(ctx, egui::Key::Tab, plain)
+            .or_else(|| take_key(ctx, egui::Key::Enter, plain))
+            .is_some()
+        {
+            self.accept_path(ctx, selected);
+        }
+    }
+
+    /// The list of completions below the caret.
+    pub(crate) fn show_path_completion(&mut self, ctx: &egui::Context) {
+        let Some(popup) = &mut self.path_completion.popup else {
+            return;
+        };
+        let mut clicked = None;
+        let response = egui::Area::new(egui::Id::new("path_completion"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(popup.anchor)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    for (i, name) in popup.candidates.iter().enumerate() {
+                        let label = egui::RichText::new(name).monospace();
+                        if ui.selectable_label(i == popup.selected, label).clicked() {
+                            clicked = Some(i);
+                        }
+                    }
+                });
+            });
+        popup.rect = Some(response.response.rect);
+        if let Some(i) = clicked {
+            self.accept_path(ctx, i);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! A fuzzy-filtered list in a popup, shared by the command palette and the
+//! symbol pickers: type to filter, Up/Down to move, Enter to choose, Escape
+//! to dismiss.
+
+use crate::fuzzy;
+use eframe::egui;
+use egui::{Key, Modifiers};
+
+/// How many matches are listed at most.
+const MAX_ITEMS: usize = 50;
+
+pub struct PickerItem<T> {
+    pub value: T,
+    pub label: String,
+    /// Shown dimmed on the right, e.g. a shortcut or file location.
+    pub detail: String,
+}
+
+#[derive(Default)]
+pub struct Picker {
+    pub open: bool,
+    query: String,
+    selected: usize,
+}
+
+impl Picker {
+    pub fn opened() -> Self {
+        Self {
+            open: true,
+            ..Default::default()
+        }
+    }
+
+    /// The text typed so far.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Shows the picker over `items`, returning the chosen value. The picker
+    /// closes itself once something is chosen or it is dismissed.
+    pub fn show<T: Clone>(
+        &mut self,
+        ctx: &egui::Context,
+        title: &str,
+        items: Vec<PickerItem<T>>,
+    ) -> Option<T> {
+        if !self.open {
+            return None;
+        }
+        let mut matches = fuzzy::filter(&self.query, items, |item| item.label.as_str());
+        matches.truncate(MAX_ITEMS);
+
+        let (up, down, enter, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(Modifiers::NONE, Key::ArrowUp),
+                i.consume_key(Modifiers::NONE, Key::ArrowDown),
+                i.consume_key(Modifiers::NONE, Key::Enter),
+                i.consume_key(Modifiers::NONE, Key::Escape),
+            )
+        });
+        if escape {
+            self.open = false;
+            return None;
+        }
+        if down {
+            self.selected += 1;
+        }
+        if up {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        self.selected = self.selected.min(matches.len().saturating_sub(1));
+
+        let mut chosen = enter
+            .then(|| matches.get(self.selected).map(|m| m.value.clone()))
+            .flatten();
+        egui::Window::new(title)
+            .title_bar(false)
+            .anchor(egui::Align2::CENTER_TOP, (0.0, 40.0))
+            .fixed_size((420.0, 0.0))
+            .show(ctx, |ui| {
+                let input = ui.add(
+                    egui::TextEdit::singleline(&mut self.query)
+                        .hint_text(title)
+                        .desired_width(f32::INFINITY),
+                );
+                input.request_focus();
+                if input.changed() {
+                    self.selected = 0;
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        for (i, item) in matches.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                let selected = i == self.selected;
+                                let label = ui.selectable_label(selected, &item.label);
+                                if label.clicked() {
+                                    chosen = Some(item.value.clone());
+                                }
+                                if selected && (up || down) {
+                                    label.scroll_to_me(None);
+                                }
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        ui.weak(&item.detail);
+                                    },
+                                );
+                            });
+                        }
+                    });
+            });
+
+        if chosen.is_some() {
+            self.open = false;
+        }
+        chosen
+    }
+}
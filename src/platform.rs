@@ -0,0 +1,72 @@
+//! Platform conventions. Shortcuts are declared with the command modifier,
+//! which egui maps to Cmd on macOS and Ctrl elsewhere, and menus show them
+//! with the platform's modifier names. What cannot be expressed that way is
+//! here: the modifiers of the caret motions, which macOS puts on Option,
+//! the key names used in the preferences, and file dialogs opened as sheets
+//! of the main window rather than free-floating windows.
+
+use eframe::egui::Modifiers;
+use raw_window_handle::{HandleError, HasWindowHandle, RawWindowHandle, WindowHandle};
+use std::cell::Cell;
+
+pub const IS_MAC: bool = cfg!(target_os = "macos");
+
+/// Whether `m` moves the caret by words: Option on macOS, Ctrl elsewhere.
+pub fn is_word_motion(m: Modifiers) -> bool {
+    if IS_MAC {
+        m.alt && !m.mac_cmd && !m.ctrl
+    } else {
+        m.command && !m.alt
+    }
+}
+
+/// Whether `m` moves the caret by sub-words and logical lines. That is Alt,
+/// except on macOS where Option already moves by words and Ctrl is free.
+pub fn is_sub_word_motion(m: Modifiers) -> bool {
+    if IS_MAC {
+        m.ctrl && !m.alt && !m.mac_cmd
+    } else {
+        m.alt && !m.command && !m.ctrl
+    }
+}
+
+/// The name of the command modifier, as in "Ctrl+scroll".
+pub fn command_key() -> &'static str {
+    if IS_MAC { "Cmd" } else { "Ctrl" }
+}
+
+/// The name of the word motion modifier, as in "Ctrl+Left".
+pub fn word_motion_key() -> &'static str {
+    if IS_MAC { "Option" } else { "Ctrl" }
+}
+
+thread_local! {
+    /// The main window, recorded every frame on the UI thread that opens
+    /// the dialogs.
+    static MAIN_WINDOW: Cell<Option<RawWindowHandle>> = const { Cell::new(None) };
+}
+
+struct MainWindow(RawWindowHandle);
+
+impl HasWindowHandle for MainWindow {
+    fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+        // The main window outlives every dialog, which is modal to it.
+        Ok(unsafe { WindowHandle::borrow_raw(self.0) })
+    }
+}
+
+pub fn remember_main_window(frame: &eframe::Frame) {
+    if let Ok(handle) = frame.window_handle() {
+        MAIN_WINDOW.with(|window| window.set(Some(handle.as_raw())));
+    }
+}
+
+/// A file dialog attached to the main window: a sheet on macOS, a modal
+/// dialog on Windows.
+pub fn file_dialog() -> rfd::FileDialog {
+    let dialog = rfd::FileDialog::new().set_can_create_directories(true);
+    match MAIN_WINDOW.with(Cell::get) {
+        Some(handle) => dialog.set_parent(&MainWindow(handle)),
+        None => dialog,
+    }
+}
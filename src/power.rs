@@ -0,0 +1,37 @@
+//! Low energy mode, for laptops on battery: the editor only repaints for
+//! input and background checks, and in this mode those checks run less
+//! often and widgets stop animating.
+
+use crate::TextEditorApp;
+use eframe::egui;
+use std::time::Duration;
+
+/// How much longer background checks wait in low energy mode.
+const SLOWDOWN: u32 = 5;
+
+/// egui's default duration of collapsing and scrolling animations.
+const ANIMATION_TIME: f32 = 1.0 / 12.0;
+
+impl TextEditorApp {
+    /// `interval`, stretched in low energy mode. Used for polling that only
+    /// notices outside changes, such as files changed on disk.
+    pub(crate) fn poll_interval(&self, interval: Duration) -> Duration {
+        if self.settings.low_energy {
+            interval * SLOWDOWN
+        } else {
+            interval
+        }
+    }
+
+    /// Turns animations off or on when the mode changed.
+    pub(crate) fn apply_power_mode(&self, ctx: &egui::Context) {
+        let animation_time = if self.settings.low_energy {
+            0.0
+        } else {
+            ANIMATION_TIME
+        };
+        if ctx.style().animation_time != animation_time {
+            ctx.style_mut(|style| style.animation_time = animation_time);
+        }
+    }
+}
@@ -0,0 +1,649 @@
+use crate::TextEditorApp;
+use crate::i18n::{self, tr, trf};
+use crate::platform;
+use crate::settings::{FileTypeSettings, RunCommand, StatusCommand, SyntaxMapping};
+use crate::ui_scale::{self, MAX_SCALE, MIN_SCALE};
+use eframe::egui;
+
+impl TextEditorApp {
+    pub(crate) fn show_preferences_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_preferences;
+        let mut changed = false;
+        egui::Window::new(tr("Preferences"))
+            .collapsible(false)
+            .default_size((420.0, 300.0))
+            .vscroll(true)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(tr("Language:"));
+                    let languages = i18n::available();
+                    let current = languages
+                        .iter()
+                        .find(|(code, _)| *code == self.settings.language)
+                        .map_or(self.settings.language.as_str(), |(_, name)| name.as_str());
+                    egui::ComboBox::from_id_source("language")
+                        .selected_text(current)
+                        .show_ui(ui, |ui| {
+                            for (code, name) in &languages {
+                                changed |= ui
+                                    .selectable_value(
+                                        &mut self.settings.language,
+                                        code.clone(),
+                                        name.as_str(),
+                                    )
+                                    .changed();
+                            }
+                        });
+                });
+                ui.label(tr(
+                    "More languages can be added as JSON files in the locales folder of the config directory.",
+                ));
+
+                ui.separator();
+                ui.heading(tr("Appearance"));
+                let monitor = ui_scale::monitor_key(ctx);
+                let stored = self.settings.ui_scales.get(&monitor).copied().unwrap_or(1.0);
+                let mut scale = self.ui_scale.dragging.unwrap_or(stored);
+                ui.horizontal(|ui| {
+                    ui.label(tr("UI scale:"));
+                    let slider = ui.add(
+                        egui::Slider::new(&mut scale, MIN_SCALE..=MAX_SCALE)
+                            .step_by(0.05)
+                            .custom_formatter(|value, _| format!("{:.0}%", value * 100.0)),
+                    );
+                    if ui.button(tr("Reset")).clicked() {
+                        scale = 1.0;
+                    }
+                    // Applied once the drag ends so the slider does not
+                    // rescale under the pointer.
+                    if slider.dragged() {
+                        self.ui_scale.dragging = Some(scale);
+                    } else {
+                        self.ui_scale.dragging = None;
+                        if scale != stored {
+                            self.settings.ui_scales.insert(monitor.clone(), scale);
+                            changed = true;
+                        }
+                    }
+                });
+                ui.weak(trf(
+                    "Remembered for this monitor ({monitor}). {key}+scroll also zooms.",
+                    &[("monitor", &monitor), ("key", &tr(platform::command_key()))],
+                ));
+
+                ui.separator();
+                ui.heading(tr("File associations"));
+                ui.label(tr(
+                    "Patterns are matched against the file name before automatic detection.",
+                ));
+                let mut syntax_names: Vec<&str> = self
+                    .syntax_set
+                    .syntaxes()
+                    .iter()
+                    .map(|s| s.name.as_str())
+                    .collect();
+                syntax_names.sort_unstable();
+
+                let mut to_remove = None;
+                egui::Grid::new("syntax_mappings")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (i, mapping) in self.settings.syntax_mappings.iter_mut().enumerate() {
+                            changed |= ui.text_edit_singleline(&mut mapping.pattern).changed();
+                            egui::ComboBox::from_id_source(("syntax_mapping", i))
+                                .selected_text(mapping.syntax.as_str())
+                                .show_ui(ui, |ui| {
+                                    for name in &syntax_names {
+                                        changed |= ui
+                                            .selectable_value(
+                                                &mut mapping.syntax,
+                                                name.to_string(),
+                                                *name,
+                                            )
+                                            .changed();
+                                    }
+                                });
+                            if ui.button(tr("Remove")).clicked() {
+                                to_remove = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if let Some(i) = to_remove {
+                    self.settings.syntax_mappings.remove(i);
+                    changed = true;
+                }
+                if ui.button(tr("Add mapping")).clicked() {
+                    self.settings.syntax_mappings.push(SyntaxMapping {
+                        pattern: String::new(),
+                        syntax: "Plain Text".to_string(),
+                    });
+                    changed = true;
+                }
+
+                ui.separator();
+                ui.heading(tr("Editing"));
+                ui.horizontal(|ui| {
+                    ui.label(tr("Wrap column:"));
+                    changed |= ui
+                        .add(
+                            egui::DragValue::new(&mut self.settings.wrap_column)
+                                .clamp_range(10..=400),
+                        )
+                        .changed();
+                });
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.long_line_warning,
+                        tr("Highlight text past the wrap column"),
+                    )
+                    .changed();
+                ui.horizontal(|ui| {
+                    ui.label(tr("Tab width:"));
+                    changed |= ui
+                        .add(egui::DragValue::new(&mut self.settings.tab_width).clamp_range(1..=16))
+                        .changed();
+                });
+                changed |= ui
+                    .checkbox(&mut self.settings.insert_spaces, tr("Indent with spaces"))
+                    .changed();
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.detect_indentation,
+                        tr("Detect indentation of opened files"),
+                    )
+                    .on_hover_text(tr("Files indented otherwise keep their own tabs or spaces"))
+                    .changed();
+                changed |= ui
+                    .checkbox(&mut self.settings.word_wrap, tr("Wrap long lines"))
+                    .changed();
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.ascii_paste,
+                        tr("Paste smart quotes, dashes and non-breaking spaces as ASCII"),
+                    )
+                    .changed();
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.continue_lists,
+                        tr("Continue lists and comments on Enter"),
+                    )
+                    .changed();
+                ui.horizontal(|ui| {
+                    ui.label(tr("Rulers:"));
+                    changed |= ui
+                        .text_edit_singleline(&mut self.settings.rulers)
+                        .on_hover_text(tr("Columns separated by commas, e.g. 80, 100"))
+                        .changed();
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr("Line spacing:"));
+                    changed |= ui
+                        .add(
+                            egui::DragValue::new(&mut self.settings.line_spacing)
+                                .clamp_range(1.0..=2.5)
+                                .speed(0.05)
+                                .max_decimals(2),
+                        )
+                        .changed();
+                    ui.label(tr("Letter spacing:"));
+                    changed |= ui
+                        .add(
+                            egui::DragValue::new(&mut self.settings.letter_spacing)
+                                .clamp_range(-1.0..=4.0)
+                                .speed(0.1)
+                                .max_decimals(1)
+                                .suffix(" pt"),
+                        )
+                        .changed();
+                });
+                ui.label(tr(
+                    "Per file type, by syntax name or file name pattern. Unset options use the ones above.",
+                ));
+                let mut to_remove = None;
+                egui::Grid::new("file_type_settings")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong(tr("File type"));
+                        ui.strong(tr("Tab width"));
+                        ui.strong(tr("Spaces"));
+                        ui.strong(tr("Wrap"));
+                        ui.strong(tr("ASCII paste"));
+                        ui.strong(tr("Continue lists"));
+                        ui.strong(tr("Rulers"));
+                        ui.end_row();
+                        for (i, overrides) in
+                            self.settings.file_type_settings.iter_mut().enumerate()
+                        {
+                            changed |= ui
+                                .add(
+                                    egui::TextEdit::singleline(&mut overrides.file_type)
+                                        .desired_width(100.0),
+                                )
+                                .changed();
+                            ui.horizontal(|ui| {
+                                let mut set = overrides.tab_width.is_some();
+                                if ui.checkbox(&mut set, "").changed() {
+                                    overrides.tab_width = set.then_some(self.settings.tab_width);
+                                    changed = true;
+                                }
+                                if let Some(width) = &mut overrides.tab_width {
+                                    changed |= ui
+                                        .add(egui::DragValue::new(width).clamp_range(1..=16))
+                                        .changed();
+                                }
+                            });
+                            changed |= option_combo(ui, ("spaces", i), &mut overrides.insert_spaces);
+                            changed |= option_combo(ui, ("wrap", i), &mut overrides.word_wrap);
+                            changed |=
+                                option_combo(ui, ("ascii_paste", i), &mut overrides.ascii_paste);
+                            changed |= option_combo(
+                                ui,
+                                ("continue_lists", i),
+                                &mut overrides.continue_lists,
+                            );
+                            changed |= ui
+                                .add(
+                                    egui::TextEdit::singleline(&mut overrides.rulers)
+                                        .desired_width(60.0),
+                                )
+                                .changed();
+                            if ui.button(tr("Remove")).clicked() {
+                                to_remove = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if let Some(i) = to_remove {
+                    self.settings.file_type_settings.remove(i);
+                    changed = true;
+                }
+                if ui.button(tr("Add file type")).clicked() {
+                    self.settings
+                        .file_type_settings
+                        .push(FileTypeSettings::default());
+                    changed = true;
+                }
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.unicode_warnings,
+                        tr("Warn about invisible and lookalike Unicode characters"),
+                    )
+                    .changed();
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.prose_by_default,
+                        tr("Open Markdown and text files in prose mode"),
+                    )
+                    .changed();
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.word_completion,
+                        tr("Suggest words in text and Markdown (Tab accepts)"),
+                    )
+                    .changed();
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.path_completion,
+                        tr("Complete file paths while typing them"),
+                    )
+                    .on_hover_text(tr(
+                        "After ./ or ../, in include and import statements and in Markdown links",
+                    ))
+                    .changed();
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.emmet,
+                        tr("Expand Emmet abbreviations in HTML and CSS with Tab"),
+                    )
+                    .changed();
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.close_blocks,
+                        tr("Close Ruby, Lua and shell blocks on Enter"),
+                    )
+                    .on_hover_text(tr("Adds end, fi, done or esac below the line"))
+                    .changed();
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.highlight_occurrences,
+                        tr("Highlight other occurrences of the word at the caret"),
+                    )
+                    .changed();
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.change_bars,
+                        tr("Mark lines changed since the last save"),
+                    )
+                    .on_hover_text(tr("Click a mark to revert that change"))
+                    .changed();
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.breadcrumbs,
+                        tr("Show the file path above the editor"),
+                    )
+                    .changed();
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.scroll_past_end,
+                        tr("Scroll past the last line"),
+                    )
+                    .changed();
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.typewriter_scrolling,
+                        tr("Typewriter scrolling"),
+                    )
+                    .on_hover_text(tr("Keeps the line being typed in the middle of the editor"))
+                    .changed();
+                changed |= ui
+                    .checkbox(&mut self.settings.low_energy, tr("Low energy mode"))
+                    .on_hover_text(tr(
+                        "Checks for changes on disk and in git less often and turns off animations",
+                    ))
+                    .changed();
+                ui.horizontal(|ui| {
+                    changed |= ui
+                        .checkbox(
+                            &mut self.settings.evict_idle_tabs,
+                            tr("Free caches of tabs not shown for"),
+                        )
+                        .on_hover_text(tr(
+                            "Drops their highlighting and undo history to save memory",
+                        ))
+                        .changed();
+                    changed |= ui
+                        .add(
+                            egui::DragValue::new(&mut self.settings.evict_after_minutes)
+                                .clamp_range(1..=1440)
+                                .suffix(" min"),
+                        )
+                        .changed();
+                });
+
+                ui.separator();
+                ui.heading(tr("Caret movement"));
+                changed |= ui
+                    .checkbox(&mut self.settings.smart_home, tr("Smart Home"))
+                    .on_hover_text(tr(
+                        "Home jumps to the first non-whitespace character, then to column 0",
+                    ))
+                    .changed();
+                ui.label(trf(
+                    "{key}+Left/Right also stops at:",
+                    &[("key", &tr(platform::word_motion_key()))],
+                ));
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.word_underscore_boundary,
+                        tr("Underscores (snake_case)"),
+                    )
+                    .changed();
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.word_camel_case_boundary,
+                        tr("Case changes (camelCase)"),
+                    )
+                    .changed();
+                ui.label(tr("Alt+Left/Right always moves by sub-word."));
+
+                ui.horizontal(|ui| {
+                    ui.label(tr("Date/time format:"));
+                    changed |= ui
+                        .text_edit_singleline(&mut self.settings.date_format)
+                        .on_hover_text(tr("strftime syntax, e.g. %Y-%m-%d %H:%M"))
+                        .changed();
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr("Author:"));
+                    changed |= ui
+                        .text_edit_singleline(&mut self.settings.author)
+                        .on_hover_text(tr("Used by file templates; empty uses git's user.name"))
+                        .changed();
+                });
+
+                ui.separator();
+                ui.heading(tr("Saving"));
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.strip_bom_on_save,
+                        tr("Strip UTF-8 byte order mark on save"),
+                    )
+                    .changed();
+                changed |= ui
+                    .checkbox(&mut self.settings.audit_log, tr("Keep an audit log of saves"))
+                    .on_hover_text(tr(
+                        "Record the time, size and hashes of every file the editor writes, shown in the Audit Log panel",
+                    ))
+                    .changed();
+
+                ui.separator();
+                ui.heading(tr("Privacy"));
+                ui.label(tr(
+                    "Files matching these patterns open as sensitive: they are kept out of the crash journal, Live View, tab groups, recently closed tabs and the audit log's hashes, and cannot be shared as a gist.",
+                ));
+                let mut to_remove = None;
+                egui::Grid::new("sensitive_patterns")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (i, pattern) in self.settings.sensitive_patterns.iter_mut().enumerate() {
+                            changed |= ui.text_edit_singleline(pattern).changed();
+                            if ui.button(tr("Remove")).clicked() {
+                                to_remove = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if let Some(i) = to_remove {
+                    self.settings.sensitive_patterns.remove(i);
+                    changed = true;
+                }
+                if ui.button(tr("Add pattern")).clicked() {
+                    self.settings.sensitive_patterns.push(String::new());
+                    changed = true;
+                }
+
+                ui.separator();
+                ui.heading(tr("Counterparts"));
+                ui.label(tr(
+                    "Switch to Counterpart moves between these files. Each rule lists paths relative to a common folder, separated by commas; {name} is the part of the file name they share.",
+                ));
+                let mut to_remove = None;
+                egui::Grid::new("counterpart_rules")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (i, rule) in self.settings.counterpart_rules.iter_mut().enumerate() {
+                            changed |= ui
+                                .add(egui::TextEdit::singleline(rule).desired_width(360.0))
+                                .changed();
+                            if ui.button(tr("Remove")).clicked() {
+                                to_remove = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if let Some(i) = to_remove {
+                    self.settings.counterpart_rules.remove(i);
+                    changed = true;
+                }
+                if ui.button(tr("Add rule")).clicked() {
+                    self.settings.counterpart_rules.push(String::new());
+                    changed = true;
+                }
+
+                ui.separator();
+                ui.heading(tr("Run commands"));
+                ui.label(tr(
+                    "Used by Run Current File. {file}, {dir} and {stem} are expanded.",
+                ));
+                let mut to_remove = None;
+                egui::Grid::new("run_commands")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (i, run) in self.settings.run_commands.iter_mut().enumerate() {
+                            egui::ComboBox::from_id_source(("run_command", i))
+                                .selected_text(run.syntax.as_str())
+                                .show_ui(ui, |ui| {
+                                    for name in &syntax_names {
+                                        changed |= ui
+                                            .selectable_value(
+                                                &mut run.syntax,
+                                                name.to_string(),
+                                                *name,
+                                            )
+                                            .changed();
+                                    }
+                                });
+                            changed |= ui.text_edit_singleline(&mut run.command).changed();
+                            if ui.button(tr("Remove")).clicked() {
+                                to_remove = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if let Some(i) = to_remove {
+                    self.settings.run_commands.remove(i);
+                    changed = true;
+                }
+                if ui.button(tr("Add run command")).clicked() {
+                    self.settings.run_commands.push(RunCommand {
+                        syntax: "Plain Text".to_string(),
+                        command: String::new(),
+                    });
+                    changed = true;
+                }
+
+                ui.separator();
+                ui.heading(tr("Terminal and environment"));
+                ui.horizontal(|ui| {
+                    ui.label(tr("Terminal command:"));
+                    changed |= ui
+                        .add(
+                            egui::TextEdit::singleline(&mut self.settings.terminal_command)
+                                .hint_text(tr("System default"))
+                                .desired_width(280.0),
+                        )
+                        .on_hover_text(tr("{dir} is replaced by the folder to open"))
+                        .changed();
+                });
+                ui.label(tr(
+                    "Variables given to run commands, cargo check and terminals as NAME=value, after those of the project's .env file.",
+                ));
+                let mut to_remove = None;
+                egui::Grid::new("environment")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (i, variable) in self.settings.environment.iter_mut().enumerate() {
+                            changed |= ui
+                                .add(egui::TextEdit::singleline(variable).desired_width(360.0))
+                                .changed();
+                            if ui.button(tr("Remove")).clicked() {
+                                to_remove = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if let Some(i) = to_remove {
+                    self.settings.environment.remove(i);
+                    changed = true;
+                }
+                if ui.button(tr("Add variable")).clicked() {
+                    self.settings.environment.push(String::new());
+                    changed = true;
+                }
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.workspace_trust,
+                        tr("Ask whether to trust newly opened folders"),
+                    )
+                    .on_hover_text(tr(
+                        "Untrusted folders get no automatic git or status commands and their .env file is ignored",
+                    ))
+                    .changed();
+
+                ui.separator();
+                ui.heading(tr("Status commands"));
+                ui.label(tr(
+                    "The first line these commands print is shown in the status bar. They run in the first workspace folder every so many seconds, or when clicked if that is 0.",
+                ));
+                let mut to_remove = None;
+                egui::Grid::new("status_commands")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (i, status) in self.settings.status_commands.iter_mut().enumerate() {
+                            changed |= ui
+                                .add(egui::TextEdit::singleline(&mut status.command).desired_width(280.0))
+                                .changed();
+                            changed |= ui
+                                .add(egui::DragValue::new(&mut status.interval).suffix(" s"))
+                                .changed();
+                            if ui.button(tr("Remove")).clicked() {
+                                to_remove = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if let Some(i) = to_remove {
+                    self.settings.status_commands.remove(i);
+                    changed = true;
+                }
+                if ui.button(tr("Add status command")).clicked() {
+                    self.settings.status_commands.push(StatusCommand {
+                        command: String::new(),
+                        interval: 60,
+                    });
+                    changed = true;
+                }
+
+                ui.separator();
+                ui.heading(tr("Sharing"));
+                ui.horizontal(|ui| {
+                    ui.label(tr("GitHub token:"));
+                    changed |= ui
+                        .add(
+                            egui::TextEdit::singleline(&mut self.settings.gist_token)
+                                .password(true),
+                        )
+                        .on_hover_text(tr("A personal access token with the gist scope"))
+                        .changed();
+                });
+
+                ui.separator();
+                ui.heading(tr("Updates"));
+                changed |= ui
+                    .checkbox(
+                        &mut self.settings.check_for_updates,
+                        tr("Check for updates on startup"),
+                    )
+                    .on_hover_text(tr("Asks GitHub for the latest release when the editor starts"))
+                    .changed();
+            });
+        self.show_preferences = open;
+
+        if changed {
+            self.settings.save();
+            i18n::set_language(&self.settings.language);
+            self.redetect_syntaxes();
+        }
+    }
+}
+
+/// A Default / On / Off choice for an option a file type may override.
+fn option_combo(ui: &mut egui::Ui, id: impl std::hash::Hash, value: &mut Option<bool>) -> bool {
+    let label = |value: Option<bool>| match value {
+        None => tr("Default"),
+        Some(true) => tr("On"),
+        Some(false) => tr("Off"),
+    };
+    let mut changed = false;
+    egui::ComboBox::from_id_source(id)
+        .selected_text(label(*value))
+        .width(70.0)
+        .show_ui(ui, |ui| {
+            for choice in [None, Some(true), Some(false)] {
+                changed |= ui.selectable_value(value, choice, label(choice)).changed();
+            }
+        });
+    changed
+}
@@ -0,0 +1,26 @@
+//! Privacy mode for buffers holding secrets such as `.env` files or keys. A
+//! sensitive tab is kept out of everything that copies its content or
+//! remembers it behind the user's back: the crash journal, Live View, the
+//! saved tab groups and the recently closed tabs, and the audit log records
+//! no hashes of it. It cannot be shared as a gist either. Files matching the
+//! sensitive patterns in the settings start out sensitive.
+
+use crate::TextEditorApp;
+
+impl TextEditorApp {
+    pub(crate) fn toggle_sensitive(&mut self) {
+        let Some(tab) = self
+            .active_tab
+            .as_ref()
+            .and_then(|key| self.tabs.get_mut(key))
+        else {
+            return;
+        };
+        tab.sensitive = !tab.sensitive;
+        if tab.sensitive
+            && let Some(id) = &tab.journal_id
+        {
+            self.journal.discard(id);
+        }
+    }
+}
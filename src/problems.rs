@@ -0,0 +1,418 @@
+//! The Problems tab: diagnostics from every source (cargo check, the TODO
+//! scanner, and whatever else reports through [`Problems::report`]) in one
+//! filterable list, with the counts shown in the status bar.
+
+use crate::TextEditorApp;
+use crate::bottom_panel::BottomTab;
+use crate::i18n::{tr, trf};
+use crate::project::{display_path, walk_files};
+use eframe::egui;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::thread;
+
+/// Files larger than this are skipped by the TODO scanner.
+const MAX_SCAN_SIZE: u64 = 1024 * 1024;
+const TODO_MARKERS: &[&str] = &["TODO", "FIXME", "XXX", "HACK"];
+
+pub const CARGO_SOURCE: &str = "cargo";
+pub const TODO_SOURCE: &str = "todo";
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 3] = [Severity::Error, Severity::Warning, Severity::Info];
+
+    pub fn icon(self) -> &'static str {
+        match self {
+            Severity::Error => "⊗",
+            Severity::Warning => "⚠",
+            Severity::Info => "ℹ",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Severity::Error => "Errors",
+            Severity::Warning => "Warnings",
+            Severity::Info => "Info",
+        }
+    }
+
+    fn color(self, visuals: &egui::Visuals) -> egui::Color32 {
+        match self {
+            Severity::Error => visuals.error_fg_color,
+            Severity::Warning => visuals.warn_fg_color,
+            Severity::Info => visuals.weak_text_color(),
+        }
+    }
+}
+
+/// A diagnostic. `line` and `column` are 0-based.
+#[derive(Clone, Debug)]
+pub struct Problem {
+    pub severity: Severity,
+    pub path: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+pub struct Problems {
+    /// Latest report of each source, replaced wholesale on every run.
+    by_source: BTreeMap<String, Vec<Problem>>,
+    /// Sources with a run in flight.
+    running: Vec<String>,
+    shown: [bool; 3],
+    file_filter: String,
+    sender: Sender<(String, Vec<Problem>)>,
+    receiver: Receiver<(String, Vec<Problem>)>,
+}
+
+impl Default for Problems {
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            by_source: BTreeMap::new(),
+            running: Vec::new(),
+            shown: [true; 3],
+            file_filter: String::new(),
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl Problems {
+    /// Replaces everything previously reported by `source`.
+    pub fn report(&mut self, source: &str, problems: Vec<Problem>) {
+        self.running.retain(|s| s != source);
+        self.by_source.insert(source.to_string(), problems);
+    }
+
+    /// Runs `job` on a background thread and reports its result as `source`.
+    /// A source that is still running is not started again.
+    pub fn spawn(
+        &mut self,
+        ctx: &egui::Context,
+        source: &str,
+        job: impl FnOnce() -> Vec<Problem> + Send + 'static,
+    ) {
+        if self.is_running(source) {
+            return;
+        }
+        self.running.push(source.to_string());
+        let sender = self.sender.clone();
+        let source = source.to_string();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let _ = sender.send((source, job()));
+            ctx.request_repaint();
+        });
+    }
+
+    pub fn is_running(&self, source: &str) -> bool {
+        self.running.iter().any(|s| s == source)
+    }
+
+    /// Applies reports that finished since the last call and returns the
+    /// sources they came from.
+    pub fn poll(&mut self) -> Vec<String> {
+        let mut finished = Vec::new();
+        while let Ok((source, problems)) = self.receiver.try_recv() {
+            self.report(&source, problems);
+            finished.push(source);
+        }
+        finished
+    }
+
+    pub fn clear(&mut self) {
+        self.by_source.clear();
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.all().filter(|(_, p)| p.severity == severity).count()
+    }
+
+    fn all(&self) -> impl Iterator<Item = (&str, &Problem)> {
+        self.by_source
+            .iter()
+            .flat_map(|(source, problems)| problems.iter().map(move |p| (source.as_str(), p)))
+    }
+}
+
+/// Runs `cargo check` in `root` with the variables `env` added and
+/// collects the compiler diagnostics.
+pub fn cargo_check(root: &Path, env: &[(String, String)]) -> Vec<Problem> {
+    let output = process::Command::new("cargo")
+        .args(["check", "--all-targets", "--message-format=json"])
+        .current_dir(root)
+        .envs(env.iter().map(|(k, v)| (k, v)))
+        .output();
+    let Ok(output) = output else {
+        return vec![Problem {
+            severity: Severity::Error,
+            path: root.join("Cargo.toml"),
+            line: 0,
+            column: 0,
+            message: tr("Could not run cargo"),
+        }];
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| parse_cargo_message(root, line))
+        .collect()
+}
+
+fn parse_cargo_message(root: &Path, line: &str) -> Option<Problem> {
+    let json: serde_json::Value = serde_json::from_str(line).ok()?;
+    if json["reason"] != "compiler-message" {
+        return None;
+    }
+    let message = &json["message"];
+    let severity = match message["level"].as_str()? {
+        "error" | "error: internal compiler error" => Severity::Error,
+        "warning" => Severity::Warning,
+        _ => Severity::Info,
+    };
+    let span = message["spans"]
+        .as_array()?
+        .iter()
+        .find(|span| span["is_primary"] == true)?;
+    let number = |key: &str| {
+        span[key]
+            .as_u64()
+            .map_or(0, |n| n.saturating_sub(1) as usize)
+    };
+    Some(Problem {
+        severity,
+        path: root.join(span["file_name"].as_str()?),
+        line: number("line_start"),
+        column: number("column_start"),
+        message: message["message"].as_str()?.to_string(),
+    })
+}
+
+/// Finds TODO-style markers in every text file below `root`.
+pub fn scan_todos(root: &Path) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    for path in walk_files(root) {
+        if fs::metadata(&path).is_ok_and(|m| m.len() > MAX_SCAN_SIZE) {
+            continue;
+        }
+        let Ok(text) = fs::read_to_string(&path) else {
+            continue;
+        };
+        for (line, content) in text.lines().enumerate() {
+            if let Some((column, marker)) = find_marker(content) {
+                problems.push(Problem {
+                    severity: if marker == "FIXME" {
+                        Severity::Warning
+                    } else {
+                        Severity::Info
+                    },
+                    path: path.clone(),
+                    line,
+                    column: content[..column].chars().count(),
+                    message: content[column..].trim().to_string(),
+                });
+            }
+        }
+    }
+    problems
+}
+
+/// The byte offset and marker of the first whole-word TODO marker in `line`.
+fn find_marker(line: &str) -> Option<(usize, &'static str)> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    TODO_MARKERS
+        .iter()
+        .filter_map(|&marker| {
+            line.match_indices(marker)
+                .find(|&(i, _)| {
+                    let before = line[..i].chars().next_back();
+                    let after = line[i + marker.len()..].chars().next();
+                    !before.is_some_and(is_word) && !after.is_some_and(is_word)
+                })
+                .map(|(i, _)| (i, marker))
+        })
+        .min_by_key(|&(i, _)| i)
+}
+
+impl TextEditorApp {
+    /// Applies finished background runs, announcing cargo check results.
+    pub(crate) fn poll_problems(&mut self) {
+        for source in self.problems.poll() {
+            if source == CARGO_SOURCE {
+                let errors = self.problems.by_source[CARGO_SOURCE]
+                    .iter()
+                    .filter(|p| p.severity == Severity::Error)
+                    .count();
+                let warnings = self.problems.by_source[CARGO_SOURCE]
+                    .iter()
+                    .filter(|p| p.severity == Severity::Warning)
+                    .count();
+                self.notifications.info(trf(
+                    "cargo check finished: {errors} errors, {warnings} warnings",
+                    &[("errors", &errors), ("warnings", &warnings)],
+                ));
+                self.set_cargo_status(
+                    trf(
+                        "cargo check: {errors} errors, {warnings} warnings",
+                        &[("errors", &errors), ("warnings", &warnings)],
+                    ),
+                    errors > 0,
+                );
+            }
+        }
+    }
+
+    /// Runs cargo check in every workspace folder holding a Cargo.toml.
+    pub(crate) fn run_cargo_check(&mut self, ctx: &egui::Context) {
+        let roots: Vec<(PathBuf, Vec<(String, String)>)> = self
+            .folders
+            .iter()
+            .filter(|root| root.join("Cargo.toml").is_file())
+            .map(|root| (root.clone(), self.process_env(root)))
+            .collect();
+        if roots.is_empty() {
+            return;
+        }
+        self.bottom_panel.show(BottomTab::Problems);
+        self.set_cargo_status(tr("cargo check…"), false);
+        let job = self.jobs.start(tr("Running cargo check"), false);
+        self.problems.spawn(ctx, CARGO_SOURCE, move || {
+            let _job = job;
+            roots
+                .iter()
+                .flat_map(|(root, env)| cargo_check(root, env))
+                .collect()
+        });
+    }
+
+    pub(crate) fn run_todo_scan(&mut self, ctx: &egui::Context) {
+        let roots = self.folders.clone();
+        if roots.is_empty() {
+            return;
+        }
+        let job = self.jobs.start(tr("Scanning for TODOs"), false);
+        self.problems.spawn(ctx, TODO_SOURCE, move || {
+            let _job = job;
+            roots.iter().flat_map(|root| scan_todos(root)).collect()
+        });
+    }
+
+    /// Status bar entry with the error and warning counts; toggles the panel.
+    pub(crate) fn problem_counts_button(&mut self, ui: &mut egui::Ui) {
+        let text = format!(
+            "{} {}  {} {}",
+            Severity::Error.icon(),
+            self.problems.count(Severity::Error),
+            Severity::Warning.icon(),
+            self.problems.count(Severity::Warning)
+        );
+        if ui
+            .add(egui::Button::new(text).frame(false))
+            .on_hover_text(tr("Problems"))
+            .clicked()
+        {
+            self.bottom_panel.toggle(BottomTab::Problems);
+        }
+    }
+
+    /// The Problems tab of the bottom panel.
+    pub(crate) fn problems_ui(&mut self, ui: &mut egui::Ui) {
+        let mut jump: Option<(PathBuf, usize, usize)> = None;
+        let mut run_check = false;
+        let mut run_scan = false;
+        let problems = &mut self.problems;
+        ui.horizontal(|ui| {
+            for (i, severity) in Severity::ALL.into_iter().enumerate() {
+                let label = format!(
+                    "{} {} ({})",
+                    severity.icon(),
+                    tr(severity.label()),
+                    problems.count(severity)
+                );
+                ui.toggle_value(&mut problems.shown[i], label);
+            }
+            ui.separator();
+            ui.add(
+                egui::TextEdit::singleline(&mut problems.file_filter)
+                    .hint_text(tr("Filter by file"))
+                    .desired_width(160.0),
+            );
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button(tr("Clear")).clicked() {
+                    problems.clear();
+                }
+                if ui.button(tr("Scan TODOs")).clicked() {
+                    run_scan = true;
+                }
+                if ui.button(tr("Run cargo check")).clicked() {
+                    run_check = true;
+                }
+                if problems.is_running(CARGO_SOURCE) || problems.is_running(TODO_SOURCE) {
+                    ui.spinner();
+                }
+            });
+        });
+        ui.separator();
+
+        let roots = &self.folders;
+        let filter = problems.file_filter.to_lowercase();
+        let mut rows: Vec<(&str, &Problem)> = problems
+            .all()
+            .filter(|(_, p)| problems.shown[p.severity as usize])
+            .filter(|(_, p)| {
+                filter.is_empty() || p.path.to_string_lossy().to_lowercase().contains(&filter)
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            (a.1.severity, &a.1.path, a.1.line).cmp(&(b.1.severity, &b.1.path, b.1.line))
+        });
+
+        let row_height = ui.text_style_height(&egui::TextStyle::Body);
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show_rows(ui, row_height, rows.len(), |ui, range| {
+                for (source, problem) in &rows[range] {
+                    ui.horizontal(|ui| {
+                        ui.colored_label(
+                            problem.severity.color(ui.visuals()),
+                            problem.severity.icon(),
+                        );
+                        let location = format!(
+                            "{}:{}:{}",
+                            display_path(roots, &problem.path),
+                            problem.line + 1,
+                            problem.column + 1
+                        );
+                        if ui.link(location).clicked() {
+                            jump = Some((problem.path.clone(), problem.line, problem.column));
+                        }
+                        ui.label(&problem.message);
+                        ui.weak(*source);
+                    });
+                }
+            });
+        let ctx = ui.ctx().clone();
+        if run_check {
+            self.run_cargo_check(&ctx);
+        }
+        if run_scan {
+            self.run_todo_scan(&ctx);
+        }
+        if let Some((path, line, column)) = jump {
+            self.open_at(&ctx, &path, line, column);
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! Opt-in frame profiling. Code wraps the work worth measuring in
+//! [`scope`], which costs one atomic load while the profiler is off; while
+//! it is on, the time spent in each named scope is summed per frame and the
+//! Profiler window shows the breakdown of recent frames.
+
+use crate::TextEditorApp;
+use crate::i18n::{tr, trf};
+use eframe::egui;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Frames kept for averages and the frame time graph.
+const HISTORY: usize = 120;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+/// Scopes finished since the current frame began, on any thread.
+static CURRENT: Mutex<Vec<(&'static str, Duration)>> = Mutex::new(Vec::new());
+
+/// Times the enclosing block while the profiler is on.
+#[must_use]
+pub struct Scope {
+    name: &'static str,
+    start: Option<Instant>,
+}
+
+pub fn scope(name: &'static str) -> Scope {
+    Scope {
+        name,
+        start: ENABLED.load(Ordering::Relaxed).then(Instant::now),
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        if let Some(start) = self.start
+            && let Ok(mut current) = CURRENT.lock()
+        {
+            current.push((self.name, start.elapsed()));
+        }
+    }
+}
+
+struct Frame {
+    total: Duration,
+    /// Time per scope name, summed over its calls, and the call count.
+    scopes: Vec<(&'static str, Duration, usize)>,
+}
+
+#[derive(Default)]
+pub struct Profiler {
+    pub open: bool,
+    frame_start: Option<Instant>,
+    frames: VecDeque<Frame>,
+}
+
+impl Profiler {
+    pub fn begin_frame(&mut self) {
+        ENABLED.store(self.open, Ordering::Relaxed);
+        if !self.open {
+            self.frames.clear();
+            self.frame_start = None;
+            return;
+        }
+        self.frame_start = Some(Instant::now());
+    }
+
+    pub fn end_frame(&mut self) {
+        let Some(start) = self.frame_start.take() else {
+            return;
+        };
+        let recorded = CURRENT
+            .lock()
+            .map(|mut current| std::mem::take(&mut *current))
+            .unwrap_or_default();
+        let mut scopes: Vec<(&'static str, Duration, usize)> = Vec::new();
+        for (name, time) in recorded {
+            match scopes.iter_mut().find(|(n, _, _)| *n == name) {
+                Some((_, total, calls)) => {
+                    *total += time;
+                    *calls += 1;
+                }
+                None => scopes.push((name, time, 1)),
+            }
+        }
+        if self.frames.len() == HISTORY {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(Frame {
+            total: start.elapsed(),
+            scopes,
+        });
+    }
+}
+
+fn ms(time: Duration) -> String {
+    format!("{:.2}", time.as_secs_f64() * 1000.0)
+}
+
+/// Bars for the recent frame times, with a line at 16.7 ms (60 fps).
+fn frame_graph(ui: &mut egui::Ui, frames: &VecDeque<Frame>) {
+    let (rect, _) =
+        ui.allocate_exact_size(egui::vec2(ui.available_width(), 60.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+    let budget = Duration::from_micros(16_667);
+    let max = frames
+        .iter()
+        .map(|f| f.total)
+        .max()
+        .unwrap_or_default()
+        .max(budget * 2);
+    let scale = |time: Duration| rect.height() * time.as_secs_f32() / max.as_secs_f32();
+    let width = rect.width() / HISTORY as f32;
+    for (i, frame) in frames.iter().enumerate() {
+        let x = rect.left() + i as f32 * width;
+        let color = if frame.total > budget {
+            ui.visuals().warn_fg_color
+        } else {
+            ui.visuals().selection.bg_fill
+        };
+        painter.rect_filled(
+            egui::Rect::from_min_max(
+                egui::pos2(x, rect.bottom() - scale(frame.total)),
+                egui::pos2(x + width.max(1.0), rect.bottom()),
+            ),
+            0.0,
+            color,
+        );
+    }
+    let y = rect.bottom() - scale(budget);
+    painter.hline(
+        rect.x_range(),
+        y,
+        ui.visuals().widgets.noninteractive.fg_stroke,
+    );
+}
+
+impl TextEditorApp {
+    pub(crate) fn show_profiler_window(&mut self, ctx: &egui::Context) {
+        let profiler = &mut self.profiler;
+        if !profiler.open {
+            return;
+        }
+        let mut open = true;
+        egui::Window::new(tr("Profiler"))
+            .open(&mut open)
+            .default_size((420.0, 300.0))
+            .show(ctx, |ui| {
+                let frames = &profiler.frames;
+                let Some(last) = frames.back() else {
+                    ui.weak(tr("Waiting for frames…"));
+                    return;
+                };
+                let average =
+                    frames.iter().map(|f| f.total).sum::<Duration>() / frames.len() as u32;
+                ui.label(trf(
+                    "Last frame: {last} ms, average of {count}: {average} ms",
+                    &[
+                        ("last", &ms(last.total)),
+                        ("count", &frames.len()),
+                        ("average", &ms(average)),
+                    ],
+                ));
+                frame_graph(ui, frames);
+                ui.separator();
+                // Every scope seen in the history, slowest on average first.
+                let mut names: Vec<&'static str> = Vec::new();
+                for frame in frames {
+                    for (name, _, _) in &frame.scopes {
+                        if !names.contains(name) {
+                            names.push(name);
+                        }
+                    }
+                }
+                let stats = |name: &str| {
+                    let times = frames.iter().map(|f| {
+                        f.scopes
+                            .iter()
+                            .find(|(n, _, _)| *n == name)
+                            .map_or(Duration::ZERO, |(_, time, _)| *time)
+                    });
+                    let total: Duration = times.clone().sum();
+                    (total / frames.len() as u32, times.max().unwrap_or_default())
+                };
+                names.sort_by_key(|name| std::cmp::Reverse(stats(name).0));
+                egui::Grid::new("profiler_scopes")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong(tr("Scope"));
+                        ui.strong(tr("Last (ms)"));
+                        ui.strong(tr("Calls"));
+                        ui.strong(tr("Average (ms)"));
+                        ui.strong(tr("Max (ms)"));
+                        ui.end_row();
+                        for name in names {
+                            let (last_time, calls) = last
+                                .scopes
+                                .iter()
+                                .find(|(n, _, _)| *n == name)
+                                .map_or((Duration::ZERO, 0), |(_, time, calls)| (*time, *calls));
+                            let (average, max) = stats(name);
+                            ui.monospace(name);
+                            ui.monospace(ms(last_time));
+                            ui.monospace(calls.to_string());
+                            ui.monospace(ms(average));
+                            ui.monospace(ms(max));
+                            ui.end_row();
+                        }
+                    });
+            });
+        profiler.open = open;
+    }
+}
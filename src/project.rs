@@ -0,0 +1,116 @@
+//! Walking the files of the workspace folders.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Directories that hold build output or dependencies rather than sources.
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules"];
+
+/// Every file below `root`, skipping hidden entries and build directories.
+pub fn walk_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with('.') {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                if !SKIPPED_DIRS.contains(&name.as_ref()) {
+                    pending.push(entry.path());
+                }
+            } else if file_type.is_file() {
+                files.push(entry.path());
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// A file or folder in a listing.
+#[derive(Clone)]
+pub struct Entry {
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+/// The entries of `dir`, folders first, then by name ignoring case.
+pub fn list_dir(dir: &Path) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| Entry {
+            is_dir: entry.file_type().is_ok_and(|t| t.is_dir()),
+            path: entry.path(),
+        })
+        .collect();
+    entries.sort_by_cached_key(|entry| {
+        let name = entry.path.file_name().unwrap_or_default();
+        (!entry.is_dir, name.to_string_lossy().to_lowercase())
+    });
+    entries
+}
+
+/// `path` with `.` and `..` components resolved without touching the file
+/// system.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// `path` relative to the folder `from`, with `/` separators as links use
+/// them. Both should be absolute.
+pub fn relative_path(from: &Path, path: &Path) -> String {
+    let (from, path) = (normalize(from), normalize(path));
+    let from: Vec<Component> = from.components().collect();
+    let to: Vec<Component> = path.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    let mut parts: Vec<String> = vec!["..".to_string(); from.len() - common];
+    parts.extend(
+        to[common..]
+            .iter()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned()),
+    );
+    parts.join("/")
+}
+
+/// Every file below any of `roots`.
+pub fn walk_roots(roots: &[PathBuf]) -> Vec<PathBuf> {
+    roots.iter().flat_map(|root| walk_files(root)).collect()
+}
+
+/// How `path` is shown in lists: relative to the workspace folder holding
+/// it, led by that folder's name when there are several.
+pub fn display_path(roots: &[PathBuf], path: &Path) -> String {
+    let Some((root, relative)) = roots
+        .iter()
+        .find_map(|root| Some((root, path.strip_prefix(root).ok()?)))
+    else {
+        return path.display().to_string();
+    };
+    match root.file_name() {
+        Some(name) if roots.len() > 1 => Path::new(name).join(relative).display().to_string(),
+        _ => relative.display().to_string(),
+    }
+}
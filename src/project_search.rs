@@ -0,0 +1,705 @@
+//! Find in Files: a plain-text search over the opened folder, shown in the
+//! bottom panel, and "Replace in N files", which previews every changed
+//! line grouped by file before anything is written. Files that cannot be
+//! changed are reported as notifications.
+//!
+//! Find All References is the same search, for the identifier at the caret
+//! as a whole word, with the lines around each match shown.
+
+use crate::bottom_panel::BottomTab;
+use crate::editing::{byte_to_char, char_to_byte};
+use crate::i18n::{tr, trf};
+use crate::jobs::{CancelToken, Job};
+use crate::profiler;
+use crate::project::{display_path, walk_roots};
+use crate::rename_symbol::identifier_at;
+use crate::search_filters::SearchFilters;
+use crate::search_history::history_field;
+use crate::stale_save::DiskStamp;
+use crate::unicode_search;
+use crate::{TextEditorApp, UTF8_BOM, atomic_write};
+use eframe::egui;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, channel};
+use std::thread;
+
+/// Files larger than this are not searched.
+const MAX_SEARCH_SIZE: u64 = 1024 * 1024;
+/// Matching lines collected before the search stops.
+const MAX_MATCHING_LINES: usize = 5000;
+
+/// A line containing at least one match. `line` is 0-based and `ranges`
+/// are byte ranges into `text`. `before` and `after` are the neighbouring
+/// lines, shown as context.
+pub struct LineMatch {
+    pub line: usize,
+    pub text: String,
+    pub ranges: Vec<Range<usize>>,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+pub struct FileMatches {
+    pub path: PathBuf,
+    pub lines: Vec<LineMatch>,
+}
+
+/// One changed line of a pending replacement.
+struct Hunk {
+    line: usize,
+    old: String,
+    new: String,
+    apply: bool,
+}
+
+struct FilePreview {
+    path: PathBuf,
+    hunks: Vec<Hunk>,
+}
+
+#[derive(Default)]
+pub struct ProjectSearch {
+    pub query: String,
+    pub replacement: String,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    /// Ignore differences in Unicode normalization, and in case beyond
+    /// ASCII.
+    pub normalize: bool,
+    pub filters: SearchFilters,
+    /// The project `filters` were loaded for.
+    filters_for: Option<PathBuf>,
+    /// Show the lines around each match.
+    pub context: bool,
+    /// Whether a search ran, so an empty result list means "no results".
+    searched: bool,
+    results: Vec<FileMatches>,
+    truncated: bool,
+    /// Whether the last search was cancelled before it went through every
+    /// file.
+    cancelled: bool,
+    receiver: Option<Receiver<(Vec<FileMatches>, bool)>>,
+    cancel: Option<CancelToken>,
+    preview: Option<Vec<FilePreview>>,
+}
+
+/// Byte ranges of the non-overlapping occurrences of `query` in `line`.
+/// Case-insensitive matching folds ASCII only, which keeps byte offsets of
+/// the folded text valid in the original; `normalize` matches as
+/// [`unicode_search::find`] does instead.
+pub fn find_ranges(
+    line: &str,
+    query: &str,
+    case_sensitive: bool,
+    normalize: bool,
+) -> Vec<Range<usize>> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    if normalize {
+        return unicode_search::find(line, query, case_sensitive);
+    }
+    let ranges = |haystack: &str, needle: &str| {
+        haystack
+            .match_indices(needle)
+            .map(|(i, m)| i..i + m.len())
+            .collect()
+    };
+    if case_sensitive {
+        ranges(line, query)
+    } else {
+        ranges(&line.to_ascii_lowercase(), &query.to_ascii_lowercase())
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Whether `range` of `line` is a whole word rather than part of one.
+fn is_whole_word(line: &str, range: &Range<usize>) -> bool {
+    !line[..range.start].ends_with(is_word_char) && !line[range.end..].starts_with(is_word_char)
+}
+
+/// Searches every text file below `roots` that `filters` let through, reporting progress to `job` and
+/// stopping early when it is cancelled. The flag is set when the search
+/// stopped at [`MAX_MATCHING_LINES`].
+pub fn search(
+    roots: &[PathBuf],
+    query: &str,
+    case_sensitive: bool,
+    whole_word: bool,
+    normalize: bool,
+    filters: &SearchFilters,
+    job: &Job,
+) -> (Vec<FileMatches>, bool) {
+    let _scope = profiler::scope("search");
+    let mut results = Vec::new();
+    let mut total = 0;
+    let mut paths = walk_roots(roots);
+    paths.retain(|path| filters.includes(&display_path(roots, path).replace('\\', "/"), path));
+    let count = paths.len();
+    for (i, path) in paths.into_iter().enumerate() {
+        if job.is_cancelled() {
+            break;
+        }
+        job.progress(i, count);
+        job.detail(path.display().to_string());
+        if fs::metadata(&path).is_ok_and(|m| m.len() > MAX_SEARCH_SIZE) {
+            continue;
+        }
+        let Ok(text) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let mut lines = Vec::new();
+        let all_lines: Vec<&str> = text.lines().collect();
+        for (line, content) in all_lines.iter().enumerate() {
+            let mut ranges = find_ranges(content, query, case_sensitive, normalize);
+            if whole_word {
+                ranges.retain(|range| is_whole_word(content, range));
+            }
+            if !ranges.is_empty() {
+                let neighbour = |line: Option<usize>| {
+                    line.and_then(|line| all_lines.get(line))
+                        .map(|text| text.to_string())
+                };
+                lines.push(LineMatch {
+                    line,
+                    text: content.to_string(),
+                    ranges,
+                    before: neighbour(line.checked_sub(1)),
+                    after: neighbour(Some(line + 1)),
+                });
+            }
+        }
+        if lines.is_empty() {
+            continue;
+        }
+        total += lines.len();
+        results.push(FileMatches { path, lines });
+        if total >= MAX_MATCHING_LINES {
+            return (results, true);
+        }
+    }
+    (results, false)
+}
+
+pub(crate) fn replace_ranges(text: &str, ranges: &[Range<usize>], replacement: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for range in ranges {
+        out.push_str(&text[last..range.start]);
+        out.push_str(replacement);
+        last = range.end;
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+/// Rewrites the accepted hunks of `text`, keeping every line ending as it
+/// was. Fails if a hunk's line no longer reads as it did in the preview.
+fn apply_hunks(text: &str, hunks: &[&Hunk]) -> Result<String, String> {
+    let mut lines: Vec<String> = text.split_inclusive('\n').map(str::to_string).collect();
+    for hunk in hunks {
+        let line = lines
+            .get_mut(hunk.line)
+            .ok_or_else(|| tr("the file got shorter"))?;
+        let body = line.trim_end_matches(['\r', '\n']);
+        if body != hunk.old {
+            return Err(trf(
+                "line {line} changed since the preview",
+                &[("line", &(hunk.line + 1))],
+            ));
+        }
+        let ending = line[body.len()..].to_string();
+        *line = format!("{}{ending}", hunk.new);
+    }
+    Ok(lines.concat())
+}
+
+impl ProjectSearch {
+    pub fn is_running(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    fn start(&mut self, ctx: &egui::Context, roots: Vec<PathBuf>, job: Job) {
+        if self.query.is_empty() {
+            return;
+        }
+        let (sender, receiver) = channel();
+        let query = self.query.clone();
+        let case_sensitive = self.case_sensitive;
+        let whole_word = self.whole_word;
+        let normalize = self.normalize;
+        let filters = self.filters.clone();
+        self.searched = true;
+        self.receiver = Some(receiver);
+        self.cancel = Some(job.cancel_token());
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let _ = sender.send(search(
+                &roots,
+                &query,
+                case_sensitive,
+                whole_word,
+                normalize,
+                &filters,
+                &job,
+            ));
+            drop(job);
+            ctx.request_repaint();
+        });
+    }
+
+    fn poll(&mut self) {
+        if let Some(receiver) = &self.receiver
+            && let Ok((results, truncated)) = receiver.try_recv()
+        {
+            self.results = results;
+            self.truncated = truncated;
+            self.cancelled = self.cancel.take().is_some_and(|c| c.is_cancelled());
+            self.receiver = None;
+        }
+    }
+
+    fn build_preview(&mut self) {
+        let files = self
+            .results
+            .iter()
+            .map(|file| FilePreview {
+                path: file.path.clone(),
+                hunks: file
+                    .lines
+                    .iter()
+                    .map(|m| Hunk {
+                        line: m.line,
+                        old: m.text.clone(),
+                        new: replace_ranges(&m.text, &m.ranges, &self.replacement),
+                        apply: true,
+                    })
+                    .collect(),
+            })
+            .collect();
+        self.preview = Some(files);
+    }
+}
+
+impl TextEditorApp {
+    pub(crate) fn find_in_files(&mut self) {
+        if !self.folders.is_empty() {
+            self.bottom_panel.show(BottomTab::Search);
+        }
+    }
+
+    /// Loads the search filters of the current project when it changed.
+    fn sync_search_filters(&mut self) {
+        let project = self.folder_path();
+        let search = &mut self.project_search;
+        if search.filters_for != project {
+            let (filters, error) = project
+                .as_deref()
+                .map(SearchFilters::load)
+                .unwrap_or_default();
+            search.filters = filters;
+            search.filters_for = project;
+            if let Some(message) = error {
+                self.notifications.error(message);
+            }
+        }
+    }
+
+    /// Opens Find in Files limited to the files below `folder`.
+    pub(crate) fn find_in_folder(&mut self, folder: &Path) {
+        self.sync_search_filters();
+        let relative = display_path(&self.folders, folder).replace('\\', "/");
+        self.project_search.filters.include = if relative.is_empty() {
+            String::new()
+        } else {
+            format!("{relative}/*")
+        };
+        self.find_in_files();
+    }
+
+    /// Searches the workspace folders for the identifier at the caret as a
+    /// whole word.
+    pub(crate) fn find_all_references(&mut self, ctx: &egui::Context) {
+        if self.folders.is_empty() {
+            self.notifications
+                .warning(tr("Open a folder to find references in it."));
+            return;
+        }
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        let Some(selection) = self.selection(ctx) else {
+            return;
+        };
+        let caret = char_to_byte(&tab.content, selection.start);
+        let Some(range) = identifier_at(&tab.content, caret) else {
+            self.notifications.warning(tr(
+                "Place the caret on an identifier to find its references.",
+            ));
+            return;
+        };
+        let search = &mut self.project_search;
+        search.query = tab.content[range].to_string();
+        search.case_sensitive = true;
+        search.whole_word = true;
+        search.normalize = false;
+        search.filters.include.clear();
+        search.context = true;
+        self.search_history.remember_search(&search.query);
+        let job = self.jobs.start(
+            trf("Finding references to {name}", &[("name", &search.query)]),
+            true,
+        );
+        search.start(ctx, self.folders.clone(), job);
+        self.bottom_panel.show(BottomTab::Search);
+    }
+
+    pub(crate) fn poll_project_search(&mut self) {
+        self.project_search.poll();
+    }
+
+    /// The Search tab of the bottom panel.
+    pub(crate) fn project_search_ui(&mut self, ui: &mut egui::Ui) {
+        let ctx = ui.ctx().clone();
+        if self.folders.is_empty() {
+            ui.weak(tr("Open a folder to search its files."));
+            return;
+        }
+        self.sync_search_filters();
+        let roots = self.folders.clone();
+        let mut run = false;
+        let search = &mut self.project_search;
+        ui.horizontal(|ui| {
+            ui.label(tr("Find:"));
+            let response = history_field(
+                ui,
+                "project_search_query",
+                &mut search.query,
+                &self.search_history.searches,
+            );
+            run |= response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            ui.checkbox(&mut search.case_sensitive, tr("Match case"));
+            ui.checkbox(&mut search.whole_word, tr("Whole word"));
+            unicode_search::checkbox(ui, &mut search.normalize);
+            ui.checkbox(&mut search.context, tr("Context"));
+            run |= ui.button(tr("Search")).clicked();
+            if search.is_running() {
+                ui.spinner();
+            }
+        });
+        run |= search.filters.ui(ui);
+        ui.horizontal(|ui| {
+            ui.label(tr("Replace:"));
+            history_field(
+                ui,
+                "project_search_replacement",
+                &mut search.replacement,
+                &self.search_history.replacements,
+            );
+            let files = search.results.len();
+            let ready = files > 0 && !search.is_running();
+            if ui
+                .add_enabled(
+                    ready,
+                    egui::Button::new(trf("Replace in {count} files…", &[("count", &files)])),
+                )
+                .clicked()
+            {
+                search.build_preview();
+                self.search_history
+                    .remember_replacement(&search.replacement);
+            }
+        });
+        if run {
+            self.search_history.remember_search(&search.query);
+            if let Some(project) = &search.filters_for {
+                search.filters.save(project);
+            }
+            let job = self.jobs.start(
+                trf("Searching for {query}", &[("query", &search.query)]),
+                true,
+            );
+            search.start(&ctx, roots.clone(), job);
+        }
+        ui.separator();
+
+        let mut jump: Option<(PathBuf, usize, usize)> = None;
+        if search.cancelled {
+            ui.weak(tr("The search was cancelled; results are incomplete."));
+        } else if search.truncated {
+            ui.weak(trf(
+                "Showing the first {count} matching lines.",
+                &[("count", &MAX_MATCHING_LINES)],
+            ));
+        } else if search.searched && search.results.is_empty() && !search.is_running() {
+            ui.weak(tr("No results."));
+        }
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                for file in &search.results {
+                    let path = display_path(&roots, &file.path);
+                    egui::CollapsingHeader::new(format!("{} ({})", path, file.lines.len()))
+                        .id_source(&file.path)
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            // Context lines that are matches themselves are not repeated.
+                            let mut shown_until = None;
+                            for (i, m) in file.lines.iter().enumerate() {
+                                let context_line = |ui: &mut egui::Ui, line: usize, text: &str| {
+                                    let label = format!("{:>5}  {}", line + 1, text.trim());
+                                    ui.label(egui::RichText::new(label).monospace().weak());
+                                };
+                                if search.context
+                                    && let Some(before) = &m.before
+                                    && shown_until.is_none_or(|line| line + 1 < m.line)
+                                {
+                                    context_line(ui, m.line - 1, before);
+                                }
+                                shown_until = Some(m.line);
+                                let label = format!("{:>5}  {}", m.line + 1, m.text.trim());
+                                if ui.link(egui::RichText::new(label).monospace()).clicked() {
+                                    let column = m
+                                        .ranges
+                                        .first()
+                                        .map_or(0, |r| byte_to_char(&m.text, r.start));
+                                    jump = Some((file.path.clone(), m.line, column));
+                                }
+                                let next = file.lines.get(i + 1).map(|n| n.line);
+                                if search.context
+                                    && let Some(after) = &m.after
+                                    && next != Some(m.line + 1)
+                                {
+                                    context_line(ui, m.line + 1, after);
+                                    shown_until = Some(m.line + 1);
+                                }
+                            }
+                        });
+                }
+            });
+        if let Some((path, line, column)) = jump {
+            self.open_at(&ctx, &path, line, column);
+        }
+    }
+
+    /// The preview window listing every pending change with a checkbox.
+    pub(crate) fn show_replace_preview(&mut self, ctx: &egui::Context) {
+        let Some(files) = &mut self.project_search.preview else {
+            return;
+        };
+        let roots = &self.folders;
+        let mut open = true;
+        let mut apply = false;
+        let mut cancel = false;
+        egui::Window::new(tr("Replace in Files"))
+            .default_size((700.0, 450.0))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let selected: usize = files
+                    .iter()
+                    .map(|f| f.hunks.iter().filter(|h| h.apply).count())
+                    .sum();
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(
+                            selected > 0,
+                            egui::Button::new(trf(
+                                "Apply {count} changes",
+                                &[("count", &selected)],
+                            )),
+                        )
+                        .clicked()
+                    {
+                        apply = true;
+                    }
+                    if ui.button(tr("Cancel")).clicked() {
+                        cancel = true;
+                    }
+                });
+                ui.separator();
+                let (removed, added) = if ui.visuals().dark_mode {
+                    (
+                        egui::Color32::from_rgb(90, 30, 30),
+                        egui::Color32::from_rgb(30, 80, 30),
+                    )
+                } else {
+                    (
+                        egui::Color32::from_rgb(255, 215, 215),
+                        egui::Color32::from_rgb(210, 250, 210),
+                    )
+                };
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        for file in files.iter_mut() {
+                            let path = display_path(roots, &file.path);
+                            let mut all = file.hunks.iter().all(|h| h.apply);
+                            if ui
+                                .checkbox(&mut all, egui::RichText::new(path).strong())
+                                .changed()
+                            {
+                                file.hunks.iter_mut().for_each(|h| h.apply = all);
+                            }
+                            for hunk in &mut file.hunks {
+                                ui.horizontal(|ui| {
+                                    ui.add_space(16.0);
+                                    ui.checkbox(&mut hunk.apply, format!("{:>5}", hunk.line + 1));
+                                    ui.vertical(|ui| {
+                                        ui.label(
+                                            egui::RichText::new(format!("- {}", hunk.old))
+                                                .monospace()
+                                                .background_color(removed),
+                                        );
+                                        ui.label(
+                                            egui::RichText::new(format!("+ {}", hunk.new))
+                                                .monospace()
+                                                .background_color(added),
+                                        );
+                                    });
+                                });
+                            }
+                        }
+                    });
+            });
+        if apply {
+            self.apply_replace_preview(ctx);
+        } else if cancel || !open {
+            self.project_search.preview = None;
+        }
+    }
+
+    /// Writes the accepted hunks, reloads open tabs showing the changed
+    /// files and searches again. Files whose open tab has unsaved edits are
+    /// left alone.
+    fn apply_replace_preview(&mut self, ctx: &egui::Context) {
+        let Some(files) = self.project_search.preview.take() else {
+            return;
+        };
+        let (mut changed_files, mut changed_lines) = (0, 0);
+        for file in &files {
+            let hunks: Vec<&Hunk> = file.hunks.iter().filter(|h| h.apply).collect();
+            if hunks.is_empty() {
+                continue;
+            }
+            let Ok(text) = fs::read_to_string(&file.path) else {
+                self.notifications.warning(trf(
+                    "{path} could not be read",
+                    &[("path", &file.path.display())],
+                ));
+                continue;
+            };
+            let on_disk = text.strip_prefix(UTF8_BOM).unwrap_or(&text);
+            let open_tab = self
+                .tabs
+                .iter()
+                .find(|(_, tab)| tab.path.as_deref() == Some(&file.path))
+                .map(|(key, _)| key.clone());
+            if let Some(key) = &open_tab
+                && self.tabs[key].content != on_disk
+            {
+                self.notifications.warning(trf(
+                    "{path} was not changed: it has unsaved changes",
+                    &[("path", &file.path.display())],
+                ));
+                continue;
+            }
+            let new_text = match apply_hunks(&text, &hunks) {
+                Ok(new_text) => new_text,
+                Err(message) => {
+                    self.notifications.warning(trf(
+                        "{path} was not changed: {reason}",
+                        &[("path", &file.path.display()), ("reason", &message)],
+                    ));
+                    continue;
+                }
+            };
+            let sensitive = open_tab
+                .as_ref()
+                .is_some_and(|key| self.tabs[key].sensitive);
+            let audit = self.audit_snapshot(&file.path, sensitive);
+            if let Err(err) = atomic_write::write(&file.path, new_text.as_bytes()) {
+                self.notifications.error(trf(
+                    "Could not write {path}: {error}",
+                    &[("path", &file.path.display()), ("error", &err)],
+                ));
+                continue;
+            }
+            self.record_save(audit, new_text.as_bytes());
+            changed_files += 1;
+            changed_lines += hunks.len();
+            if let Some(key) = open_tab
+                && let Some(tab) = self.tabs.get_mut(&key)
+            {
+                tab.content = new_text
+                    .strip_prefix(UTF8_BOM)
+                    .unwrap_or(&new_text)
+                    .to_string();
+                tab.mark_saved();
+                tab.disk_stamp = DiskStamp::read(&file.path);
+                if let Some(index) = &mut self.symbol_index {
+                    index.reindex(&file.path, &tab.content);
+                }
+            }
+        }
+        self.notifications.info(trf(
+            "Replaced {lines} lines in {files} files",
+            &[("lines", &changed_lines), ("files", &changed_files)],
+        ));
+        if !self.folders.is_empty() {
+            let job = self.jobs.start(
+                trf(
+                    "Searching for {query}",
+                    &[("query", &self.project_search.query)],
+                ),
+                true,
+            );
+            self.project_search.start(ctx, self.folders.clone(), job);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hunk(line: usize, old: &str, new: &str) -> Hunk {
+        Hunk {
+            line,
+            old: old.to_string(),
+            new: new.to_string(),
+            apply: true,
+        }
+    }
+
+    #[test]
+    fn hunks_keep_line_endings() {
+        let text = "let a = 1;\r\nlet b = 2;\nlet c = 3;";
+        let first = hunk(0, "let a = 1;", "let x = 1;");
+        let last = hunk(2, "let c = 3;", "let z = 3;");
+        assert_eq!(
+            apply_hunks(text, &[&first, &last]).unwrap(),
+            "let x = 1;\r\nlet b = 2;\nlet z = 3;"
+        );
+    }
+
+    #[test]
+    fn stale_hunks_are_refused() {
+        let changed = hunk(1, "let b = 2;", "let y = 2;");
+        assert!(apply_hunks("let a = 1;\nlet b = 3;\n", &[&changed]).is_err());
+        let missing = hunk(5, "let b = 2;", "let y = 2;");
+        assert!(apply_hunks("let b = 2;\n", &[&missing]).is_err());
+    }
+
+    #[test]
+    fn ranges_are_replaced() {
+        let ranges = find_ranges("Foo foo FOO", "foo", false, false);
+        assert_eq!(ranges, [0..3, 4..7, 8..11]);
+        assert_eq!(replace_ranges("Foo foo FOO", &ranges, "bar"), "bar bar bar");
+        assert_eq!(find_ranges("Foo foo FOO", "foo", true, false), vec![4..7]);
+    }
+}
@@ -0,0 +1,91 @@
+//! Prose mode: a per-tab reading and writing layout for Markdown and plain
+//! text, with a proportional font, roomier lines, soft wrap at a readable
+//! width and Ctrl+Up/Down moving by paragraph.
+
+use crate::TextEditorApp;
+use eframe::egui;
+use std::path::Path;
+
+/// Extensions of files that are prose rather than code.
+const PROSE_EXTENSIONS: &[&str] = &["md", "markdown", "txt", "text", "rst", "adoc"];
+
+/// Font size relative to the body text style.
+const FONT_SCALE: f32 = 1.15;
+/// Line height relative to the font size.
+const LINE_SPACING: f32 = 1.6;
+/// Lines wrap at this width even when the window is wider.
+pub const MAX_WIDTH: f32 = 720.0;
+
+pub fn is_prose_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| PROSE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// The font prose tabs are laid out with.
+pub fn font(style: &egui::Style) -> egui::FontId {
+    let body = egui::TextStyle::Body.resolve(style);
+    egui::FontId::proportional(body.size * FONT_SCALE)
+}
+
+/// Spreads the lines of a layout job built with [`font`].
+pub fn space_lines(job: &mut egui::text::LayoutJob, font: &egui::FontId) {
+    for section in &mut job.sections {
+        section.format.line_height = Some(font.size * LINE_SPACING);
+    }
+}
+
+/// Start of the paragraph after the one containing `pos`, or the end of the
+/// text. Paragraphs are separated by blank lines.
+pub fn next_paragraph(chars: &[char], pos: usize) -> usize {
+    let starts = paragraph_starts(chars);
+    starts
+        .into_iter()
+        .find(|&start| start > pos)
+        .unwrap_or(chars.len())
+}
+
+/// Start of the paragraph containing `pos`, or of the one before it if the
+/// caret already is at its start.
+pub fn prev_paragraph(chars: &[char], pos: usize) -> usize {
+    let starts = paragraph_starts(chars);
+    starts
+        .into_iter()
+        .rev()
+        .find(|&start| start < pos)
+        .unwrap_or(0)
+}
+
+/// Positions of the first character of every paragraph.
+fn paragraph_starts(chars: &[char]) -> Vec<usize> {
+    let mut starts = Vec::new();
+    let mut previous_blank = true;
+    let mut line_start = 0;
+    while line_start <= chars.len() {
+        let line_end = chars[line_start..]
+            .iter()
+            .position(|&c| c == '\n')
+            .map_or(chars.len(), |i| line_start + i);
+        let blank = chars[line_start..line_end]
+            .iter()
+            .all(|c| c.is_whitespace());
+        if previous_blank && !blank {
+            starts.push(line_start);
+        }
+        previous_blank = blank;
+        line_start = line_end + 1;
+    }
+    starts
+}
+
+impl TextEditorApp {
+    pub(crate) fn toggle_prose_mode(&mut self) {
+        if let Some(tab) = self
+            .active_tab
+            .as_ref()
+            .and_then(|key| self.tabs.get_mut(key))
+        {
+            tab.prose = !tab.prose;
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Asks before closing the window while tabs have unsaved changes, offering
+//! to save or discard them all.
+
+use crate::TextEditorApp;
+use crate::i18n::tr;
+use eframe::egui;
+
+#[derive(Default)]
+pub struct QuitConfirm {
+    open: bool,
+    /// Set once the user chose how to handle the unsaved tabs, so the next
+    /// close request goes through.
+    allowed: bool,
+}
+
+impl TextEditorApp {
+    fn modified_tabs(&self) -> Vec<String> {
+        self.open_order
+            .iter()
+            .filter(|key| self.tabs.get(*key).is_some_and(|tab| tab.is_modified()))
+            .cloned()
+            .collect()
+    }
+
+    /// Cancels a close request while there are unsaved tabs and asks what to
+    /// do with them instead.
+    pub(crate) fn intercept_close(&mut self, ctx: &egui::Context) {
+        if !ctx.input(|i| i.viewport().close_requested()) || self.quit.allowed {
+            return;
+        }
+        if !self.modified_tabs().is_empty() {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.quit.open = true;
+        }
+    }
+
+    /// Saves every modified tab, stopping at the first one that is not
+    /// saved (e.g. its Save dialog was cancelled). Returns whether all were.
+    fn save_all_modified(&mut self) -> bool {
+        let active = self.active_tab.clone();
+        let mut saved = true;
+        for key in self.modified_tabs() {
+            self.active_tab = Some(key);
+            if !self.save_active() {
+                saved = false;
+                break;
+            }
+        }
+        if saved {
+            self.active_tab = active;
+        }
+        saved
+    }
+
+    fn quit_now(&mut self, ctx: &egui::Context) {
+        self.quit.allowed = true;
+        self.quit.open = false;
+        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+    }
+
+    pub(crate) fn show_quit_confirm(&mut self, ctx: &egui::Context) {
+        if !self.quit.open {
+            return;
+        }
+        let modified = self.modified_tabs();
+        if modified.is_empty() {
+            self.quit.open = false;
+            return;
+        }
+        let mut save_all = false;
+        let mut discard_all = false;
+        let mut cancel = false;
+        egui::Window::new(tr("Unsaved Changes"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(tr("These files have unsaved changes:"));
+                egui::ScrollArea::vertical()
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        for key in &modified {
+                            ui.label(format!("• {key}"));
+                        }
+                    });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    save_all = ui.button(tr("Save All")).clicked();
+                    discard_all = ui.button(tr("Discard All")).clicked();
+                    cancel = ui.button(tr("Cancel")).clicked();
+                });
+            });
+        if save_all && self.save_all_modified() {
+            self.quit_now(ctx);
+        } else if discard_all {
+            // Untitled buffers would otherwise come back from the journal.
+            for key in &modified {
+                self.close_tab(key);
+            }
+            self.quit_now(ctx);
+        } else if cancel {
+            self.quit.open = false;
+        }
+    }
+}
@@ -0,0 +1,333 @@
+//! Pretty printing and minifying of JSON, XML and HTML, for documents that
+//! arrive as one enormous line. The format is taken from the tab's syntax,
+//! or guessed from the first character of the text.
+//!
+//! Both directions work on tokens rather than a parsed tree, so key order,
+//! number spelling and attribute quoting are kept exactly as written.
+
+use crate::TextEditorApp;
+use crate::i18n::{tr, trf};
+use crate::tags::VOID_ELEMENTS;
+use eframe::egui;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Json,
+    Xml,
+    Html,
+}
+
+/// HTML elements whose contents are kept exactly as written.
+const RAW_ELEMENTS: &[&str] = &["pre", "script", "style", "textarea"];
+
+fn detect(text: &str, syntax: Option<&str>) -> Option<Format> {
+    match syntax {
+        Some("JSON") => return Some(Format::Json),
+        Some("XML") => return Some(Format::Xml),
+        Some(name) if name.starts_with("HTML") => return Some(Format::Html),
+        _ => {}
+    }
+    let text = text.trim_start();
+    if text.starts_with(['{', '[']) {
+        Some(Format::Json)
+    } else if text.starts_with('<') {
+        let head = text[..text.len().min(1024)].to_ascii_lowercase();
+        if head.contains("<!doctype html") || head.contains("<html") {
+            Some(Format::Html)
+        } else {
+            Some(Format::Xml)
+        }
+    } else {
+        None
+    }
+}
+
+/// Calls `visit` with each character of `json` and whether it is inside a
+/// string.
+fn json_chars(json: &str, mut visit: impl FnMut(char, bool)) {
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in json.chars() {
+        let quoted = in_string || c == '"';
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+        }
+        visit(c, quoted);
+    }
+}
+
+fn validate_json(json: &str) -> Result<(), String> {
+    serde_json::from_str::<serde::de::IgnoredAny>(json)
+        .map(|_| ())
+        .map_err(|err| trf("Not valid JSON: {error}", &[("error", &err)]))
+}
+
+pub fn pretty_json(json: &str, indent: &str) -> Result<String, String> {
+    validate_json(json)?;
+    let mut out = String::with_capacity(json.len() * 2);
+    let mut depth = 0;
+    let newline = |out: &mut String, depth: usize| {
+        out.push('\n');
+        for _ in 0..depth {
+            out.push_str(indent);
+        }
+    };
+    json_chars(json, |c, quoted| {
+        if quoted {
+            out.push(c);
+            return;
+        }
+        match c {
+            '{' | '[' => {
+                out.push(c);
+                depth += 1;
+                newline(&mut out, depth);
+            }
+            '}' | ']' => {
+                depth -= 1;
+                // Empty containers stay on one line.
+                if out.trim_end().ends_with(['{', '[']) {
+                    out.truncate(out.trim_end().len());
+                } else {
+                    newline(&mut out, depth);
+                }
+                out.push(c);
+            }
+            ',' => {
+                out.push(c);
+                newline(&mut out, depth);
+            }
+            ':' => out.push_str(": "),
+            c if c.is_whitespace() => {}
+            c => out.push(c),
+        }
+    });
+    Ok(out)
+}
+
+pub fn minify_json(json: &str) -> Result<String, String> {
+    validate_json(json)?;
+    let mut out = String::with_capacity(json.len());
+    json_chars(json, |c, quoted| {
+        if quoted || !c.is_whitespace() {
+            out.push(c);
+        }
+    });
+    Ok(out)
+}
+
+enum Token<'a> {
+    Open(&'a str, String),
+    Close(&'a str, String),
+    /// Self-closing tags, comments, doctypes and the like.
+    Standalone(&'a str),
+    Text(&'a str),
+    /// The contents of a raw element, kept as they are.
+    Raw(&'a str),
+}
+
+/// The name of the tag `tag` (including its angle brackets), lowercased.
+fn tag_name(tag: &str) -> String {
+    tag.trim_start_matches(['<', '/'])
+        .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .next()
+        .unwrap_or("")
+        .to_ascii_lowercase()
+}
+
+fn tokenize(markup: &str, format: Format) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = markup;
+    while !rest.is_empty() {
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            tokens.push(Token::Text(&rest[..end]));
+            rest = &rest[end..];
+            continue;
+        }
+        let terminator = if rest.starts_with("<!--") {
+            "-->"
+        } else if rest.starts_with("<![CDATA[") {
+            "]]>"
+        } else {
+            ">"
+        };
+        let end = if terminator == ">" {
+            // Skip over `>` inside quoted attribute values.
+            let mut quote = None;
+            rest.char_indices()
+                .find(|(_, c)| match quote {
+                    Some(q) if *c == q => {
+                        quote = None;
+                        false
+                    }
+                    Some(_) => false,
+                    None if *c == '"' || *c == '\'' => {
+                        quote = Some(*c);
+                        false
+                    }
+                    None => *c == '>',
+                })
+                .map(|(i, _)| i + 1)
+        } else {
+            rest.find(terminator).map(|i| i + terminator.len())
+        }
+        .unwrap_or(rest.len());
+        let tag = &rest[..end];
+        rest = &rest[end..];
+        let name = tag_name(tag);
+        if tag.starts_with("</") {
+            tokens.push(Token::Close(tag, name));
+        } else if tag.starts_with("<!")
+            || tag.starts_with("<?")
+            || tag.ends_with("/>")
+            || (format == Format::Html && VOID_ELEMENTS.contains(&name.as_str()))
+        {
+            tokens.push(Token::Standalone(tag));
+        } else {
+            let raw = format == Format::Html && RAW_ELEMENTS.contains(&name.as_str());
+            tokens.push(Token::Open(tag, name.clone()));
+            if raw {
+                let close = format!("</{name}");
+                let end = rest.to_ascii_lowercase().find(&close).unwrap_or(rest.len());
+                if end > 0 {
+                    tokens.push(Token::Raw(&rest[..end]));
+                }
+                rest = &rest[end..];
+            }
+        }
+    }
+    tokens
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn pretty_markup(markup: &str, format: Format, indent: &str) -> String {
+    let tokens = tokenize(markup, format);
+    let mut lines: Vec<String> = Vec::new();
+    let mut depth: usize = 0;
+    let mut i = 0;
+    while i < tokens.len() {
+        let line = |depth: usize, text: &str| format!("{}{text}", indent.repeat(depth));
+        match &tokens[i] {
+            Token::Open(tag, name) => {
+                // An element holding only text, or nothing, stays on one line.
+                let (inner, skip) = match tokens.get(i + 1) {
+                    Some(Token::Text(text)) => (text.trim().to_string(), 2),
+                    Some(Token::Raw(text)) => (text.to_string(), 2),
+                    _ => (String::new(), 1),
+                };
+                if let Some(Token::Close(close, close_name)) = tokens.get(i + skip)
+                    && close_name == name
+                {
+                    let inner = if matches!(tokens[i + 1], Token::Raw(_)) {
+                        inner
+                    } else {
+                        collapse_whitespace(&inner)
+                    };
+                    lines.push(line(depth, &format!("{tag}{inner}{close}")));
+                    i += skip + 1;
+                    continue;
+                }
+                lines.push(line(depth, tag));
+                depth += 1;
+            }
+            Token::Close(tag, _) => {
+                depth = depth.saturating_sub(1);
+                lines.push(line(depth, tag));
+            }
+            Token::Standalone(tag) => lines.push(line(depth, tag)),
+            Token::Text(text) => {
+                let text = collapse_whitespace(text);
+                if !text.is_empty() {
+                    lines.push(line(depth, &text));
+                }
+            }
+            Token::Raw(text) => lines.push(text.to_string()),
+        }
+        i += 1;
+    }
+    lines.join("\n")
+}
+
+fn minify_markup(markup: &str, format: Format) -> String {
+    let mut out = String::with_capacity(markup.len());
+    for token in tokenize(markup, format) {
+        match token {
+            Token::Open(tag, _) | Token::Close(tag, _) | Token::Standalone(tag) => {
+                out.push_str(tag);
+            }
+            Token::Raw(text) => out.push_str(text),
+            Token::Text(text) => {
+                // Whitespace between tags goes; inside text it shrinks to
+                // one space, which is all HTML shows of it anyway.
+                if !text.trim().is_empty() {
+                    let start = if text.starts_with(char::is_whitespace) {
+                        " "
+                    } else {
+                        ""
+                    };
+                    let end = if text.ends_with(char::is_whitespace) {
+                        " "
+                    } else {
+                        ""
+                    };
+                    out.push_str(start);
+                    out.push_str(&collapse_whitespace(text));
+                    out.push_str(end);
+                }
+            }
+        }
+    }
+    out
+}
+
+impl TextEditorApp {
+    /// Pretty prints (or, with `minify`, compacts) the selection, or the
+    /// whole buffer when nothing is selected.
+    pub(crate) fn reformat_document(&mut self, ctx: &egui::Context, minify: bool) {
+        let Some(range) = self.selection_or(ctx, |text, _| 0..text.len()) else {
+            return;
+        };
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        let text = &tab.content[range.clone()];
+        let Some(format) = detect(text, tab.syntax.as_deref()) else {
+            self.notifications
+                .warning(tr("This does not look like JSON, XML or HTML."));
+            return;
+        };
+        let options = tab.editor_options(&self.settings);
+        let indent = if options.insert_spaces {
+            " ".repeat(options.tab_width)
+        } else {
+            "\t".to_string()
+        };
+        let result = match (format, minify) {
+            (Format::Json, false) => pretty_json(text, &indent),
+            (Format::Json, true) => minify_json(text),
+            (_, false) => Ok(pretty_markup(text, format, &indent)),
+            (_, true) => Ok(minify_markup(text, format)),
+        };
+        match result {
+            Ok(mut formatted) => {
+                if !minify && text.ends_with('\n') {
+                    formatted.push('\n');
+                }
+                self.replace_range(ctx, range, &formatted);
+            }
+            Err(err) => self.notifications.error(err),
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! The Rename File dialog, for the active tab or an entry of the file
+//! browser. Names are checked for characters the platform
+//! does not allow, replacing an existing file needs confirmation, and a
+//! failed rename is shown in the dialog instead of being ignored.
+
+use crate::TextEditorApp;
+use crate::i18n::{tr, trf};
+use eframe::egui;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Device names Windows reserves regardless of extension.
+#[cfg(windows)]
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+#[derive(Default)]
+pub struct RenameDialog {
+    pub open: bool,
+    /// The file or folder picked in the file browser; the active tab is
+    /// renamed when this is unset.
+    path: Option<PathBuf>,
+    input: String,
+    error: Option<String>,
+    /// Set once the user was told the target exists; the next OK replaces it.
+    confirm_overwrite: bool,
+}
+
+/// Checks that `name` can be used as a file name on this platform.
+pub fn validate_file_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err(tr("Enter a file name."));
+    }
+    if name == "." || name == ".." {
+        return Err(trf(
+            "\"{name}\" is not a valid file name.",
+            &[("name", &name)],
+        ));
+    }
+    let forbidden: &[char] = if cfg!(windows) {
+        &['/', '\\', '<', '>', ':', '"', '|', '?', '*']
+    } else {
+        &['/']
+    };
+    if let Some(c) = name
+        .chars()
+        .find(|c| forbidden.contains(c) || c.is_control())
+    {
+        return Err(if c.is_control() {
+            tr("File names cannot contain control characters.")
+        } else {
+            trf("File names cannot contain \"{char}\".", &[("char", &c)])
+        });
+    }
+    #[cfg(windows)]
+    {
+        if name.ends_with(['.', ' ']) {
+            return Err(tr("File names cannot end with a dot or a space."));
+        }
+        let stem = name.split('.').next().unwrap_or(name).trim_end();
+        if RESERVED_NAMES
+            .iter()
+            .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+        {
+            return Err(trf("\"{name}\" is a reserved name.", &[("name", &stem)]));
+        }
+    }
+    Ok(())
+}
+
+/// Whether `new_path` names a different file than `old_path`, rather than
+/// the same file with its case changed on a case-insensitive file system.
+fn is_other_file(old_path: &Path, new_path: &Path) -> bool {
+    match (fs::canonicalize(old_path), fs::canonicalize(new_path)) {
+        (Ok(old), Ok(new)) => old != new,
+        (_, Err(_)) => false,
+        (Err(_), Ok(_)) => true,
+    }
+}
+
+impl TextEditorApp {
+    pub(crate) fn start_rename(&mut self) {
+        if let Some(tab_name) = &self.active_tab
+            && let Some(tab) = self.tabs.get(tab_name)
+        {
+            self.rename = RenameDialog {
+                open: true,
+                input: tab.title.clone(),
+                ..Default::default()
+            };
+        }
+    }
+
+    pub(crate) fn start_rename_path(&mut self, path: &Path) {
+        self.rename = RenameDialog {
+            open: true,
+            path: Some(path.to_path_buf()),
+            input: path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into(),
+            ..Default::default()
+        };
+    }
+
+    /// Renames `old_path` to `new_name` in the same folder. Errors and the
+    /// overwrite question are left in the dialog.
+    fn rename_on_disk(&mut self, old_path: &Path, new_name: &str) -> Option<PathBuf> {
+        let new_path = old_path.with_file_name(new_name);
+        if new_path != *old_path {
+            if is_other_file(old_path, &new_path) && !self.rename.confirm_overwrite {
+                self.rename.error = Some(trf(
+                    "{name} already exists. Press Replace to overwrite it.",
+                    &[("name", &new_name)],
+                ));
+                self.rename.confirm_overwrite = true;
+                return None;
+            }
+            if let Err(err) = fs::rename(old_path, &new_path) {
+                self.rename.error = Some(trf("Could not rename: {error}", &[("error", &err)]));
+                self.rename.confirm_overwrite = false;
+                return None;
+            }
+        }
+        self.file_tree.renamed(old_path, &new_path);
+        Some(new_path)
+    }
+
+    /// Renames the active tab, and its file if it has one, or the entry
+    /// picked in the file browser along with the tabs showing it.
+    fn apply_rename(&mut self) {
+        let new_title = self.rename.input.trim().to_string();
+        if let Err(message) = validate_file_name(&new_title) {
+            self.rename.error = Some(message);
+            return;
+        }
+        if let Some(old_path) = self.rename.path.clone() {
+            let Some(new_path) = self.rename_on_disk(&old_path, &new_title) else {
+                return;
+            };
+            // Tabs on the entry, or on files inside a renamed folder.
+            let moved: Vec<(String, PathBuf)> = self
+                .tabs
+                .iter()
+                .filter_map(|(key, tab)| {
+                    let rest = tab.path.as_ref()?.strip_prefix(&old_path).ok()?;
+                    Some((key.clone(), rest.to_path_buf()))
+                })
+                .collect();
+            for (key, rest) in moved {
+                let is_entry = rest.as_os_str().is_empty();
+                if let Some(tab) = self.tabs.get_mut(&key) {
+                    tab.path = Some(if is_entry {
+                        new_path.clone()
+                    } else {
+                        new_path.join(rest)
+                    });
+                }
+                if is_entry {
+                    self.retitle_tab(&key, &new_title);
+                }
+            }
+            self.rename.open = false;
+            return;
+        }
+        let Some(key) = self.active_tab.clone() else {
+            return;
+        };
+        if let Some(old_path) = self.tabs[&key].path.clone() {
+            let Some(new_path) = self.rename_on_disk(&old_path, &new_title) else {
+                return;
+            };
+            if let Some(tab) = self.tabs.get_mut(&key) {
+                tab.path = Some(new_path);
+            }
+        }
+
+        self.retitle_tab(&key, &new_title);
+        self.rename.open = false;
+    }
+
+    pub(crate) fn show_rename_window(&mut self, ctx: &egui::Context) {
+        if !self.rename.open {
+            return;
+        }
+        let mut open = true;
+        let mut submit = false;
+        let mut cancel = false;
+        let dialog = &mut self.rename;
+        let title = if dialog.path.as_deref().is_some_and(Path::is_dir) {
+            tr("Rename Folder")
+        } else {
+            tr("Rename File")
+        };
+        egui::Window::new(title)
+            .collapsible(false)
+            .resizable(false)
+            .default_size((300.0, 120.0))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(tr("New name:"));
+                let response = ui.text_edit_singleline(&mut dialog.input);
+                if response.changed() {
+                    dialog.error = None;
+                    dialog.confirm_overwrite = false;
+                }
+                submit = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if let Some(error) = &dialog.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+                ui.horizontal(|ui| {
+                    let label = if dialog.confirm_overwrite {
+                        tr("Replace")
+                    } else {
+                        tr("OK")
+                    };
+                    submit |= ui.button(label).clicked();
+                    cancel = ui.button(tr("Cancel")).clicked();
+                });
+            });
+        if submit {
+            self.apply_rename();
+        }
+        if cancel || !open {
+            self.rename.open = false;
+        }
+    }
+}
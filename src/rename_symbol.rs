@@ -0,0 +1,191 @@
+//! Rename Occurrences: F2 on an identifier opens a small box under it, and
+//! the new name replaces every whole-word occurrence in the buffer at once.
+//! The occurrences are outlined in the editor while the box is open.
+//!
+//! egui's editor has a single caret, so the occurrences are rewritten in
+//! one edit when the name is confirmed rather than typed into in place.
+
+use crate::TextEditorApp;
+use crate::editing::{byte_to_char, char_to_byte, editor_id};
+use crate::i18n::{tr, trf};
+use eframe::egui;
+use egui::text::CCursor;
+use std::ops::Range;
+use std::sync::Arc;
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Byte ranges of the whole-word occurrences of `word` in `text`.
+pub fn occurrences(text: &str, word: &str) -> Vec<Range<usize>> {
+    if word.is_empty() {
+        return Vec::new();
+    }
+    text.match_indices(word)
+        .map(|(start, _)| start..start + word.len())
+        .filter(|range| {
+            !text[..range.start].ends_with(is_ident_char)
+                && !text[range.end..].starts_with(is_ident_char)
+        })
+        .collect()
+}
+
+/// The identifier at byte offset `pos` of `text`.
+pub fn identifier_at(text: &str, pos: usize) -> Option<Range<usize>> {
+    let start = text[..pos]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_ident_char(*c))
+        .last()
+        .map_or(pos, |(i, _)| i);
+    let end = text[pos..]
+        .find(|c: char| !is_ident_char(c))
+        .map_or(text.len(), |i| pos + i);
+    (start < end).then_some(start..end)
+}
+
+#[derive(Default)]
+pub struct RenameSymbol {
+    /// The tab being renamed in; the box is open while this is set.
+    tab: Option<String>,
+    word: String,
+    input: String,
+    /// Byte offset of the occurrence the box was opened on.
+    origin: usize,
+    /// Where the box goes: below the origin, in screen coordinates.
+    anchor: Option<egui::Pos2>,
+}
+
+impl RenameSymbol {
+    /// Outlines the occurrences in the editor of `tab_key` and places the
+    /// box under the one it was opened on.
+    pub fn paint(
+        &mut self,
+        ui: &egui::Ui,
+        tab_key: &str,
+        text: &str,
+        galley: &Arc<egui::Galley>,
+        galley_pos: egui::Pos2,
+    ) {
+        if self.tab.as_deref() != Some(tab_key) {
+            return;
+        }
+        let stroke = ui.visuals().selection.stroke;
+        for range in occurrences(text, &self.word) {
+            let rect_at = |byte: usize| {
+                galley
+                    .pos_from_ccursor(CCursor::new(byte_to_char(text, byte)))
+                    .translate(galley_pos.to_vec2())
+            };
+            let rect = rect_at(range.start).union(rect_at(range.end));
+            ui.painter().rect_stroke(rect.expand(1.0), 2.0, stroke);
+            if range.start == self.origin {
+                self.anchor = Some(rect.left_bottom() + egui::vec2(0.0, 4.0));
+            }
+        }
+    }
+}
+
+impl TextEditorApp {
+    /// Opens the rename box for the selected identifier or the one at the
+    /// caret.
+    pub(crate) fn start_rename_occurrences(&mut self, ctx: &egui::Context) {
+        let Some(key) = self.active_tab.clone() else {
+            return;
+        };
+        let Some(selection) = self.selection(ctx) else {
+            return;
+        };
+        let tab = &self.tabs[&key];
+        if tab.read_only {
+            return;
+        }
+        let content = &tab.content;
+        let start = char_to_byte(content, selection.start);
+        let end = char_to_byte(content, selection.end);
+        let Some(range) = identifier_at(content, start).filter(|range| range.end >= end) else {
+            self.notifications
+                .warning(tr("Place the caret on an identifier to rename it."));
+            return;
+        };
+        let word = content[range.clone()].to_string();
+        self.rename_symbol = RenameSymbol {
+            tab: Some(key),
+            input: word.clone(),
+            word,
+            origin: range.start,
+            anchor: None,
+        };
+    }
+
+    /// The rename box: Enter renames every occurrence, Escape cancels.
+    pub(crate) fn show_rename_occurrences(&mut self, ctx: &egui::Context) {
+        let Some(key) = self.rename_symbol.tab.clone() else {
+            return;
+        };
+        if self.active_tab.as_ref() != Some(&key) {
+            self.rename_symbol = RenameSymbol::default();
+            return;
+        }
+        let (enter, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+        if escape {
+            self.rename_symbol = RenameSymbol::default();
+            return;
+        }
+        let rename = &mut self.rename_symbol;
+        let count = occurrences(&self.tabs[&key].content, &rename.word).len();
+        let valid = !rename.input.is_empty() && rename.input.chars().all(is_ident_char);
+        let mut area =
+            egui::Area::new(egui::Id::new("rename_occurrences")).order(egui::Order::Foreground);
+        area = match rename.anchor {
+            Some(anchor) => area.fixed_pos(anchor),
+            None => area.anchor(egui::Align2::CENTER_TOP, (0.0, 40.0)),
+        };
+        area.show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    let input =
+                        ui.add(egui::TextEdit::singleline(&mut rename.input).desired_width(180.0));
+                    input.request_focus();
+                    ui.weak(trf("{count} occurrences", &[("count", &count)]));
+                });
+                if !valid {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        tr("The new name must be an identifier."),
+                    );
+                }
+            });
+        });
+        if !enter || !valid {
+            return;
+        }
+        let rename = std::mem::take(&mut self.rename_symbol);
+        let content = &self.tabs[&key].content;
+        let mut renamed = String::with_capacity(content.len());
+        let mut last = 0;
+        let mut caret = 0;
+        for range in occurrences(content, &rename.word) {
+            renamed.push_str(&content[last..range.start]);
+            if range.start == rename.origin {
+                caret = renamed.len() + rename.input.len();
+            }
+            renamed.push_str(&rename.input);
+            last = range.end;
+        }
+        renamed.push_str(&content[last..]);
+        let caret = byte_to_char(&renamed, caret);
+        let end = content.len();
+        self.replace_range(ctx, 0..end, &renamed);
+        self.set_selection(ctx, caret..caret);
+        if let Some(key) = &self.active_tab {
+            ctx.memory_mut(|m| m.request_focus(editor_id(key)));
+        }
+    }
+}
@@ -0,0 +1,297 @@
+//! The Console tab: an interactive interpreter that code from the editor can
+//! be sent to with Ctrl+Enter. Interpreters are pluggable through the
+//! [`Interpreter`] trait; the built-in ones drive a REPL process over stdin.
+
+use crate::TextEditorApp;
+use crate::bottom_panel::BottomTab;
+use crate::i18n::{tr, trf};
+use crate::run::{OutputLine, forward};
+use eframe::egui;
+use std::io::{self, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{Receiver, channel};
+
+/// Most transcript entries kept; older ones are dropped.
+const MAX_TRANSCRIPT: usize = 10_000;
+
+pub trait Interpreter {
+    /// Submits `code` for evaluation. Results arrive through [`Interpreter::poll`].
+    fn send(&mut self, code: &str) -> io::Result<()>;
+    /// Output produced since the last call.
+    fn poll(&mut self) -> Vec<OutputLine>;
+    fn is_alive(&mut self) -> bool;
+    fn stop(&mut self);
+}
+
+/// A REPL program reading code from stdin.
+pub struct Backend {
+    pub name: &'static str,
+    program: &'static str,
+    args: &'static [&'static str],
+    /// Syntax names whose code goes to this backend by default.
+    syntaxes: &'static [&'static str],
+    /// Appended to each submission, e.g. a blank line to close Python blocks.
+    terminator: &'static str,
+}
+
+pub const BACKENDS: &[Backend] = &[
+    Backend {
+        name: "Rust (evcxr)",
+        program: "evcxr",
+        args: &[],
+        syntaxes: &["Rust"],
+        terminator: "\n",
+    },
+    Backend {
+        name: "Python",
+        program: "python3",
+        // Interactive, unbuffered, with the prompts blanked out.
+        args: &["-i", "-u", "-q", "-c", "import sys; sys.ps1 = sys.ps2 = ''"],
+        syntaxes: &["Python"],
+        terminator: "\n\n",
+    },
+    Backend {
+        name: "Shell",
+        program: "sh",
+        args: &[],
+        syntaxes: &["Bourne Again Shell (bash)", "Shell-Unix-Generic"],
+        terminator: "\n",
+    },
+];
+
+fn backend_for_syntax(syntax: &str) -> Option<usize> {
+    BACKENDS.iter().position(|b| b.syntaxes.contains(&syntax))
+}
+
+struct ProcessInterpreter {
+    child: Child,
+    stdin: ChildStdin,
+    output: Receiver<OutputLine>,
+    terminator: &'static str,
+}
+
+impl ProcessInterpreter {
+    fn start(backend: &Backend, ctx: &egui::Context) -> io::Result<Self> {
+        let mut child = Command::new(backend.program)
+            .args(backend.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let (sender, output) = channel();
+        if let Some(stdout) = child.stdout.take() {
+            forward(stdout, false, sender.clone(), ctx.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward(stderr, true, sender, ctx.clone());
+        }
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| io::Error::other("no stdin"))?;
+        Ok(Self {
+            child,
+            stdin,
+            output,
+            terminator: backend.terminator,
+        })
+    }
+}
+
+impl Interpreter for ProcessInterpreter {
+    fn send(&mut self, code: &str) -> io::Result<()> {
+        self.stdin.write_all(code.trim_end().as_bytes())?;
+        self.stdin.write_all(self.terminator.as_bytes())?;
+        self.stdin.flush()
+    }
+
+    fn poll(&mut self) -> Vec<OutputLine> {
+        self.output.try_iter().collect()
+    }
+
+    fn is_alive(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(None))
+    }
+
+    fn stop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+impl Drop for ProcessInterpreter {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+enum Entry {
+    Input(String),
+    Output(OutputLine),
+    Notice(String),
+}
+
+#[derive(Default)]
+pub struct Console {
+    backend: usize,
+    interpreter: Option<Box<dyn Interpreter>>,
+    transcript: Vec<Entry>,
+    input: String,
+}
+
+impl Console {
+    fn push(&mut self, entry: Entry) {
+        self.transcript.push(entry);
+        if self.transcript.len() > MAX_TRANSCRIPT {
+            self.transcript
+                .drain(..self.transcript.len() - MAX_TRANSCRIPT);
+        }
+    }
+
+    fn restart(&mut self, ctx: &egui::Context) {
+        self.interpreter = None;
+        let backend = &BACKENDS[self.backend];
+        match ProcessInterpreter::start(backend, ctx) {
+            Ok(interpreter) => {
+                self.interpreter = Some(Box::new(interpreter));
+                self.push(Entry::Notice(trf(
+                    "{name} started",
+                    &[("name", &backend.name)],
+                )));
+            }
+            Err(e) => self.push(Entry::Notice(trf(
+                "Could not start {program}: {error}",
+                &[("program", &backend.program), ("error", &e)],
+            ))),
+        }
+    }
+
+    /// Evaluates `code`, starting the interpreter if needed.
+    fn evaluate(&mut self, ctx: &egui::Context, code: &str) {
+        if code.trim().is_empty() {
+            return;
+        }
+        if !self.interpreter.as_mut().is_some_and(|i| i.is_alive()) {
+            self.restart(ctx);
+        }
+        let Some(interpreter) = &mut self.interpreter else {
+            return;
+        };
+        let result = interpreter.send(code);
+        for line in code.trim_end().lines() {
+            self.push(Entry::Input(line.to_string()));
+        }
+        if let Err(e) = result {
+            self.push(Entry::Notice(trf(
+                "Could not send: {error}",
+                &[("error", &e)],
+            )));
+        }
+    }
+
+    pub fn poll(&mut self) {
+        let lines = self
+            .interpreter
+            .as_mut()
+            .map(|i| i.poll())
+            .unwrap_or_default();
+        for line in lines {
+            self.push(Entry::Output(line));
+        }
+    }
+}
+
+impl TextEditorApp {
+    /// Sends the selection, or the caret's line, to the console (Ctrl+Enter).
+    pub(crate) fn evaluate_selection(&mut self, ctx: &egui::Context) {
+        let Some(range) = self.selection_or(ctx, |text, pos| {
+            let start = text[..pos].rfind('\n').map_or(0, |i| i + 1);
+            let end = text[pos..].find('\n').map_or(text.len(), |i| pos + i);
+            start..end
+        }) else {
+            return;
+        };
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        let code = tab.content[range].to_string();
+        let backend = tab.syntax.as_deref().and_then(backend_for_syntax);
+        let console = &mut self.console;
+        if let Some(backend) = backend
+            && backend != console.backend
+        {
+            console.backend = backend;
+            console.interpreter = None;
+        }
+        console.evaluate(ctx, &code);
+        self.bottom_panel.show(BottomTab::Console);
+    }
+
+    /// The Console tab of the bottom panel.
+    pub(crate) fn console_ui(&mut self, ui: &mut egui::Ui) {
+        let ctx = ui.ctx().clone();
+        let console = &mut self.console;
+        ui.horizontal(|ui| {
+            let before = console.backend;
+            egui::ComboBox::from_id_source("console_backend")
+                .selected_text(BACKENDS[console.backend].name)
+                .show_ui(ui, |ui| {
+                    for (i, backend) in BACKENDS.iter().enumerate() {
+                        ui.selectable_value(&mut console.backend, i, backend.name);
+                    }
+                });
+            if console.backend != before {
+                console.interpreter = None;
+            }
+            let running = console.interpreter.as_mut().is_some_and(|i| i.is_alive());
+            if ui
+                .button(if running { tr("Restart") } else { tr("Start") })
+                .clicked()
+            {
+                console.restart(&ctx);
+            }
+            if running && ui.button(tr("Stop")).clicked() {
+                console.interpreter = None;
+                console.push(Entry::Notice(tr("Stopped")));
+            }
+            if ui.button(tr("Clear")).clicked() {
+                console.transcript.clear();
+            }
+        });
+        ui.separator();
+
+        let input_height = ui.spacing().interact_size.y + ui.spacing().item_spacing.y * 2.0;
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        let visuals = ui.visuals().clone();
+        egui::ScrollArea::both()
+            .auto_shrink([false, false])
+            .max_height(ui.available_height() - input_height)
+            .stick_to_bottom(true)
+            .show_rows(ui, row_height, console.transcript.len(), |ui, range| {
+                for entry in &console.transcript[range] {
+                    let text = match entry {
+                        Entry::Input(code) => egui::RichText::new(format!("» {code}"))
+                            .monospace()
+                            .color(visuals.strong_text_color()),
+                        Entry::Output(line) if line.is_error => egui::RichText::new(&line.text)
+                            .monospace()
+                            .color(visuals.error_fg_color),
+                        Entry::Output(line) => egui::RichText::new(&line.text).monospace(),
+                        Entry::Notice(text) => egui::RichText::new(text).italics().weak(),
+                    };
+                    ui.add(egui::Label::new(text).wrap(false));
+                }
+            });
+
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut console.input)
+                .font(egui::TextStyle::Monospace)
+                .hint_text(tr("Evaluate…"))
+                .desired_width(f32::INFINITY),
+        );
+        if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            let code = std::mem::take(&mut console.input);
+            console.evaluate(&ctx, &code);
+            response.request_focus();
+        }
+    }
+}
@@ -0,0 +1,186 @@
+//! The Resources window, showing roughly how much memory each tab and the
+//! symbol index take, and the optional eviction of caches from tabs that
+//! have not been looked at for a while: their highlighting is dropped (it
+//! is rebuilt when the tab is shown again) and so is their undo history.
+
+use crate::TextEditorApp;
+use crate::editing::editor_id;
+use crate::i18n::{tr, trf};
+use eframe::egui;
+use egui::text_edit::TextEditState;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// How often background tabs are checked for eviction.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+pub struct Resources {
+    pub open: bool,
+    /// When each tab was last the active one.
+    last_active: HashMap<String, Instant>,
+    /// Tabs whose caches were dropped since they were last active.
+    evicted: HashSet<String>,
+    last_sweep: Instant,
+}
+
+impl Default for Resources {
+    fn default() -> Self {
+        Self {
+            open: false,
+            last_active: HashMap::new(),
+            evicted: HashSet::new(),
+            last_sweep: Instant::now(),
+        }
+    }
+}
+
+/// `bytes` in the largest unit that keeps the number above one.
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+/// Resident memory of the whole process, where the platform reports it.
+fn resident_bytes() -> Option<usize> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: usize = statm.split_whitespace().nth(1)?.parse().ok()?;
+    Some(pages * 4096)
+}
+
+impl TextEditorApp {
+    /// Notes which tab is active and, when eviction is on, drops the caches
+    /// of tabs idle for longer than the configured time.
+    pub(crate) fn track_tab_activity(&mut self, ctx: &egui::Context) {
+        let resources = &mut self.resources;
+        if let Some(key) = &self.active_tab {
+            resources.last_active.insert(key.clone(), Instant::now());
+            resources.evicted.remove(key);
+        }
+        resources
+            .last_active
+            .retain(|key, _| self.tabs.contains_key(key));
+        if !self.settings.evict_idle_tabs || resources.last_sweep.elapsed() < SWEEP_INTERVAL {
+            return;
+        }
+        resources.last_sweep = Instant::now();
+        let idle = Duration::from_secs(u64::from(self.settings.evict_after_minutes) * 60);
+        let due: Vec<String> = resources
+            .last_active
+            .iter()
+            .filter(|(key, at)| {
+                Some(*key) != self.active_tab.as_ref()
+                    && !resources.evicted.contains(*key)
+                    && at.elapsed() >= idle
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+        self.evict_tabs(ctx, &due);
+    }
+
+    fn evict_tabs(&mut self, ctx: &egui::Context, keys: &[String]) {
+        for key in keys {
+            let Some(tab) = self.tabs.get_mut(key) else {
+                continue;
+            };
+            tab.highlight.clear();
+            let id = editor_id(key);
+            if let Some(mut state) = TextEditState::load(ctx, id) {
+                state.clear_undoer();
+                state.store(ctx, id);
+            }
+            self.resources.evicted.insert(key.clone());
+        }
+    }
+
+    pub(crate) fn show_resources_window(&mut self, ctx: &egui::Context) {
+        if !self.resources.open {
+            return;
+        }
+        let mut open = true;
+        let mut evict_now = false;
+        egui::Window::new(tr("Resources"))
+            .open(&mut open)
+            .default_size((460.0, 320.0))
+            .show(ctx, |ui| {
+                if let Some(resident) = resident_bytes() {
+                    ui.label(trf(
+                        "Process memory: {size}",
+                        &[("size", &format_bytes(resident))],
+                    ));
+                }
+                let index = self.symbol_index.as_ref().map_or(0, |i| i.approx_bytes());
+                ui.label(trf(
+                    "Symbol index: {size}",
+                    &[("size", &format_bytes(index))],
+                ));
+                ui.weak(tr("Sizes are estimates. Undo history is not counted."));
+                ui.separator();
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("resources_tabs")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong(tr("Tab"));
+                            ui.strong(tr("Text"));
+                            ui.strong(tr("Highlighting"));
+                            ui.strong(tr("Idle"));
+                            ui.end_row();
+                            let (mut text_total, mut highlight_total) = (0, 0);
+                            for key in &self.open_order {
+                                let tab = &self.tabs[key];
+                                let text = tab.content.capacity();
+                                let highlight = tab.highlight.approx_bytes();
+                                text_total += text;
+                                highlight_total += highlight;
+                                ui.label(&tab.title);
+                                ui.label(format_bytes(text));
+                                if self.resources.evicted.contains(key) {
+                                    ui.weak(tr("dropped"));
+                                } else {
+                                    ui.label(format_bytes(highlight));
+                                }
+                                let idle = self.resources.last_active.get(key).map_or(0, |at| {
+                                    at.elapsed().as_secs() / 60
+                                });
+                                if Some(key) == self.active_tab.as_ref() {
+                                    ui.label(tr("active"));
+                                } else {
+                                    ui.label(trf("{minutes} min", &[("minutes", &idle)]));
+                                }
+                                ui.end_row();
+                            }
+                            ui.strong(tr("Total"));
+                            ui.strong(format_bytes(text_total));
+                            ui.strong(format_bytes(highlight_total));
+                            ui.end_row();
+                        });
+                });
+                ui.separator();
+                evict_now = ui
+                    .button(tr("Free Caches of Background Tabs"))
+                    .on_hover_text(tr(
+                        "Drops the highlighting and undo history of every tab except the active one",
+                    ))
+                    .clicked();
+            });
+        self.resources.open = open;
+        if evict_now {
+            let keys: Vec<String> = self
+                .open_order
+                .iter()
+                .filter(|key| Some(*key) != self.active_tab.as_ref())
+                .cloned()
+                .collect();
+            self.evict_tabs(ctx, &keys);
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! "Run Current File" (F5): runs the active file with the command configured
+//! for its language and streams the output into the Output tab.
+
+use crate::TextEditorApp;
+use crate::bottom_panel::BottomTab;
+use crate::i18n::{tr, trf};
+use eframe::egui;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::thread;
+
+/// Most lines kept in the Output tab; older ones are dropped.
+const MAX_OUTPUT_LINES: usize = 10_000;
+
+pub struct OutputLine {
+    pub text: String,
+    pub is_error: bool,
+}
+
+#[derive(Default)]
+pub struct RunOutput {
+    pub command: String,
+    pub lines: Vec<OutputLine>,
+    /// Exit status of the finished process, for display.
+    pub status: Option<String>,
+    child: Option<Child>,
+    receiver: Option<Receiver<OutputLine>>,
+}
+
+impl RunOutput {
+    pub fn is_running(&self) -> bool {
+        self.child.is_some()
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(child) = &mut self.child {
+            let _ = child.kill();
+        }
+    }
+
+    fn push(&mut self, line: OutputLine) {
+        self.lines.push(line);
+        if self.lines.len() > MAX_OUTPUT_LINES {
+            self.lines.drain(..self.lines.len() - MAX_OUTPUT_LINES);
+        }
+    }
+}
+
+/// Quotes `text` as a single shell word.
+pub fn shell_quote(text: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        format!("'{}'", text.replace('\'', r"'\''"))
+    }
+}
+
+/// Expands `{file}`, `{dir}` and `{stem}` in a run command template.
+pub fn expand(template: &str, path: &Path) -> String {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    template
+        .replace("{file}", &shell_quote(&path.to_string_lossy()))
+        .replace("{dir}", &shell_quote(&dir.to_string_lossy()))
+        .replace("{stem}", &shell_quote(&stem))
+}
+
+pub fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.args(["/C", command]);
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.args(["-c", command]);
+        shell
+    }
+}
+
+pub fn forward(
+    stream: impl Read + Send + 'static,
+    is_error: bool,
+    sender: Sender<OutputLine>,
+    ctx: egui::Context,
+) {
+    thread::spawn(move || {
+        for text in BufReader::new(stream).lines().map_while(Result::ok) {
+            if sender.send(OutputLine { text, is_error }).is_err() {
+                break;
+            }
+            ctx.request_repaint();
+        }
+    });
+}
+
+impl TextEditorApp {
+    pub(crate) fn run_current_file(&mut self, ctx: &egui::Context) {
+        if !self.save_active() {
+            return;
+        }
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        let Some(path) = tab.path.clone() else {
+            return;
+        };
+        let syntax = tab.syntax.as_deref().unwrap_or("Plain Text");
+        self.bottom_panel.show(BottomTab::Output);
+        self.run_output.stop();
+
+        let Some(template) = self.settings.run_command(syntax) else {
+            self.run_output = RunOutput {
+                status: Some(trf(
+                    "No run command configured for {syntax}",
+                    &[("syntax", &syntax)],
+                )),
+                ..Default::default()
+            };
+            return;
+        };
+        let command = expand(template, &path);
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let child = shell(&command)
+            .current_dir(dir)
+            .envs(self.process_env(dir))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+        let mut output = RunOutput {
+            command,
+            ..Default::default()
+        };
+        match child {
+            Ok(mut child) => {
+                let (sender, receiver) = channel();
+                if let Some(stdout) = child.stdout.take() {
+                    forward(stdout, false, sender.clone(), ctx.clone());
+                }
+                if let Some(stderr) = child.stderr.take() {
+                    forward(stderr, true, sender, ctx.clone());
+                }
+                output.child = Some(child);
+                output.receiver = Some(receiver);
+                self.set_run_status(format!("▶ {}", output.command), false);
+            }
+            Err(e) => output.status = Some(trf("Could not start: {error}", &[("error", &e)])),
+        }
+        self.run_output = output;
+    }
+
+    /// Collects new output and notices when the process exits.
+    pub(crate) fn poll_run_output(&mut self) {
+        let output = &mut self.run_output;
+        if let Some(receiver) = &output.receiver {
+            let lines: Vec<OutputLine> = receiver.try_iter().collect();
+            for line in lines {
+                output.push(line);
+            }
+        }
+        if let Some(child) = &mut output.child
+            && let Ok(Some(status)) = child.try_wait()
+        {
+            let message = match status.code() {
+                Some(code) => trf("Exited with code {code}", &[("code", &code)]),
+                None => tr("Stopped"),
+            };
+            let summary = format!("{}: {message}", output.command);
+            output.status = Some(message);
+            output.child = None;
+            if status.success() {
+                self.notifications.info(summary.clone());
+            } else {
+                self.notifications.warning(summary.clone());
+            }
+            self.set_run_status(summary, !status.success());
+        }
+    }
+
+    /// The Output tab of the bottom panel.
+    pub(crate) fn run_output_ui(&mut self, ui: &mut egui::Ui) {
+        let mut run_again = false;
+        let output = &mut self.run_output;
+        ui.horizontal(|ui| {
+            ui.monospace(&output.command);
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if output.is_running() {
+                    if ui.button(tr("Stop")).clicked() {
+                        output.stop();
+                    }
+                    ui.spinner();
+                } else if !output.command.is_empty() && ui.button(tr("Run again")).clicked() {
+                    run_again = true;
+                }
+                if ui.button(tr("Clear")).clicked() {
+                    output.lines.clear();
+                }
+                if let Some(status) = &output.status {
+                    ui.weak(status);
+                }
+            });
+        });
+        ui.separator();
+
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        let error_color = ui.visuals().error_fg_color;
+        egui::ScrollArea::both()
+            .auto_shrink([false, false])
+            .stick_to_bottom(true)
+            .show_rows(ui, row_height, output.lines.len(), |ui, range| {
+                for line in &output.lines[range] {
+                    let mut text = egui::RichText::new(&line.text).monospace();
+                    if line.is_error {
+                        text = text.color(error_color);
+                    }
+                    ui.add(egui::Label::new(text).wrap(false));
+                }
+            });
+        if run_again {
+            self.run_current_file(ui.ctx());
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Searching within syntax scopes, e.g. only inside comments or strings,
+//! using the scope stack syntect builds while parsing.
+
+use crate::TextEditorApp;
+use crate::editing::byte_to_char;
+use crate::unicode_search;
+use eframe::egui;
+use std::ops::Range;
+use syntect::parsing::{ParseState, Scope, ScopeStack, SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchScope {
+    #[default]
+    Anywhere,
+    Comments,
+    Strings,
+    /// Everything outside comments and strings.
+    Code,
+}
+
+impl SearchScope {
+    pub const ALL: [SearchScope; 4] = [
+        SearchScope::Anywhere,
+        SearchScope::Comments,
+        SearchScope::Strings,
+        SearchScope::Code,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SearchScope::Anywhere => "Anywhere",
+            SearchScope::Comments => "Only in comments",
+            SearchScope::Strings => "Only in strings",
+            SearchScope::Code => "Only in code",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Comment,
+    String,
+    Code,
+}
+
+/// Splits `text` into byte ranges of comments, strings and other code.
+fn classify(
+    text: &str,
+    syntax_set: &SyntaxSet,
+    syntax: &SyntaxReference,
+) -> Vec<(Range<usize>, Class)> {
+    let comment = Scope::new("comment").unwrap();
+    let string = Scope::new("string").unwrap();
+    let class_of = |stack: &ScopeStack| {
+        let scopes = stack.as_slice();
+        if scopes.iter().any(|s| comment.is_prefix_of(*s)) {
+            Class::Comment
+        } else if scopes.iter().any(|s| string.is_prefix_of(*s)) {
+            Class::String
+        } else {
+            Class::Code
+        }
+    };
+
+    let mut regions: Vec<(Range<usize>, Class)> = Vec::new();
+    let mut push = |range: Range<usize>, class: Class| {
+        if range.is_empty() {
+            return;
+        }
+        match regions.last_mut() {
+            Some((last, last_class)) if *last_class == class && last.end == range.start => {
+                last.end = range.end;
+            }
+            _ => regions.push((range, class)),
+        }
+    };
+
+    let mut parse_state = ParseState::new(syntax);
+    let mut stack = ScopeStack::new();
+    let mut offset = 0;
+    for line in LinesWithEndings::from(text) {
+        let ops = parse_state.parse_line(line, syntax_set).unwrap_or_default();
+        let mut position = 0;
+        for (index, op) in ops {
+            push(offset + position..offset + index, class_of(&stack));
+            position = index;
+            let _ = stack.apply(&op);
+        }
+        push(offset + position..offset + line.len(), class_of(&stack));
+        offset += line.len();
+    }
+    regions
+}
+
+/// Byte ranges of `needle` in `text` that lie entirely within `scope`,
+/// compared as [`unicode_search::find`] does if `normalize` is set.
+pub fn find_matches(
+    text: &str,
+    needle: &str,
+    normalize: bool,
+    scope: SearchScope,
+    syntax_set: &SyntaxSet,
+    syntax: &SyntaxReference,
+) -> Vec<Range<usize>> {
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    let matches = if normalize {
+        unicode_search::find(text, needle, true)
+    } else {
+        text.match_indices(needle)
+            .map(|(i, m)| i..i + m.len())
+            .collect()
+    };
+    let wanted = match scope {
+        SearchScope::Anywhere => return matches,
+        SearchScope::Comments => Class::Comment,
+        SearchScope::Strings => Class::String,
+        SearchScope::Code => Class::Code,
+    };
+    let regions: Vec<Range<usize>> = classify(text, syntax_set, syntax)
+        .into_iter()
+        .filter(|(_, class)| *class == wanted)
+        .map(|(range, _)| range)
+        .collect();
+    matches
+        .into_iter()
+        .filter(|m| {
+            // Regions are sorted, so find the last one starting at or before the match.
+            let index = regions.partition_point(|r| r.start <= m.start);
+            index > 0 && regions[index - 1].end >= m.end
+        })
+        .collect()
+}
+
+impl TextEditorApp {
+    /// Matches of `needle` in the active tab, limited to the Find scope.
+    pub(crate) fn find_matches(&self, needle: &str) -> Vec<Range<usize>> {
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return Vec::new();
+        };
+        let syntax = tab
+            .syntax
+            .as_deref()
+            .and_then(|name| self.syntax_set.find_syntax_by_name(name))
+            .unwrap_or_else(|| self.syntax_set.find_syntax_plain_text());
+        find_matches(
+            &tab.content,
+            needle,
+            self.find_normalize,
+            self.find_scope,
+            &self.syntax_set,
+            syntax,
+        )
+    }
+
+    /// Selects the next match after the caret, wrapping around.
+    pub(crate) fn find_next(&mut self, ctx: &egui::Context) {
+        let matches = self.find_matches(&self.find_input);
+        self.found_count = matches.len();
+        let caret = self
+            .selection_or(ctx, |_, pos| pos..pos)
+            .map_or(0, |range| range.end);
+        let Some(next) = matches
+            .iter()
+            .find(|m| m.start >= caret)
+            .or(matches.first())
+            .cloned()
+        else {
+            return;
+        };
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        let start = byte_to_char(&tab.content, next.start);
+        let end = start + tab.content[next].chars().count();
+        self.set_selection(ctx, start..end);
+        self.scroll_to_caret = true;
+    }
+}
@@ -0,0 +1,155 @@
+//! The file filters of Find in Files: globs of files to include and to
+//! exclude, and a file type. They are kept per project in the data
+//! directory, keyed by the first workspace folder, so a monorepo keeps
+//! its vendored code out of every search.
+
+use crate::i18n::tr;
+use crate::settings::data_dir;
+use crate::{glob, json_file};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const FILTERS_FILE: &str = "search_filters.json";
+
+fn filters_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join(FILTERS_FILE))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FileType {
+    #[default]
+    All,
+    Rust,
+    Markdown,
+}
+
+impl FileType {
+    pub const ALL: [FileType; 3] = [FileType::All, FileType::Rust, FileType::Markdown];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FileType::All => "All files",
+            FileType::Rust => "Rust",
+            FileType::Markdown => "Markdown",
+        }
+    }
+
+    fn extensions(self) -> &'static [&'static str] {
+        match self {
+            FileType::All => &[],
+            FileType::Rust => &["rs"],
+            FileType::Markdown => &["md", "markdown"],
+        }
+    }
+
+    fn matches(self, path: &Path) -> bool {
+        self == FileType::All
+            || path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| {
+                    self.extensions()
+                        .iter()
+                        .any(|e| e.eq_ignore_ascii_case(ext))
+                })
+    }
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchFilters {
+    /// Comma-separated globs; when set, only matching files are searched.
+    pub include: String,
+    /// Comma-separated globs of files never searched.
+    pub exclude: String,
+    pub file_type: FileType,
+}
+
+/// The globs of a comma-separated pattern list.
+fn patterns(list: &str) -> impl Iterator<Item = &str> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+}
+
+/// Whether `pattern` matches the path shown as `relative`, or one of its
+/// folder or file names, so "vendor" leaves out every folder of that name.
+fn matches(pattern: &str, relative: &str) -> bool {
+    glob::matches(pattern, relative) || relative.split('/').any(|part| glob::matches(pattern, part))
+}
+
+fn load_all() -> Result<BTreeMap<PathBuf, SearchFilters>, String> {
+    match filters_path() {
+        Some(path) => json_file::load(&path).map(Option::unwrap_or_default),
+        None => Ok(BTreeMap::new()),
+    }
+}
+
+impl SearchFilters {
+    /// The filters last used in `project`, with a message to show if the
+    /// stored filters cannot be read; they are then not saved over.
+    pub fn load(project: &Path) -> (Self, Option<String>) {
+        match load_all() {
+            Ok(mut all) => (all.remove(project).unwrap_or_default(), None),
+            Err(message) => (SearchFilters::default(), Some(message)),
+        }
+    }
+
+    /// Remembers the filters for `project`.
+    pub fn save(&self, project: &Path) {
+        let Ok(mut all) = load_all() else {
+            return;
+        };
+        if all.get(project) == Some(self) {
+            return;
+        }
+        if *self == SearchFilters::default() {
+            all.remove(project);
+        } else {
+            all.insert(project.to_path_buf(), self.clone());
+        }
+        if let Some(path) = filters_path() {
+            let _ = json_file::save(&path, &all);
+        }
+    }
+
+    /// Whether the file at `path`, shown as `relative` with `/` separators,
+    /// is searched.
+    pub fn includes(&self, relative: &str, path: &Path) -> bool {
+        let mut include = patterns(&self.include).peekable();
+        self.file_type.matches(path)
+            && (include.peek().is_none() || include.any(|pattern| matches(pattern, relative)))
+            && !patterns(&self.exclude).any(|pattern| matches(pattern, relative))
+    }
+
+    /// The filter fields. Returns whether Enter was pressed in one of them.
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut enter = false;
+        ui.horizontal(|ui| {
+            ui.label(tr("Files to include:"));
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.include)
+                    .hint_text(tr("e.g. src/*, *.rs"))
+                    .desired_width(200.0),
+            );
+            enter |= response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            ui.label(tr("Exclude:"));
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.exclude)
+                    .hint_text(tr("e.g. vendor, *.min.js"))
+                    .desired_width(200.0),
+            );
+            enter |= response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            egui::ComboBox::from_id_source("search_file_type")
+                .selected_text(tr(self.file_type.label()))
+                .show_ui(ui, |ui| {
+                    for file_type in FileType::ALL {
+                        ui.selectable_value(&mut self.file_type, file_type, tr(file_type.label()));
+                    }
+                });
+        });
+        enter
+    }
+}
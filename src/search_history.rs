@@ -0,0 +1,160 @@
+//! Recent find/replace queries and named saved searches, persisted in the
+//! data directory. Search fields walk the history with Up/Down.
+
+use crate::i18n::tr;
+use crate::settings::data_dir;
+use crate::{TextEditorApp, json_file};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const HISTORY_FILE: &str = "search_history.json";
+/// Queries remembered per list.
+const MAX_HISTORY: usize = 50;
+
+/// A named find/replace pair for recurring refactors.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    pub find: String,
+    pub replace: String,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchHistory {
+    /// Most recent first.
+    pub searches: Vec<String>,
+    pub replacements: Vec<String>,
+    pub saved: Vec<SavedSearch>,
+}
+
+fn history_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join(HISTORY_FILE))
+}
+
+fn remember(list: &mut Vec<String>, query: &str) -> bool {
+    if query.is_empty() || list.first().is_some_and(|q| q == query) {
+        return false;
+    }
+    list.retain(|q| q != query);
+    list.insert(0, query.to_string());
+    list.truncate(MAX_HISTORY);
+    true
+}
+
+impl SearchHistory {
+    /// The stored history, with a message to show if it cannot be read;
+    /// the file is then not saved over.
+    pub fn load() -> (Self, Option<String>) {
+        json_file::load_or_default(history_path().as_deref())
+    }
+
+    pub fn save(&self) {
+        if let Some(path) = history_path() {
+            let _ = json_file::save(&path, self);
+        }
+    }
+
+    pub fn remember_search(&mut self, query: &str) {
+        if remember(&mut self.searches, query) {
+            self.save();
+        }
+    }
+
+    pub fn remember_replacement(&mut self, replacement: &str) {
+        if remember(&mut self.replacements, replacement) {
+            self.save();
+        }
+    }
+}
+
+/// A single-line text field where Up/Down step through `history`.
+pub fn history_field(
+    ui: &mut egui::Ui,
+    id: &str,
+    text: &mut String,
+    history: &[String],
+) -> egui::Response {
+    let id = ui.make_persistent_id(id);
+    let position_id = id.with("history_position");
+    if ui.memory(|m| m.has_focus(id)) {
+        let (up, down) = ui.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+            )
+        });
+        // `None` means the user's own text rather than a history entry.
+        let mut position: Option<usize> = ui.data(|d| d.get_temp(position_id)).flatten();
+        if up && !history.is_empty() {
+            position = Some(position.map_or(0, |p| (p + 1).min(history.len() - 1)));
+        }
+        if down {
+            position = position.and_then(|p| p.checked_sub(1));
+        }
+        if up || down {
+            *text = position.map_or_else(String::new, |p| history[p].clone());
+            ui.data_mut(|d| d.insert_temp(position_id, position));
+        }
+    }
+    let response = ui.add(egui::TextEdit::singleline(text).id(id));
+    if response.changed() {
+        ui.data_mut(|d| d.insert_temp(position_id, None::<usize>));
+    }
+    response.on_hover_text(tr("Up/Down: recent entries"))
+}
+
+impl TextEditorApp {
+    /// Menu in the Find & Replace window for loading and saving named pairs.
+    pub(crate) fn saved_searches_menu(&mut self, ui: &mut egui::Ui) {
+        ui.menu_button(tr("Saved"), |ui| {
+            let history = &mut self.search_history;
+            let mut remove = None;
+            for (i, saved) in history.saved.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(&saved.name)
+                        .on_hover_text(format!("{} → {}", saved.find, saved.replace))
+                        .clicked()
+                    {
+                        self.replace_find_input = saved.find.clone();
+                        self.replace_with_input = saved.replace.clone();
+                        ui.close_menu();
+                    }
+                    if ui.small_button("🗑").on_hover_text(tr("Delete")).clicked() {
+                        remove = Some(i);
+                    }
+                });
+            }
+            if let Some(i) = remove {
+                history.saved.remove(i);
+                history.save();
+            }
+            if !history.saved.is_empty() {
+                ui.separator();
+            }
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.saved_search_name)
+                        .hint_text(tr("Name"))
+                        .desired_width(120.0),
+                );
+                let name = self.saved_search_name.trim();
+                if ui
+                    .add_enabled(!name.is_empty(), egui::Button::new(tr("Save current")))
+                    .clicked()
+                {
+                    history.saved.retain(|s| s.name != name);
+                    history.saved.push(SavedSearch {
+                        name: name.to_string(),
+                        find: self.replace_find_input.clone(),
+                        replace: self.replace_with_input.clone(),
+                    });
+                    history.save();
+                    self.saved_search_name.clear();
+                }
+            });
+        });
+    }
+}
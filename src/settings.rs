@@ -0,0 +1,459 @@
+//! User settings, persisted as JSON in the platform config directory, or
+//! beside the executable in portable mode.
+
+use crate::i18n::trf;
+use crate::json_file;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const SETTINGS_FILE: &str = "settings.json";
+/// File next to the executable that turns on portable mode.
+const PORTABLE_MARKER: &str = "portable";
+
+/// Maps a file name pattern (e.g. `*.svelte` or `Justfile`) to a syntax name.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SyntaxMapping {
+    pub pattern: String,
+    pub syntax: String,
+}
+
+/// The command "Run Current File" uses for a syntax. `{file}`, `{dir}` and
+/// `{stem}` expand to the (quoted) file path, its directory and its name
+/// without extension.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RunCommand {
+    pub syntax: String,
+    pub command: String,
+}
+
+/// A shell command whose first line of output is shown in the status bar,
+/// see [`crate::status_items`]. It runs in the first workspace folder every
+/// `interval` seconds, or only when clicked if that is 0.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct StatusCommand {
+    pub command: String,
+    pub interval: u64,
+}
+
+/// Editor options for one file type, given as a syntax name (e.g. `YAML`)
+/// or a file name pattern (e.g. `*.md`). Options left unset fall back to
+/// the global ones.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FileTypeSettings {
+    pub file_type: String,
+    pub tab_width: Option<usize>,
+    pub insert_spaces: Option<bool>,
+    pub word_wrap: Option<bool>,
+    pub ascii_paste: Option<bool>,
+    pub continue_lists: Option<bool>,
+    /// Comma-separated columns; empty keeps the global rulers.
+    pub rulers: String,
+}
+
+/// The editor options in effect for a tab, see [`Settings::editor_options`].
+pub struct EditorOptions {
+    pub tab_width: usize,
+    pub insert_spaces: bool,
+    pub word_wrap: bool,
+    pub ascii_paste: bool,
+    pub continue_lists: bool,
+    pub rulers: Vec<usize>,
+}
+
+/// Parses a comma-separated list of columns, skipping anything else.
+pub fn parse_rulers(text: &str) -> Vec<usize> {
+    text.split(',')
+        .filter_map(|column| column.trim().parse().ok())
+        .collect()
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub syntax_mappings: Vec<SyntaxMapping>,
+    /// Drop the UTF-8 byte order mark when saving files that had one.
+    pub strip_bom_on_save: bool,
+    /// Record every save in the audit log, see [`crate::audit_log`].
+    pub audit_log: bool,
+    /// Column used by "Hard wrap" and the long line warning.
+    pub wrap_column: usize,
+    /// Highlight the part of lines that extends past `wrap_column`.
+    pub long_line_warning: bool,
+    /// Distance between tab stops, in columns.
+    pub tab_width: usize,
+    /// Tab inserts spaces up to the next tab stop instead of a tab character.
+    pub insert_spaces: bool,
+    /// Infer tabs or spaces and the indent width of opened files from their
+    /// content, see [`crate::indentation`].
+    pub detect_indentation: bool,
+    /// Wrap long lines at the edge of the editor instead of scrolling.
+    pub word_wrap: bool,
+    /// Turn smart quotes, dashes and non-breaking spaces into ASCII when
+    /// pasting, see [`crate::paste::to_ascii`].
+    pub ascii_paste: bool,
+    /// Enter continues Markdown lists, blockquotes and line comments, see
+    /// [`crate::continuation`].
+    pub continue_lists: bool,
+    /// Comma-separated columns at which vertical guides are drawn.
+    pub rulers: String,
+    /// Line height of code tabs relative to the font's; prose tabs have
+    /// their own.
+    pub line_spacing: f32,
+    /// Extra space between letters in the editor, in points.
+    pub letter_spacing: f32,
+    /// Per file type overrides of the six options above.
+    pub file_type_settings: Vec<FileTypeSettings>,
+    /// Home toggles between the first non-whitespace character and column 0.
+    pub smart_home: bool,
+    /// Word motions stop at underscores inside identifiers.
+    pub word_underscore_boundary: bool,
+    /// Word motions stop at camelCase humps.
+    pub word_camel_case_boundary: bool,
+    /// Underline invisible characters, bidi controls and homoglyphs.
+    pub unicode_warnings: bool,
+    /// Suggest words from a dictionary while writing text and Markdown,
+    /// see [`crate::word_completion`].
+    pub word_completion: bool,
+    /// List matching files while typing a relative path, see
+    /// [`crate::path_completion`].
+    pub path_completion: bool,
+    /// Expand Emmet abbreviations with Tab in HTML and CSS, see
+    /// [`crate::emmet`].
+    pub emmet: bool,
+    /// Add `end`, `fi` and the like when Enter completes a line opening a
+    /// block, see [`crate::block_end`].
+    pub close_blocks: bool,
+    /// Highlight the other occurrences of the word at the caret, see
+    /// [`crate::word_highlight`].
+    pub highlight_occurrences: bool,
+    /// Mark lines changed since the last save in a gutter, see
+    /// [`crate::change_bars`].
+    pub change_bars: bool,
+    /// Show the active file's path above the editor, see
+    /// [`crate::breadcrumbs`].
+    pub breadcrumbs: bool,
+    /// Open Markdown and text files in prose mode.
+    pub prose_by_default: bool,
+    /// Keep the caret vertically centered while typing.
+    pub typewriter_scrolling: bool,
+    /// Drop the highlighting and undo history of tabs not shown for
+    /// `evict_after_minutes`, see [`crate::resources`].
+    pub evict_idle_tabs: bool,
+    pub evict_after_minutes: u32,
+    /// Check for outside changes less often and skip animations, see
+    /// [`crate::power`].
+    pub low_energy: bool,
+    /// Allow scrolling the last line up to the top of the editor.
+    pub scroll_past_end: bool,
+    /// `strftime`-style format used by "Insert Date/Time".
+    pub date_format: String,
+    /// Name filled into `{{author}}` in file templates; empty uses git's
+    /// `user.name`.
+    pub author: String,
+    /// GitHub personal access token used by "Share as Gist".
+    pub gist_token: String,
+    pub run_commands: Vec<RunCommand>,
+    /// Command opening a terminal, `{dir}` standing for the folder; empty
+    /// uses the platform's, see [`crate::terminal`].
+    pub terminal_command: String,
+    /// `NAME=value` variables added to the environment of run commands,
+    /// cargo check and terminals.
+    pub environment: Vec<String>,
+    pub status_commands: Vec<StatusCommand>,
+    /// Ask whether to trust newly opened folders and keep untrusted ones in
+    /// restricted mode, see [`crate::trust`].
+    pub workspace_trust: bool,
+    /// Look for a newer release on startup, see [`crate::update`].
+    pub check_for_updates: bool,
+    /// Version whose update announcement was turned down.
+    pub skipped_update: String,
+    /// Name of the custom color theme in use, see [`crate::theme_editor`];
+    /// empty for the built-in light and dark themes.
+    pub color_theme: String,
+    /// Code of the UI language, e.g. "de"; "en" uses the built-in strings.
+    pub language: String,
+    /// File name patterns of files opened as sensitive, see [`crate::privacy`].
+    pub sensitive_patterns: Vec<String>,
+    /// Groups of files Switch to Counterpart moves between, see
+    /// [`crate::counterpart`].
+    pub counterpart_rules: Vec<String>,
+    /// UI zoom factor per monitor, keyed by [`crate::ui_scale::monitor_key`].
+    pub ui_scales: BTreeMap<String, f32>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            syntax_mappings: vec![
+                SyntaxMapping {
+                    pattern: "*.svelte".to_string(),
+                    syntax: "HTML".to_string(),
+                },
+                SyntaxMapping {
+                    pattern: "Justfile".to_string(),
+                    syntax: "Makefile".to_string(),
+                },
+            ],
+            strip_bom_on_save: false,
+            audit_log: false,
+            wrap_column: 80,
+            long_line_warning: false,
+            tab_width: 4,
+            insert_spaces: false,
+            detect_indentation: true,
+            word_wrap: false,
+            ascii_paste: false,
+            continue_lists: true,
+            rulers: String::new(),
+            line_spacing: 1.0,
+            letter_spacing: 0.0,
+            file_type_settings: vec![
+                FileTypeSettings {
+                    file_type: "YAML".to_string(),
+                    tab_width: Some(2),
+                    insert_spaces: Some(true),
+                    ..Default::default()
+                },
+                FileTypeSettings {
+                    file_type: "Markdown".to_string(),
+                    word_wrap: Some(true),
+                    ..Default::default()
+                },
+                FileTypeSettings {
+                    file_type: "Rust".to_string(),
+                    insert_spaces: Some(true),
+                    ascii_paste: Some(true),
+                    rulers: "100".to_string(),
+                    ..Default::default()
+                },
+            ],
+            smart_home: true,
+            word_underscore_boundary: false,
+            word_camel_case_boundary: false,
+            unicode_warnings: true,
+            word_completion: false,
+            path_completion: true,
+            emmet: true,
+            close_blocks: true,
+            highlight_occurrences: true,
+            change_bars: true,
+            breadcrumbs: true,
+            prose_by_default: false,
+            typewriter_scrolling: false,
+            evict_idle_tabs: false,
+            evict_after_minutes: 30,
+            low_energy: false,
+            scroll_past_end: false,
+            date_format: "%Y-%m-%d %H:%M".to_string(),
+            author: String::new(),
+            gist_token: String::new(),
+            run_commands: [
+                ("Rust", "cargo run"),
+                ("Python", "python3 {file}"),
+                ("JavaScript", "node {file}"),
+                ("Bourne Again Shell (bash)", "bash {file}"),
+                ("Go", "go run {file}"),
+                ("Ruby", "ruby {file}"),
+                ("Perl", "perl {file}"),
+                ("PHP", "php {file}"),
+            ]
+            .into_iter()
+            .map(|(syntax, command)| RunCommand {
+                syntax: syntax.to_string(),
+                command: command.to_string(),
+            })
+            .collect(),
+            terminal_command: String::new(),
+            environment: Vec::new(),
+            status_commands: Vec::new(),
+            workspace_trust: true,
+            check_for_updates: false,
+            skipped_update: String::new(),
+            color_theme: String::new(),
+            language: "en".to_string(),
+            ui_scales: BTreeMap::new(),
+            sensitive_patterns: [".env", ".env.*", "*.pem", "*.key", "id_rsa", "id_ed25519"]
+                .map(String::from)
+                .to_vec(),
+            counterpart_rules: crate::counterpart::default_rules(),
+        }
+    }
+}
+
+impl Settings {
+    /// Reads the settings, with a message to show if the file exists but
+    /// cannot be read.
+    pub fn load() -> (Self, Option<String>) {
+        match config_dir() {
+            Some(dir) => Self::load_from(&dir.join(SETTINGS_FILE)),
+            None => (Self::default(), None),
+        }
+    }
+
+    /// Reads the settings at `path`. A file that does not parse is moved to
+    /// `settings.json.bak`, so saving the defaults in its place does not
+    /// lose what the user wrote; if it cannot be moved, it stays where it is
+    /// and is not saved over.
+    fn load_from(path: &Path) -> (Self, Option<String>) {
+        let Ok(json) = fs::read_to_string(path) else {
+            return (Self::default(), None);
+        };
+        let error = match serde_json::from_str(&json) {
+            Ok(settings) => return (settings, None),
+            Err(err) => err,
+        };
+        let backup = path.with_extension("json.bak");
+        let message = match fs::rename(path, &backup) {
+            Ok(()) => trf(
+                "The settings could not be read ({error}) and were moved to {path}; the defaults are used",
+                &[("error", &error), ("path", &backup.display())],
+            ),
+            Err(_) => trf(
+                "The settings could not be read ({error}); the defaults are used, and changes are not saved until the file is fixed",
+                &[("error", &error)],
+            ),
+        };
+        (Self::default(), Some(message))
+    }
+
+    pub fn save(&self) {
+        if let Some(dir) = config_dir() {
+            let _ = json_file::save(&dir.join(SETTINGS_FILE), self);
+        }
+    }
+
+    pub fn run_command(&self, syntax: &str) -> Option<&str> {
+        self.run_commands
+            .iter()
+            .find(|run| run.syntax == syntax)
+            .map(|run| run.command.as_str())
+    }
+
+    /// Whether `path` matches one of the sensitive file patterns.
+    pub fn is_sensitive(&self, path: &Path) -> bool {
+        let Some(file_name) = path.file_name() else {
+            return false;
+        };
+        let file_name = file_name.to_string_lossy();
+        self.sensitive_patterns
+            .iter()
+            .any(|pattern| crate::glob::matches(pattern, &file_name))
+    }
+
+    /// The global editor options with those of every file type matching
+    /// `syntax` or `file_name` merged over them, later entries winning.
+    pub fn editor_options(&self, syntax: Option<&str>, file_name: &str) -> EditorOptions {
+        let mut options = EditorOptions {
+            tab_width: self.tab_width,
+            insert_spaces: self.insert_spaces,
+            word_wrap: self.word_wrap,
+            ascii_paste: self.ascii_paste,
+            continue_lists: self.continue_lists,
+            rulers: parse_rulers(&self.rulers),
+        };
+        let matching = self.file_type_settings.iter().filter(|overrides| {
+            syntax == Some(overrides.file_type.as_str())
+                || crate::glob::matches(&overrides.file_type, file_name)
+        });
+        for overrides in matching {
+            options.tab_width = overrides.tab_width.unwrap_or(options.tab_width);
+            options.insert_spaces = overrides.insert_spaces.unwrap_or(options.insert_spaces);
+            options.word_wrap = overrides.word_wrap.unwrap_or(options.word_wrap);
+            options.ascii_paste = overrides.ascii_paste.unwrap_or(options.ascii_paste);
+            options.continue_lists = overrides.continue_lists.unwrap_or(options.continue_lists);
+            if !overrides.rulers.trim().is_empty() {
+                options.rulers = parse_rulers(&overrides.rulers);
+            }
+        }
+        options
+    }
+
+    /// The syntax configured for `path`, if any mapping matches its file name.
+    pub fn syntax_override(&self, path: &Path) -> Option<&str> {
+        let file_name = path.file_name()?.to_string_lossy();
+        self.syntax_mappings
+            .iter()
+            .find(|m| crate::glob::matches(&m.pattern, &file_name))
+            .map(|m| m.syntax.as_str())
+    }
+}
+
+/// Directory next to the executable that holds everything in portable mode.
+static PORTABLE_ROOT: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// Switches to portable mode when `--portable` was passed or a `portable`
+/// marker file sits next to the executable, so configuration and state live
+/// beside the binary (e.g. on a USB stick) instead of in the home directory.
+/// Must run before anything reads or writes settings.
+pub fn init_portable(args: &[String]) {
+    PORTABLE_ROOT.get_or_init(|| {
+        let exe_dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
+        let requested = args.iter().any(|arg| arg == "--portable");
+        (requested || exe_dir.join(PORTABLE_MARKER).is_file())
+            .then(|| exe_dir.join("rusty-editor-data"))
+    });
+}
+
+pub fn is_portable() -> bool {
+    PORTABLE_ROOT.get().is_some_and(Option::is_some)
+}
+
+fn portable_root() -> Option<&'static Path> {
+    PORTABLE_ROOT.get()?.as_deref()
+}
+
+/// Directory holding the editor's configuration files.
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(root) = portable_root() {
+        return Some(root.join("config"));
+    }
+    dirs::config_dir().map(|dir| dir.join("rusty-editor"))
+}
+
+/// Directory for state the editor keeps on its own, such as journals.
+pub fn data_dir() -> Option<PathBuf> {
+    if let Some(root) = portable_root() {
+        return Some(root.join("data"));
+    }
+    dirs::data_local_dir().map(|dir| dir.join("rusty-editor"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unreadable_settings_are_kept_aside() {
+        let dir = std::env::temp_dir().join(format!("rusty-editor-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(SETTINGS_FILE);
+        fs::write(&path, "{ \"tab_width\": 2, oops }").unwrap();
+        let (settings, message) = Settings::load_from(&path);
+        assert_eq!(settings.tab_width, Settings::default().tab_width);
+        assert!(message.is_some());
+        assert!(!path.exists());
+        assert_eq!(
+            fs::read_to_string(dir.join("settings.json.bak")).unwrap(),
+            "{ \"tab_width\": 2, oops }"
+        );
+
+        // When it cannot be moved aside, it is not saved over either.
+        fs::remove_file(dir.join("settings.json.bak")).unwrap();
+        fs::create_dir_all(dir.join("settings.json.bak/keep")).unwrap();
+        fs::write(&path, "{ oops }").unwrap();
+        let (settings, message) = Settings::load_from(&path);
+        assert!(message.is_some());
+        assert!(json_file::save(&path, &settings).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{ oops }");
+
+        fs::write(&path, "{ \"tab_width\": 2 }").unwrap();
+        let (settings, message) = Settings::load_from(&path);
+        assert_eq!(settings.tab_width, 2);
+        assert!(message.is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,62 @@
+//! Soft wrap support for code tabs: a hooked arrow in a narrow gutter marks
+//! rows that continue the line above, and the rows of the last frame are
+//! remembered so Home can tell a continuation row from the start of a line.
+
+use eframe::egui;
+use std::ops::Range;
+
+/// Width reserved left of the text for the wrap indicators.
+pub const GUTTER_WIDTH: f32 = 12.0;
+
+/// Character ranges of the rows that continue a wrapped line, as laid out in
+/// the last frame.
+#[derive(Default)]
+pub struct WrappedRows {
+    rows: Vec<Range<usize>>,
+}
+
+impl WrappedRows {
+    pub fn clear(&mut self) {
+        self.rows.clear();
+    }
+
+    /// Whether the caret at `pos` sits on a continuation row.
+    pub fn is_continuation(&self, pos: usize) -> bool {
+        self.rows.iter().any(|row| row.contains(&pos))
+    }
+}
+
+/// Records the continuation rows of `galley` and draws their indicators in
+/// the gutter left of `galley_pos`.
+pub fn show_indicators(
+    ui: &egui::Ui,
+    galley: &egui::Galley,
+    galley_pos: egui::Pos2,
+    wrapped: &mut WrappedRows,
+) {
+    wrapped.rows.clear();
+    let stroke = egui::Stroke::new(1.0, ui.visuals().weak_text_color());
+    let clip = ui.clip_rect();
+    let mut start = 0;
+    let mut continues = false;
+    for row in &galley.rows {
+        let end = start + row.char_count_excluding_newline();
+        if continues {
+            wrapped.rows.push(start..end + 1);
+            let rect = row.rect.translate(galley_pos.to_vec2());
+            if clip.y_range().intersects(rect.y_range()) {
+                let x = galley_pos.x - GUTTER_WIDTH + 3.0;
+                let (top, mid) = (rect.top() + 2.0, rect.center().y);
+                ui.painter()
+                    .line_segment([egui::pos2(x, top), egui::pos2(x, mid)], stroke);
+                ui.painter().arrow(
+                    egui::pos2(x, mid),
+                    egui::vec2(GUTTER_WIDTH - 6.0, 0.0),
+                    stroke,
+                );
+            }
+        }
+        continues = !row.ends_with_newline;
+        start += row.char_count_including_newline();
+    }
+}
@@ -0,0 +1,117 @@
+//! Protection against overwriting changes made by other programs. Each tab
+//! remembers the modification time and content hash of its file as last
+//! loaded or saved; when the file no longer matches at save time, the save
+//! stops and asks whether to overwrite, compare or cancel.
+
+use crate::diff::DiffView;
+use crate::i18n::{tr, trf};
+use crate::journal::content_hash;
+use crate::{TextEditorApp, UTF8_BOM};
+use eframe::egui;
+use std::fs;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// What a file on disk looked like when the editor last read or wrote it.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DiskStamp {
+    modified: Option<SystemTime>,
+    hash: u64,
+}
+
+impl DiskStamp {
+    pub fn read(path: &Path) -> Option<Self> {
+        let bytes = fs::read(path).ok()?;
+        Some(Self {
+            modified: fs::metadata(path).and_then(|m| m.modified()).ok(),
+            hash: content_hash(&String::from_utf8_lossy(&bytes)),
+        })
+    }
+
+    /// Whether the file at `path` differs from this stamp. A newer
+    /// modification time alone, as left by `touch`, does not count.
+    fn changed(&self, path: &Path) -> bool {
+        let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+        if modified.is_some() && modified == self.modified {
+            return false;
+        }
+        Self::read(path).is_some_and(|now| now.hash != self.hash)
+    }
+}
+
+#[derive(Default)]
+pub struct StaleSave {
+    /// The tab whose save is waiting for an answer.
+    asking: Option<String>,
+    /// The tab the user chose to overwrite; its next save skips the check.
+    overwrite: Option<String>,
+}
+
+impl StaleSave {
+    /// Whether saving tab `key` to `path` has to wait for the user, because
+    /// the file changed since `stamp` was taken.
+    pub fn blocks(&mut self, key: &str, path: &Path, stamp: Option<&DiskStamp>) -> bool {
+        if self.overwrite.take().is_some_and(|k| k == key) {
+            return false;
+        }
+        if stamp.is_some_and(|stamp| stamp.changed(path)) {
+            self.asking = Some(key.to_string());
+            return true;
+        }
+        false
+    }
+}
+
+impl TextEditorApp {
+    pub(crate) fn show_stale_save_dialog(&mut self, ctx: &egui::Context) {
+        let Some(key) = self.stale_save.asking.clone() else {
+            return;
+        };
+        let Some(path) = self.tabs.get(&key).and_then(|tab| tab.path.clone()) else {
+            self.stale_save.asking = None;
+            return;
+        };
+        let mut overwrite = false;
+        let mut compare = false;
+        let mut cancel = false;
+        egui::Window::new(tr("File Changed on Disk"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(trf(
+                    "{name} was changed by another program. Saving now would overwrite those changes.",
+                    &[("name", &key)],
+                ));
+                ui.horizontal(|ui| {
+                    overwrite = ui.button(tr("Overwrite")).clicked();
+                    compare = ui
+                        .button(tr("Compare…"))
+                        .on_hover_text(tr("Show the differences to merge them by hand"))
+                        .clicked();
+                    cancel = ui.button(tr("Cancel")).clicked();
+                });
+            });
+        if overwrite {
+            self.stale_save = StaleSave {
+                asking: None,
+                overwrite: Some(key.clone()),
+            };
+            self.active_tab = Some(key);
+            self.save_active();
+        } else if compare {
+            self.stale_save.asking = None;
+            let on_disk = fs::read(&path)
+                .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+                .unwrap_or_default();
+            self.diff_view = Some(DiffView::new(
+                trf("{name} (on disk)", &[("name", &key)]),
+                on_disk.strip_prefix(UTF8_BOM).unwrap_or(&on_disk),
+                trf("{name} (unsaved)", &[("name", &key)]),
+                &self.tabs[&key].content,
+            ));
+        } else if cancel {
+            self.stale_save.asking = None;
+        }
+    }
+}
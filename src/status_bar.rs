@@ -0,0 +1,125 @@
+use crate::TextEditorApp;
+use crate::conflicts::find_conflicts;
+use crate::i18n::{tr, trf};
+use crate::indentation;
+use crate::profiler;
+use crate::settings;
+use crate::unicode_warnings;
+use eframe::egui;
+
+impl TextEditorApp {
+    pub(crate) fn show_status_bar(&mut self, ctx: &egui::Context) {
+        let _scope = profiler::scope("status bar");
+        let mut toggle_follow = false;
+        let mut convert_indentation = None;
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                self.jobs_button(ui);
+                self.restricted_mode_button(ui);
+                self.branch_button(ui);
+                self.problem_counts_button(ui);
+                self.notifications_button(ui);
+                self.status_items_ui(ui);
+                if let Some(url) = self.live_view.url() {
+                    ui.separator();
+                    ui.hyperlink_to(tr("Live"), &url)
+                        .on_hover_text(trf("Live View running at {url}", &[("url", &url)]));
+                }
+                if settings::is_portable() {
+                    ui.separator();
+                    ui.label(tr("Portable"))
+                        .on_hover_text(tr("Settings and state are stored next to the executable"));
+                }
+                ui.separator();
+
+                let Some(tab) = self
+                    .active_tab
+                    .as_ref()
+                    .and_then(|name| self.tabs.get_mut(name))
+                else {
+                    ui.label(tr("Ready"));
+                    return;
+                };
+                let conflicts = if tab.follow.is_none() {
+                    find_conflicts(&tab.content).len()
+                } else {
+                    0
+                };
+                if conflicts > 0 {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        trf("{count} merge conflict(s)", &[("count", &conflicts)]),
+                    );
+                    ui.separator();
+                }
+                let suspicious = if self.settings.unicode_warnings && tab.follow.is_none() {
+                    unicode_warnings::find(&tab.content).len()
+                } else {
+                    0
+                };
+                if suspicious > 0 {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        trf(
+                            "⚠ {count} suspicious character(s)",
+                            &[("count", &suspicious)],
+                        ),
+                    )
+                    .on_hover_text(tr(
+                        "Invisible, bidirectional or lookalike characters; see Edit > Remove Suspicious Characters",
+                    ));
+                    ui.separator();
+                }
+                if tab.read_only {
+                    ui.label(tr("Read-only"));
+                    ui.separator();
+                }
+                if tab.sensitive {
+                    ui.label(format!("🔒 {}", tr("Sensitive")))
+                        .on_hover_text(tr(
+                            "Not written to the crash journal or shown in Live View",
+                        ));
+                    ui.separator();
+                }
+                if tab.path.is_some() {
+                    let mut following = tab.follow.is_some();
+                    if ui
+                        .toggle_value(&mut following, tr("Follow"))
+                        .on_hover_text(tr("Tail the file as it grows"))
+                        .changed()
+                    {
+                        toggle_follow = true;
+                    }
+                    ui.separator();
+                }
+                ui.label(tab.syntax.as_deref().unwrap_or("Plain Text"));
+                ui.separator();
+                convert_indentation = indentation::status_menu(ui, tab, &self.settings);
+                ui.separator();
+
+                let encoding = if tab.bom {
+                    tr("UTF-8 with BOM")
+                } else {
+                    "UTF-8".to_string()
+                };
+                ui.menu_button(encoding, |ui| {
+                    if tab.bom {
+                        if ui.button(tr("Remove byte order mark")).clicked() {
+                            tab.bom = false;
+                            ui.close_menu();
+                        }
+                    } else if ui.button(tr("Add byte order mark")).clicked() {
+                        tab.bom = true;
+                        ui.close_menu();
+                    }
+                });
+            });
+        });
+        if let Some(target) = convert_indentation {
+            self.convert_indentation(ctx, Some(target));
+        }
+        if toggle_follow {
+            self.toggle_follow();
+        }
+    }
+}
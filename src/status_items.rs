@@ -0,0 +1,213 @@
+//! Status bar items put up by other parts of the editor instead of being
+//! built into the bar: the program started with Run Current File, the last
+//! cargo check, and status commands configured in Preferences, whose first
+//! line of output is shown and refreshed on an interval or on click.
+
+use crate::TextEditorApp;
+use crate::bottom_panel::BottomTab;
+use crate::i18n::tr;
+use crate::run::shell;
+use eframe::egui;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, channel};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// What clicking an item does.
+#[derive(Clone)]
+pub enum StatusAction {
+    Show(BottomTab),
+    /// Runs the status command again.
+    Refresh(String),
+}
+
+pub struct StatusItem {
+    pub text: String,
+    pub tooltip: String,
+    pub action: Option<StatusAction>,
+    /// Drawn in the warning color.
+    pub warning: bool,
+}
+
+/// A status command's last run and the one in progress.
+#[derive(Default)]
+struct CommandState {
+    started: Option<Instant>,
+    receiver: Option<Receiver<(String, bool)>>,
+}
+
+#[derive(Default)]
+pub struct StatusItems {
+    /// In the order they were first set, keyed by an id of the provider's
+    /// choosing.
+    items: Vec<(String, StatusItem)>,
+    commands: HashMap<String, CommandState>,
+}
+
+impl StatusItems {
+    /// Shows `item` under `id`, replacing the item it had before.
+    pub fn set(&mut self, id: &str, item: StatusItem) {
+        match self.items.iter_mut().find(|(key, _)| key == id) {
+            Some((_, slot)) => *slot = item,
+            None => self.items.push((id.to_string(), item)),
+        }
+    }
+}
+
+/// The first non-empty line `command` prints, and whether it succeeded.
+fn run_status_command(
+    command: &str,
+    dir: Option<PathBuf>,
+    env: Vec<(String, String)>,
+) -> (String, bool) {
+    let mut process = shell(command);
+    if let Some(dir) = dir {
+        process.current_dir(dir);
+    }
+    match process.envs(env).output() {
+        Ok(output) => {
+            let success = output.status.success();
+            let stream = if success || output.stderr.is_empty() {
+                &output.stdout
+            } else {
+                &output.stderr
+            };
+            let text = String::from_utf8_lossy(stream)
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .unwrap_or_default()
+                .to_string();
+            (text, success)
+        }
+        Err(err) => (err.to_string(), false),
+    }
+}
+
+impl TextEditorApp {
+    /// Starts the status commands that are due and shows the results of
+    /// those that finished.
+    pub(crate) fn poll_status_commands(&mut self, ctx: &egui::Context) {
+        // Nothing runs on its own in a restricted folder.
+        let configured = match self.folder_path() {
+            Some(dir) if !self.is_trusted(&dir) => Vec::new(),
+            _ => self.settings.status_commands.clone(),
+        };
+        let items = &mut self.status_items;
+        items
+            .commands
+            .retain(|command, _| configured.iter().any(|c| &c.command == command));
+        items.items.retain(|(id, _)| {
+            id.strip_prefix("command:")
+                .is_none_or(|command| configured.iter().any(|c| c.command == command))
+        });
+        for status_command in configured.iter().filter(|c| !c.command.trim().is_empty()) {
+            let command = &status_command.command;
+            let state = self
+                .status_items
+                .commands
+                .entry(command.clone())
+                .or_default();
+            let finished = state.receiver.as_ref().and_then(|r| r.try_recv().ok());
+            if let Some((text, success)) = finished {
+                state.receiver = None;
+                self.status_items.set(
+                    &format!("command:{command}"),
+                    StatusItem {
+                        text,
+                        tooltip: command.clone(),
+                        action: Some(StatusAction::Refresh(command.clone())),
+                        warning: !success,
+                    },
+                );
+                continue;
+            }
+            if state.receiver.is_some() {
+                continue;
+            }
+            let interval = Duration::from_secs(status_command.interval);
+            let wait = match state.started {
+                None => Duration::ZERO,
+                Some(_) if interval.is_zero() => continue,
+                Some(started) => interval.saturating_sub(started.elapsed()),
+            };
+            if !wait.is_zero() {
+                ctx.request_repaint_after(wait);
+                continue;
+            }
+            state.started = Some(Instant::now());
+            let dir = self.folder_path();
+            let env = dir
+                .as_deref()
+                .map(|dir| self.process_env(dir))
+                .unwrap_or_default();
+            let (sender, receiver) = channel();
+            let (thread_command, thread_ctx) = (command.clone(), ctx.clone());
+            thread::spawn(move || {
+                let _ = sender.send(run_status_command(&thread_command, dir, env));
+                thread_ctx.request_repaint();
+            });
+            if let Some(state) = self.status_items.commands.get_mut(command) {
+                state.receiver = Some(receiver);
+            }
+        }
+    }
+
+    /// The items in the status bar, each after a separator.
+    pub(crate) fn status_items_ui(&mut self, ui: &mut egui::Ui) {
+        let mut clicked = None;
+        for (_, item) in &self.status_items.items {
+            ui.separator();
+            let text = if item.warning {
+                egui::RichText::new(&item.text).color(ui.visuals().warn_fg_color)
+            } else {
+                egui::RichText::new(&item.text)
+            };
+            let response = ui
+                .add(egui::Button::new(text).frame(false))
+                .on_hover_text(&item.tooltip);
+            if response.clicked() {
+                clicked = item.action.clone();
+            }
+        }
+        match clicked {
+            Some(StatusAction::Show(tab)) => self.bottom_panel.show(tab),
+            Some(StatusAction::Refresh(command)) => {
+                // Forgetting the last run makes it due.
+                if let Some(state) = self.status_items.commands.get_mut(&command)
+                    && state.receiver.is_none()
+                {
+                    state.started = None;
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// Shows the state of the last cargo check.
+    pub(crate) fn set_cargo_status(&mut self, text: String, warning: bool) {
+        self.status_items.set(
+            "cargo",
+            StatusItem {
+                text,
+                tooltip: tr("Show the problems"),
+                action: Some(StatusAction::Show(BottomTab::Problems)),
+                warning,
+            },
+        );
+    }
+
+    /// Shows the state of the program started with Run Current File.
+    pub(crate) fn set_run_status(&mut self, text: String, warning: bool) {
+        self.status_items.set(
+            "run",
+            StatusItem {
+                text,
+                tooltip: tr("Show the output"),
+                action: Some(StatusAction::Show(BottomTab::Output)),
+                warning,
+            },
+        );
+    }
+}
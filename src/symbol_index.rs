@@ -0,0 +1,216 @@
+//! Project-wide symbol index behind "Go to Symbol in Workspace" (Ctrl+T).
+//!
+//! A background thread walks the workspace folders every few seconds and
+//! re-extracts symbols only from files whose modification time changed,
+//! sending the results back over a channel. Saving a buffer reindexes that
+//! file right away.
+
+use crate::TextEditorApp;
+use crate::i18n::tr;
+use crate::jobs::Job;
+use crate::picker::PickerItem;
+use crate::project::{display_path, walk_roots};
+use crate::symbols::{self, Symbol};
+use eframe::egui;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+const RESCAN_INTERVAL: Duration = Duration::from_secs(5);
+/// Larger files are most likely generated and not worth indexing.
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+enum Update {
+    Indexed(PathBuf, Vec<Symbol>),
+    Removed(PathBuf),
+}
+
+pub struct SymbolIndex {
+    roots: Vec<PathBuf>,
+    files: BTreeMap<PathBuf, Vec<Symbol>>,
+    updates: Receiver<Update>,
+    stop: Arc<AtomicBool>,
+}
+
+impl SymbolIndex {
+    /// Starts the scanner; `job` reports the first full scan, and
+    /// cancelling it stops the scanner.
+    pub fn start(roots: Vec<PathBuf>, ctx: egui::Context, job: Job) -> Self {
+        let (sender, updates) = channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let worker_roots = roots.clone();
+        let worker_stop = stop.clone();
+        thread::spawn(move || scan_loop(&worker_roots, &sender, &worker_stop, &ctx, job));
+        Self {
+            roots,
+            files: BTreeMap::new(),
+            updates,
+            stop,
+        }
+    }
+
+    pub fn roots(&self) -> &[PathBuf] {
+        &self.roots
+    }
+
+    /// Applies the updates the scanner sent since the last call.
+    pub fn poll(&mut self) {
+        for update in self.updates.try_iter() {
+            match update {
+                Update::Indexed(path, symbols) => {
+                    self.files.insert(path, symbols);
+                }
+                Update::Removed(path) => {
+                    self.files.remove(&path);
+                }
+            }
+        }
+    }
+
+    /// Reindexes `path` from `content`, e.g. right after it was saved.
+    pub fn reindex(&mut self, path: &Path, content: &str) {
+        if self.roots.iter().any(|root| path.starts_with(root)) && symbols::supported(path) {
+            self.files
+                .insert(path.to_path_buf(), symbols::extract(path, content));
+        }
+    }
+
+    /// Rough heap size of the index in bytes.
+    pub fn approx_bytes(&self) -> usize {
+        self.files
+            .iter()
+            .map(|(path, symbols)| {
+                path.as_os_str().len()
+                    + symbols
+                        .iter()
+                        .map(|s| std::mem::size_of::<Symbol>() + s.name.capacity())
+                        .sum::<usize>()
+            })
+            .sum()
+    }
+
+    pub fn symbols(&self) -> impl Iterator<Item = (&Path, &Symbol)> {
+        self.files
+            .iter()
+            .flat_map(|(path, symbols)| symbols.iter().map(move |s| (path.as_path(), s)))
+    }
+}
+
+impl Drop for SymbolIndex {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn scan_loop(
+    roots: &[PathBuf],
+    sender: &Sender<Update>,
+    stop: &AtomicBool,
+    ctx: &egui::Context,
+    job: Job,
+) {
+    let mut seen: HashMap<PathBuf, SystemTime> = HashMap::new();
+    let mut job = Some(job);
+    while !stop.load(Ordering::Relaxed) {
+        let mut changed = false;
+        let mut present = HashMap::new();
+        let paths = walk_roots(roots);
+        for (i, path) in paths.iter().enumerate() {
+            if let Some(job) = &job {
+                if job.is_cancelled() {
+                    return;
+                }
+                job.progress(i, paths.len());
+            }
+            if !symbols::supported(path) {
+                continue;
+            }
+            let Ok(metadata) = fs::metadata(path) else {
+                continue;
+            };
+            let Ok(modified) = metadata.modified() else {
+                continue;
+            };
+            if seen.get(path) != Some(&modified) && metadata.len() <= MAX_FILE_SIZE {
+                let symbols = fs::read_to_string(path)
+                    .map(|text| symbols::extract(path, &text))
+                    .unwrap_or_default();
+                if sender.send(Update::Indexed(path.clone(), symbols)).is_err() {
+                    return;
+                }
+                changed = true;
+            }
+            present.insert(path.clone(), modified);
+        }
+        for path in seen.keys().filter(|p| !present.contains_key(*p)) {
+            if sender.send(Update::Removed(path.clone())).is_err() {
+                return;
+            }
+            changed = true;
+        }
+        seen = present;
+        job = None;
+        if changed {
+            ctx.request_repaint();
+        }
+
+        let mut slept = Duration::ZERO;
+        while slept < RESCAN_INTERVAL && !stop.load(Ordering::Relaxed) {
+            thread::sleep(Duration::from_millis(250));
+            slept += Duration::from_millis(250);
+        }
+    }
+}
+
+impl TextEditorApp {
+    /// Keeps the index in step with the workspace folders and applies
+    /// pending updates.
+    pub(crate) fn sync_symbol_index(&mut self, ctx: &egui::Context) {
+        let current = self
+            .symbol_index
+            .as_ref()
+            .map_or(&[][..], |index| index.roots());
+        if current != self.folders.as_slice() {
+            self.symbol_index = (!self.folders.is_empty()).then(|| {
+                let job = self.jobs.start(tr("Indexing symbols"), true);
+                SymbolIndex::start(self.folders.clone(), ctx.clone(), job)
+            });
+        }
+        if let Some(index) = &mut self.symbol_index {
+            index.poll();
+        }
+    }
+
+    pub(crate) fn show_workspace_symbols(&mut self, ctx: &egui::Context) {
+        if !self.symbol_picker.open {
+            return;
+        }
+        let Some(index) = &self.symbol_index else {
+            self.symbol_picker.open = false;
+            return;
+        };
+        let roots = index.roots();
+        let items = index
+            .symbols()
+            .map(|(path, symbol)| {
+                let relative = display_path(roots, path);
+                PickerItem {
+                    value: (path.to_path_buf(), symbol.line),
+                    label: symbol.name.clone(),
+                    detail: format!("{}  {}:{}", symbol.kind.label(), relative, symbol.line + 1),
+                }
+            })
+            .collect();
+        if let Some((path, line)) =
+            self.symbol_picker
+                .show(ctx, &tr("Go to Symbol in Workspace"), items)
+        {
+            self.open_at(ctx, &path, line, 0);
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! Line-based symbol extraction, in the spirit of ctags: good enough to
+//! jump to functions, types and headings without a real parser.
+
+use std::path::Path;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SymbolKind {
+    Function,
+    Type,
+    Impl,
+    Module,
+    Constant,
+    Macro,
+    Heading,
+}
+
+impl SymbolKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            SymbolKind::Function => "fn",
+            SymbolKind::Type => "type",
+            SymbolKind::Impl => "impl",
+            SymbolKind::Module => "mod",
+            SymbolKind::Constant => "const",
+            SymbolKind::Macro => "macro",
+            SymbolKind::Heading => "heading",
+        }
+    }
+}
+
+/// A symbol definition. `line` is 0-based.
+#[derive(Clone, Debug)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub line: usize,
+}
+
+#[derive(Clone, Copy)]
+enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    Go,
+    Markdown,
+}
+
+fn language(path: &Path) -> Option<Language> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    Some(match ext.as_str() {
+        "rs" => Language::Rust,
+        "py" | "pyw" => Language::Python,
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" => Language::JavaScript,
+        "go" => Language::Go,
+        "md" | "markdown" => Language::Markdown,
+        _ => return None,
+    })
+}
+
+/// Whether symbols can be extracted from files like `path`.
+pub fn supported(path: &Path) -> bool {
+    language(path).is_some()
+}
+
+/// Extracts the symbols defined in `text`, choosing the rules by the file
+/// extension of `path`.
+pub fn extract(path: &Path, text: &str) -> Vec<Symbol> {
+    let Some(language) = language(path) else {
+        return Vec::new();
+    };
+    let parse = match language {
+        Language::Rust => rust_symbol,
+        Language::Python => python_symbol,
+        Language::JavaScript => javascript_symbol,
+        Language::Go => go_symbol,
+        Language::Markdown => markdown_symbol,
+    };
+    let mut in_fence = false;
+    text.lines()
+        .enumerate()
+        .filter_map(|(line, text)| {
+            if matches!(language, Language::Markdown) && text.trim_start().starts_with("```") {
+                in_fence = !in_fence;
+                return None;
+            }
+            if in_fence {
+                return None;
+            }
+            parse(text.trim_start()).map(|(kind, name)| Symbol { name, kind, line })
+        })
+        .collect()
+}
+
+/// The identifier at the start of `s`.
+fn ident(s: &str) -> Option<&str> {
+    let end = s
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .unwrap_or(s.len());
+    (end > 0).then(|| &s[..end])
+}
+
+/// Strips any of the `words` (followed by whitespace) from the front of `s`.
+fn strip_words<'a>(mut s: &'a str, words: &[&str]) -> &'a str {
+    'outer: loop {
+        for word in words {
+            if let Some(rest) = s.strip_prefix(word)
+                && rest.starts_with(char::is_whitespace)
+            {
+                s = rest.trim_start();
+                continue 'outer;
+            }
+        }
+        return s;
+    }
+}
+
+fn rust_symbol(line: &str) -> Option<(SymbolKind, String)> {
+    let mut s = line;
+    if let Some(rest) = s.strip_prefix("pub(") {
+        s = rest.split_once(')')?.1.trim_start();
+    }
+    s = strip_words(s, &["pub", "default", "async", "unsafe"]);
+    if let Some(rest) = s.strip_prefix("extern ") {
+        s = rest.trim_start();
+        if s.starts_with('"') {
+            s = s[1..].split_once('"')?.1.trim_start();
+        }
+    }
+    if let Some(rest) = s.strip_prefix("const ")
+        && rest.trim_start().starts_with("fn ")
+    {
+        s = rest.trim_start();
+    }
+    if let Some(rest) = s.strip_prefix("macro_rules!") {
+        return Some((SymbolKind::Macro, ident(rest.trim_start())?.to_string()));
+    }
+    if let Some(rest) = s.strip_prefix("impl") {
+        // `impl<T> Trait for Type` is named by everything up to the brace.
+        let rest = if rest.starts_with('<') {
+            skip_generics(rest)
+        } else {
+            rest.strip_prefix(char::is_whitespace)?
+        };
+        let name = rest.split(['{', ';']).next()?;
+        let name = name.split(" where").next()?.trim();
+        return (!name.is_empty()).then(|| (SymbolKind::Impl, name.to_string()));
+    }
+    let (keyword, rest) = s.split_once(char::is_whitespace)?;
+    let kind = match keyword {
+        "fn" => SymbolKind::Function,
+        "struct" | "enum" | "union" | "trait" | "type" => SymbolKind::Type,
+        "mod" => SymbolKind::Module,
+        "const" | "static" => SymbolKind::Constant,
+        _ => return None,
+    };
+    let rest = strip_words(rest.trim_start(), &["mut"]);
+    let name = ident(rest)?;
+    (name != "_").then(|| (kind, name.to_string()))
+}
+
+/// Skips a balanced `<...>` at the start of `s`.
+fn skip_generics(s: &str) -> &str {
+    let mut depth = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    return s[i + 1..].trim_start();
+                }
+            }
+            _ => {}
+        }
+    }
+    ""
+}
+
+fn python_symbol(line: &str) -> Option<(SymbolKind, String)> {
+    let s = strip_words(line, &["async"]);
+    let (kind, rest) = if let Some(rest) = s.strip_prefix("def ") {
+        (SymbolKind::Function, rest)
+    } else if let Some(rest) = s.strip_prefix("class ") {
+        (SymbolKind::Type, rest)
+    } else {
+        return None;
+    };
+    Some((kind, ident(rest.trim_start())?.to_string()))
+}
+
+fn javascript_symbol(line: &str) -> Option<(SymbolKind, String)> {
+    let s = strip_words(line, &["export", "default", "declare", "abstract", "async"]);
+    if let Some(rest) = s.strip_prefix("function") {
+        let rest = rest.trim_start_matches('*').trim_start();
+        return Some((SymbolKind::Function, ident(rest)?.to_string()));
+    }
+    let (keyword, rest) = s.split_once(char::is_whitespace)?;
+    let rest = rest.trim_start();
+    match keyword {
+        "class" | "interface" | "enum" => Some((SymbolKind::Type, ident(rest)?.to_string())),
+        "type" if rest.contains('=') => Some((SymbolKind::Type, ident(rest)?.to_string())),
+        // `const name = (...) => ...` and `const name = function ...`
+        "const" | "let" | "var" => {
+            let name = ident(rest)?;
+            let value = rest.split_once('=')?.1.trim_start();
+            let value = strip_words(value, &["async"]);
+            let is_function = value.starts_with("function")
+                || (value.starts_with('(') || ident(value).is_some()) && rest.contains("=>");
+            is_function.then(|| (SymbolKind::Function, name.to_string()))
+        }
+        _ => None,
+    }
+}
+
+fn go_symbol(line: &str) -> Option<(SymbolKind, String)> {
+    if let Some(rest) = line.strip_prefix("func ") {
+        let rest = rest.trim_start();
+        // Methods: `func (r *Receiver) Name(`.
+        let rest = if rest.starts_with('(') {
+            rest.split_once(')')?.1.trim_start()
+        } else {
+            rest
+        };
+        return Some((SymbolKind::Function, ident(rest)?.to_string()));
+    }
+    if let Some(rest) = line.strip_prefix("type ") {
+        return Some((SymbolKind::Type, ident(rest.trim_start())?.to_string()));
+    }
+    None
+}
+
+fn markdown_symbol(line: &str) -> Option<(SymbolKind, String)> {
+    markdown_heading(line).map(|(_, title)| (SymbolKind::Heading, title.to_string()))
+}
+
+/// The level and title of an ATX heading line (`## Title`).
+pub fn markdown_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let title = line[level..]
+        .strip_prefix(' ')?
+        .trim()
+        .trim_end_matches('#')
+        .trim();
+    (!title.is_empty()).then_some((level, title))
+}
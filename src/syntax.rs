@@ -0,0 +1,118 @@
+//! Choosing a syntax definition for a buffer.
+//!
+//! Detection order: the user's file associations, the file extension (or
+//! full file name for things like `Makefile`), an editor modeline, the
+//! shebang interpreter, and finally syntect's own first-line patterns.
+
+use crate::settings::Settings;
+use std::path::Path;
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+
+/// How many lines at the start and end of a file are scanned for modelines.
+const MODELINE_SCAN_LINES: usize = 5;
+
+pub fn detect(
+    settings: &Settings,
+    syntax_set: &SyntaxSet,
+    path: Option<&Path>,
+    content: &str,
+) -> Option<String> {
+    if let Some(path) = path {
+        if let Some(name) = settings.syntax_override(path)
+            && syntax_set.find_syntax_by_name(name).is_some()
+        {
+            return Some(name.to_string());
+        }
+        if let Some(syntax) = by_file_name(syntax_set, path) {
+            return Some(syntax.name.clone());
+        }
+    }
+    from_content(syntax_set, content).map(|s| s.name.clone())
+}
+
+fn by_file_name<'a>(syntax_set: &'a SyntaxSet, path: &Path) -> Option<&'a SyntaxReference> {
+    let file_name = path.file_name()?.to_string_lossy();
+    let extension = path.extension().map(|e| e.to_string_lossy());
+    extension
+        .and_then(|ext| syntax_set.find_syntax_by_extension(&ext))
+        .or_else(|| syntax_set.find_syntax_by_extension(&file_name))
+}
+
+/// Detects the language from a modeline or the first line of the buffer.
+fn from_content<'a>(syntax_set: &'a SyntaxSet, content: &str) -> Option<&'a SyntaxReference> {
+    let first_line = content.lines().next().unwrap_or_default();
+    modeline_language(content)
+        .and_then(|lang| syntax_set.find_syntax_by_token(&lang))
+        .or_else(|| {
+            shebang_interpreter(first_line)
+                .and_then(|interp| syntax_set.find_syntax_by_token(&interp))
+        })
+        .or_else(|| syntax_set.find_syntax_by_first_line(first_line))
+}
+
+/// Extracts the interpreter name from a shebang, e.g. `python` from
+/// `#!/usr/bin/env python3` or `bash` from `#!/bin/bash -e`.
+fn shebang_interpreter(first_line: &str) -> Option<String> {
+    let rest = first_line.strip_prefix("#!")?;
+    let mut words = rest.split_whitespace();
+    let mut program = words.next()?.rsplit('/').next()?;
+    if program == "env" {
+        program = words.find(|w| !w.starts_with('-') && !w.contains('='))?;
+    }
+    let name = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    match name {
+        "" => None,
+        "sh" | "zsh" | "ksh" | "dash" => Some("bash".to_string()),
+        "node" | "nodejs" | "deno" => Some("js".to_string()),
+        "runghc" | "runhaskell" => Some("haskell".to_string()),
+        _ => Some(name.to_string()),
+    }
+}
+
+/// Looks for a Vim (`vim: set ft=python:`) or Emacs (`-*- mode: python -*-`)
+/// modeline near the start or end of the file.
+fn modeline_language(content: &str) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let tail_start = lines
+        .len()
+        .saturating_sub(MODELINE_SCAN_LINES)
+        .max(MODELINE_SCAN_LINES);
+    let candidates = lines
+        .iter()
+        .take(MODELINE_SCAN_LINES)
+        .chain(lines.iter().skip(tail_start));
+    for line in candidates {
+        if let Some(lang) = vim_modeline(line).or_else(|| emacs_modeline(line)) {
+            return Some(lang);
+        }
+    }
+    None
+}
+
+fn vim_modeline(line: &str) -> Option<String> {
+    let start = ["vim:", "vi:", "ex:"]
+        .iter()
+        .find_map(|tag| line.find(tag).map(|i| i + tag.len()))?;
+    line[start..]
+        .split(|c: char| c.is_whitespace() || c == ':')
+        .find_map(|opt| {
+            opt.strip_prefix("ft=")
+                .or_else(|| opt.strip_prefix("filetype="))
+                .or_else(|| opt.strip_prefix("syntax="))
+        })
+        .filter(|lang| !lang.is_empty())
+        .map(str::to_string)
+}
+
+fn emacs_modeline(line: &str) -> Option<String> {
+    let start = line.find("-*-")? + 3;
+    let end = start + line[start..].find("-*-")?;
+    let body = line[start..end].trim();
+    if !body.contains(':') {
+        return Some(body.to_lowercase()).filter(|b| !b.is_empty());
+    }
+    body.split(';').find_map(|pair| {
+        let (key, value) = pair.split_once(':')?;
+        (key.trim().eq_ignore_ascii_case("mode")).then(|| value.trim().to_lowercase())
+    })
+}
@@ -0,0 +1,371 @@
+//! Tab groups: tabs can be put in named, colored groups ("frontend",
+//! "tests"), shown as a strip under their label. A group's tabs are saved
+//! or closed together from the tab context menu or the Tab Groups window.
+//!
+//! Groups and the files in them are kept in the data directory, so a file
+//! rejoins its group when it is opened again. Untitled and sensitive tabs
+//! stay in their group only until they are closed.
+
+use crate::i18n::{tr, trf};
+use crate::settings::data_dir;
+use crate::{FileTab, TextEditorApp, json_file};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+const TAB_GROUPS_FILE: &str = "tab_groups.json";
+
+/// The colors groups are given in turn, and can be switched between.
+const COLORS: &[(&str, [u8; 3])] = &[
+    ("Blue", [80, 130, 220]),
+    ("Green", [90, 180, 100]),
+    ("Orange", [230, 150, 60]),
+    ("Purple", [160, 100, 210]),
+    ("Red", [220, 80, 80]),
+    ("Teal", [60, 170, 170]),
+    ("Yellow", [210, 190, 60]),
+    ("Grey", [140, 140, 140]),
+];
+
+fn tab_groups_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join(TAB_GROUPS_FILE))
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TabGroup {
+    pub name: String,
+    pub color: [u8; 3],
+}
+
+impl TabGroup {
+    pub fn color32(&self) -> egui::Color32 {
+        let [r, g, b] = self.color;
+        egui::Color32::from_rgb(r, g, b)
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Stored {
+    groups: Vec<TabGroup>,
+    /// The group of each file put in one.
+    files: BTreeMap<PathBuf, String>,
+}
+
+/// What the tab context menu asked for.
+pub enum GroupAction {
+    /// Puts a tab in a group, or takes it out of its group.
+    Assign(String, Option<String>),
+    /// Creates a group and puts a tab in it.
+    Create(String, String),
+    Save(String),
+    Close(String),
+}
+
+#[derive(Default)]
+pub struct TabGroups {
+    stored: Stored,
+    /// The group of each untitled or sensitive tab, by tab key.
+    unsaved: HashMap<String, String>,
+    /// Whether the Tab Groups window is shown.
+    pub open: bool,
+    new_name: String,
+}
+
+impl TabGroups {
+    /// The stored groups, with a message to show if they cannot be read;
+    /// the file is then not saved over.
+    pub fn load() -> (Self, Option<String>) {
+        let (stored, error) = json_file::load_or_default(tab_groups_path().as_deref());
+        let groups = TabGroups {
+            stored,
+            ..Default::default()
+        };
+        (groups, error)
+    }
+
+    fn save(&self) {
+        if let Some(path) = tab_groups_path() {
+            let _ = json_file::save(&path, &self.stored);
+        }
+    }
+
+    /// Sensitive files are not written to the data directory, not even by
+    /// name.
+    fn stored_path(tab: &FileTab) -> Option<&PathBuf> {
+        tab.path.as_ref().filter(|_| !tab.sensitive)
+    }
+
+    pub fn group_of(&self, key: &str, tab: &FileTab) -> Option<&TabGroup> {
+        let name = match Self::stored_path(tab) {
+            Some(path) => self.stored.files.get(path),
+            None => self.unsaved.get(key),
+        }?;
+        self.stored.groups.iter().find(|group| &group.name == name)
+    }
+
+    fn assign(&mut self, key: &str, tab: &FileTab, group: Option<String>) {
+        match (Self::stored_path(tab), group) {
+            (Some(path), Some(group)) => {
+                self.stored.files.insert(path.clone(), group);
+            }
+            (Some(path), None) => {
+                self.stored.files.remove(path);
+            }
+            (None, Some(group)) => {
+                self.unsaved.insert(key.to_string(), group);
+            }
+            (None, None) => {
+                self.unsaved.remove(key);
+            }
+        }
+        self.save();
+    }
+
+    /// Adds a group named `name` in the next color, unless one exists.
+    fn create(&mut self, name: &str) {
+        if self.stored.groups.iter().any(|group| group.name == name) {
+            return;
+        }
+        let (_, color) = COLORS[self.stored.groups.len() % COLORS.len()];
+        self.stored.groups.push(TabGroup {
+            name: name.to_string(),
+            color,
+        });
+        self.save();
+    }
+
+    fn delete(&mut self, name: &str) {
+        self.stored.groups.retain(|group| group.name != name);
+        self.stored.files.retain(|_, group| group != name);
+        self.unsaved.retain(|_, group| group != name);
+        self.save();
+    }
+
+    /// Forgets the group of a closed untitled or sensitive tab.
+    pub fn forget_tab(&mut self, key: &str) {
+        self.unsaved.remove(key);
+    }
+
+    /// The tab group entries of a tab's context menu.
+    pub fn menu(&mut self, ui: &mut egui::Ui, key: &str, tab: &FileTab) -> Option<GroupAction> {
+        let current = self.group_of(key, tab).map(|group| group.name.clone());
+        let mut action = None;
+        ui.menu_button(tr("Tab Group"), |ui| {
+            for group in &self.stored.groups {
+                let selected = current.as_ref() == Some(&group.name);
+                let label = egui::RichText::new(format!("■ {}", group.name));
+                if ui.radio(selected, label.color(group.color32())).clicked() {
+                    action = Some(GroupAction::Assign(
+                        key.to_string(),
+                        (!selected).then(|| group.name.clone()),
+                    ));
+                    ui.close_menu();
+                }
+            }
+            if current.is_some() && ui.button(tr("Remove from Group")).clicked() {
+                action = Some(GroupAction::Assign(key.to_string(), None));
+                ui.close_menu();
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.new_name)
+                        .hint_text(tr("New group"))
+                        .desired_width(120.0),
+                );
+                let enter = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                let name = self.new_name.trim().to_string();
+                if (ui
+                    .add_enabled(!name.is_empty(), egui::Button::new(tr("Add")))
+                    .clicked()
+                    || enter)
+                    && !name.is_empty()
+                {
+                    action = Some(GroupAction::Create(key.to_string(), name));
+                    self.new_name.clear();
+                    ui.close_menu();
+                }
+            });
+        });
+        if let Some(group) = current {
+            if ui
+                .button(trf("Save Group {name}", &[("name", &group)]))
+                .clicked()
+            {
+                action = Some(GroupAction::Save(group.clone()));
+                ui.close_menu();
+            }
+            if ui
+                .button(trf("Close Group {name}", &[("name", &group)]))
+                .clicked()
+            {
+                action = Some(GroupAction::Close(group));
+                ui.close_menu();
+            }
+        }
+        action
+    }
+}
+
+impl TextEditorApp {
+    /// The open tabs in group `name`, in tab bar order.
+    fn group_tabs(&self, name: &str) -> Vec<String> {
+        self.open_order
+            .iter()
+            .filter(|key| {
+                self.tab_groups
+                    .group_of(key, &self.tabs[*key])
+                    .is_some_and(|group| group.name == name)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Saves the modified tabs of group `name`, stopping at the first one
+    /// that is not saved.
+    fn save_group(&mut self, name: &str) {
+        let active = self.active_tab.clone();
+        for key in self.group_tabs(name) {
+            if !self.tabs[&key].is_modified() {
+                continue;
+            }
+            self.active_tab = Some(key);
+            if !self.save_active() {
+                return;
+            }
+        }
+        self.active_tab = active;
+    }
+
+    /// Closes the tabs of group `name`, keeping those with unsaved changes.
+    fn close_group(&mut self, name: &str) {
+        let mut kept = 0;
+        for key in self.group_tabs(name) {
+            if self.tabs[&key].is_modified() {
+                kept += 1;
+            } else {
+                self.close_tab(&key);
+            }
+        }
+        if kept > 0 {
+            self.notifications.warning(trf(
+                "Kept {count} tabs with unsaved changes open",
+                &[("count", &kept)],
+            ));
+        }
+    }
+
+    pub(crate) fn apply_group_action(&mut self, action: GroupAction) {
+        match action {
+            GroupAction::Assign(key, group) => {
+                if let Some(tab) = self.tabs.get(&key) {
+                    self.tab_groups.assign(&key, tab, group);
+                }
+            }
+            GroupAction::Create(key, name) => {
+                self.tab_groups.create(&name);
+                if let Some(tab) = self.tabs.get(&key) {
+                    self.tab_groups.assign(&key, tab, Some(name));
+                }
+            }
+            GroupAction::Save(name) => self.save_group(&name),
+            GroupAction::Close(name) => self.close_group(&name),
+        }
+    }
+
+    pub(crate) fn show_tab_groups_window(&mut self, ctx: &egui::Context) {
+        if !self.tab_groups.open {
+            return;
+        }
+        let mut open = true;
+        let mut create = false;
+        let mut action = None;
+        let mut delete = None;
+        let counts: Vec<usize> = self
+            .tab_groups
+            .stored
+            .groups
+            .iter()
+            .map(|group| self.group_tabs(&group.name).len())
+            .collect();
+        let groups = &mut self.tab_groups;
+        let mut recolored = false;
+        egui::Window::new(tr("Tab Groups"))
+            .default_width(360.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if groups.stored.groups.is_empty() {
+                    ui.weak(tr("No groups yet. Add one, or use a tab's context menu."));
+                }
+                egui::Grid::new("tab_groups")
+                    .num_columns(4)
+                    .spacing([8.0, 4.0])
+                    .show(ui, |ui| {
+                        for (group, count) in groups.stored.groups.iter_mut().zip(&counts) {
+                            egui::ComboBox::from_id_source(("tab_group_color", &group.name))
+                                .width(40.0)
+                                .selected_text(egui::RichText::new("■").color(group.color32()))
+                                .show_ui(ui, |ui| {
+                                    for (name, color) in COLORS {
+                                        let [r, g, b] = *color;
+                                        let label = egui::RichText::new(format!("■ {}", tr(name)))
+                                            .color(egui::Color32::from_rgb(r, g, b));
+                                        if ui
+                                            .selectable_label(group.color == *color, label)
+                                            .clicked()
+                                        {
+                                            group.color = *color;
+                                            recolored = true;
+                                        }
+                                    }
+                                });
+                            ui.label(&group.name);
+                            ui.weak(trf("{count} open", &[("count", count)]));
+                            ui.horizontal(|ui| {
+                                if ui.button(tr("Save")).clicked() {
+                                    action = Some(GroupAction::Save(group.name.clone()));
+                                }
+                                if ui.button(tr("Close")).clicked() {
+                                    action = Some(GroupAction::Close(group.name.clone()));
+                                }
+                                if ui
+                                    .button(tr("Delete"))
+                                    .on_hover_text(tr("Delete the group; its tabs stay open"))
+                                    .clicked()
+                                {
+                                    delete = Some(group.name.clone());
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut groups.new_name)
+                            .hint_text(tr("Name, e.g. tests"))
+                            .desired_width(200.0),
+                    );
+                    create |=
+                        response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    create |= ui.button(tr("Add Group")).clicked();
+                });
+            });
+        if recolored {
+            groups.save();
+        }
+        let name = groups.new_name.trim().to_string();
+        if create && !name.is_empty() {
+            groups.create(&name);
+            groups.new_name.clear();
+        }
+        if let Some(name) = delete {
+            groups.delete(&name);
+        }
+        if let Some(action) = action {
+            self.apply_group_action(action);
+        }
+        self.tab_groups.open = open;
+    }
+}
@@ -0,0 +1,247 @@
+//! Tag matching for XML and HTML: the tag pair around the caret is
+//! outlined, typing `</` closes the innermost open element, and commands
+//! jump between a tag and its partner or select an element's contents.
+//!
+//! Tags are found by scanning, not parsing, so a stray `<` in a script can
+//! confuse the matching until the next tag.
+
+use crate::TextEditorApp;
+use crate::editing::{byte_to_char, char_to_byte};
+use crate::motion::take_text;
+use eframe::egui;
+use egui::text::CCursor;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// HTML elements that never have a closing tag.
+pub const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Whether files with `syntax` are made of tags.
+pub fn is_markup(syntax: Option<&str>) -> bool {
+    syntax.is_some_and(|name| name == "XML" || name.starts_with("HTML"))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Open,
+    Close,
+}
+
+/// An opening or closing tag: its byte range including the angle brackets,
+/// and the byte range of its name.
+struct Tag {
+    kind: Kind,
+    range: Range<usize>,
+    name: Range<usize>,
+}
+
+/// The opening and closing tags of `text`, in order. Self-closing tags,
+/// void elements, comments and declarations are left out.
+fn tags(text: &str) -> Vec<Tag> {
+    let mut tags = Vec::new();
+    let mut from = 0;
+    while let Some(start) = text[from..].find('<').map(|i| from + i) {
+        let rest = &text[start..];
+        if rest.starts_with("<!--") {
+            from = rest.find("-->").map_or(text.len(), |i| start + i + 3);
+            continue;
+        }
+        let mut quote = None;
+        let Some(end) = rest
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| match quote {
+                Some(q) if *c == q => {
+                    quote = None;
+                    false
+                }
+                Some(_) => false,
+                None if *c == '"' || *c == '\'' => {
+                    quote = Some(*c);
+                    false
+                }
+                None => *c == '>' || *c == '<',
+            })
+            .filter(|(_, c)| *c == '>')
+            .map(|(i, _)| start + i + 1)
+        else {
+            from = start + 1;
+            continue;
+        };
+        from = end;
+        let tag = &text[start..end];
+        let (kind, name_start) = if tag.starts_with("</") {
+            (Kind::Close, start + 2)
+        } else {
+            (Kind::Open, start + 1)
+        };
+        let name_len = text[name_start..end]
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '_' | ':' | '.')))
+            .unwrap_or(0);
+        let name = name_start..name_start + name_len;
+        if name.is_empty()
+            || (kind == Kind::Open
+                && (tag.ends_with("/>")
+                    || VOID_ELEMENTS.contains(&text[name.clone()].to_ascii_lowercase().as_str())))
+        {
+            continue;
+        }
+        tags.push(Tag {
+            kind,
+            range: start..end,
+            name,
+        });
+    }
+    tags
+}
+
+/// The elements of `text` as pairs of opening and closing tags. An opening
+/// tag that is never closed is skipped.
+fn pairs(text: &str) -> Vec<(Tag, Tag)> {
+    let names_match =
+        |a: &Tag, b: &Tag| text[a.name.clone()].eq_ignore_ascii_case(&text[b.name.clone()]);
+    let mut open: Vec<Tag> = Vec::new();
+    let mut pairs = Vec::new();
+    for tag in tags(text) {
+        match tag.kind {
+            Kind::Open => open.push(tag),
+            Kind::Close => {
+                if let Some(index) = open.iter().rposition(|o| names_match(o, &tag)) {
+                    open.truncate(index + 1);
+                    pairs.push((open.pop().unwrap(), tag));
+                }
+            }
+        }
+    }
+    pairs
+}
+
+/// The pair with a tag at byte offset `pos`, inner tags winning.
+fn pair_at(text: &str, pos: usize) -> Option<(Tag, Tag)> {
+    let inside = |tag: &Tag| tag.range.start < pos && pos < tag.range.end;
+    pairs(text)
+        .into_iter()
+        .find(|(open, close)| inside(open) || inside(close))
+}
+
+/// The name of the innermost element still open at byte offset `pos`.
+fn unclosed_at(text: &str, pos: usize) -> Option<&str> {
+    let mut open: Vec<Tag> = Vec::new();
+    for tag in tags(&text[..pos]) {
+        match tag.kind {
+            Kind::Open => open.push(tag),
+            Kind::Close => {
+                let name = &text[tag.name.clone()];
+                if let Some(index) = open
+                    .iter()
+                    .rposition(|o| text[o.name.clone()].eq_ignore_ascii_case(name))
+                {
+                    open.truncate(index);
+                }
+            }
+        }
+    }
+    open.last().map(|tag| &text[tag.name.clone()])
+}
+
+/// Outlines the names of the tag pair around the caret at character index
+/// `caret`.
+pub fn paint_matching(
+    ui: &egui::Ui,
+    text: &str,
+    caret: usize,
+    galley: &Arc<egui::Galley>,
+    galley_pos: egui::Pos2,
+) {
+    let Some((open, close)) = pair_at(text, char_to_byte(text, caret)) else {
+        return;
+    };
+    let stroke = ui.visuals().widgets.noninteractive.fg_stroke;
+    for name in [open.name, close.name] {
+        let rect_at = |byte: usize| {
+            galley
+                .pos_from_ccursor(CCursor::new(byte_to_char(text, byte)))
+                .translate(galley_pos.to_vec2())
+        };
+        let rect = rect_at(name.start).union(rect_at(name.end));
+        ui.painter().rect_stroke(rect.expand(1.0), 2.0, stroke);
+    }
+}
+
+impl TextEditorApp {
+    /// The active tab's text and selection, if it is an XML or HTML tab.
+    fn markup(&self, ctx: &egui::Context) -> Option<(&str, Range<usize>)> {
+        let tab = self.tabs.get(self.active_tab.as_ref()?)?;
+        if !is_markup(tab.syntax.as_deref()) {
+            return None;
+        }
+        let selection = self.selection(ctx)?;
+        let text = tab.content.as_str();
+        Some((
+            text,
+            char_to_byte(text, selection.start)..char_to_byte(text, selection.end),
+        ))
+    }
+
+    /// Typing `/` right after `<` completes the closing tag of the
+    /// innermost open element.
+    pub(crate) fn handle_tag_close(&mut self, ctx: &egui::Context) {
+        if !self.editor_has_focus(ctx) {
+            return;
+        }
+        let Some((text, selection)) = self.markup(ctx) else {
+            return;
+        };
+        if !selection.is_empty() || !text[..selection.start].ends_with('<') {
+            return;
+        }
+        let Some(name) = unclosed_at(text, selection.start - 1) else {
+            return;
+        };
+        let closing = format!("/{name}>");
+        if take_text(ctx, "/") {
+            self.insert_text(ctx, &closing);
+        }
+    }
+
+    /// Moves the caret from a tag to its partner.
+    pub(crate) fn go_to_matching_tag(&mut self, ctx: &egui::Context) {
+        let Some((text, selection)) = self.markup(ctx) else {
+            return;
+        };
+        let Some((open, close)) = pair_at(text, selection.start) else {
+            return;
+        };
+        let target = if open.range.contains(&selection.start) {
+            close.name.start
+        } else {
+            open.name.start
+        };
+        let target = byte_to_char(text, target);
+        self.set_selection(ctx, target..target);
+        self.scroll_to_caret = true;
+    }
+
+    /// Selects the contents of the innermost element around the selection,
+    /// or of the one around that when they are selected already.
+    pub(crate) fn select_tag_contents(&mut self, ctx: &egui::Context) {
+        let Some((text, selection)) = self.markup(ctx) else {
+            return;
+        };
+        let Some(contents) = pairs(text)
+            .into_iter()
+            .map(|(open, close)| open.range.end..close.range.start)
+            .filter(|c| {
+                c.start <= selection.start && selection.end <= c.end && c.len() > selection.len()
+            })
+            .min_by_key(|c| c.len())
+        else {
+            return;
+        };
+        let range = byte_to_char(text, contents.start)..byte_to_char(text, contents.end);
+        self.set_selection(ctx, range);
+    }
+}
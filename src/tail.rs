@@ -0,0 +1,199 @@
+//! Follow mode for log files: the tab tails its file, appending only the
+//! bytes written since the last poll, and is drawn as a virtualized list of
+//! lines that sticks to the bottom unless the user scrolls up.
+
+use crate::i18n::trf;
+use crate::stale_save::DiskStamp;
+use crate::{FileTab, TextEditorApp};
+use eframe::egui;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+pub struct Follow {
+    /// Bytes of the file already in the buffer. Only whole lines are read,
+    /// so this always ends on a line break.
+    offset: u64,
+    /// Byte offset of every line start in the buffer.
+    line_starts: Vec<usize>,
+    last_poll: Instant,
+    /// Set while the file cannot be read, so the failure is reported once.
+    failing: bool,
+}
+
+fn line_starts(text: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+impl Follow {
+    fn new(content: &str, offset: u64) -> Self {
+        Self {
+            offset,
+            line_starts: line_starts(content),
+            last_poll: Instant::now(),
+            failing: false,
+        }
+    }
+
+    /// Appends complete lines written to `path` since the last poll. Returns
+    /// false if the file shrank (e.g. was rotated) and must be reloaded.
+    fn poll(&mut self, path: &Path, content: &mut String) -> io::Result<bool> {
+        self.last_poll = Instant::now();
+        let len = fs::metadata(path)?.len();
+        if len < self.offset {
+            return Ok(false);
+        }
+        if len == self.offset {
+            return Ok(true);
+        }
+        let mut bytes = Vec::new();
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(self.offset))?;
+        file.take(len - self.offset).read_to_end(&mut bytes)?;
+        let Some(end) = bytes.iter().rposition(|&b| b == b'\n') else {
+            return Ok(true);
+        };
+        let text = String::from_utf8_lossy(&bytes[..=end]);
+        let base = content.len();
+        content.push_str(&text);
+        self.line_starts
+            .extend(text.match_indices('\n').map(|(i, _)| base + i + 1));
+        self.offset += end as u64 + 1;
+        Ok(true)
+    }
+
+    fn line<'a>(&self, content: &'a str, index: usize) -> &'a str {
+        let start = self.line_starts[index];
+        let end = self
+            .line_starts
+            .get(index + 1)
+            .map_or(content.len(), |&next| next - 1);
+        &content[start..end]
+    }
+}
+
+/// Records that `tab` holds what is on disk, so it does not show as
+/// modified once it is no longer followed.
+fn loaded(tab: &mut FileTab) {
+    tab.mark_saved();
+    tab.disk_stamp = tab.path.as_deref().and_then(DiskStamp::read);
+}
+
+impl TextEditorApp {
+    pub(crate) fn toggle_follow(&mut self) {
+        let Some(tab) = self
+            .active_tab
+            .as_ref()
+            .and_then(|key| self.tabs.get_mut(key))
+        else {
+            return;
+        };
+        if tab.follow.take().is_some() {
+            return;
+        }
+        let Some(path) = &tab.path else {
+            return;
+        };
+        if tab.is_modified() {
+            self.notifications.warning(trf(
+                "Save or revert {path} before following it",
+                &[("path", &path.display())],
+            ));
+            return;
+        }
+        // Start from what is on disk, cut back to the last complete line.
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                self.notifications.error(trf(
+                    "Could not follow {path}: {error}",
+                    &[("path", &path.display()), ("error", &err)],
+                ));
+                return;
+            }
+        };
+        let end = bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+        tab.content = String::from_utf8_lossy(&bytes[..end]).into_owned();
+        tab.follow = Some(Follow::new(&tab.content, end as u64));
+        loaded(tab);
+    }
+
+    /// Polls every followed file that is due.
+    pub(crate) fn poll_followed_files(&mut self, ctx: &egui::Context) {
+        let interval = self.poll_interval(POLL_INTERVAL);
+        let mut reload = Vec::new();
+        let mut appended = Vec::new();
+        for (key, tab) in &mut self.tabs {
+            let (Some(follow), Some(path)) = (&mut tab.follow, &tab.path) else {
+                continue;
+            };
+            if follow.last_poll.elapsed() >= interval {
+                let len = tab.content.len();
+                match follow.poll(path, &mut tab.content) {
+                    Ok(true) => {
+                        follow.failing = false;
+                        if tab.content.len() != len {
+                            appended.push(key.clone());
+                        }
+                    }
+                    Ok(false) => reload.push(key.clone()),
+                    Err(err) => {
+                        if !follow.failing {
+                            self.notifications.warning(trf(
+                                "Stopped receiving updates from {path}: {error}",
+                                &[("path", &path.display()), ("error", &err)],
+                            ));
+                        }
+                        follow.failing = true;
+                    }
+                }
+            }
+            ctx.request_repaint_after(interval);
+        }
+        for key in reload {
+            if let Some(tab) = self.tabs.get_mut(&key) {
+                tab.content.clear();
+                tab.follow = Some(Follow::new("", 0));
+                loaded(tab);
+            }
+        }
+        for key in appended {
+            if let Some(tab) = self.tabs.get_mut(&key) {
+                loaded(tab);
+            }
+        }
+    }
+
+    /// Draws a followed tab: only the visible lines are laid out, so huge
+    /// logs stay responsive.
+    pub(crate) fn show_follow_view(&mut self, ui: &mut egui::Ui) {
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        let Some(follow) = &tab.follow else {
+            return;
+        };
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        // The last line start is the empty line after the final newline.
+        let rows = follow.line_starts.len().saturating_sub(1);
+        egui::ScrollArea::both()
+            .id_source(("follow_scroll", &tab.title))
+            .auto_shrink([false, false])
+            .stick_to_bottom(true)
+            .show_rows(ui, row_height, rows, |ui, range| {
+                for index in range {
+                    ui.add(
+                        egui::Label::new(
+                            egui::RichText::new(follow.line(&tab.content, index)).monospace(),
+                        )
+                        .wrap(false),
+                    );
+                }
+            });
+    }
+}
@@ -0,0 +1,167 @@
+//! "New File from Template": starts an untitled buffer from a built-in or
+//! user template. User templates are the files in the `templates` folder of
+//! the config directory, named after the file they create. Templates may use
+//! `{{date}}`, `{{year}}`, `{{filename}}`, `{{name}}` (the file name without
+//! extension) and `{{author}}`.
+
+use crate::i18n::tr;
+use crate::picker::{Picker, PickerItem};
+use crate::settings::config_dir;
+use crate::{FileTab, TextEditorApp, git, journal, prose};
+use eframe::egui;
+use std::fs;
+use std::path::Path;
+
+const TEMPLATES_DIR: &str = "templates";
+
+/// (name, file name, content)
+const BUILT_IN: &[(&str, &str, &str)] = &[
+    (
+        "Rust main",
+        "main.rs",
+        "//! {{filename}}, created {{date}} by {{author}}.\n\nfn main() {\n    println!(\"Hello, world!\");\n}\n",
+    ),
+    (
+        "Cargo.toml",
+        "Cargo.toml",
+        "[package]\nname = \"{{name}}\"\nversion = \"0.1.0\"\nedition = \"2024\"\nauthors = [\"{{author}}\"]\n\n[dependencies]\n",
+    ),
+    (
+        "Python script",
+        "script.py",
+        "#!/usr/bin/env python3\n\"\"\"{{filename}}, created {{date}} by {{author}}.\"\"\"\n\n\ndef main():\n    pass\n\n\nif __name__ == \"__main__\":\n    main()\n",
+    ),
+    (
+        "MIT license",
+        "LICENSE",
+        "MIT License\n\nCopyright (c) {{year}} {{author}}\n\n\
+Permission is hereby granted, free of charge, to any person obtaining a copy\n\
+of this software and associated documentation files (the \"Software\"), to deal\n\
+in the Software without restriction, including without limitation the rights\n\
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell\n\
+copies of the Software, and to permit persons to whom the Software is\n\
+furnished to do so, subject to the following conditions:\n\n\
+The above copyright notice and this permission notice shall be included in all\n\
+copies or substantial portions of the Software.\n\n\
+THE SOFTWARE IS PROVIDED \"AS IS\", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR\n\
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,\n\
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE\n\
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER\n\
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,\n\
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE\n\
+SOFTWARE.\n",
+    ),
+    (
+        "Markdown note",
+        "note.md",
+        "---\ntitle: {{name}}\ndate: {{date}}\nauthor: {{author}}\ntags: []\n---\n\n# {{name}}\n\n",
+    ),
+];
+
+#[derive(Clone)]
+pub struct Template {
+    pub name: String,
+    pub file_name: String,
+    pub content: String,
+}
+
+/// The built-in templates followed by the user's.
+pub fn all() -> Vec<Template> {
+    let mut templates: Vec<Template> = BUILT_IN
+        .iter()
+        .map(|(name, file_name, content)| Template {
+            name: tr(name),
+            file_name: file_name.to_string(),
+            content: content.to_string(),
+        })
+        .collect();
+    if let Some(entries) = config_dir().and_then(|dir| fs::read_dir(dir.join(TEMPLATES_DIR)).ok()) {
+        let mut user: Vec<Template> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .filter_map(|path| {
+                let file_name = path.file_name()?.to_string_lossy().to_string();
+                Some(Template {
+                    name: file_name.clone(),
+                    file_name,
+                    content: fs::read_to_string(&path).ok()?,
+                })
+            })
+            .collect();
+        user.sort_by(|a, b| a.name.cmp(&b.name));
+        templates.extend(user);
+    }
+    templates
+}
+
+/// `content` with the placeholders filled in.
+pub fn expand(content: &str, file_name: &str, author: &str) -> String {
+    let now = chrono::Local::now();
+    let stem = Path::new(file_name)
+        .file_stem()
+        .map_or(file_name.to_string(), |stem| {
+            stem.to_string_lossy().to_string()
+        });
+    content
+        .replace("{{date}}", &now.format("%Y-%m-%d").to_string())
+        .replace("{{year}}", &now.format("%Y").to_string())
+        .replace("{{filename}}", file_name)
+        .replace("{{name}}", &stem)
+        .replace("{{author}}", author)
+}
+
+impl TextEditorApp {
+    /// The author name from the preferences, falling back to git's
+    /// `user.name`.
+    pub(crate) fn template_author(&self) -> String {
+        let author = self.settings.author.trim();
+        if !author.is_empty() {
+            return author.to_string();
+        }
+        let dir = self.folder_path().unwrap_or_else(|| ".".into());
+        git::run(&dir, &["config", "user.name"])
+            .map(|name| name.trim().to_string())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn show_template_picker(&mut self, ctx: &egui::Context) {
+        if !self.template_picker.open {
+            return;
+        }
+        let items = all()
+            .into_iter()
+            .map(|template| PickerItem {
+                label: template.name.clone(),
+                detail: template.file_name.clone(),
+                value: template,
+            })
+            .collect();
+        let Some(template) = self
+            .template_picker
+            .show(ctx, &tr("New file from template"), items)
+        else {
+            return;
+        };
+        let content = expand(
+            &template.content,
+            &template.file_name,
+            &self.template_author(),
+        );
+        let path = Path::new(&template.file_name);
+        let syntax = self.detect_syntax(Some(path), &content);
+        let prose = self.settings.prose_by_default && prose::is_prose_file(path);
+        self.add_tab(FileTab {
+            title: template.file_name.clone(),
+            content,
+            syntax,
+            prose,
+            journal_id: Some(journal::Journal::new_id()),
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn open_template_picker(&mut self) {
+        self.template_picker = Picker::opened();
+    }
+}
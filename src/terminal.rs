@@ -0,0 +1,142 @@
+//! Shell integration: opening the system terminal in the project or next to
+//! the active file, and the environment of everything the editor starts.
+//!
+//! Processes get the editor's own environment plus the variables of the
+//! `.env` file in their workspace folder and those set in Preferences, in
+//! that order, so the user's own win. The `.env` file of a folder in
+//! restricted mode is skipped, see [`crate::trust`].
+
+use crate::TextEditorApp;
+use crate::i18n::trf;
+use crate::run::{shell, shell_quote};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Terminals tried in order on Linux and the BSDs when none is configured.
+const TERMINALS: &[&str] = &[
+    "x-terminal-emulator",
+    "gnome-terminal",
+    "konsole",
+    "xfce4-terminal",
+    "alacritty",
+    "kitty",
+    "xterm",
+];
+
+/// `NAME=value` lines, skipping blank lines and `#` comments. An `export`
+/// prefix and quotes around the value are dropped, as shells would.
+pub fn parse_env(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (name, value) = line.split_once('=')?;
+            let value = value.trim();
+            let value = ['"', '\'']
+                .iter()
+                .find_map(|q| value.strip_prefix(*q)?.strip_suffix(*q))
+                .unwrap_or(value);
+            Some((name.trim().to_string(), value.to_string()))
+        })
+        .filter(|(name, _)| !name.is_empty())
+        .collect()
+}
+
+/// Starts the configured terminal, or the platform's, in `dir`.
+fn spawn_terminal(command: &str, dir: &Path, env: &[(String, String)]) -> io::Result<()> {
+    let start = |mut command: Command| {
+        command
+            .current_dir(dir)
+            .envs(env.iter().map(|(k, v)| (k, v)))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map(drop)
+    };
+    if !command.trim().is_empty() {
+        let command = command.replace("{dir}", &shell_quote(&dir.to_string_lossy()));
+        return start(shell(&command));
+    }
+    if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", "cmd"]);
+        return start(command);
+    }
+    if cfg!(target_os = "macos") {
+        let mut command = Command::new("open");
+        command.args(["-a", "Terminal"]).arg(dir);
+        return start(command);
+    }
+    let from_env = std::env::var("TERMINAL").ok();
+    let mut error = io::Error::from(io::ErrorKind::NotFound);
+    for program in from_env
+        .iter()
+        .map(String::as_str)
+        .chain(TERMINALS.iter().copied())
+    {
+        match start(Command::new(program)) {
+            Ok(()) => return Ok(()),
+            Err(err) => error = err,
+        }
+    }
+    Err(error)
+}
+
+impl TextEditorApp {
+    /// The variables added to the environment of processes working in
+    /// `dir`.
+    pub(crate) fn process_env(&self, dir: &Path) -> Vec<(String, String)> {
+        let mut env = self
+            .folders
+            .iter()
+            .find(|root| dir.starts_with(root))
+            .filter(|root| self.is_trusted(root))
+            .and_then(|root| fs::read_to_string(root.join(".env")).ok())
+            .map(|text| parse_env(&text))
+            .unwrap_or_default();
+        env.extend(parse_env(&self.settings.environment.join("\n")));
+        env
+    }
+
+    fn open_terminal(&mut self, dir: &Path) {
+        let env = self.process_env(dir);
+        if let Err(err) = spawn_terminal(&self.settings.terminal_command, dir, &env) {
+            self.notifications.error(trf(
+                "Could not open a terminal: {error}",
+                &[("error", &err)],
+            ));
+        }
+    }
+
+    /// Opens a terminal in the workspace folder of the active file, or the
+    /// first one.
+    pub(crate) fn open_terminal_at_root(&mut self) {
+        let file = self
+            .active_tab
+            .as_ref()
+            .and_then(|key| self.tabs[key].path.clone());
+        let root = file
+            .and_then(|file| self.folders.iter().find(|root| file.starts_with(root)))
+            .or(self.folders.first())
+            .cloned();
+        if let Some(root) = root {
+            self.open_terminal(&root);
+        }
+    }
+
+    pub(crate) fn open_terminal_at_file(&mut self) {
+        if let Some(dir) = self
+            .active_tab
+            .as_ref()
+            .and_then(|key| self.tabs[key].path.as_deref())
+            .and_then(Path::parent)
+            .map(Path::to_path_buf)
+        {
+            self.open_terminal(&dir);
+        }
+    }
+}
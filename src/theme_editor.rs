@@ -0,0 +1,507 @@
+//! Custom color themes. The theme editor starts from the light or dark
+//! interface and one of the built-in syntect themes, and overrides the
+//! colors of panels, the editor background, the selection and the gutter,
+//! and of syntax token kinds. Changes are previewed as they are made.
+//!
+//! Saved themes are JSON files in the `themes` folder of the data directory
+//! and are picked from View > Color Theme next to the built-in ones.
+
+use crate::highlight::HighlightCache;
+use crate::i18n::{tr, trf};
+use crate::settings::data_dir;
+use crate::{DARK_THEME, LIGHT_THEME, TextEditorApp, atomic_write};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use syntect::highlighting::{Color, ScopeSelectors, StyleModifier, Theme, ThemeItem};
+use syntect::parsing::Scope;
+
+const THEMES_DIR: &str = "themes";
+
+/// The interface colors a theme can set, by key.
+const UI_SLOTS: &[(&str, &str)] = &[
+    ("panel", "Panels"),
+    ("window", "Windows and menus"),
+    ("editor", "Editor background"),
+    ("text", "Text"),
+    ("selection", "Selection"),
+    ("gutter", "Gutter"),
+    ("link", "Links"),
+];
+
+/// The token kinds listed in the editor, by scope.
+const TOKEN_SLOTS: &[(&str, &str)] = &[
+    ("comment", "Comments"),
+    ("string", "Strings"),
+    ("constant.numeric", "Numbers"),
+    ("constant.language", "Constants"),
+    ("keyword", "Keywords"),
+    ("storage", "Storage and modifiers"),
+    ("entity.name.function", "Function names"),
+    ("entity.name.type", "Type names"),
+    ("support", "Library names"),
+    ("variable", "Variables"),
+    ("punctuation", "Punctuation"),
+    ("markup.heading", "Headings"),
+];
+
+fn themes_dir() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join(THEMES_DIR))
+}
+
+/// The file a theme named `name` is saved in.
+fn theme_file(name: &str) -> Option<PathBuf> {
+    let stem: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_' | ' ') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    themes_dir().map(|dir| dir.join(format!("{}.json", stem.trim())))
+}
+
+fn to_color32([r, g, b]: [u8; 3]) -> egui::Color32 {
+    egui::Color32::from_rgb(r, g, b)
+}
+
+fn to_syntect([r, g, b]: [u8; 3]) -> Color {
+    Color { r, g, b, a: 255 }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct CustomTheme {
+    pub name: String,
+    /// Built on the dark interface rather than the light one.
+    pub dark: bool,
+    /// The syntect theme token colors start from.
+    pub base: String,
+    /// Interface colors by [`UI_SLOTS`] key.
+    pub ui: BTreeMap<String, [u8; 3]>,
+    /// Token colors by scope.
+    pub tokens: BTreeMap<String, [u8; 3]>,
+}
+
+impl Default for CustomTheme {
+    fn default() -> Self {
+        CustomTheme {
+            name: String::new(),
+            dark: false,
+            base: LIGHT_THEME.to_string(),
+            ui: BTreeMap::new(),
+            tokens: BTreeMap::new(),
+        }
+    }
+}
+
+impl CustomTheme {
+    pub fn gutter(&self) -> Option<egui::Color32> {
+        self.ui.get("gutter").copied().map(to_color32)
+    }
+
+    fn visuals(&self) -> egui::Visuals {
+        let mut visuals = if self.dark {
+            egui::Visuals::dark()
+        } else {
+            egui::Visuals::light()
+        };
+        for (key, color) in &self.ui {
+            let color = to_color32(*color);
+            match key.as_str() {
+                "panel" => visuals.panel_fill = color,
+                "window" => visuals.window_fill = color,
+                "editor" => visuals.extreme_bg_color = color,
+                "text" => visuals.override_text_color = Some(color),
+                "selection" => visuals.selection.bg_fill = color,
+                "link" => visuals.hyperlink_color = color,
+                _ => {}
+            }
+        }
+        visuals
+    }
+
+    /// `base` with the token colors put over it. Base rules for a scope
+    /// within an overridden one are dropped, so the override wins there too.
+    fn syntax_theme(&self, base: &Theme) -> Theme {
+        let mut theme = base.clone();
+        theme.name = Some(self.name.clone());
+        if let Some(text) = self.ui.get("text") {
+            theme.settings.foreground = Some(to_syntect(*text));
+        }
+        if let Some(editor) = self.ui.get("editor") {
+            theme.settings.background = Some(to_syntect(*editor));
+        }
+        let overridden: Vec<Scope> = self
+            .tokens
+            .keys()
+            .filter_map(|scope| Scope::new(scope).ok())
+            .collect();
+        for item in &mut theme.scopes {
+            item.scope.selectors.retain(|selector| {
+                selector
+                    .path
+                    .as_slice()
+                    .last()
+                    .is_none_or(|last| !overridden.iter().any(|scope| scope.is_prefix_of(*last)))
+            });
+        }
+        theme.scopes.retain(|item| !item.scope.selectors.is_empty());
+        let overrides = self.tokens.iter().filter_map(|(scope, color)| {
+            Some(ThemeItem {
+                scope: ScopeSelectors::from_str(scope).ok()?,
+                style: StyleModifier {
+                    foreground: Some(to_syntect(*color)),
+                    background: None,
+                    font_style: None,
+                },
+            })
+        });
+        theme.scopes.splice(0..0, overrides);
+        theme
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let path = theme_file(&self.name).ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "no data directory")
+        })?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        atomic_write::write(&path, serde_json::to_string_pretty(self)?.as_bytes())
+    }
+}
+
+#[derive(Default)]
+pub struct ThemeEditor {
+    /// The saved custom themes, by name.
+    pub themes: Vec<CustomTheme>,
+    /// The theme being edited, previewed while the editor is open.
+    draft: Option<CustomTheme>,
+    new_scope: String,
+}
+
+impl ThemeEditor {
+    pub fn load() -> Self {
+        let mut themes: Vec<CustomTheme> = themes_dir()
+            .and_then(|dir| fs::read_dir(dir).ok())
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| fs::read_to_string(entry.ok()?.path()).ok())
+            .filter_map(|json| serde_json::from_str::<CustomTheme>(&json).ok())
+            .filter(|theme| !theme.name.is_empty())
+            .collect();
+        themes.sort_by(|a, b| a.name.cmp(&b.name));
+        ThemeEditor {
+            themes,
+            ..Default::default()
+        }
+    }
+}
+
+/// One color row of the theme editor: whether the theme sets the color,
+/// and the color. Returns whether anything changed.
+fn color_row(
+    ui: &mut egui::Ui,
+    colors: &mut BTreeMap<String, [u8; 3]>,
+    key: &str,
+    label: &str,
+    fallback: egui::Color32,
+) -> bool {
+    let mut set = colors.contains_key(key);
+    let mut changed = ui.checkbox(&mut set, label).changed();
+    let mut color = colors
+        .get(key)
+        .copied()
+        .unwrap_or([fallback.r(), fallback.g(), fallback.b()]);
+    ui.add_enabled_ui(set, |ui| {
+        changed |= ui.color_edit_button_srgb(&mut color).changed();
+    });
+    if set {
+        colors.insert(key.to_string(), color);
+    } else {
+        colors.remove(key);
+    }
+    ui.end_row();
+    changed
+}
+
+impl TextEditorApp {
+    /// The custom theme in use: the one being edited, or the one chosen in
+    /// the settings.
+    pub(crate) fn custom_theme(&self) -> Option<&CustomTheme> {
+        self.theme_editor.draft.as_ref().or_else(|| {
+            self.theme_editor
+                .themes
+                .iter()
+                .find(|theme| theme.name == self.settings.color_theme)
+        })
+    }
+
+    /// Sets the interface and syntax colors from the custom theme in use,
+    /// or the built-in light or dark theme.
+    pub(crate) fn apply_theme(&mut self, ctx: &egui::Context) {
+        match self.custom_theme().cloned() {
+            Some(custom) => {
+                self.dark_mode = custom.dark;
+                ctx.set_visuals(custom.visuals());
+                let base = self
+                    .theme_set
+                    .themes
+                    .get(&custom.base)
+                    .unwrap_or(&self.theme_set.themes[LIGHT_THEME]);
+                self.theme = custom.syntax_theme(base);
+            }
+            None => {
+                let (visuals, name) = if self.dark_mode {
+                    (egui::Visuals::dark(), DARK_THEME)
+                } else {
+                    (egui::Visuals::light(), LIGHT_THEME)
+                };
+                ctx.set_visuals(visuals);
+                self.theme = self.theme_set.themes[name].clone();
+            }
+        }
+        // Highlighting is cached per theme name, which a preview keeps.
+        for tab in self.tabs.values_mut() {
+            tab.highlight = HighlightCache::default();
+        }
+    }
+
+    /// Switches to the custom theme `name`, or the built-in theme.
+    fn use_color_theme(&mut self, ctx: &egui::Context, name: Option<String>) {
+        self.settings.color_theme = name.unwrap_or_default();
+        self.settings.save();
+        self.apply_theme(ctx);
+    }
+
+    /// The Color Theme submenu of the View menu.
+    pub(crate) fn color_theme_menu(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        let mut chosen = None;
+        ui.menu_button(tr("Color Theme"), |ui| {
+            let builtin = self.settings.color_theme.is_empty();
+            if ui.radio(builtin && !self.dark_mode, tr("Light")).clicked() {
+                chosen = Some((None, false));
+            }
+            if ui.radio(builtin && self.dark_mode, tr("Dark")).clicked() {
+                chosen = Some((None, true));
+            }
+            if !self.theme_editor.themes.is_empty() {
+                ui.separator();
+            }
+            for theme in &self.theme_editor.themes {
+                if ui
+                    .radio(self.settings.color_theme == theme.name, &theme.name)
+                    .clicked()
+                {
+                    chosen = Some((Some(theme.name.clone()), theme.dark));
+                }
+            }
+            ui.separator();
+            if ui.button(tr("Edit Theme…")).clicked() {
+                self.open_theme_editor();
+                ui.close_menu();
+            }
+        });
+        if let Some((name, dark)) = chosen {
+            self.dark_mode = dark;
+            self.use_color_theme(ctx, name);
+            ui.close_menu();
+        }
+    }
+
+    /// Opens the theme editor on the custom theme in use, or a new one
+    /// based on the current built-in theme.
+    pub(crate) fn open_theme_editor(&mut self) {
+        let draft = self.custom_theme().cloned().unwrap_or_else(|| CustomTheme {
+            name: tr("My Theme"),
+            dark: self.dark_mode,
+            base: if self.dark_mode {
+                DARK_THEME
+            } else {
+                LIGHT_THEME
+            }
+            .to_string(),
+            ..Default::default()
+        });
+        self.theme_editor.draft = Some(draft);
+    }
+
+    pub(crate) fn show_theme_editor(&mut self, ctx: &egui::Context) {
+        let Some(mut draft) = self.theme_editor.draft.clone() else {
+            return;
+        };
+        let mut open = true;
+        let mut changed = false;
+        let mut save = false;
+        let mut delete = false;
+        let mut base_names: Vec<&String> = self.theme_set.themes.keys().collect();
+        base_names.sort();
+        let saved = self
+            .theme_editor
+            .themes
+            .iter()
+            .any(|theme| theme.name == draft.name);
+        let base_visuals = if draft.dark {
+            egui::Visuals::dark()
+        } else {
+            egui::Visuals::light()
+        };
+        let new_scope = &mut self.theme_editor.new_scope;
+        egui::Window::new(tr("Theme Editor"))
+            .default_width(380.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                egui::Grid::new("theme_editor_base")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label(tr("Name:"));
+                        ui.text_edit_singleline(&mut draft.name);
+                        ui.end_row();
+                        ui.label(tr("Interface:"));
+                        ui.horizontal(|ui| {
+                            changed |= ui
+                                .radio_value(&mut draft.dark, false, tr("Light"))
+                                .changed();
+                            changed |= ui.radio_value(&mut draft.dark, true, tr("Dark")).changed();
+                        });
+                        ui.end_row();
+                        ui.label(tr("Syntax colors from:"));
+                        egui::ComboBox::from_id_source("theme_editor_base_theme")
+                            .selected_text(&draft.base)
+                            .show_ui(ui, |ui| {
+                                for name in &base_names {
+                                    changed |= ui
+                                        .selectable_value(&mut draft.base, name.to_string(), *name)
+                                        .changed();
+                                }
+                            });
+                        ui.end_row();
+                    });
+                egui::ScrollArea::vertical()
+                    .max_height(420.0)
+                    .show(ui, |ui| {
+                        ui.heading(tr("Interface"));
+                        egui::Grid::new("theme_editor_ui")
+                            .num_columns(2)
+                            .show(ui, |ui| {
+                                for (key, label) in UI_SLOTS {
+                                    let fallback = match *key {
+                                        "panel" => base_visuals.panel_fill,
+                                        "window" => base_visuals.window_fill,
+                                        "editor" => base_visuals.extreme_bg_color,
+                                        "selection" => base_visuals.selection.bg_fill,
+                                        "link" => base_visuals.hyperlink_color,
+                                        "gutter" => base_visuals.faint_bg_color,
+                                        _ => base_visuals.text_color(),
+                                    };
+                                    changed |=
+                                        color_row(ui, &mut draft.ui, key, &tr(label), fallback);
+                                }
+                            });
+                        ui.heading(tr("Syntax"));
+                        egui::Grid::new("theme_editor_tokens")
+                            .num_columns(2)
+                            .show(ui, |ui| {
+                                let extra: Vec<String> = draft
+                                    .tokens
+                                    .keys()
+                                    .filter(|scope| TOKEN_SLOTS.iter().all(|(s, _)| s != scope))
+                                    .cloned()
+                                    .collect();
+                                let fallback = base_visuals.text_color();
+                                for (scope, label) in TOKEN_SLOTS {
+                                    changed |= color_row(
+                                        ui,
+                                        &mut draft.tokens,
+                                        scope,
+                                        &tr(label),
+                                        fallback,
+                                    );
+                                }
+                                for scope in extra {
+                                    changed |=
+                                        color_row(ui, &mut draft.tokens, &scope, &scope, fallback);
+                                }
+                            });
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(new_scope)
+                                    .hint_text(tr("Scope, e.g. entity.name.tag"))
+                                    .desired_width(200.0),
+                            );
+                            let scope = new_scope.trim().to_string();
+                            if ui
+                                .add_enabled(
+                                    Scope::new(&scope).is_ok() && !scope.is_empty(),
+                                    egui::Button::new(tr("Add Scope")),
+                                )
+                                .clicked()
+                            {
+                                draft.tokens.insert(scope, [128, 128, 128]);
+                                new_scope.clear();
+                                changed = true;
+                            }
+                        });
+                    });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    save = ui
+                        .add_enabled(!draft.name.trim().is_empty(), egui::Button::new(tr("Save")))
+                        .on_hover_text(tr("Save the theme and use it"))
+                        .clicked();
+                    delete = ui
+                        .add_enabled(saved, egui::Button::new(tr("Delete")))
+                        .clicked();
+                });
+            });
+        draft.name = draft.name.trim_start().to_string();
+        self.theme_editor.draft = Some(draft.clone());
+        if changed {
+            self.apply_theme(ctx);
+        }
+        if save {
+            draft.name = draft.name.trim().to_string();
+            match draft.save() {
+                Ok(()) => {
+                    let themes = &mut self.theme_editor.themes;
+                    themes.retain(|theme| theme.name != draft.name);
+                    themes.push(draft.clone());
+                    themes.sort_by(|a, b| a.name.cmp(&b.name));
+                    self.notifications
+                        .info(trf("Saved theme {name}", &[("name", &draft.name)]));
+                    self.theme_editor.draft = Some(draft.clone());
+                    self.use_color_theme(ctx, Some(draft.name.clone()));
+                }
+                Err(err) => self.notifications.error(trf(
+                    "Could not save theme {name}: {error}",
+                    &[("name", &draft.name), ("error", &err)],
+                )),
+            }
+        }
+        if delete {
+            if let Some(path) = theme_file(&draft.name) {
+                let _ = fs::remove_file(path);
+            }
+            self.theme_editor
+                .themes
+                .retain(|theme| theme.name != draft.name);
+            if self.settings.color_theme == draft.name {
+                self.settings.color_theme.clear();
+                self.settings.save();
+            }
+            open = false;
+        }
+        if !open {
+            // Closing without saving goes back to the theme in use.
+            self.theme_editor.draft = None;
+            self.apply_theme(ctx);
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! Text transforms applied to the selection from Edit > Transform.
+
+use crate::TextEditorApp;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use eframe::egui;
+use std::fmt::Write;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Transform {
+    Base64Encode,
+    Base64Decode,
+    UrlEncode,
+    UrlDecode,
+    HtmlEscape,
+    HtmlUnescape,
+    HexDump,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 7] = [
+        Transform::Base64Encode,
+        Transform::Base64Decode,
+        Transform::UrlEncode,
+        Transform::UrlDecode,
+        Transform::HtmlEscape,
+        Transform::HtmlUnescape,
+        Transform::HexDump,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Transform::Base64Encode => "Base64 Encode",
+            Transform::Base64Decode => "Base64 Decode",
+            Transform::UrlEncode => "URL Encode",
+            Transform::UrlDecode => "URL Decode",
+            Transform::HtmlEscape => "HTML Escape",
+            Transform::HtmlUnescape => "HTML Unescape",
+            Transform::HexDump => "Hex Dump",
+        }
+    }
+
+    /// Returns `None` when the input cannot be decoded.
+    pub fn apply(self, text: &str) -> Option<String> {
+        match self {
+            Transform::Base64Encode => Some(BASE64.encode(text)),
+            Transform::Base64Decode => {
+                let compact: String = text.split_whitespace().collect();
+                String::from_utf8(BASE64.decode(compact).ok()?).ok()
+            }
+            Transform::UrlEncode => Some(url_encode(text)),
+            Transform::UrlDecode => url_decode(text),
+            Transform::HtmlEscape => Some(html_escape(text)),
+            Transform::HtmlUnescape => Some(html_unescape(text)),
+            Transform::HexDump => Some(hex_dump(text.as_bytes())),
+        }
+    }
+}
+
+fn url_encode(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => {
+                let _ = write!(out, "%{byte:02X}");
+            }
+        }
+    }
+    out
+}
+
+fn url_decode(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+                out.push(u8::from_str_radix(hex, 16).ok()?);
+                i += 3;
+            }
+            b'+' => {
+                out.push(b' ');
+                i += 1;
+            }
+            b => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+const HTML_ENTITIES: &[(char, &str)] = &[
+    ('&', "&amp;"),
+    ('<', "&lt;"),
+    ('>', "&gt;"),
+    ('"', "&quot;"),
+    ('\'', "&#39;"),
+];
+
+pub(crate) fn html_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match HTML_ENTITIES.iter().find(|(ch, _)| *ch == c) {
+            Some((_, entity)) => out.push_str(entity),
+            None => out.push(c),
+        }
+    }
+    out
+}
+
+fn html_unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end + 1))
+        });
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Classic `offset  hex bytes  |ascii|` dump, 16 bytes per row.
+fn hex_dump(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for (row, chunk) in bytes.chunks(16).enumerate() {
+        let _ = write!(out, "{:08x}  ", row * 16);
+        for i in 0..16 {
+            match chunk.get(i) {
+                Some(b) => {
+                    let _ = write!(out, "{b:02x} ");
+                }
+                None => out.push_str("   "),
+            }
+            if i == 7 {
+                out.push(' ');
+            }
+        }
+        out.push_str(" |");
+        out.extend(chunk.iter().map(|&b| {
+            if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '.'
+            }
+        }));
+        out.push_str("|\n");
+    }
+    out
+}
+
+impl TextEditorApp {
+    /// Applies `transform` to the selection, or to the whole buffer when
+    /// nothing is selected. Input that fails to decode is left untouched.
+    pub(crate) fn apply_transform(&mut self, ctx: &egui::Context, transform: Transform) {
+        let Some(range) = self.selection_or(ctx, |text, _| 0..text.len()) else {
+            return;
+        };
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        if let Some(text) = transform.apply(&tab.content[range.clone()]) {
+            self.replace_range(ctx, range, &text);
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Workspace trust: the first time a folder is opened, the editor asks
+//! whether its files can be trusted. Until they are, the folder is in
+//! restricted mode: nothing runs in it on its own (the git branch refresh
+//! and status commands), and its `.env` file is not passed to processes.
+//! Commands the user starts explicitly still run.
+//!
+//! Decisions are kept per folder in the data directory and apply to the
+//! folders below it, the nearest decision winning.
+
+use crate::i18n::{tr, trf};
+use crate::settings::data_dir;
+use crate::{TextEditorApp, json_file};
+use eframe::egui;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+const TRUST_FILE: &str = "trust.json";
+
+fn trust_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join(TRUST_FILE))
+}
+
+#[derive(Default)]
+pub struct Trust {
+    /// Whether each decided folder is trusted.
+    decisions: BTreeMap<PathBuf, bool>,
+    /// Folders asked about in this session, so closing the prompt does not
+    /// bring it back.
+    asked: HashSet<PathBuf>,
+    /// The folder the prompt is shown for.
+    prompt: Option<PathBuf>,
+}
+
+impl Trust {
+    /// The stored decisions, with a message to show if they cannot be
+    /// read; the file is then not saved over.
+    pub fn load() -> (Self, Option<String>) {
+        let (decisions, error) = json_file::load_or_default(trust_path().as_deref());
+        let trust = Trust {
+            decisions,
+            ..Default::default()
+        };
+        (trust, error)
+    }
+
+    fn save(&self) {
+        if let Some(path) = trust_path() {
+            let _ = json_file::save(&path, &self.decisions);
+        }
+    }
+
+    /// The decision for `folder` or the nearest folder above it.
+    fn decision(&self, folder: &Path) -> Option<bool> {
+        folder
+            .ancestors()
+            .find_map(|dir| self.decisions.get(dir).copied())
+    }
+
+    pub fn set(&mut self, folder: &Path, trusted: bool) {
+        self.decisions.insert(folder.to_path_buf(), trusted);
+        self.save();
+    }
+}
+
+impl TextEditorApp {
+    /// Whether things may run on their own in `path`: it is outside the
+    /// workspace folders, or in a trusted one.
+    pub(crate) fn is_trusted(&self, path: &Path) -> bool {
+        if !self.settings.workspace_trust {
+            return true;
+        }
+        self.folders
+            .iter()
+            .find(|root| path.starts_with(root))
+            .is_none_or(|root| self.trust.decision(root) == Some(true))
+    }
+
+    /// The first workspace folder in restricted mode.
+    fn restricted_folder(&self) -> Option<&PathBuf> {
+        self.folders.iter().find(|root| !self.is_trusted(root))
+    }
+
+    /// Status bar entry while a folder is restricted; opens the prompt.
+    pub(crate) fn restricted_mode_button(&mut self, ui: &mut egui::Ui) {
+        let Some(folder) = self.restricted_folder().cloned() else {
+            return;
+        };
+        let text = egui::RichText::new(tr("Restricted Mode")).color(ui.visuals().warn_fg_color);
+        if ui
+            .add(egui::Button::new(text).frame(false))
+            .on_hover_text(trf(
+                "Nothing runs on its own in {folder} until you trust it",
+                &[("folder", &folder.display())],
+            ))
+            .clicked()
+        {
+            self.trust.prompt = Some(folder);
+        }
+        ui.separator();
+    }
+
+    /// Asks about the first workspace folder without a decision.
+    pub(crate) fn show_trust_prompt(&mut self, ctx: &egui::Context) {
+        if !self.settings.workspace_trust {
+            return;
+        }
+        if self.trust.prompt.is_none()
+            && let Some(folder) = self.folders.iter().find(|root| {
+                self.trust.decision(root).is_none() && !self.trust.asked.contains(*root)
+            })
+        {
+            self.trust.asked.insert(folder.clone());
+            self.trust.prompt = Some(folder.clone());
+        }
+        let Some(folder) = self.trust.prompt.clone() else {
+            return;
+        };
+        let mut open = true;
+        let mut decision = None;
+        egui::Window::new(tr("Trust This Folder?"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(trf(
+                    "Do you trust the authors of the files in {folder}?",
+                    &[("folder", &folder.display())],
+                ));
+                ui.add_space(4.0);
+                ui.weak(tr("In restricted mode the editor does not run git or status commands in the folder on its own, and does not pass its .env file to the programs it starts."));
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button(tr("Trust Folder")).clicked() {
+                        decision = Some(true);
+                    }
+                    if ui.button(tr("Stay Restricted")).clicked() {
+                        decision = Some(false);
+                    }
+                });
+            });
+        if let Some(trusted) = decision {
+            self.trust.set(&folder, trusted);
+        }
+        if decision.is_some() || !open {
+            self.trust.prompt = None;
+        }
+    }
+}
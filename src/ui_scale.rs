@@ -0,0 +1,57 @@
+//! Application-wide UI scale on top of what the system reports, since the
+//! detected pixel density is wrong on some Linux/Wayland setups. The scale
+//! is remembered per monitor and can be changed from the preferences, with
+//! Ctrl+scroll, or with egui's Ctrl+Plus/Minus/0.
+
+use crate::TextEditorApp;
+use eframe::egui;
+
+pub const MIN_SCALE: f32 = 0.5;
+pub const MAX_SCALE: f32 = 3.0;
+
+#[derive(Default)]
+pub struct UiScale {
+    /// The monitor and zoom factor set on the last frame, to tell egui's
+    /// keyboard zoom apart from a switch to another monitor.
+    applied: Option<(String, f32)>,
+    /// Value of the preferences slider while it is being dragged.
+    pub dragging: Option<f32>,
+}
+
+/// Identifies the monitor the window is on by its size.
+pub fn monitor_key(ctx: &egui::Context) -> String {
+    ctx.input(|i| i.viewport().monitor_size).map_or_else(
+        || "default".to_string(),
+        |size| format!("{}x{}", size.x, size.y),
+    )
+}
+
+impl TextEditorApp {
+    /// Applies the scale stored for the current monitor, first taking in any
+    /// zoom the user did since the last frame.
+    pub(crate) fn apply_ui_scale(&mut self, ctx: &egui::Context) {
+        let key = monitor_key(ctx);
+        let stored = self.settings.ui_scales.get(&key).copied().unwrap_or(1.0);
+        let current = ctx.zoom_factor();
+        let mut scale = stored;
+        if let Some((applied_key, applied)) = &self.ui_scale.applied
+            && *applied_key == key
+            && current != *applied
+        {
+            scale = current;
+        }
+        let delta = ctx.input(|i| i.zoom_delta());
+        if delta != 1.0 {
+            scale *= delta;
+        }
+        let scale = scale.clamp(MIN_SCALE, MAX_SCALE);
+        if scale != stored {
+            self.settings.ui_scales.insert(key.clone(), scale);
+            self.settings.save();
+        }
+        if scale != current {
+            ctx.set_zoom_factor(scale);
+        }
+        self.ui_scale.applied = Some((key, scale));
+    }
+}
@@ -0,0 +1,380 @@
+//! "Insert Unicode Character": a searchable table of characters that are
+//! handy in docs and comments (arrows, math, box drawing, Greek letters,
+//! typography, a few symbols and emoji), plus any code point typed as
+//! `U+2192` or hex. Recently inserted characters are kept in the data
+//! directory.
+
+use crate::i18n::tr;
+use crate::settings::data_dir;
+use crate::{TextEditorApp, fuzzy, json_file};
+use eframe::egui;
+use std::path::PathBuf;
+
+const RECENT_FILE: &str = "recent_characters.json";
+const MAX_RECENT: usize = 24;
+/// Search results shown at most.
+const MAX_RESULTS: usize = 240;
+
+const CHARACTERS: &[(char, &str)] = &[
+    ('←', "LEFTWARDS ARROW"),
+    ('↑', "UPWARDS ARROW"),
+    ('→', "RIGHTWARDS ARROW"),
+    ('↓', "DOWNWARDS ARROW"),
+    ('↔', "LEFT RIGHT ARROW"),
+    ('↕', "UP DOWN ARROW"),
+    ('↖', "NORTH WEST ARROW"),
+    ('↗', "NORTH EAST ARROW"),
+    ('↘', "SOUTH EAST ARROW"),
+    ('↙', "SOUTH WEST ARROW"),
+    ('↩', "LEFTWARDS ARROW WITH HOOK"),
+    ('↪', "RIGHTWARDS ARROW WITH HOOK"),
+    ('↵', "DOWNWARDS ARROW WITH CORNER LEFTWARDS"),
+    ('⇐', "LEFTWARDS DOUBLE ARROW"),
+    ('⇒', "RIGHTWARDS DOUBLE ARROW"),
+    ('⇔', "LEFT RIGHT DOUBLE ARROW"),
+    ('⟵', "LONG LEFTWARDS ARROW"),
+    ('⟶', "LONG RIGHTWARDS ARROW"),
+    ('⟹', "LONG RIGHTWARDS DOUBLE ARROW"),
+    ('↦', "RIGHTWARDS ARROW FROM BAR"),
+    ('↻', "CLOCKWISE OPEN CIRCLE ARROW"),
+    ('±', "PLUS-MINUS SIGN"),
+    ('×', "MULTIPLICATION SIGN"),
+    ('÷', "DIVISION SIGN"),
+    ('−', "MINUS SIGN"),
+    ('·', "MIDDLE DOT"),
+    ('∘', "RING OPERATOR"),
+    ('≈', "ALMOST EQUAL TO"),
+    ('≠', "NOT EQUAL TO"),
+    ('≡', "IDENTICAL TO"),
+    ('≤', "LESS-THAN OR EQUAL TO"),
+    ('≥', "GREATER-THAN OR EQUAL TO"),
+    ('≪', "MUCH LESS-THAN"),
+    ('≫', "MUCH GREATER-THAN"),
+    ('∞', "INFINITY"),
+    ('√', "SQUARE ROOT"),
+    ('∑', "N-ARY SUMMATION"),
+    ('∏', "N-ARY PRODUCT"),
+    ('∫', "INTEGRAL"),
+    ('∂', "PARTIAL DIFFERENTIAL"),
+    ('∆', "INCREMENT"),
+    ('∇', "NABLA"),
+    ('∀', "FOR ALL"),
+    ('∃', "THERE EXISTS"),
+    ('∄', "THERE DOES NOT EXIST"),
+    ('∅', "EMPTY SET"),
+    ('∈', "ELEMENT OF"),
+    ('∉', "NOT AN ELEMENT OF"),
+    ('⊂', "SUBSET OF"),
+    ('⊆', "SUBSET OF OR EQUAL TO"),
+    ('⊃', "SUPERSET OF"),
+    ('∩', "INTERSECTION"),
+    ('∪', "UNION"),
+    ('∧', "LOGICAL AND"),
+    ('∨', "LOGICAL OR"),
+    ('¬', "NOT SIGN"),
+    ('⊕', "CIRCLED PLUS"),
+    ('⊗', "CIRCLED TIMES"),
+    ('⊤', "DOWN TACK"),
+    ('⊥', "UP TACK"),
+    ('⊢', "RIGHT TACK"),
+    ('∴', "THEREFORE"),
+    ('∵', "BECAUSE"),
+    ('∝', "PROPORTIONAL TO"),
+    ('°', "DEGREE SIGN"),
+    ('′', "PRIME"),
+    ('″', "DOUBLE PRIME"),
+    ('‰', "PER MILLE SIGN"),
+    ('¹', "SUPERSCRIPT ONE"),
+    ('²', "SUPERSCRIPT TWO"),
+    ('³', "SUPERSCRIPT THREE"),
+    ('ⁿ', "SUPERSCRIPT LATIN SMALL LETTER N"),
+    ('₀', "SUBSCRIPT ZERO"),
+    ('₁', "SUBSCRIPT ONE"),
+    ('₂', "SUBSCRIPT TWO"),
+    ('½', "VULGAR FRACTION ONE HALF"),
+    ('⅓', "VULGAR FRACTION ONE THIRD"),
+    ('¼', "VULGAR FRACTION ONE QUARTER"),
+    ('¾', "VULGAR FRACTION THREE QUARTERS"),
+    ('ℕ', "DOUBLE-STRUCK CAPITAL N"),
+    ('ℤ', "DOUBLE-STRUCK CAPITAL Z"),
+    ('ℚ', "DOUBLE-STRUCK CAPITAL Q"),
+    ('ℝ', "DOUBLE-STRUCK CAPITAL R"),
+    ('ℂ', "DOUBLE-STRUCK CAPITAL C"),
+    ('α', "GREEK SMALL LETTER ALPHA"),
+    ('β', "GREEK SMALL LETTER BETA"),
+    ('γ', "GREEK SMALL LETTER GAMMA"),
+    ('δ', "GREEK SMALL LETTER DELTA"),
+    ('ε', "GREEK SMALL LETTER EPSILON"),
+    ('ζ', "GREEK SMALL LETTER ZETA"),
+    ('η', "GREEK SMALL LETTER ETA"),
+    ('θ', "GREEK SMALL LETTER THETA"),
+    ('ι', "GREEK SMALL LETTER IOTA"),
+    ('κ', "GREEK SMALL LETTER KAPPA"),
+    ('λ', "GREEK SMALL LETTER LAMDA"),
+    ('μ', "GREEK SMALL LETTER MU"),
+    ('ν', "GREEK SMALL LETTER NU"),
+    ('ξ', "GREEK SMALL LETTER XI"),
+    ('π', "GREEK SMALL LETTER PI"),
+    ('ρ', "GREEK SMALL LETTER RHO"),
+    ('σ', "GREEK SMALL LETTER SIGMA"),
+    ('τ', "GREEK SMALL LETTER TAU"),
+    ('υ', "GREEK SMALL LETTER UPSILON"),
+    ('φ', "GREEK SMALL LETTER PHI"),
+    ('χ', "GREEK SMALL LETTER CHI"),
+    ('ψ', "GREEK SMALL LETTER PSI"),
+    ('ω', "GREEK SMALL LETTER OMEGA"),
+    ('Γ', "GREEK CAPITAL LETTER GAMMA"),
+    ('Δ', "GREEK CAPITAL LETTER DELTA"),
+    ('Θ', "GREEK CAPITAL LETTER THETA"),
+    ('Λ', "GREEK CAPITAL LETTER LAMDA"),
+    ('Π', "GREEK CAPITAL LETTER PI"),
+    ('Σ', "GREEK CAPITAL LETTER SIGMA"),
+    ('Φ', "GREEK CAPITAL LETTER PHI"),
+    ('Ψ', "GREEK CAPITAL LETTER PSI"),
+    ('Ω', "GREEK CAPITAL LETTER OMEGA"),
+    ('─', "BOX DRAWINGS LIGHT HORIZONTAL"),
+    ('│', "BOX DRAWINGS LIGHT VERTICAL"),
+    ('┌', "BOX DRAWINGS LIGHT DOWN AND RIGHT"),
+    ('┐', "BOX DRAWINGS LIGHT DOWN AND LEFT"),
+    ('└', "BOX DRAWINGS LIGHT UP AND RIGHT"),
+    ('┘', "BOX DRAWINGS LIGHT UP AND LEFT"),
+    ('├', "BOX DRAWINGS LIGHT VERTICAL AND RIGHT"),
+    ('┤', "BOX DRAWINGS LIGHT VERTICAL AND LEFT"),
+    ('┬', "BOX DRAWINGS LIGHT DOWN AND HORIZONTAL"),
+    ('┴', "BOX DRAWINGS LIGHT UP AND HORIZONTAL"),
+    ('┼', "BOX DRAWINGS LIGHT VERTICAL AND HORIZONTAL"),
+    ('═', "BOX DRAWINGS DOUBLE HORIZONTAL"),
+    ('║', "BOX DRAWINGS DOUBLE VERTICAL"),
+    ('╔', "BOX DRAWINGS DOUBLE DOWN AND RIGHT"),
+    ('╗', "BOX DRAWINGS DOUBLE DOWN AND LEFT"),
+    ('╚', "BOX DRAWINGS DOUBLE UP AND RIGHT"),
+    ('╝', "BOX DRAWINGS DOUBLE UP AND LEFT"),
+    ('╭', "BOX DRAWINGS LIGHT ARC DOWN AND RIGHT"),
+    ('╮', "BOX DRAWINGS LIGHT ARC DOWN AND LEFT"),
+    ('╰', "BOX DRAWINGS LIGHT ARC UP AND RIGHT"),
+    ('╯', "BOX DRAWINGS LIGHT ARC UP AND LEFT"),
+    ('█', "FULL BLOCK"),
+    ('▓', "DARK SHADE"),
+    ('▒', "MEDIUM SHADE"),
+    ('░', "LIGHT SHADE"),
+    ('▀', "UPPER HALF BLOCK"),
+    ('▄', "LOWER HALF BLOCK"),
+    ('■', "BLACK SQUARE"),
+    ('□', "WHITE SQUARE"),
+    ('▲', "BLACK UP-POINTING TRIANGLE"),
+    ('▶', "BLACK RIGHT-POINTING TRIANGLE"),
+    ('▼', "BLACK DOWN-POINTING TRIANGLE"),
+    ('◀', "BLACK LEFT-POINTING TRIANGLE"),
+    ('●', "BLACK CIRCLE"),
+    ('○', "WHITE CIRCLE"),
+    ('◆', "BLACK DIAMOND"),
+    ('◇', "WHITE DIAMOND"),
+    ('–', "EN DASH"),
+    ('—', "EM DASH"),
+    ('…', "HORIZONTAL ELLIPSIS"),
+    ('‘', "LEFT SINGLE QUOTATION MARK"),
+    ('’', "RIGHT SINGLE QUOTATION MARK"),
+    ('“', "LEFT DOUBLE QUOTATION MARK"),
+    ('”', "RIGHT DOUBLE QUOTATION MARK"),
+    ('„', "DOUBLE LOW-9 QUOTATION MARK"),
+    ('«', "LEFT-POINTING DOUBLE ANGLE QUOTATION MARK"),
+    ('»', "RIGHT-POINTING DOUBLE ANGLE QUOTATION MARK"),
+    ('•', "BULLET"),
+    ('†', "DAGGER"),
+    ('‡', "DOUBLE DAGGER"),
+    ('§', "SECTION SIGN"),
+    ('¶', "PILCROW SIGN"),
+    ('©', "COPYRIGHT SIGN"),
+    ('®', "REGISTERED SIGN"),
+    ('™', "TRADE MARK SIGN"),
+    ('№', "NUMERO SIGN"),
+    ('\u{a0}', "NO-BREAK SPACE"),
+    ('€', "EURO SIGN"),
+    ('£', "POUND SIGN"),
+    ('¥', "YEN SIGN"),
+    ('¢', "CENT SIGN"),
+    ('₹', "INDIAN RUPEE SIGN"),
+    ('₿', "BITCOIN SIGN"),
+    ('✓', "CHECK MARK"),
+    ('✔', "HEAVY CHECK MARK"),
+    ('✗', "BALLOT X"),
+    ('✘', "HEAVY BALLOT X"),
+    ('☐', "BALLOT BOX"),
+    ('☑', "BALLOT BOX WITH CHECK"),
+    ('☒', "BALLOT BOX WITH X"),
+    ('★', "BLACK STAR"),
+    ('☆', "WHITE STAR"),
+    ('♥', "BLACK HEART SUIT"),
+    ('⚠', "WARNING SIGN"),
+    ('⚡', "HIGH VOLTAGE SIGN"),
+    ('⌘', "PLACE OF INTEREST SIGN"),
+    ('⌥', "OPTION KEY"),
+    ('⇧', "UPWARDS WHITE ARROW"),
+    ('⌫', "ERASE TO THE LEFT"),
+    ('⏎', "RETURN SYMBOL"),
+    ('⎋', "BROKEN CIRCLE WITH NORTHWEST ARROW"),
+    ('😀', "GRINNING FACE"),
+    ('😉', "WINKING FACE"),
+    ('👍', "THUMBS UP SIGN"),
+    ('👎', "THUMBS DOWN SIGN"),
+    ('🎉', "PARTY POPPER"),
+    ('🚀', "ROCKET"),
+    ('🐛', "BUG"),
+    ('🔥', "FIRE"),
+    ('💡', "ELECTRIC LIGHT BULB"),
+    ('📝', "MEMO"),
+    ('📌', "PUSHPIN"),
+    ('🔒', "LOCK"),
+    ('✨', "SPARKLES"),
+    ('❌', "CROSS MARK"),
+    ('✅', "WHITE HEAVY CHECK MARK"),
+    ('🦀', "CRAB"),
+];
+
+fn name_of(c: char) -> Option<&'static str> {
+    CHARACTERS
+        .iter()
+        .find(|(code, _)| *code == c)
+        .map(|(_, name)| *name)
+}
+
+/// The code point typed as `U+2192`, `0x2192` or plain hex.
+fn parse_code_point(query: &str) -> Option<char> {
+    let query = query.trim();
+    let prefixed = query
+        .strip_prefix("U+")
+        .or_else(|| query.strip_prefix("u+"))
+        .or_else(|| query.strip_prefix("0x"));
+    // Bare hex needs four digits, so short words like "bed" stay searches.
+    let hex = match prefixed {
+        Some(hex) => hex,
+        None if query.len() >= 4 => query,
+        None => return None,
+    };
+    if hex.is_empty() || hex.len() > 6 {
+        return None;
+    }
+    char::from_u32(u32::from_str_radix(hex, 16).ok()?).filter(|c| !c.is_control())
+}
+
+fn code(c: char) -> String {
+    format!("U+{:04X}", c as u32)
+}
+
+/// The characters matching `query` by name or code point.
+fn search(query: &str) -> Vec<char> {
+    let query = query.trim();
+    if query.is_empty() {
+        return CHARACTERS.iter().map(|(c, _)| *c).collect();
+    }
+    let typed = parse_code_point(query);
+    let by_name = fuzzy::filter(query, CHARACTERS.to_vec(), |(_, name)| name)
+        .into_iter()
+        .map(|(c, _)| c)
+        .filter(|&c| Some(c) != typed);
+    typed.into_iter().chain(by_name).collect()
+}
+
+fn recent_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join(RECENT_FILE))
+}
+
+#[derive(Default)]
+pub struct UnicodePicker {
+    pub open: bool,
+    query: String,
+    /// Most recent first, loaded when the picker first opens.
+    recent: Option<Vec<char>>,
+}
+
+impl UnicodePicker {
+    fn remember(&mut self, c: char) {
+        let recent = self.recent.get_or_insert_with(Vec::new);
+        recent.retain(|&r| r != c);
+        recent.insert(0, c);
+        recent.truncate(MAX_RECENT);
+        if let Some(path) = recent_path() {
+            let _ = json_file::save(&path, recent);
+        }
+    }
+}
+
+/// A button showing `c` large, with its name and code point on hover.
+fn character_button(ui: &mut egui::Ui, c: char) -> bool {
+    let label = if c.is_whitespace() {
+        code(c)
+    } else {
+        c.to_string()
+    };
+    let hover = match name_of(c) {
+        Some(name) => format!("{} {name}", code(c)),
+        None => code(c),
+    };
+    ui.add(
+        egui::Button::new(egui::RichText::new(label).size(20.0)).min_size(egui::vec2(32.0, 32.0)),
+    )
+    .on_hover_text(hover)
+    .clicked()
+}
+
+impl TextEditorApp {
+    pub(crate) fn show_unicode_picker(&mut self, ctx: &egui::Context) {
+        let picker = &mut self.unicode_picker;
+        if !picker.open {
+            return;
+        }
+        if picker.recent.is_none() {
+            let (recent, error) = json_file::load_or_default(recent_path().as_deref());
+            picker.recent = Some(recent);
+            if let Some(message) = error {
+                self.notifications.error(message);
+            }
+        }
+        let picker = &mut self.unicode_picker;
+        let recent = picker.recent.clone().unwrap_or_default();
+        let mut open = true;
+        let mut chosen = None;
+        egui::Window::new(tr("Insert Unicode Character"))
+            .collapsible(false)
+            .default_size((380.0, 360.0))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut picker.query)
+                        .hint_text(tr("Name or code point, e.g. arrow or U+2192"))
+                        .desired_width(f32::INFINITY),
+                )
+                .request_focus();
+                if !recent.is_empty() {
+                    ui.label(tr("Recent:"));
+                    ui.horizontal_wrapped(|ui| {
+                        for &c in &recent {
+                            if character_button(ui, c) {
+                                chosen = Some(c);
+                            }
+                        }
+                    });
+                    ui.separator();
+                }
+                let mut results = search(&picker.query);
+                results.truncate(MAX_RESULTS);
+                if results.is_empty() {
+                    ui.weak(tr("No matching characters"));
+                }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    ui.horizontal_wrapped(|ui| {
+                        for c in results {
+                            if character_button(ui, c) {
+                                chosen = Some(c);
+                            }
+                        }
+                    });
+                });
+            });
+        picker.open = open;
+        if let Some(c) = chosen {
+            picker.remember(c);
+            self.insert_text(ctx, &c.to_string());
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! Unicode-aware matching for Find, Replace and Find in Files: text and
+//! query are compared in canonical decomposition (NFD), so a precomposed
+//! "é" matches an "e" followed by a combining accent, and case-insensitive
+//! matching folds every script rather than ASCII only. Matches are mapped
+//! back to byte ranges of the original text, and never end or start inside
+//! a character or before a combining mark that belongs to it.
+
+use crate::i18n::tr;
+use eframe::egui;
+use std::ops::Range;
+use unicode_normalization::char::{decompose_canonical, is_combining_mark};
+
+/// `text` decomposed and, unless `case_sensitive`, case folded, with the
+/// byte offset in `text` of the character each byte came from and one
+/// more entry for the end.
+fn fold(text: &str, case_sensitive: bool) -> (String, Vec<usize>) {
+    let mut folded = String::with_capacity(text.len());
+    let mut origins = Vec::with_capacity(text.len() + 1);
+    for (offset, c) in text.char_indices() {
+        let start = folded.len();
+        decompose_canonical(c, |d| {
+            if case_sensitive {
+                folded.push(d);
+                return;
+            }
+            for lower in d.to_lowercase() {
+                match lower {
+                    'ß' => folded.push_str("ss"),
+                    'ς' => folded.push('σ'),
+                    lower => folded.push(lower),
+                }
+            }
+        });
+        origins.resize(origins.len() + folded.len() - start, offset);
+    }
+    origins.push(text.len());
+    (folded, origins)
+}
+
+/// Byte ranges of the non-overlapping occurrences of `query` in `text`,
+/// ignoring differences in Unicode normalization and, unless
+/// `case_sensitive`, in case.
+pub fn find(text: &str, query: &str, case_sensitive: bool) -> Vec<Range<usize>> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let (needle, _) = fold(query, case_sensitive);
+    let (haystack, origins) = fold(text, case_sensitive);
+    // Where a character of `text` starts in the folded text.
+    let is_boundary = |i: usize| i == 0 || i == haystack.len() || origins[i] != origins[i - 1];
+    haystack
+        .match_indices(&needle)
+        .map(|(start, m)| start..start + m.len())
+        .filter(|m| {
+            is_boundary(m.start)
+                && is_boundary(m.end)
+                && !haystack[m.end..].starts_with(is_combining_mark)
+        })
+        .map(|m| origins[m.start]..origins[m.end])
+        .collect()
+}
+
+/// The option shown next to every search field.
+pub fn checkbox(ui: &mut egui::Ui, normalize: &mut bool) {
+    ui.checkbox(normalize, tr("Unicode-aware"))
+        .on_hover_text(tr(
+            "Match composed and decomposed accents alike, and ignore case in every script",
+        ));
+}
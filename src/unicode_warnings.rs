@@ -0,0 +1,245 @@
+//! Warnings for Unicode that hides what code really says: invisible
+//! characters, bidirectional controls that reorder what is displayed
+//! ("Trojan Source") and Cyrillic or Greek letters inside otherwise Latin
+//! words. They are underlined in the editor, named in a hover popup, and can
+//! be removed or escaped.
+
+use crate::TextEditorApp;
+use crate::i18n::{tr, trf};
+use eframe::egui;
+use egui::text::{LayoutJob, LayoutSection};
+use std::ops::Range;
+
+/// A character worth a warning: its Unicode name and, for homoglyphs, the
+/// Latin letter it passes for.
+pub struct Suspicious {
+    pub name: &'static str,
+    pub lookalike: Option<char>,
+}
+
+const INVISIBLE: &[(char, &str)] = &[
+    ('\u{00ad}', "SOFT HYPHEN"),
+    ('\u{061c}', "ARABIC LETTER MARK"),
+    ('\u{115f}', "HANGUL CHOSEONG FILLER"),
+    ('\u{1160}', "HANGUL JUNGSEONG FILLER"),
+    ('\u{180e}', "MONGOLIAN VOWEL SEPARATOR"),
+    ('\u{200b}', "ZERO WIDTH SPACE"),
+    ('\u{200c}', "ZERO WIDTH NON-JOINER"),
+    ('\u{200d}', "ZERO WIDTH JOINER"),
+    ('\u{200e}', "LEFT-TO-RIGHT MARK"),
+    ('\u{200f}', "RIGHT-TO-LEFT MARK"),
+    ('\u{202a}', "LEFT-TO-RIGHT EMBEDDING"),
+    ('\u{202b}', "RIGHT-TO-LEFT EMBEDDING"),
+    ('\u{202c}', "POP DIRECTIONAL FORMATTING"),
+    ('\u{202d}', "LEFT-TO-RIGHT OVERRIDE"),
+    ('\u{202e}', "RIGHT-TO-LEFT OVERRIDE"),
+    ('\u{2060}', "WORD JOINER"),
+    ('\u{2061}', "FUNCTION APPLICATION"),
+    ('\u{2062}', "INVISIBLE TIMES"),
+    ('\u{2063}', "INVISIBLE SEPARATOR"),
+    ('\u{2064}', "INVISIBLE PLUS"),
+    ('\u{2066}', "LEFT-TO-RIGHT ISOLATE"),
+    ('\u{2067}', "RIGHT-TO-LEFT ISOLATE"),
+    ('\u{2068}', "FIRST STRONG ISOLATE"),
+    ('\u{2069}', "POP DIRECTIONAL ISOLATE"),
+    ('\u{3164}', "HANGUL FILLER"),
+    ('\u{feff}', "ZERO WIDTH NO-BREAK SPACE"),
+    ('\u{ffa0}', "HALFWIDTH HANGUL FILLER"),
+];
+
+const HOMOGLYPHS: &[(char, char, &str)] = &[
+    ('\u{0430}', 'a', "CYRILLIC SMALL LETTER A"),
+    ('\u{0435}', 'e', "CYRILLIC SMALL LETTER IE"),
+    ('\u{043e}', 'o', "CYRILLIC SMALL LETTER O"),
+    ('\u{0440}', 'p', "CYRILLIC SMALL LETTER ER"),
+    ('\u{0441}', 'c', "CYRILLIC SMALL LETTER ES"),
+    ('\u{0443}', 'y', "CYRILLIC SMALL LETTER U"),
+    ('\u{0445}', 'x', "CYRILLIC SMALL LETTER HA"),
+    ('\u{0455}', 's', "CYRILLIC SMALL LETTER DZE"),
+    (
+        '\u{0456}',
+        'i',
+        "CYRILLIC SMALL LETTER BYELORUSSIAN-UKRAINIAN I",
+    ),
+    ('\u{0458}', 'j', "CYRILLIC SMALL LETTER JE"),
+    ('\u{0501}', 'd', "CYRILLIC SMALL LETTER KOMI DE"),
+    ('\u{0405}', 'S', "CYRILLIC CAPITAL LETTER DZE"),
+    (
+        '\u{0406}',
+        'I',
+        "CYRILLIC CAPITAL LETTER BYELORUSSIAN-UKRAINIAN I",
+    ),
+    ('\u{0408}', 'J', "CYRILLIC CAPITAL LETTER JE"),
+    ('\u{0410}', 'A', "CYRILLIC CAPITAL LETTER A"),
+    ('\u{0412}', 'B', "CYRILLIC CAPITAL LETTER VE"),
+    ('\u{0415}', 'E', "CYRILLIC CAPITAL LETTER IE"),
+    ('\u{041a}', 'K', "CYRILLIC CAPITAL LETTER KA"),
+    ('\u{041c}', 'M', "CYRILLIC CAPITAL LETTER EM"),
+    ('\u{041d}', 'H', "CYRILLIC CAPITAL LETTER EN"),
+    ('\u{041e}', 'O', "CYRILLIC CAPITAL LETTER O"),
+    ('\u{0420}', 'P', "CYRILLIC CAPITAL LETTER ER"),
+    ('\u{0421}', 'C', "CYRILLIC CAPITAL LETTER ES"),
+    ('\u{0422}', 'T', "CYRILLIC CAPITAL LETTER TE"),
+    ('\u{0425}', 'X', "CYRILLIC CAPITAL LETTER HA"),
+    ('\u{0391}', 'A', "GREEK CAPITAL LETTER ALPHA"),
+    ('\u{0392}', 'B', "GREEK CAPITAL LETTER BETA"),
+    ('\u{0395}', 'E', "GREEK CAPITAL LETTER EPSILON"),
+    ('\u{0396}', 'Z', "GREEK CAPITAL LETTER ZETA"),
+    ('\u{0397}', 'H', "GREEK CAPITAL LETTER ETA"),
+    ('\u{0399}', 'I', "GREEK CAPITAL LETTER IOTA"),
+    ('\u{039a}', 'K', "GREEK CAPITAL LETTER KAPPA"),
+    ('\u{039c}', 'M', "GREEK CAPITAL LETTER MU"),
+    ('\u{039d}', 'N', "GREEK CAPITAL LETTER NU"),
+    ('\u{039f}', 'O', "GREEK CAPITAL LETTER OMICRON"),
+    ('\u{03a1}', 'P', "GREEK CAPITAL LETTER RHO"),
+    ('\u{03a4}', 'T', "GREEK CAPITAL LETTER TAU"),
+    ('\u{03a5}', 'Y', "GREEK CAPITAL LETTER UPSILON"),
+    ('\u{03a7}', 'X', "GREEK CAPITAL LETTER CHI"),
+    ('\u{03bd}', 'v', "GREEK SMALL LETTER NU"),
+    ('\u{03bf}', 'o', "GREEK SMALL LETTER OMICRON"),
+];
+
+/// What is suspicious about the character at byte offset `index` of
+/// `text`, if anything. Homoglyphs only count next to an ASCII letter, so
+/// ordinary Cyrillic or Greek text is left alone.
+pub fn check(text: &str, index: usize) -> Option<Suspicious> {
+    let c = text[index..].chars().next()?;
+    if let Some((_, name)) = INVISIBLE.iter().find(|(code, _)| *code == c) {
+        return Some(Suspicious {
+            name,
+            lookalike: None,
+        });
+    }
+    let (_, latin, name) = HOMOGLYPHS.iter().find(|(code, _, _)| *code == c)?;
+    let before = text[..index].chars().next_back();
+    let after = text[index + c.len_utf8()..].chars().next();
+    [before, after]
+        .into_iter()
+        .flatten()
+        .any(|c| c.is_ascii_alphabetic())
+        .then_some(Suspicious {
+            name,
+            lookalike: Some(*latin),
+        })
+}
+
+/// Byte ranges of every suspicious character in `text`.
+pub fn find(text: &str) -> Vec<Range<usize>> {
+    text.char_indices()
+        .filter(|&(i, _)| check(text, i).is_some())
+        .map(|(i, c)| i..i + c.len_utf8())
+        .collect()
+}
+
+/// `text` with invisible characters removed and homoglyphs replaced by the
+/// letter they imitate.
+pub fn remove(text: &str) -> String {
+    replace(text, |suspicious, _| {
+        suspicious.lookalike.map(String::from).unwrap_or_default()
+    })
+}
+
+/// `text` with suspicious characters written as `\u{...}` escapes.
+pub fn escape(text: &str) -> String {
+    replace(text, |_, c| format!("\\u{{{:04x}}}", c as u32))
+}
+
+fn replace(text: &str, with: impl Fn(&Suspicious, char) -> String) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (i, c) in text.char_indices() {
+        match check(text, i) {
+            Some(suspicious) => out.push_str(&with(&suspicious, c)),
+            None => out.push(c),
+        }
+    }
+    out
+}
+
+/// Underlines the suspicious characters of a laid out buffer and gives them
+/// a background, as invisible ones have no glyph to underline.
+pub fn mark(job: &mut LayoutJob, color: egui::Color32) {
+    let ranges = find(&job.text);
+    if ranges.is_empty() {
+        return;
+    }
+    let mut sections = Vec::with_capacity(job.sections.len() + ranges.len() * 2);
+    let mut ranges = ranges.into_iter().peekable();
+    for section in job.sections.drain(..) {
+        let mut start = section.byte_range.start;
+        let mut leading_space = section.leading_space;
+        let mut push = |range: Range<usize>, format: egui::TextFormat| {
+            if !range.is_empty() {
+                sections.push(LayoutSection {
+                    leading_space: std::mem::take(&mut leading_space),
+                    byte_range: range,
+                    format,
+                });
+            }
+        };
+        while let Some(range) = ranges.next_if(|r| r.start < section.byte_range.end) {
+            push(start..range.start, section.format.clone());
+            push(
+                range.clone(),
+                egui::TextFormat {
+                    underline: egui::Stroke::new(2.0, color),
+                    background: color.gamma_multiply(0.3),
+                    ..section.format.clone()
+                },
+            );
+            start = range.end;
+        }
+        push(start..section.byte_range.end, section.format);
+    }
+    job.sections = sections;
+}
+
+/// The name and code point of a suspicious character, for the hover popup.
+pub fn describe(text: &str, index: usize) -> Option<String> {
+    let suspicious = check(text, index)?;
+    let c = text[index..].chars().next()?;
+    let code = format!("U+{:04X}", c as u32);
+    Some(match suspicious.lookalike {
+        Some(latin) => trf(
+            "{code} {name}, looks like “{latin}”",
+            &[
+                ("code", &code),
+                ("name", &suspicious.name),
+                ("latin", &latin),
+            ],
+        ),
+        None => trf(
+            "{code} {name}, invisible",
+            &[("code", &code), ("name", &suspicious.name)],
+        ),
+    })
+}
+
+impl TextEditorApp {
+    /// Removes or escapes the suspicious characters in the selection, or in
+    /// the whole buffer when nothing is selected.
+    pub(crate) fn clean_suspicious(&mut self, ctx: &egui::Context, escape_them: bool) {
+        let Some(range) = self.selection_or(ctx, |text, _| 0..text.len()) else {
+            return;
+        };
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        let text = &tab.content[range.clone()];
+        let count = find(text).len();
+        if count == 0 {
+            self.notifications
+                .info(tr("No suspicious characters found"));
+            return;
+        }
+        let cleaned = if escape_them {
+            escape(text)
+        } else {
+            remove(text)
+        };
+        self.replace_range(ctx, range, &cleaned);
+        self.notifications.info(trf(
+            "Cleaned {count} suspicious character(s)",
+            &[("count", &count)],
+        ));
+    }
+}
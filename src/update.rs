@@ -0,0 +1,508 @@
+//! Update checks: on startup (when turned on in the preferences) and
+//! from Help > Check for Updates, the latest GitHub release is compared with
+//! the running version. A newer one is announced with its changelog and, on
+//! platforms the release has a build for, can be downloaded and put in place
+//! of the running binary, taking effect on the next start.
+//!
+//! Only the archive named for this platform is installed, as
+//! `rusty-editor-<os>-<arch>.tar.gz` (`.zip` on Windows), and only once it
+//! matches the SHA-256 digest published next to it as `<archive>.sha256`.
+
+use crate::TextEditorApp;
+use crate::i18n::{tr, trf};
+use crate::jobs::Job;
+use eframe::egui;
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use std::fs;
+use std::io::{self, Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, channel};
+use std::thread;
+use std::time::Duration;
+
+const LATEST_RELEASE_API: &str =
+    "https://api.github.com/repos/eskutkaan/rusty-editor/releases/latest";
+const TIMEOUT: Duration = Duration::from_secs(30);
+/// Downloads larger than this are refused.
+const MAX_DOWNLOAD: u64 = 256 * 1024 * 1024;
+const BINARY_NAME: &str = "rusty-editor";
+
+#[derive(Deserialize, Clone)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+    #[serde(default)]
+    size: u64,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Release {
+    tag_name: String,
+    #[serde(default)]
+    body: String,
+    html_url: String,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+impl Release {
+    fn version(&self) -> &str {
+        self.tag_name.trim_start_matches('v')
+    }
+}
+
+/// The numeric parts of a version such as `v1.2.3-beta`, without trailing
+/// zeros so `1.2` and `1.2.0` compare equal.
+fn version_parts(version: &str) -> Vec<u64> {
+    let core = version
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default();
+    let mut parts: Vec<u64> = core
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect();
+    while parts.last() == Some(&0) {
+        parts.pop();
+    }
+    parts
+}
+
+fn is_newer(candidate: &str, current: &str) -> bool {
+    version_parts(candidate) > version_parts(current)
+}
+
+/// The name of the release archive built for `os` and `arch`, as in
+/// `std::env::consts`.
+fn asset_name(os: &str, arch: &str) -> Option<String> {
+    if !matches!(os, "linux" | "windows" | "macos") || !matches!(arch, "x86_64" | "aarch64") {
+        return None;
+    }
+    let extension = if os == "windows" { "zip" } else { "tar.gz" };
+    Some(format!("{BINARY_NAME}-{os}-{arch}.{extension}"))
+}
+
+/// The release archive built for this platform and its checksum file. Any
+/// other asset, such as an installer package, is never installed.
+fn platform_asset(assets: &[Asset]) -> Option<(&Asset, &Asset)> {
+    let name = asset_name(std::env::consts::OS, std::env::consts::ARCH)?;
+    let checksum = format!("{name}.sha256");
+    let find = |name: &str| assets.iter().find(|asset| asset.name == name);
+    Some((find(&name)?, find(&checksum)?))
+}
+
+/// Checks `bytes` against a `.sha256` file, which holds the hex digest,
+/// optionally followed by the file name.
+fn verify_checksum(bytes: &[u8], checksum_file: &str) -> Result<(), String> {
+    let expected = checksum_file
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let actual: String = ring::digest::digest(&ring::digest::SHA256, bytes)
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(tr("The download does not match its published checksum"))
+    }
+}
+
+fn latest_release() -> Result<Release, String> {
+    ureq::get(LATEST_RELEASE_API)
+        .timeout(TIMEOUT)
+        .set("Accept", "application/vnd.github+json")
+        .set("User-Agent", "rusty-editor")
+        .call()
+        .map_err(|e| e.to_string())?
+        .into_json()
+        .map_err(|e| e.to_string())
+}
+
+fn download(asset: &Asset, job: &Job) -> Result<Vec<u8>, String> {
+    let response = ureq::get(&asset.browser_download_url)
+        .timeout(Duration::from_secs(600))
+        .set("User-Agent", "rusty-editor")
+        .call()
+        .map_err(|e| e.to_string())?;
+    let total = response
+        .header("Content-Length")
+        .and_then(|length| length.parse().ok())
+        .unwrap_or(asset.size);
+    if total > MAX_DOWNLOAD {
+        return Err(tr("The download is too large"));
+    }
+    let mut reader = response.into_reader().take(MAX_DOWNLOAD);
+    let mut bytes = Vec::new();
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        if job.is_cancelled() {
+            return Err(tr("The download was cancelled"));
+        }
+        let read = reader.read(&mut buffer).map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        bytes.extend_from_slice(&buffer[..read]);
+        job.progress(bytes.len(), total as usize);
+    }
+    Ok(bytes)
+}
+
+fn is_binary_name(name: &str) -> bool {
+    name == format!("{BINARY_NAME}{}", std::env::consts::EXE_SUFFIX)
+}
+
+/// The editor binary in a downloaded `.zip` or `.tar.gz`.
+fn unpack(name: &str, bytes: Vec<u8>) -> Result<Vec<u8>, String> {
+    let not_found = || tr("The download does not contain the editor");
+    let mut binary = Vec::new();
+    if name.ends_with(".zip") {
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(|e| e.to_string())?;
+        let index = (0..archive.len())
+            .find(|&i| {
+                archive.by_index(i).is_ok_and(|entry| {
+                    entry.is_file()
+                        && entry
+                            .enclosed_name()
+                            .and_then(|path| {
+                                path.file_name()
+                                    .map(|n| is_binary_name(&n.to_string_lossy()))
+                            })
+                            .unwrap_or(false)
+                })
+            })
+            .ok_or_else(not_found)?;
+        archive
+            .by_index(index)
+            .and_then(|mut entry| Ok(entry.read_to_end(&mut binary)?))
+            .map_err(|e| e.to_string())?;
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        let mut archive = tar::Archive::new(GzDecoder::new(Cursor::new(bytes)));
+        let entries = archive.entries().map_err(|e| e.to_string())?;
+        let mut entry = entries
+            .filter_map(Result::ok)
+            .find(|entry| {
+                entry.header().entry_type().is_file()
+                    && entry.path().is_ok_and(|path| {
+                        path.file_name()
+                            .is_some_and(|n| is_binary_name(&n.to_string_lossy()))
+                    })
+            })
+            .ok_or_else(not_found)?;
+        entry.read_to_end(&mut binary).map_err(|e| e.to_string())?;
+    } else {
+        return Err(not_found());
+    }
+    Ok(binary)
+}
+
+/// Where the running binary is moved aside while it is replaced.
+fn sibling(exe: &Path, suffix: &str) -> PathBuf {
+    let mut name = exe.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    exe.with_file_name(name)
+}
+
+/// Puts `binary` in place of the running executable, keeping the old one
+/// until the next start, as Windows cannot delete a running program.
+fn install(binary: &[u8]) -> io::Result<()> {
+    let exe = std::env::current_exe()?;
+    let new = sibling(&exe, ".new");
+    let old = sibling(&exe, ".old");
+    let result = fs::write(&new, binary)
+        .and_then(|()| fs::set_permissions(&new, fs::metadata(&exe)?.permissions()))
+        .and_then(|()| {
+            let _ = fs::remove_file(&old);
+            fs::rename(&exe, &old)
+        })
+        .and_then(|()| {
+            fs::rename(&new, &exe).inspect_err(|_| {
+                let _ = fs::rename(&old, &exe);
+            })
+        });
+    if result.is_err() {
+        let _ = fs::remove_file(&new);
+    }
+    result
+}
+
+#[derive(Default)]
+pub struct Updates {
+    /// Whether the startup check has been started.
+    started: bool,
+    /// The check in flight, and whether the user asked for it.
+    check: Option<(Receiver<Result<Release, String>>, bool)>,
+    /// The newer release, shown in the Update Available window.
+    available: Option<Release>,
+    open: bool,
+    installing: Option<Receiver<Result<(), String>>>,
+    installed: bool,
+}
+
+impl TextEditorApp {
+    pub(crate) fn check_for_updates(&mut self, ctx: &egui::Context, manual: bool) {
+        if self.updates.check.is_some() {
+            return;
+        }
+        let (sender, receiver) = channel();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let _ = sender.send(latest_release());
+            ctx.request_repaint();
+        });
+        self.updates.check = Some((receiver, manual));
+    }
+
+    /// Starts the startup check and handles the result of checks.
+    pub(crate) fn poll_updates(&mut self, ctx: &egui::Context) {
+        if !self.updates.started {
+            self.updates.started = true;
+            if let Ok(exe) = std::env::current_exe() {
+                let _ = fs::remove_file(sibling(&exe, ".old"));
+            }
+            if self.settings.check_for_updates {
+                self.check_for_updates(ctx, false);
+            }
+        }
+        let Some((receiver, manual)) = &self.updates.check else {
+            return;
+        };
+        let manual = *manual;
+        let Ok(result) = receiver.try_recv() else {
+            return;
+        };
+        self.updates.check = None;
+        let current = env!("CARGO_PKG_VERSION");
+        match result {
+            Ok(release)
+                if is_newer(release.version(), current)
+                    && (manual || self.settings.skipped_update != release.version()) =>
+            {
+                self.notifications.info(trf(
+                    "Rusty Editor {version} is available",
+                    &[("version", &release.version())],
+                ));
+                self.updates.available = Some(release);
+                self.updates.installed = false;
+                self.updates.open = true;
+            }
+            Ok(_) if manual => self.notifications.info(trf(
+                "Rusty Editor {version} is the latest version",
+                &[("version", &current)],
+            )),
+            Err(err) if manual => self.notifications.error(trf(
+                "Could not check for updates: {error}",
+                &[("error", &err)],
+            )),
+            _ => {}
+        }
+    }
+
+    fn start_install(&mut self, ctx: &egui::Context, release: &Release) {
+        let Some((asset, checksum)) = platform_asset(&release.assets)
+            .map(|(asset, checksum)| (asset.clone(), checksum.clone()))
+        else {
+            return;
+        };
+        let job = self.jobs.start(
+            trf("Downloading {version}", &[("version", &release.version())]),
+            true,
+        );
+        let (sender, receiver) = channel();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            job.detail(&asset.name);
+            let result = download(&checksum, &job)
+                .and_then(|sum| {
+                    let bytes = download(&asset, &job)?;
+                    verify_checksum(&bytes, &String::from_utf8_lossy(&sum))?;
+                    Ok(bytes)
+                })
+                .and_then(|bytes| unpack(&asset.name, bytes))
+                .and_then(|binary| install(&binary).map_err(|e| e.to_string()));
+            drop(job);
+            let _ = sender.send(result);
+            ctx.request_repaint();
+        });
+        self.updates.installing = Some(receiver);
+    }
+
+    pub(crate) fn show_update_window(&mut self, ctx: &egui::Context) {
+        if let Some(receiver) = &self.updates.installing
+            && let Ok(result) = receiver.try_recv()
+        {
+            self.updates.installing = None;
+            match result {
+                Ok(()) => {
+                    self.updates.installed = true;
+                    self.notifications
+                        .info(tr("The update is installed; restart the editor to use it"));
+                }
+                Err(err) => self.notifications.error(trf(
+                    "Could not install the update: {error}",
+                    &[("error", &err)],
+                )),
+            }
+        }
+        if !self.updates.open {
+            return;
+        }
+        let Some(release) = self.updates.available.clone() else {
+            return;
+        };
+        let mut open = true;
+        let mut install = false;
+        let mut skip = false;
+        let mut later = false;
+        let installing = self.updates.installing.is_some();
+        let installed = self.updates.installed;
+        egui::Window::new(tr("Update Available"))
+            .collapsible(false)
+            .default_width(460.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.heading(trf(
+                    "Rusty Editor {version} is available",
+                    &[("version", &release.version())],
+                ));
+                ui.label(trf(
+                    "You are running version {version}.",
+                    &[("version", &env!("CARGO_PKG_VERSION"))],
+                ));
+                ui.separator();
+                ui.strong(tr("What's new"));
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        if release.body.trim().is_empty() {
+                            ui.weak(tr("The release has no notes."));
+                        } else {
+                            ui.label(release.body.trim());
+                        }
+                    });
+                ui.hyperlink_to(tr("Open the release page"), &release.html_url);
+                ui.separator();
+                if installed {
+                    ui.label(tr("The update is installed; restart the editor to use it"));
+                    return;
+                }
+                let asset = platform_asset(&release.assets);
+                ui.horizontal(|ui| {
+                    let button = ui
+                        .add_enabled(
+                            asset.is_some() && !installing,
+                            egui::Button::new(tr("Download and Install")),
+                        )
+                        .on_disabled_hover_text(if installing {
+                            tr("Downloading…")
+                        } else {
+                            tr("The release has no download for this platform")
+                        });
+                    install = button.clicked();
+                    if installing {
+                        ui.spinner();
+                    }
+                    skip = ui.button(tr("Skip This Version")).clicked();
+                    later = ui.button(tr("Later")).clicked();
+                });
+                if let Some((asset, _)) = asset {
+                    ui.weak(trf(
+                        "Replaces the running program with {file}",
+                        &[("file", &asset.name)],
+                    ));
+                }
+            });
+        if install {
+            self.start_install(ctx, &release);
+        }
+        if skip {
+            self.settings.skipped_update = release.version().to_string();
+            self.settings.save();
+        }
+        if !open || skip || later {
+            self.updates.open = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(name: &str) -> Asset {
+        Asset {
+            name: name.to_string(),
+            browser_download_url: String::new(),
+            size: 0,
+        }
+    }
+
+    #[test]
+    fn versions_compare_numerically() {
+        assert!(is_newer("v1.10.0", "1.9.3"));
+        assert!(is_newer("0.2", "0.1.9"));
+        assert!(!is_newer("1.2", "1.2.0"));
+        assert!(!is_newer("v1.2.0-beta", "1.2.0"));
+    }
+
+    #[test]
+    fn only_the_expected_archive_is_installed() {
+        assert_eq!(
+            asset_name("linux", "x86_64").as_deref(),
+            Some("rusty-editor-linux-x86_64.tar.gz")
+        );
+        assert_eq!(
+            asset_name("windows", "aarch64").as_deref(),
+            Some("rusty-editor-windows-aarch64.zip")
+        );
+        assert_eq!(asset_name("freebsd", "x86_64"), None);
+
+        let Some(name) = asset_name(std::env::consts::OS, std::env::consts::ARCH) else {
+            return;
+        };
+        let os = std::env::consts::OS;
+        let arch = std::env::consts::ARCH;
+        let packages = [
+            asset(&format!("rusty-editor-{os}-{arch}.deb")),
+            asset(&format!("rusty-editor-{os}-{arch}.dmg")),
+            asset(&format!("rusty-editor-{os}-{arch}.msi")),
+            asset(&format!("rusty-editor-{os}-{arch}.AppImage")),
+        ];
+        assert!(platform_asset(&packages).is_none());
+
+        let mut assets = packages.to_vec();
+        assets.push(asset(&name));
+        // Without a checksum the archive is not offered either.
+        assert!(platform_asset(&assets).is_none());
+        assets.push(asset(&format!("{name}.sha256")));
+        let (archive, checksum) = platform_asset(&assets).unwrap();
+        assert_eq!(archive.name, name);
+        assert_eq!(checksum.name, format!("{name}.sha256"));
+    }
+
+    #[test]
+    fn unknown_downloads_are_not_unpacked() {
+        assert!(unpack("rusty-editor-linux-x86_64.deb", b"!<arch>".to_vec()).is_err());
+    }
+
+    #[test]
+    fn checksum_must_match() {
+        let digest = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        assert!(verify_checksum(b"hello", digest).is_ok());
+        assert!(
+            verify_checksum(
+                b"hello",
+                &format!("{}  archive.tar.gz\n", digest.to_uppercase())
+            )
+            .is_ok()
+        );
+        assert!(verify_checksum(b"hello!", digest).is_err());
+        assert!(verify_checksum(b"hello", "").is_err());
+    }
+}
@@ -0,0 +1,155 @@
+//! "Open URL…": fetches an http(s) URL on a background thread and shows the
+//! body in a read-only tab, with the syntax guessed from the URL's path.
+
+use crate::clipboard;
+use crate::i18n::{tr, trf};
+use crate::{FileTab, TextEditorApp};
+use eframe::egui;
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, channel};
+use std::thread;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Default)]
+pub struct UrlOpener {
+    pub show: bool,
+    input: String,
+    /// Fetches in flight, with their URL.
+    pending: Vec<(String, Receiver<Result<String, String>>)>,
+    error: Option<String>,
+}
+
+impl UrlOpener {
+    /// Opens the dialog, prefilled with a URL from the clipboard if there is one.
+    pub fn open(&mut self) {
+        self.show = true;
+        self.error = None;
+        if let Some(text) = clipboard::read_text()
+            && is_url(text.trim())
+        {
+            self.input = text.trim().to_string();
+        }
+    }
+}
+
+pub fn is_url(text: &str) -> bool {
+    (text.starts_with("http://") || text.starts_with("https://"))
+        && !text.contains(char::is_whitespace)
+}
+
+fn fetch(url: &str) -> Result<String, String> {
+    let response = ureq::get(url)
+        .timeout(TIMEOUT)
+        .call()
+        .map_err(|e| e.to_string())?;
+    response.into_string().map_err(|e| e.to_string())
+}
+
+/// The last path segment of `url`, without query or fragment.
+fn file_name(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let path = path.split_once("://").map_or(path, |(_, rest)| rest);
+    match path.trim_end_matches('/').rsplit_once('/') {
+        Some((_, name)) if !name.is_empty() => name.to_string(),
+        _ => path.trim_end_matches('/').to_string(),
+    }
+}
+
+impl TextEditorApp {
+    fn start_fetch(&mut self, ctx: &egui::Context, url: String) {
+        let (sender, receiver) = channel();
+        let ctx = ctx.clone();
+        let target = url.clone();
+        thread::spawn(move || {
+            let _ = sender.send(fetch(&target));
+            ctx.request_repaint();
+        });
+        self.url_opener.pending.push((url, receiver));
+    }
+
+    /// Opens tabs for fetches that finished since the last frame.
+    fn poll_fetches(&mut self) {
+        let mut finished = Vec::new();
+        self.url_opener
+            .pending
+            .retain(|(url, receiver)| match receiver.try_recv() {
+                Ok(result) => {
+                    finished.push((url.clone(), result));
+                    false
+                }
+                Err(_) => true,
+            });
+        for (url, result) in finished {
+            match result {
+                Ok(content) => {
+                    let name = file_name(&url);
+                    let syntax = self.detect_syntax(Some(&PathBuf::from(&name)), &content);
+                    self.add_tab(FileTab {
+                        title: name,
+                        content,
+                        syntax,
+                        read_only: true,
+                        ..Default::default()
+                    });
+                }
+                Err(message) => {
+                    self.url_opener.show = true;
+                    self.url_opener.error = Some(format!("{url}: {message}"));
+                }
+            }
+        }
+    }
+
+    pub(crate) fn show_open_url_window(&mut self, ctx: &egui::Context) {
+        self.poll_fetches();
+        if !self.url_opener.show {
+            return;
+        }
+        let mut open = true;
+        let mut submit = None;
+        let opener = &mut self.url_opener;
+        egui::Window::new(tr("Open URL"))
+            .collapsible(false)
+            .resizable(false)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let input = ui.add(
+                    egui::TextEdit::singleline(&mut opener.input)
+                        .hint_text("https://…")
+                        .desired_width(360.0),
+                );
+                let url = opener.input.trim();
+                let valid = is_url(url);
+                let entered = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(valid, egui::Button::new(tr("Open")))
+                        .clicked()
+                        || (entered && valid)
+                    {
+                        submit = Some(url.to_string());
+                    }
+                    if !opener.pending.is_empty() {
+                        ui.spinner();
+                        ui.label(trf(
+                            "Fetching {count}…",
+                            &[("count", &opener.pending.len())],
+                        ));
+                    }
+                });
+                if let Some(error) = &opener.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+            });
+        if let Some(url) = submit {
+            self.url_opener.error = None;
+            self.url_opener.show = false;
+            self.start_fetch(ctx, url);
+        }
+        if !open {
+            self.url_opener.show = false;
+        }
+    }
+}
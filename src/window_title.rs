@@ -0,0 +1,71 @@
+//! The window title ("file — folder — Rusty Editor") and the application
+//! icon. The title follows the active tab and its modified state; the app id
+//! doubles as the Wayland app_id and X11 WM_CLASS so desktops can match the
+//! window to its launcher.
+
+use crate::TextEditorApp;
+use eframe::egui;
+
+pub const APP_NAME: &str = "Rusty Editor";
+pub const APP_ID: &str = "rusty-editor";
+
+const ICON_SIZE: u32 = 64;
+
+/// A rust-coloured rounded square with a few lines of "text", drawn here so
+/// the binary needs no image assets.
+pub fn icon() -> egui::IconData {
+    let size = ICON_SIZE as i32;
+    let radius = 12;
+    let background = [0xb7, 0x41, 0x0e, 0xff];
+    let ink = [0xff, 0xf4, 0xe8, 0xff];
+    // (row, first column, last column) of each text line.
+    let lines = [(18, 14, 50), (28, 14, 42), (38, 14, 46), (48, 14, 34)];
+    let mut rgba = Vec::with_capacity((ICON_SIZE * ICON_SIZE * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            // Distance into the corner square, if the pixel is in one.
+            let dx = (radius - x).max(x - (size - 1 - radius)).max(0);
+            let dy = (radius - y).max(y - (size - 1 - radius)).max(0);
+            let pixel = if dx * dx + dy * dy > radius * radius {
+                [0, 0, 0, 0]
+            } else if lines
+                .iter()
+                .any(|&(row, from, to)| (row..row + 4).contains(&y) && (from..=to).contains(&x))
+            {
+                ink
+            } else {
+                background
+            };
+            rgba.extend_from_slice(&pixel);
+        }
+    }
+    egui::IconData {
+        rgba,
+        width: ICON_SIZE,
+        height: ICON_SIZE,
+    }
+}
+
+impl TextEditorApp {
+    fn window_title(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) {
+            let marker = if tab.is_modified() { "• " } else { "" };
+            parts.push(format!("{marker}{}", tab.title));
+        }
+        if let Some(name) = self.folders.first().and_then(|folder| folder.file_name()) {
+            parts.push(name.to_string_lossy().to_string());
+        }
+        parts.push(APP_NAME.to_string());
+        parts.join(" — ")
+    }
+
+    /// Sends the title to the window when it changed since the last frame.
+    pub(crate) fn update_window_title(&mut self, ctx: &egui::Context) {
+        let title = self.window_title();
+        if title != self.last_window_title {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.clone()));
+            self.last_window_title = title;
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Word completion for prose: while typing a word in a text or Markdown tab,
+//! the most likely ending is shown greyed after the caret, and after a word
+//! and a space the word that most often follows it in the document. Tab
+//! accepts the suggestion.
+//!
+//! Words are ranked by how often the document uses them, then by their
+//! place in a bundled English frequency list.
+
+use crate::TextEditorApp;
+use crate::editing::char_to_byte;
+use crate::journal::content_hash;
+use crate::motion::take_key;
+use eframe::egui;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// English words, most frequent first.
+const DICTIONARY: &str = include_str!("../dictionaries/en.txt");
+
+/// Letters typed before whole words are suggested.
+const MIN_PREFIX: usize = 2;
+/// How often a word must have followed another to be suggested after it.
+const MIN_FOLLOWS: u32 = 2;
+
+/// Dictionary words with their rank, most frequent first.
+fn dictionary() -> &'static [&'static str] {
+    static WORDS: OnceLock<Vec<&'static str>> = OnceLock::new();
+    WORDS.get_or_init(|| {
+        DICTIONARY
+            .lines()
+            .map(str::trim)
+            .filter(|w| !w.is_empty())
+            .collect()
+    })
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphabetic() || c == '\''
+}
+
+/// The words of `text`, each with whether only whitespace separates it from
+/// the word before.
+fn words(text: &str) -> impl Iterator<Item = (&str, bool)> {
+    let mut rest = text;
+    let mut adjacent = false;
+    std::iter::from_fn(move || {
+        loop {
+            let start = rest.find(is_word_char)?;
+            let gap = &rest[..start];
+            let joined = adjacent && gap.chars().all(char::is_whitespace);
+            let len = rest[start..]
+                .find(|c: char| !is_word_char(c))
+                .unwrap_or(rest.len() - start);
+            let word = &rest[start..start + len];
+            rest = &rest[start + len..];
+            adjacent = true;
+            if word.chars().any(char::is_alphabetic) {
+                return Some((word, joined));
+            }
+        }
+    })
+}
+
+/// Matches the case of what was typed: `Th` completes to `The`, `TH` to
+/// `THE`.
+fn match_case(word: &str, typed: &str) -> String {
+    let mut chars = typed.chars();
+    let first_upper = chars.next().is_some_and(char::is_uppercase);
+    if first_upper && typed.chars().count() > 1 && chars.all(char::is_uppercase) {
+        word.to_uppercase()
+    } else if first_upper {
+        let mut out: String = word
+            .chars()
+            .next()
+            .into_iter()
+            .flat_map(char::to_uppercase)
+            .collect();
+        out.extend(word.chars().skip(1));
+        out
+    } else {
+        word.to_string()
+    }
+}
+
+#[derive(Default)]
+pub struct WordCompletion {
+    /// Tab key and hash of the buffer the counts below were taken from.
+    counted: Option<(String, u64)>,
+    counts: HashMap<String, u32>,
+    follows: HashMap<String, HashMap<String, u32>>,
+    /// The character index of the caret and the text Tab inserts there.
+    pub suggestion: Option<(usize, String)>,
+}
+
+impl WordCompletion {
+    fn count(&mut self, tab_key: &str, text: &str) {
+        let hash = content_hash(text);
+        if self
+            .counted
+            .as_ref()
+            .is_some_and(|(key, counted)| key == tab_key && *counted == hash)
+        {
+            return;
+        }
+        self.counts.clear();
+        self.follows.clear();
+        let mut previous: Option<String> = None;
+        for (word, joined) in words(text) {
+            let word = word.to_lowercase();
+            *self.counts.entry(word.clone()).or_default() += 1;
+            if let Some(previous) = previous.filter(|_| joined) {
+                *self
+                    .follows
+                    .entry(previous)
+                    .or_default()
+                    .entry(word.clone())
+                    .or_default() += 1;
+            }
+            previous = Some(word);
+        }
+        self.counted = Some((tab_key.to_string(), hash));
+    }
+
+    /// Updates [`Self::suggestion`] for the caret at character index
+    /// `caret` of `text`.
+    pub fn update(&mut self, tab_key: &str, text: &str, caret: usize) {
+        self.suggestion = None;
+        let byte = char_to_byte(text, caret);
+        let (before, after) = text.split_at(byte);
+        // Only at the end of a line, where the greyed text has room.
+        if !after.split('\n').next().unwrap_or("").trim().is_empty() {
+            return;
+        }
+        self.count(tab_key, text);
+        let typed_len = before.len() - before.trim_end_matches(is_word_char).len();
+        let typed = &before[before.len() - typed_len..];
+        let completion = if typed.is_empty() {
+            self.next_word(before)
+        } else {
+            self.complete(typed)
+        };
+        self.suggestion = completion.map(|text| (caret, text));
+    }
+
+    /// The rest of the likeliest word starting with `typed`.
+    fn complete(&self, typed: &str) -> Option<String> {
+        let typed_chars = typed.chars().count();
+        if typed_chars < MIN_PREFIX {
+            return None;
+        }
+        let prefix = typed.to_lowercase();
+        let dictionary = dictionary();
+        let score = |word: &str| {
+            let used = self.counts.get(word).copied().unwrap_or(0);
+            let rank = dictionary.iter().position(|w| *w == word);
+            (used, rank.map_or(0, |rank| dictionary.len() - rank))
+        };
+        let candidates = self
+            .counts
+            .keys()
+            .map(String::as_str)
+            .chain(dictionary.iter().copied())
+            .filter(|word| word.starts_with(&prefix) && word.chars().count() > typed_chars + 1);
+        let best = candidates.max_by_key(|word| score(word))?;
+        let word = match_case(best, typed);
+        Some(word.chars().skip(typed_chars).collect())
+    }
+
+    /// The word that most often followed the one before the caret, when the
+    /// caret is right after that word and a space.
+    fn next_word(&self, before: &str) -> Option<String> {
+        let previous = before.strip_suffix(' ')?;
+        let len = previous.len() - previous.trim_end_matches(is_word_char).len();
+        let previous = &previous[previous.len() - len..];
+        if previous.is_empty() {
+            return None;
+        }
+        let (word, _) = self
+            .follows
+            .get(&previous.to_lowercase())?
+            .iter()
+            .filter(|(_, count)| **count >= MIN_FOLLOWS)
+            .max_by_key(|(word, count)| (**count, std::cmp::Reverse(word.as_str())))?;
+        Some(word.clone())
+    }
+}
+
+impl TextEditorApp {
+    /// Whether the active tab gets word suggestions.
+    pub(crate) fn word_completion_enabled(&self) -> bool {
+        self.settings.word_completion
+            && self
+                .active_tab
+                .as_ref()
+                .and_then(|key| self.tabs.get(key))
+                .is_some_and(|tab| {
+                    !tab.read_only
+                        && (tab.prose
+                            || matches!(
+                                tab.syntax.as_deref(),
+                                None | Some("Markdown" | "Plain Text")
+                            ))
+                })
+    }
+
+    /// Tab inserts the suggestion shown after the caret.
+    pub(crate) fn handle_word_completion(&mut self, ctx: &egui::Context) {
+        let Some((caret, text)) = self.word_completion.suggestion.clone() else {
+            return;
+        };
+        if !self.word_completion_enabled()
+            || !self.editor_has_focus(ctx)
+            || self.selection(ctx) != Some(caret..caret)
+        {
+            return;
+        }
+        if take_key(ctx, egui::Key::Tab, |m| m.is_none()).is_some() {
+            self.word_completion.suggestion = None;
+            self.insert_text(ctx, &text);
+        }
+    }
+}
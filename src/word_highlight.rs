@@ -0,0 +1,75 @@
+//! Occurrence highlighting: once the caret has rested inside a word for a
+//! moment, the other whole-word occurrences of that word on screen get a
+//! soft background. Only the visible rows are searched, so large files cost
+//! no more than small ones.
+
+use crate::editing::{byte_to_char, char_to_byte};
+use crate::rename_symbol::{identifier_at, occurrences};
+use eframe::egui;
+use egui::text::CCursor;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How long the caret has to stay put before occurrences show.
+const DELAY: Duration = Duration::from_millis(400);
+
+#[derive(Default)]
+pub struct WordHighlight {
+    /// The tab and character offset of the caret, and when it got there.
+    caret: Option<(String, usize, Instant)>,
+}
+
+impl WordHighlight {
+    /// Paints the occurrences of the word at `caret`, a character offset
+    /// into `text`, in the visible part of the editor of `tab_key`.
+    pub fn paint(
+        &mut self,
+        ui: &egui::Ui,
+        tab_key: &str,
+        text: &str,
+        caret: usize,
+        galley: &Arc<egui::Galley>,
+        galley_pos: egui::Pos2,
+    ) {
+        let since = match &self.caret {
+            Some((key, at, since)) if key == tab_key && *at == caret => *since,
+            _ => {
+                let now = Instant::now();
+                self.caret = Some((tab_key.to_string(), caret, now));
+                now
+            }
+        };
+        let waited = since.elapsed();
+        if waited < DELAY {
+            ui.ctx().request_repaint_after(DELAY - waited);
+            return;
+        }
+        let caret = char_to_byte(text, caret);
+        let Some(word) = identifier_at(text, caret) else {
+            return;
+        };
+        // Whole lines from the top to the bottom of the view.
+        let clip = ui.clip_rect();
+        let offset_at = |pos: egui::Pos2| {
+            char_to_byte(text, galley.cursor_from_pos(pos - galley_pos).ccursor.index)
+        };
+        let top = offset_at(clip.left_top());
+        let bottom = offset_at(clip.right_bottom());
+        let start = text[..top].rfind('\n').map_or(0, |i| i + 1);
+        let end = text[bottom..].find('\n').map_or(text.len(), |i| bottom + i);
+        let fill = ui.visuals().selection.bg_fill.gamma_multiply(0.35);
+        for range in occurrences(&text[start..end], &text[word.clone()]) {
+            let range = start + range.start..start + range.end;
+            if range == word {
+                continue;
+            }
+            let rect_at = |byte: usize| {
+                galley
+                    .pos_from_ccursor(CCursor::new(byte_to_char(text, byte)))
+                    .translate(galley_pos.to_vec2())
+            };
+            let rect = rect_at(range.start).union(rect_at(range.end));
+            ui.painter().rect_filled(rect, 2.0, fill);
+        }
+    }
+}
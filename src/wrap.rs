@@ -0,0 +1,233 @@
+//! Hard wrapping of prose and comment blocks. List items, bulleted with
+//! `-`, `+` or `*` or numbered as `1.` or `1)`, each start a paragraph of
+//! their own whose continuation lines are indented under the item's text.
+
+use crate::TextEditorApp;
+use eframe::egui;
+use std::ops::Range;
+
+/// Comment and quote markers recognised as a line prefix when reflowing,
+/// longest first so `///` wins over `//`.
+const PREFIX_MARKERS: &[&str] = &["///", "//!", "//", "#", "--", ";;", ";", "%", ">"];
+
+/// Byte range of the paragraph around `pos`: the surrounding lines that
+/// share its comment prefix and are not blank.
+pub fn paragraph_at(text: &str, pos: usize) -> Range<usize> {
+    let lines: Vec<(usize, &str)> = line_starts(text).collect();
+    let Some(current) = lines.iter().rposition(|(start, _)| *start <= pos) else {
+        return pos..pos;
+    };
+    let prefix = line_prefix(lines[current].1);
+    let in_paragraph = |line: &str| {
+        let own = line_prefix(line);
+        own.trim_end() == prefix.trim_end() && !line[own.len()..].trim().is_empty()
+    };
+    if !in_paragraph(lines[current].1) {
+        return pos..pos;
+    }
+    // A list item starts a paragraph, so one never reaches above it.
+    let starts_item = |line: &str| list_marker(&line[line_prefix(line).len()..]) > 0;
+    let mut first = current;
+    while first > 0 && !starts_item(lines[first].1) && in_paragraph(lines[first - 1].1) {
+        first -= 1;
+    }
+    let mut last = current;
+    while last + 1 < lines.len()
+        && in_paragraph(lines[last + 1].1)
+        && !starts_item(lines[last + 1].1)
+    {
+        last += 1;
+    }
+    let end = lines[last].0 + lines[last].1.len();
+    let end = if text[end..].starts_with('\n') {
+        end + 1
+    } else {
+        end
+    };
+    lines[first].0..end
+}
+
+/// Reflows `text` so that no line exceeds `width` characters where
+/// possible. Paragraphs are separated by blank lines and list items, and
+/// each keeps the indentation and comment prefix of its first line. Lines
+/// keep their `\r\n` or `\n` endings.
+pub fn hard_wrap(text: &str, width: usize) -> String {
+    let mut out = String::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut ending = "\n";
+    for line in text.split_inclusive('\n') {
+        let body = line.trim_end_matches(['\r', '\n']);
+        let prefix = line_prefix(body);
+        let rest = &body[prefix.len()..];
+        let is_blank = rest.trim().is_empty();
+        let continues = list_marker(rest) == 0
+            && paragraph
+                .first()
+                .is_some_and(|first| line_prefix(first).trim_end() == prefix.trim_end());
+        if is_blank || !continues {
+            flush_paragraph(&mut out, &paragraph, width, ending);
+            paragraph.clear();
+        }
+        if is_blank {
+            out.push_str(line);
+        } else {
+            if paragraph.is_empty() {
+                ending = if line.ends_with("\r\n") { "\r\n" } else { "\n" };
+            }
+            paragraph.push(body);
+        }
+    }
+    flush_paragraph(&mut out, &paragraph, width, ending);
+    if !text.ends_with('\n') && out.ends_with('\n') {
+        out.truncate(out.len() - ending.len());
+    }
+    out
+}
+
+fn flush_paragraph(out: &mut String, lines: &[&str], width: usize, ending: &str) {
+    let Some(first) = lines.first() else {
+        return;
+    };
+    let prefix = line_prefix(first);
+    let rest = &first[prefix.len()..];
+    let marker = &rest[..list_marker(rest)];
+    // Continuation lines line up with the text of a list item.
+    let hanging = format!("{prefix}{}", " ".repeat(marker.chars().count()));
+    let available = width.saturating_sub(hanging.chars().count()).max(1);
+    let mut lead = format!("{prefix}{marker}");
+    let mut current = String::new();
+    let words = std::iter::once(&rest[marker.len()..])
+        .chain(
+            lines[1..]
+                .iter()
+                .map(|line| &line[line_prefix(line).len()..]),
+        )
+        .flat_map(str::split_whitespace);
+    for word in words {
+        let needed =
+            current.chars().count() + word.chars().count() + usize::from(!current.is_empty());
+        if !current.is_empty() && needed > available {
+            out.push_str(&lead);
+            out.push_str(&current);
+            out.push_str(ending);
+            current.clear();
+            lead.clone_from(&hanging);
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        out.push_str(&lead);
+        out.push_str(&current);
+        out.push_str(ending);
+    }
+}
+
+/// Length of the list marker `text` starts with, including the spaces
+/// after it: `-`, `+` or `*`, or a number followed by `.` or `)`. Zero if
+/// there is none.
+fn list_marker(text: &str) -> usize {
+    let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let marker = if digits > 0 {
+        match text[digits..].chars().next() {
+            Some('.' | ')') => digits + 1,
+            _ => return 0,
+        }
+    } else if text.starts_with(['-', '+', '*']) {
+        1
+    } else {
+        return 0;
+    };
+    let spaces = text[marker..].len() - text[marker..].trim_start_matches(' ').len();
+    if spaces == 0 { 0 } else { marker + spaces }
+}
+
+/// Leading indentation plus an optional comment marker and the single
+/// space that follows it.
+fn line_prefix(line: &str) -> &str {
+    let indent = line.len() - line.trim_start().len();
+    let rest = &line[indent..];
+    let marker = PREFIX_MARKERS
+        .iter()
+        .find(|m| rest.starts_with(**m))
+        .map_or(0, |m| m.len());
+    let space = usize::from(rest[marker..].starts_with(' ') && marker > 0);
+    &line[..indent + marker + space]
+}
+
+fn line_starts(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut start = 0;
+    text.split('\n').map(move |line| {
+        let item = (start, line);
+        start += line.len() + 1;
+        item
+    })
+}
+
+impl TextEditorApp {
+    /// Reflows the selection, or the paragraph around the caret, at the
+    /// configured wrap column.
+    pub(crate) fn hard_wrap(&mut self, ctx: &egui::Context) {
+        let Some(range) = self.selection_or(ctx, paragraph_at) else {
+            return;
+        };
+        let Some(tab) = self.active_tab.as_ref().and_then(|key| self.tabs.get(key)) else {
+            return;
+        };
+        let wrapped = hard_wrap(&tab.content[range.clone()], self.settings.wrap_column);
+        self.replace_range(ctx, range, &wrapped);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_items_stay_separate() {
+        assert_eq!(hard_wrap("* a\n* b\n", 80), "* a\n* b\n");
+        assert_eq!(hard_wrap("- a\n- b\n", 80), "- a\n- b\n");
+        assert_eq!(hard_wrap("1. a\n2) b\n+ c\n", 80), "1. a\n2) b\n+ c\n");
+    }
+
+    #[test]
+    fn list_items_wrap_under_their_text() {
+        assert_eq!(
+            hard_wrap("- one two three\n  four\n- five\n", 10),
+            "- one two\n  three\n  four\n- five\n"
+        );
+        assert_eq!(
+            hard_wrap("// 10. alpha beta gamma\n", 16),
+            "// 10. alpha\n//     beta\n//     gamma\n"
+        );
+    }
+
+    #[test]
+    fn paragraphs_are_joined() {
+        assert_eq!(hard_wrap("one\ntwo\n\nthree", 80), "one two\n\nthree");
+        assert_eq!(
+            hard_wrap("/// one two\n/// three\n", 12),
+            "/// one two\n/// three\n"
+        );
+        assert_eq!(hard_wrap("-not a list\nitem\n", 80), "-not a list item\n");
+    }
+
+    #[test]
+    fn crlf_endings_are_kept() {
+        assert_eq!(
+            hard_wrap("one two\r\nthree\r\n\r\nfour\r\n", 9),
+            "one two\r\nthree\r\n\r\nfour\r\n"
+        );
+        assert_eq!(hard_wrap("a\r\nb", 80), "a b");
+    }
+
+    #[test]
+    fn paragraph_stops_at_list_items() {
+        let text = "- a\n  b\n- c\n";
+        assert_eq!(paragraph_at(text, 0), 0..8);
+        assert_eq!(paragraph_at(text, 4), 0..8);
+        assert_eq!(paragraph_at(text, 8), 8..12);
+    }
+}